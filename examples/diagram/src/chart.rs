@@ -0,0 +1,156 @@
+//! chart renders convergence curves recorded by local_search::convergence::ConvergenceRecorder as an SVG line chart,
+//! one line per configuration, so that runs can be compared visually.
+use std::rc::Rc;
+
+use local_search::convergence::ConvergenceSample;
+use local_search::score::HardSoftScoring;
+use usvg::NodeExt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConvergenceMetric {
+    BestHardScore,
+    BestSoftScore,
+}
+
+impl ConvergenceMetric {
+    fn get<_Score: HardSoftScoring>(&self, sample: &ConvergenceSample<_Score>) -> f64 {
+        match self {
+            ConvergenceMetric::BestHardScore => sample.best_score.get_hard_score(),
+            ConvergenceMetric::BestSoftScore => sample.best_score.get_soft_score(),
+        }
+    }
+}
+
+const CURVE_COLORS: [(u8, u8, u8); 6] = [
+    (31, 119, 180),
+    (255, 127, 14),
+    (44, 160, 44),
+    (214, 39, 40),
+    (148, 103, 189),
+    (140, 86, 75),
+];
+
+/// Render the metric of every curve against iteration, with all curves sharing the same axes. Curves are coloured in
+/// the order they are given.
+pub fn render_convergence_svg<_Score: HardSoftScoring>(
+    curves: &[&[ConvergenceSample<_Score>]],
+    metric: ConvergenceMetric,
+    width: f64,
+    height: f64,
+) -> String {
+    let padding = 20.0;
+    let size = usvg::Size::new(width, height).unwrap();
+    let rtree = usvg::Tree::create(usvg::Svg {
+        size,
+        view_box: usvg::ViewBox {
+            rect: size.to_rect(0.0, 0.0),
+            aspect: usvg::AspectRatio::default(),
+        },
+    });
+    rtree.root().append_kind(usvg::NodeKind::Path(usvg::Path {
+        fill: Some(usvg::Fill {
+            paint: usvg::Paint::Color(usvg::Color::white()),
+            opacity: usvg::Opacity::new(1.0),
+            ..usvg::Fill::default()
+        }),
+        stroke: None,
+        data: Rc::new(usvg::PathData::from_rect(
+            usvg::Rect::new(0.0, 0.0, size.width(), size.height()).unwrap(),
+        )),
+        ..usvg::Path::default()
+    }));
+
+    let mut axes = usvg::PathData::new();
+    axes.push_move_to(padding, padding);
+    axes.push_line_to(padding, height - padding);
+    axes.push_line_to(width - padding, height - padding);
+    rtree.root().append_kind(usvg::NodeKind::Path(usvg::Path {
+        stroke: Some(usvg::Stroke {
+            paint: usvg::Paint::Color(usvg::Color::black()),
+            opacity: usvg::Opacity::new(1.0),
+            ..usvg::Stroke::default()
+        }),
+        data: Rc::new(axes),
+        ..usvg::Path::default()
+    }));
+
+    let points = curves.iter().flat_map(|curve| curve.iter());
+    let max_iteration = points
+        .clone()
+        .map(|sample| sample.iteration)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f64;
+    let (min_value, max_value) = points
+        .map(|sample| metric.get(sample))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    let value_range = if max_value > min_value {
+        max_value - min_value
+    } else {
+        1.0
+    };
+    let to_x = |iteration: u64| padding + (iteration as f64 / max_iteration) * (width - 2.0 * padding);
+    let to_y = |value: f64| height - padding - ((value - min_value) / value_range) * (height - 2.0 * padding);
+
+    for (curve, (red, green, blue)) in curves.iter().zip(CURVE_COLORS.iter().cycle()) {
+        let mut path_data = usvg::PathData::new();
+        for (i, sample) in curve.iter().enumerate() {
+            let (x, y) = (to_x(sample.iteration), to_y(metric.get(sample)));
+            if i == 0 {
+                path_data.push_move_to(x, y);
+            } else {
+                path_data.push_line_to(x, y);
+            }
+        }
+        if path_data.is_empty() {
+            continue;
+        }
+        rtree.root().append_kind(usvg::NodeKind::Path(usvg::Path {
+            stroke: Some(usvg::Stroke {
+                paint: usvg::Paint::Color(usvg::Color::new_rgb(*red, *green, *blue)),
+                opacity: usvg::Opacity::new(1.0),
+                width: usvg::StrokeWidth::new(2.0),
+                ..usvg::Stroke::default()
+            }),
+            data: Rc::new(path_data),
+            ..usvg::Path::default()
+        }));
+    }
+
+    rtree.to_string(&usvg::XmlOptions::default())
+}
+
+#[cfg(test)]
+mod chart_tests {
+    use std::time::Duration;
+
+    use local_search::convergence::ConvergenceSample;
+    use local_search::score::IntegerHardSoft;
+
+    use crate::chart::{render_convergence_svg, ConvergenceMetric};
+
+    fn sample(iteration: u64, best_soft_score: i64) -> ConvergenceSample<IntegerHardSoft> {
+        let best_score = IntegerHardSoft {
+            hard_score: 0,
+            soft_score: best_soft_score,
+        };
+        ConvergenceSample {
+            elapsed: Duration::from_millis(iteration),
+            iteration,
+            current_score: best_score,
+            best_score,
+        }
+    }
+
+    #[test]
+    fn renders_one_path_per_curve() {
+        let first = vec![sample(1, 10), sample(2, 5), sample(3, 1)];
+        let second = vec![sample(1, 8), sample(2, 8)];
+        let svg = render_convergence_svg(&[&first, &second], ConvergenceMetric::BestSoftScore, 400.0, 300.0);
+        assert!(svg.starts_with("<svg"));
+        // Background, axes, and one path per curve.
+        assert_eq!(4, svg.matches("<path").count());
+    }
+}
@@ -10,6 +10,7 @@ use itertools::Itertools;
 use crate::geometry::h_v_line_intersection;
 use crate::primitives::{HorizontalSegment, Padding, PortNumber, Ports, Unit, VerticalSegment};
 
+pub mod chart;
 pub mod geometry;
 pub mod primitives;
 
@@ -0,0 +1,77 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{Duration, NaiveDate};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use rand_pcg::Pcg64;
+
+use employee_scheduling::{get_ils_with_rng, Employee, MainArgs};
+use local_search::config::{IteratedLocalSearchConfig, LocalSearchConfig, SolverConfig};
+use local_search::constraints::ConstraintWeights;
+
+fn get_args(window_size: usize) -> MainArgs<'static> {
+    let start_date = NaiveDate::parse_from_str("2022-05-09", "%Y-%m-%d").unwrap();
+    MainArgs {
+        start_date,
+        end_date: start_date + Duration::days(30),
+        employees: (0..7).map(|id| Employee { id }).collect::<BTreeSet<Employee>>(),
+        employee_to_holidays: HashMap::new(),
+        seed: "42",
+        solver_config: SolverConfig {
+            local_search: LocalSearchConfig {
+                max_iterations: 100,
+                window_size,
+                best_solutions_capacity: 64,
+                all_solutions_capacity: 100_000,
+                all_solution_iteration_expiry: 1_000,
+                ..LocalSearchConfig::default()
+            },
+            iterated_local_search: IteratedLocalSearchConfig {
+                max_iterations: 5,
+                max_allow_no_improvement_for: 20,
+                best_solutions_capacity: 64,
+                all_solutions_capacity: 100_000,
+                all_solution_iteration_expiry: 1_000,
+                ..IteratedLocalSearchConfig::default()
+            },
+            ..SolverConfig::default()
+        },
+        constraint_weights: ConstraintWeights::new(),
+    }
+}
+
+fn bench_rng<_R>(c: &mut Criterion, name: &str)
+where
+    _R: rand::Rng + SeedableRng<Seed = [u8; 32]> + Clone + 'static,
+{
+    let mut group = c.benchmark_group(format!(
+        "IteratedLocalSearch::execute_round - employee scheduling, {}",
+        name
+    ));
+    group.sample_size(10);
+    for window_size in [100, 1_000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(window_size),
+            &window_size,
+            |b, window_size| {
+                b.iter(|| {
+                    let mut iterated_local_search = get_ils_with_rng::<_R>(get_args(*window_size)).unwrap();
+                    while !iterated_local_search.is_finished() {
+                        iterated_local_search.execute_round().unwrap();
+                    }
+                    black_box(iterated_local_search.get_best_solution())
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn employee_scheduling_rng(c: &mut Criterion) {
+    bench_rng::<ChaCha20Rng>(c, "ChaCha20Rng");
+    bench_rng::<Pcg64>(c, "Pcg64");
+}
+
+criterion_group!(benches, employee_scheduling_rng);
+criterion_main!(benches);
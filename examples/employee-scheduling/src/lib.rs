@@ -1,35 +1,37 @@
-#[macro_use]
-extern crate derivative;
-
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
 use std::ops::Bound::{Excluded, Unbounded};
+use std::sync::Arc;
 
 use chrono::{Datelike, NaiveDate, Weekday};
 use itertools::{Itertools, MinMaxResult};
-use ordered_float::OrderedFloat;
 use rand::prelude::SliceRandom;
-use rand::Rng;
+use rand::SeedableRng;
 
 use crate::ScheduleRandomMove::{ChangeDay, SwapDays};
-use blake2::{digest::consts::U32, Blake2b, Digest};
-use local_search::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
-use local_search::local_search::{
-    History, InitialSolutionGenerator, LocalSearch, MoveProposer, Score, ScoredSolution, Solution,
-    SolutionScoreCalculator,
+use local_search::analysis::ScoreContributionCalculator;
+use local_search::assignment::{Assignment, Domain};
+use local_search::config::SolverConfig;
+use local_search::constraints::{
+    constraint_matches, Constraint, ConstraintLevel, ConstraintMatch, ConstraintScore,
+    ConstraintScoreCalculator, ConstraintViolation, ConstraintWeights, ScoreExplanation,
 };
-use rand_chacha::rand_core::SeedableRng;
+use local_search::error::SolverError;
+use local_search::modeling::ListDomain;
+use local_search::prelude::*;
+use local_search::propagation::ConstraintNetwork;
+use local_search::rng::{DefaultRng, Seed};
 use serde::{Deserialize, Serialize};
 
-type Blake2b256 = Blake2b<U32>;
-pub type IlsType = IteratedLocalSearch<
-    rand_chacha::ChaCha20Rng,
+pub type IlsType<_R = DefaultRng> = IteratedLocalSearch<
+    _R,
     ScheduleSolution,
     ScheduleScore,
     ScheduleSolutionScoreCalculator,
-    ScheduleRandomMoveProposer,
-    ScheduleInitialSolutionGenerator,
-    SchedulePerturbation,
+    ScheduleRandomMoveProposer<_R>,
+    ScheduleInitialSolutionGenerator<_R>,
+    SchedulePerturbation<_R>,
 >;
 
 pub struct MainArgs<'a> {
@@ -38,82 +40,63 @@ pub struct MainArgs<'a> {
     pub employees: BTreeSet<Employee>,
     pub employee_to_holidays: HashMap<Employee, HashSet<Holiday>>,
     pub seed: &'a str,
-    pub local_search_max_iterations: u64,
-    pub window_size: u64,
-    pub best_solutions_capacity: usize,
-    pub all_solutions_capacity: usize,
-    pub all_solution_iteration_expiry: u64,
-    pub iterated_local_search_max_iterations: u64,
-    pub max_allow_no_improvement_for: u64,
-}
 
-pub fn hash_str(input: &str) -> [u8; 32] {
-    let mut hasher = Blake2b256::new();
-    hasher.update(input.as_bytes());
-    let seed = hasher.finalize();
-    seed.into()
+    /// The settings of the solver, e.g. loaded from solver.toml, see SolverConfig. Its constraint weights are ignored
+    /// in favor of constraint_weights.
+    pub solver_config: SolverConfig,
+
+    /// Overrides the weights of the schedule constraints by name, e.g. to make balanced days weigh more than same
+    /// weekdays. Keep a clone to change the weights between solves.
+    pub constraint_weights: ConstraintWeights,
 }
 
-pub fn get_ils(args: MainArgs) -> IlsType {
-    let seed = hash_str(args.seed);
-    // let move_proposer = ScheduleMoveProposer::new(args.employees.clone());
-    let move_proposer = ScheduleRandomMoveProposer::default();
-    let solution_score_calculator = ScheduleSolutionScoreCalculator::new(args.employee_to_holidays.clone());
-    let solver_rng = rand_chacha::ChaCha20Rng::from_seed(seed);
-    let local_search: LocalSearch<
-        rand_chacha::ChaCha20Rng,
-        ScheduleSolution,
-        ScheduleScore,
-        ScheduleSolutionScoreCalculator,
-        ScheduleRandomMoveProposer,
-    > = LocalSearch::new(
-        move_proposer,
-        solution_score_calculator,
-        args.local_search_max_iterations,
-        args.window_size.try_into().unwrap(),
-        args.best_solutions_capacity,
-        args.all_solutions_capacity,
-        args.all_solution_iteration_expiry,
-        solver_rng,
-    );
+/// Wire up the solver for args, or return SolverError if args has no employees or a setting is out of range.
+pub fn get_ils(args: MainArgs) -> Result<IlsType, SolverError> {
+    get_ils_with_rng(args)
+}
 
+/// Like get_ils, but with another random number generator than the default, e.g. rand_pcg::Pcg64, which is faster
+/// for large window sizes, see benches/rng_benchmark.rs.
+pub fn get_ils_with_rng<_R>(args: MainArgs) -> Result<IlsType<_R>, SolverError>
+where
+    _R: rand::Rng + SeedableRng<Seed = [u8; 32]> + Clone + 'static,
+{
+    let seed = Seed::from(args.seed);
     let initial_solution_generator = ScheduleInitialSolutionGenerator::new(
         args.start_date,
         args.end_date,
         args.employees.clone().iter().copied().collect(),
         args.employee_to_holidays.clone(),
-    );
-    let solution_score_calculator = ScheduleSolutionScoreCalculator::new(args.employee_to_holidays.clone());
-    let perturbation = SchedulePerturbation::default();
-    let history = History::<rand_chacha::ChaCha20Rng, ScheduleSolution, ScheduleScore>::new(
-        args.best_solutions_capacity,
-        args.all_solutions_capacity,
-        args.all_solution_iteration_expiry,
-    );
-    let acceptance_criterion = AcceptanceCriterion::default();
-    let iterated_local_search_rng = rand_chacha::ChaCha20Rng::from_seed(seed);
-    let iterated_local_search_max_iterations = args.iterated_local_search_max_iterations;
-    let max_allow_no_improvement_for = args.max_allow_no_improvement_for;
-    let iterated_local_search: IteratedLocalSearch<
-        rand_chacha::ChaCha20Rng,
-        ScheduleSolution,
-        ScheduleScore,
-        ScheduleSolutionScoreCalculator,
-        ScheduleRandomMoveProposer,
-        ScheduleInitialSolutionGenerator,
-        SchedulePerturbation,
-    > = IteratedLocalSearch::new(
+    )?;
+    // let move_proposer = ScheduleMoveProposer::new(args.employees.clone());
+    let move_proposer = ScheduleRandomMoveProposer::with_domain(initial_solution_generator.get_domain());
+    let mut solution_score_calculator =
+        ScheduleSolutionScoreCalculator::new(args.employee_to_holidays.clone());
+    solution_score_calculator.set_constraint_weights(args.constraint_weights.clone());
+    let solver_rng = seed.get_rng();
+    let local_search = LocalSearch::from_config(
+        move_proposer,
+        solution_score_calculator,
+        solver_rng,
+        &args.solver_config.local_search,
+    )?;
+
+    let mut solution_score_calculator =
+        ScheduleSolutionScoreCalculator::new(args.employee_to_holidays.clone());
+    solution_score_calculator.set_constraint_weights(args.constraint_weights.clone());
+    let iterated_local_search_rng = seed.get_rng();
+    IteratedLocalSearchBuilder::new(
         initial_solution_generator,
         solution_score_calculator,
         local_search,
-        perturbation,
-        history,
-        acceptance_criterion,
-        iterated_local_search_max_iterations,
-        max_allow_no_improvement_for,
+        SchedulePerturbation::from_config(&args.solver_config)?,
         iterated_local_search_rng,
-    );
-    iterated_local_search
+    )
+    .config(&args.solver_config.iterated_local_search)
+    .acceptance_criterion(DefaultAcceptanceCriterion::from_config(
+        &args.solver_config.acceptance,
+    )?)
+    .try_build()
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
@@ -124,24 +107,17 @@ pub struct Employee {
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Holiday(pub NaiveDate);
 
-#[derive(Derivative, Serialize, Deserialize)]
-#[derivative(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Solution, Serialize, Deserialize)]
 pub struct ScheduleSolution {
-    #[derivative(PartialEq = "ignore")]
-    #[derivative(PartialOrd = "ignore")]
-    #[derivative(Hash = "ignore")]
+    #[solution(skip)]
     start_date: NaiveDate,
 
-    #[derivative(PartialEq = "ignore")]
-    #[derivative(PartialOrd = "ignore")]
-    #[derivative(Hash = "ignore")]
+    #[solution(skip)]
     end_date: NaiveDate,
 
     pub date_to_employee: Vec<Employee>,
 
-    #[derivative(PartialEq = "ignore")]
-    #[derivative(PartialOrd = "ignore")]
-    #[derivative(Hash = "ignore")]
+    #[solution(skip)]
     pub employees: Vec<Employee>,
 }
 
@@ -191,30 +167,38 @@ impl ScheduleSolution {
     }
 }
 
-fn get_weekday_to_employee_counts_score(solution: &ScheduleSolution) -> f64 {
+/// Employees should be scheduled on the same weekdays. For every weekday with more than one employee, the least
+/// frequent employee's count is a violation that involves all the days with that weekday.
+fn get_weekday_to_employee_counts_violations(
+    days_to_employees: &[(NaiveDate, Employee)],
+) -> Vec<ConstraintMatch> {
     let mut day_counts = HashMap::new();
-    for (date, employee) in solution.get_days_to_employees() {
-        if date.weekday() == Weekday::Sat || date.weekday() == Weekday::Sun {
+    let mut weekday_to_days = HashMap::new();
+    for (index, (date, employee)) in days_to_employees.iter().enumerate() {
+        if is_weekend(date) {
             continue;
         }
         let day_count = day_counts.entry(date.weekday()).or_insert_with(HashMap::new);
         *day_count.entry(employee).or_insert_with(|| 0) += 1;
+        weekday_to_days
+            .entry(date.weekday())
+            .or_insert_with(Vec::new)
+            .push(index);
     }
 
-    let mut score = 0.0;
+    let mut violations = vec![];
     for (day, employee_count) in day_counts {
         if employee_count.len() <= 1 {
             continue;
         }
-        match employee_count.values().into_iter().minmax() {
+        match employee_count.values().minmax() {
             MinMaxResult::NoElements => {}
             MinMaxResult::OneElement(_) => {}
-            MinMaxResult::MinMax(min, _max) => {
-                score += *min as f64;
-            }
+            MinMaxResult::MinMax(min, _max) => violations
+                .push(ConstraintMatch::new(weekday_to_days.remove(&day).unwrap()).with_weight(*min as f64)),
         }
     }
-    score
+    violations
 }
 
 fn is_weekend(date: &chrono::NaiveDate) -> bool {
@@ -234,188 +218,385 @@ impl Debug for ScheduleSolution {
     }
 }
 
-impl Solution for ScheduleSolution {}
+/// Each day is a decision variable whose value is the employee scheduled on that day.
+impl Assignment for ScheduleSolution {
+    type Value = Employee;
+
+    fn get_variable_count(&self) -> usize {
+        self.end_date.signed_duration_since(self.start_date).num_days() as usize + 1
+    }
+
+    fn get_value(&self, variable: usize) -> Self::Value {
+        self.date_to_employee[variable]
+    }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
-pub struct ScheduleScore {
-    pub hard_score: OrderedFloat<f64>,
-    pub soft_score: OrderedFloat<f64>,
+    fn set_value(&mut self, variable: usize, value: Self::Value) {
+        self.date_to_employee[variable] = value;
+    }
 }
 
-impl Score for ScheduleScore {
-    fn is_best(&self) -> bool {
-        self.hard_score == 0.0 && self.soft_score == 0.0
+/// Get the hard constraints of a schedule that involve at most two days: employees are not scheduled on their holidays,
+/// and not on two consecutive days.
+pub fn get_constraint_network(
+    solution: &ScheduleSolution,
+    employee_to_holidays: &HashMap<Employee, HashSet<Holiday>>,
+) -> ConstraintNetwork<ScheduleSolution> {
+    let mut network = ConstraintNetwork::new();
+    for (employee, holidays) in employee_to_holidays {
+        let employee = *employee;
+        for holiday in holidays {
+            if let Some(index) = solution.get_date_index(holiday.0) {
+                network.add_unary(index, move |candidate| *candidate != employee);
+            }
+        }
+    }
+    for index in 1..solution.get_variable_count() {
+        network.add_binary(index - 1, index, |left, right| left != right);
     }
+    network
 }
 
+/// Any employee can be scheduled on any day; holidays are left to the score calculator.
+#[derive(Default)]
+pub struct ScheduleDomain {}
+
+impl Domain for ScheduleDomain {
+    type _Solution = ScheduleSolution;
+
+    fn get_values(&self, solution: &Self::_Solution, _variable: usize) -> Vec<Employee> {
+        solution.employees.clone()
+    }
+}
+
+/// Days on holiday and too frequent shifts are hard constraints, fairness and regularity are soft constraints.
+pub type ScheduleScore = FloatHardSoft;
+
+/// ScheduleView is what the constraints read of a schedule. It is built once per score, so that the constraints share
+/// its days instead of each of them collecting the days of the solution again.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct ScheduleView {
+    days_to_employees: Vec<(NaiveDate, Employee)>,
+    employees_to_days: BTreeMap<Employee, Vec<NaiveDate>>,
+}
+
+impl Solution for ScheduleView {}
+
+impl ScheduleView {
+    fn new(solution: &ScheduleSolution) -> Self {
+        let days_to_employees = solution.get_days_to_employees();
+        let mut employees_to_days: BTreeMap<Employee, Vec<NaiveDate>> = BTreeMap::new();
+        for (date, employee) in &days_to_employees {
+            employees_to_days.entry(*employee).or_default().push(*date);
+        }
+        Self {
+            days_to_employees,
+            employees_to_days,
+        }
+    }
+
+    fn get_date_index(&self, date: NaiveDate) -> Option<usize> {
+        let start_date = self.days_to_employees.first()?.0;
+        let index = usize::try_from(date.signed_duration_since(start_date).num_days()).ok()?;
+        (index < self.days_to_employees.len()).then_some(index)
+    }
+}
+
+/// Every constraint lists its violations with the days, as indices into the solution's days, that take part in them.
+/// Both the score and the per-day score contributions are derived from the same list of violations.
+///
+/// It is not a BoundedScoreCalculator, so schedules are never solved with BranchAndBound: the soft constraints are
+/// about the whole schedule, e.g. balanced days, so a partial schedule has no useful lower bound, and without pruning
+/// even a month of a few employees has far too many schedules to enumerate.
 pub struct ScheduleSolutionScoreCalculator {
-    employee_to_holidays: HashMap<Employee, HashSet<Holiday>>,
+    constraints: ConstraintScoreCalculator<ScheduleView, ScheduleScore>,
 }
 
 impl ScheduleSolutionScoreCalculator {
     pub fn new(employee_to_holidays: HashMap<Employee, HashSet<Holiday>>) -> Self {
-        Self { employee_to_holidays }
+        let mut constraints = ConstraintScoreCalculator::new();
+        for schedule_constraint in get_schedule_constraints(employee_to_holidays) {
+            constraints.add_constraint(schedule_constraint);
+        }
+        Self { constraints }
     }
-}
 
-impl SolutionScoreCalculator for ScheduleSolutionScoreCalculator {
-    type _Solution = ScheduleSolution;
-    type _Score = ScheduleScore;
+    /// Get how much every constraint contributes to the score of solution, e.g. to report which rules are broken.
+    pub fn get_constraint_scores(&self, solution: &ScheduleSolution) -> Vec<ConstraintScore> {
+        self.constraints
+            .get_constraint_scores(&ScheduleView::new(solution))
+    }
 
-    fn get_scored_solution(
-        &self,
-        solution: Self::_Solution,
-    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
-        let mut hard_score = 0.0;
-        let mut soft_score = 0.0;
-
-        // Holidays are a hard constraint.
-        for (employee, holidays) in &self.employee_to_holidays {
-            for holiday in holidays {
-                let actual_employee = solution.get_employee_for_date(holiday.0).unwrap();
-                if actual_employee == *employee {
-                    hard_score += 1.0;
-                }
-            }
-        }
+    /// Explain the score of solution by its violated constraints. The entities of every match are the indices of the
+    /// days that take part in it.
+    pub fn explain(&self, solution: &ScheduleSolution) -> ScoreExplanation {
+        self.constraints.explain(&ScheduleView::new(solution))
+    }
 
-        let days_to_employees: Vec<(NaiveDate, Employee)> = solution.get_days_to_employees();
-        let employees_to_days = solution.get_employees_to_days();
+    /// Use weights instead of the default weights of the schedule constraints, see get_schedule_constraints for their
+    /// names.
+    pub fn set_constraint_weights(&mut self, weights: ConstraintWeights) {
+        self.constraints.set_constraint_weights(weights);
+    }
 
-        // Employee not scheduled on two consecutive days hard constraint.
-        for window in days_to_employees.windows(2) {
-            let first_employee = window[0].1;
-            let second_employee = window[1].1;
-            if first_employee == second_employee {
-                hard_score += 1.0;
-            }
-        }
+    pub fn get_constraint_weights(&self) -> &ConstraintWeights {
+        self.constraints.get_constraint_weights()
+    }
+}
 
-        // Hard constraint, can't be scheduled for consecutive weekends
-        for window in days_to_employees.windows(9) {
-            let date1 = window[0];
-            let date2 = window[1];
-            let date3 = window[7];
-            let date4 = window[8];
-            if !(is_weekend(&date1.0) && is_weekend(&date2.0)) {
+/// For every window of window_size days, every employee scheduled more than max_count times in the window is a
+/// violation involving the days they are scheduled on.
+fn get_too_many_days_in_window_violations(
+    days_to_employees: &[(NaiveDate, Employee)],
+    window_size: usize,
+    max_count: usize,
+) -> Vec<ConstraintMatch> {
+    let mut violations = vec![];
+    for (window_start, window) in days_to_employees.windows(window_size).enumerate() {
+        let counts = window.iter().map(|(_day, employee)| employee).counts();
+        for (employee, count) in counts {
+            if count <= max_count {
                 continue;
             }
-            if date1.1 == date3.1 {
-                hard_score += 1.0;
-            }
-            if date1.1 == date4.1 {
-                hard_score += 1.0;
-            }
-            if date2.1 == date3.1 {
-                hard_score += 1.0;
-            }
-            if date2.1 == date4.1 {
-                hard_score += 1.0;
-            }
-        }
-
-        // Hard constraint, no more than 3 times per 14 days.
-        for window in days_to_employees.windows(14) {
-            let violations = window
-                .iter()
-                .map(|(day, employee)| employee)
-                .counts()
-                .into_iter()
-                .filter(|(_employee, count)| *count > 3)
-                .count();
-            hard_score += violations as f64;
+            violations.push(ConstraintMatch::new(
+                window
+                    .iter()
+                    .enumerate()
+                    .filter(|(_index, (_day, other_employee))| other_employee == employee)
+                    .map(|(index, _)| window_start + index)
+                    .collect(),
+            ));
         }
+    }
+    violations
+}
 
-        // Soft constraint, no more than 2 times per 7 days.
-        for window in days_to_employees.windows(7) {
-            let violations = window
+/// Get every constraint of a schedule.
+fn get_schedule_constraints(
+    employee_to_holidays: HashMap<Employee, HashSet<Holiday>>,
+) -> Vec<Constraint<ScheduleView>> {
+    vec![
+        constraint_matches("holidays", ConstraintLevel::Hard, move |view: &ScheduleView| {
+            let mut violations = vec![];
+            for (employee, holidays) in &employee_to_holidays {
+                for holiday in holidays {
+                    let index = view.get_date_index(holiday.0).unwrap();
+                    if view.days_to_employees[index].1 == *employee {
+                        violations.push(ConstraintMatch::new(vec![index]));
+                    }
+                }
+            }
+            violations
+        }),
+        constraint_matches(
+            "no consecutive days",
+            ConstraintLevel::Hard,
+            |view: &ScheduleView| {
+                let days_to_employees = &view.days_to_employees;
+                let mut violations = vec![];
+                for (index, window) in days_to_employees.windows(2).enumerate() {
+                    let first_employee = window[0].1;
+                    let second_employee = window[1].1;
+                    if first_employee == second_employee {
+                        violations.push(ConstraintMatch::new(vec![index, index + 1]));
+                    }
+                }
+                violations
+            },
+        ),
+        constraint_matches(
+            "no consecutive weekends",
+            ConstraintLevel::Hard,
+            |view: &ScheduleView| {
+                let days_to_employees = &view.days_to_employees;
+                let mut violations = vec![];
+                for (index, window) in days_to_employees.windows(9).enumerate() {
+                    if !(is_weekend(&window[0].0) && is_weekend(&window[1].0)) {
+                        continue;
+                    }
+                    for (first, second) in [(0, 7), (0, 8), (1, 7), (1, 8)] {
+                        if window[first].1 == window[second].1 {
+                            violations.push(ConstraintMatch::new(vec![index + first, index + second]));
+                        }
+                    }
+                }
+                violations
+            },
+        ),
+        constraint_matches(
+            "at most 3 days per 14 days",
+            ConstraintLevel::Hard,
+            |view: &ScheduleView| get_too_many_days_in_window_violations(&view.days_to_employees, 14, 3),
+        ),
+        constraint_matches(
+            "at most 2 days per 7 days",
+            ConstraintLevel::Soft,
+            |view: &ScheduleView| get_too_many_days_in_window_violations(&view.days_to_employees, 7, 2),
+        ),
+        constraint_matches("same weekdays", ConstraintLevel::Soft, |view: &ScheduleView| {
+            get_weekday_to_employee_counts_violations(&view.days_to_employees)
+        }),
+        constraint_matches("balanced days", ConstraintLevel::Soft, |view: &ScheduleView| {
+            let min_max_days = view
+                .employees_to_days
                 .iter()
-                .map(|(day, employee)| employee)
-                .counts()
-                .into_iter()
-                .filter(|(_employee, count)| *count > 2)
-                .count();
-            soft_score += violations as f64;
-        }
+                .map(|(_employee, days)| days.len())
+                .minmax();
+            match min_max_days {
+                MinMaxResult::MinMax(min, max) if max > min => {
+                    vec![ConstraintMatch::new((0..view.days_to_employees.len()).collect())
+                        .with_weight((max - min) as f64)]
+                }
+                _ => vec![],
+            }
+        }),
+        constraint_matches(
+            "balanced weekends",
+            ConstraintLevel::Soft,
+            |view: &ScheduleView| {
+                let min_max_weekends = view
+                    .employees_to_days
+                    .iter()
+                    .map(|(_employee, days)| days.iter().filter(|day| is_weekend(day)).count())
+                    .minmax();
+                match min_max_weekends {
+                    MinMaxResult::MinMax(min, max) if max > min => vec![ConstraintMatch::new(
+                        view.days_to_employees
+                            .iter()
+                            .enumerate()
+                            .filter(|(_index, (day, _employee))| is_weekend(day))
+                            .map(|(index, _)| index)
+                            .collect(),
+                    )
+                    .with_weight((max - min) as f64)],
+                    _ => vec![],
+                }
+            },
+        ),
+    ]
+}
 
-        // Soft constraint, try to schedule employees on same weekdays
-        soft_score += get_weekday_to_employee_counts_score(&solution);
+impl SolutionScoreCalculator for ScheduleSolutionScoreCalculator {
+    type _Solution = ScheduleSolution;
+    type _Score = ScheduleScore;
 
-        // Difference in total days is a soft constraint.
-        let min_max_days = employees_to_days
-            .iter()
-            .map(|(_employee, days)| days.len())
-            .minmax();
-        if let MinMaxResult::MinMax(min, max) = min_max_days {
-            soft_score += (max - min) as f64
-        }
+    fn get_scored_solution(
+        &self,
+        solution: Self::_Solution,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let score = self.constraints.get_score(&ScheduleView::new(&solution));
+        ScoredSolution::new(solution, score)
+    }
 
-        // Difference in total weekends is a soft constraint.
-        let min_max_weekends = employees_to_days
-            .iter()
-            .map(|(_employee, days)| {
-                days.into_iter()
-                    .filter(|day| day.weekday() == Weekday::Sat || day.weekday() == Weekday::Sun)
-                    .collect()
-            })
-            .map(|days: Vec<&NaiveDate>| days.len())
-            .minmax();
-        if let MinMaxResult::MinMax(min, max) = min_max_weekends {
-            soft_score += (max - min) as f64
-        }
+    fn verify(&self, solution: &Self::_Solution) -> Result<(), Vec<ConstraintViolation>> {
+        self.constraints.verify(&ScheduleView::new(solution))
+    }
+}
 
-        ScoredSolution {
-            score: ScheduleScore {
-                hard_score: OrderedFloat(hard_score),
-                soft_score: OrderedFloat(soft_score),
-            },
-            solution,
+/// Each day contributes an even share of every violation it takes part in, so the contributions sum to the score.
+impl ScoreContributionCalculator for ScheduleSolutionScoreCalculator {
+    fn get_score_contributions(&self, solution: &Self::_Solution) -> Vec<Self::_Score> {
+        let mut contributions = vec![ScheduleScore::zero(); solution.get_days_to_employees().len()];
+        for violation in self.explain(solution).violations {
+            for found in violation.matches {
+                let share = found.weight / found.entities.len() as f64;
+                for day in found.entities {
+                    let contribution = &mut contributions[day];
+                    match violation.level {
+                        ConstraintLevel::Hard => contribution.hard_score.0 += share,
+                        ConstraintLevel::Soft => contribution.soft_score.0 += share,
+                    }
+                }
+            }
         }
+        contributions
     }
 }
 
-pub struct ScheduleInitialSolutionGenerator {
+pub struct ScheduleInitialSolutionGenerator<_R = DefaultRng> {
     start_date: NaiveDate,
     end_date: NaiveDate,
     employees: Vec<Employee>,
     employee_to_holidays: HashMap<Employee, HashSet<Holiday>>,
+    phantom_r: PhantomData<_R>,
 }
 
-impl ScheduleInitialSolutionGenerator {
+impl<_R> ScheduleInitialSolutionGenerator<_R>
+where
+    _R: rand::Rng,
+{
+    /// Create a generator that schedules employees from start_date to end_date, or return SolverError::EmptyProblem
+    /// if there are no employees to schedule.
     pub fn new(
         start_date: NaiveDate,
         end_date: NaiveDate,
         employees: Vec<Employee>,
         employee_to_holidays: HashMap<Employee, HashSet<Holiday>>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self, SolverError> {
+        if employees.is_empty() {
+            return Err(SolverError::EmptyProblem(
+                "employees must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
             start_date,
             end_date,
             employees,
             employee_to_holidays,
+            phantom_r: PhantomData,
+        })
+    }
+
+    fn get_template(&self) -> ScheduleSolution {
+        let days = self.end_date.signed_duration_since(self.start_date).num_days() as usize + 1;
+        ScheduleSolution {
+            start_date: self.start_date,
+            end_date: self.end_date,
+            date_to_employee: vec![self.employees[0]; days],
+            employees: self.employees.clone(),
+        }
+    }
+
+    /// Get the employees that can be scheduled on every day, without the employees that are on holiday, or that are
+    /// the only option for a neighbouring day, see get_constraint_network. If the constraints can't be satisfied at
+    /// all every employee is left, and local search does its best.
+    pub fn get_domain(&self) -> ListDomain<ScheduleSolution> {
+        let solution = self.get_template();
+        let mut domain = ListDomain::uniform(solution.get_variable_count(), self.employees.clone());
+        if get_constraint_network(&solution, &self.employee_to_holidays)
+            .propagate(&mut domain)
+            .is_err()
+        {
+            domain = ListDomain::uniform(solution.get_variable_count(), self.employees.clone());
         }
+        domain
     }
 }
 
-impl InitialSolutionGenerator for ScheduleInitialSolutionGenerator {
-    type R = rand_chacha::ChaCha20Rng;
+impl<_R> InitialSolutionGenerator for ScheduleInitialSolutionGenerator<_R>
+where
+    _R: rand::Rng,
+{
+    type R = _R;
     type Solution = ScheduleSolution;
 
     fn generate_initial_solution(&self, rng: &mut Self::R) -> Self::Solution {
         let days = self.end_date.signed_duration_since(self.start_date).num_days() as u32 + 1;
+        let mut solution = self.get_template();
+        let domain = self.get_domain();
         let mut date_to_employee = Vec::with_capacity(days as usize);
-        for day in self.start_date.iter_days() {
-            date_to_employee.push(*self.employees.choose(rng).unwrap());
+        for (index, day) in self.start_date.iter_days().enumerate() {
+            let employees = if index < domain.get_variable_count() {
+                domain.get_variable_values(index)
+            } else {
+                &self.employees
+            };
+            date_to_employee.push(*employees.choose(rng).unwrap());
             if day > self.end_date {
                 break;
             }
         }
-        Self::Solution {
-            start_date: self.start_date,
-            end_date: self.end_date,
-            date_to_employee,
-            employees: self.employees.clone(),
-        }
+        solution.date_to_employee = date_to_employee;
+        solution
     }
 }
 
@@ -425,20 +606,39 @@ pub enum ScheduleRandomMove {
     SwapDays,
 }
 
-pub struct ScheduleRandomMoveProposer {
+pub struct ScheduleRandomMoveProposer<_R = DefaultRng> {
     random_move_types: Vec<(ScheduleRandomMove, u64)>,
+    domain: Option<Arc<ListDomain<ScheduleSolution>>>,
+    phantom_r: PhantomData<_R>,
 }
 
-impl Default for ScheduleRandomMoveProposer {
+impl<_R> Default for ScheduleRandomMoveProposer<_R> {
     fn default() -> Self {
         Self {
             random_move_types: vec![(ChangeDay, 1), (SwapDays, 4)],
+            domain: None,
+            phantom_r: PhantomData,
         }
     }
 }
 
-impl MoveProposer for ScheduleRandomMoveProposer {
-    type R = rand_chacha::ChaCha20Rng;
+impl<_R> ScheduleRandomMoveProposer<_R> {
+    /// Only propose employees of domain for every day, e.g. the domain of ScheduleInitialSolutionGenerator::get_domain,
+    /// so that no move schedules an employee on a day that propagation pruned them from. Swaps that would do so change
+    /// a single day instead.
+    pub fn with_domain(domain: ListDomain<ScheduleSolution>) -> Self {
+        Self {
+            domain: Some(Arc::new(domain)),
+            ..Self::default()
+        }
+    }
+}
+
+impl<_R> MoveProposer for ScheduleRandomMoveProposer<_R>
+where
+    _R: rand::Rng + Clone + 'static,
+{
+    type R = _R;
     type Solution = ScheduleSolution;
 
     fn iter_local_moves(
@@ -446,35 +646,59 @@ impl MoveProposer for ScheduleRandomMoveProposer {
         start: &Self::Solution,
         rng: &mut Self::R,
     ) -> Box<dyn Iterator<Item = Self::Solution>> {
-        struct MoveIterator {
+        struct MoveIterator<_R> {
             solution: ScheduleSolution,
-            days_to_employees: Vec<(NaiveDate, Employee)>,
             random_move_types: Vec<(ScheduleRandomMove, u64)>,
-            rng: rand_chacha::ChaCha20Rng,
+            domain: Option<Arc<ListDomain<ScheduleSolution>>>,
+            rng: _R,
         }
-        impl Iterator for MoveIterator {
+        /// Get the employees that may be scheduled on the day at index.
+        fn get_employees<'a>(
+            domain: Option<&'a Arc<ListDomain<ScheduleSolution>>>,
+            solution: &'a ScheduleSolution,
+            index: usize,
+        ) -> &'a [Employee] {
+            match domain {
+                Some(domain) if index < domain.get_variable_count() => domain.get_variable_values(index),
+                _ => &solution.employees,
+            }
+        }
+        impl<_R: rand::Rng> MoveIterator<_R> {
+            fn change_day(&mut self, new_solution: &mut ScheduleSolution, index: usize) {
+                let employees = get_employees(self.domain.as_ref(), &self.solution, index);
+                new_solution.date_to_employee[index] = *employees.choose(&mut self.rng).unwrap();
+            }
+        }
+        impl<_R: rand::Rng> Iterator for MoveIterator<_R> {
             type Item = ScheduleSolution;
 
             fn next(&mut self) -> Option<Self::Item> {
                 let current_move = self
                     .random_move_types
                     .choose_weighted(&mut self.rng, |s| s.1)
-                    .unwrap()
+                    .expect("default move types have positive weights")
                     .0;
                 let mut new_solution: ScheduleSolution = self.solution.clone();
+                let days = self.solution.date_to_employee.len();
                 match current_move {
                     ChangeDay => {
-                        let (day, _current_employee) = self.days_to_employees.choose(&mut self.rng).unwrap();
-                        let new_employee = self.solution.employees.choose(&mut self.rng).unwrap();
-                        *new_solution.get_mut_employee_for_date(*day).unwrap() = *new_employee;
+                        let index = self.rng.gen_range(0..days);
+                        self.change_day(&mut new_solution, index);
                     }
                     SwapDays => {
-                        let xs: Vec<&(NaiveDate, Employee)> =
-                            self.days_to_employees.choose_multiple(&mut self.rng, 2).collect();
-                        let (day1, employee1) = xs[0];
-                        let (day2, employee2) = xs[1];
-                        *new_solution.get_mut_employee_for_date(*day1).unwrap() = *employee2;
-                        *new_solution.get_mut_employee_for_date(*day2).unwrap() = *employee1;
+                        let indices = rand::seq::index::sample(&mut self.rng, days, 2);
+                        let (index1, index2) = (indices.index(0), indices.index(1));
+                        let employee1 = self.solution.date_to_employee[index1];
+                        let employee2 = self.solution.date_to_employee[index2];
+                        if get_employees(self.domain.as_ref(), &self.solution, index1).contains(&employee2)
+                            && get_employees(self.domain.as_ref(), &self.solution, index2)
+                                .contains(&employee1)
+                        {
+                            new_solution.date_to_employee[index1] = employee2;
+                            new_solution.date_to_employee[index2] = employee1;
+                        } else {
+                            self.change_day(&mut new_solution, index1);
+                        }
                     }
                 }
                 Some(new_solution)
@@ -483,18 +707,19 @@ impl MoveProposer for ScheduleRandomMoveProposer {
 
         Box::new(MoveIterator {
             solution: start.clone(),
-            days_to_employees: start.get_days_to_employees(),
             random_move_types: self.random_move_types.clone(),
+            domain: self.domain.clone(),
             rng: rng.clone(),
         })
     }
 }
 
-pub struct ScheduleMoveProposer {
+pub struct ScheduleMoveProposer<_R = DefaultRng> {
     pub next_employees: HashMap<Employee, Employee>,
+    phantom_r: PhantomData<_R>,
 }
 
-impl ScheduleMoveProposer {
+impl<_R> ScheduleMoveProposer<_R> {
     pub fn new(employees: BTreeSet<Employee>) -> Self {
         let mut next_employees = HashMap::with_capacity(employees.len());
         for employee in &employees {
@@ -504,12 +729,18 @@ impl ScheduleMoveProposer {
             };
             next_employees.insert(*employee, next_employee);
         }
-        Self { next_employees }
+        Self {
+            next_employees,
+            phantom_r: PhantomData,
+        }
     }
 }
 
-impl MoveProposer for ScheduleMoveProposer {
-    type R = rand_chacha::ChaCha20Rng;
+impl<_R> MoveProposer for ScheduleMoveProposer<_R>
+where
+    _R: rand::Rng,
+{
+    type R = _R;
     type Solution = ScheduleSolution;
 
     fn iter_local_moves(
@@ -564,23 +795,41 @@ pub enum SchedulePerturbationStrategy {
     ChangeDaysSubsetRandomly,
 }
 
-pub struct SchedulePerturbation {
+pub struct SchedulePerturbation<_R = DefaultRng> {
     strategy: Vec<(SchedulePerturbationStrategy, u64)>,
+    strength: PerturbationStrength<ScheduleScore>,
+    phantom_r: PhantomData<_R>,
 }
 
-impl SchedulePerturbation {
+impl<_R> SchedulePerturbation<_R> {
     pub fn default() -> Self {
-        Self {
-            strategy: vec![
-                (SchedulePerturbationStrategy::DoNothing, 10),
-                (SchedulePerturbationStrategy::ChangeDaysSubsetRandomly, 100),
-            ],
-        }
+        Self::from_config(&SolverConfig::default()).expect("default strategies have positive weights")
+    }
+
+    /// Create with the strategy weights of config, by the names do_nothing and change_days_subset_randomly, see
+    /// SolverConfig::get_perturbation_weights.
+    pub fn from_config(config: &SolverConfig) -> Result<Self, SolverError> {
+        let strategy = config.get_perturbation_weights(vec![
+            ("do_nothing", SchedulePerturbationStrategy::DoNothing, 10),
+            (
+                "change_days_subset_randomly",
+                SchedulePerturbationStrategy::ChangeDaysSubsetRandomly,
+                100,
+            ),
+        ])?;
+        Ok(Self {
+            strategy,
+            strength: PerturbationStrength::default(),
+            phantom_r: PhantomData,
+        })
     }
 }
 
-impl Perturbation for SchedulePerturbation {
-    type _R = rand_chacha::ChaCha20Rng;
+impl<_R> Perturbation for SchedulePerturbation<_R>
+where
+    _R: rand::Rng,
+{
+    type _R = _R;
     type _Solution = ScheduleSolution;
     type _Score = ScheduleScore;
     type _SSC = ScheduleSolutionScoreCalculator;
@@ -591,16 +840,18 @@ impl Perturbation for SchedulePerturbation {
         history: &History<Self::_R, Self::_Solution, Self::_Score>,
         rng: &mut Self::_R,
     ) -> Self::_Solution {
-        let current_strategy = self.strategy.choose_weighted(rng, |s| s.1).unwrap().0;
+        self.strength.observe(history);
+        let current_strategy = self
+            .strategy
+            .choose_weighted(rng, |s| s.1)
+            .expect("strategy weights are checked in from_config")
+            .0;
         let mut new_solution = current.solution.clone();
         match current_strategy {
             SchedulePerturbationStrategy::DoNothing => new_solution,
             SchedulePerturbationStrategy::ChangeDaysSubsetRandomly => {
                 let total_days = new_solution.date_to_employee.len();
-                let number_of_days_to_alter = match history.is_best_solution(current.clone()) {
-                    true => rng.gen_range(1..=(total_days / 20).clamp(1, total_days)),
-                    false => rng.gen_range(1..=(total_days / 2).clamp(1, total_days)),
-                };
+                let number_of_days_to_alter = rng.gen_range(1..=self.strength.get_size(total_days));
                 let mut indices: Vec<usize> = (0..total_days).collect();
                 indices.shuffle(rng);
                 for index in indices.into_iter().take(number_of_days_to_alter) {
@@ -611,3 +862,129 @@ impl Perturbation for SchedulePerturbation {
         }
     }
 }
+
+#[cfg(test)]
+mod score_tests {
+    use super::*;
+
+    /// Two weeks from Monday 2024-01-01 for employees 1, 2 and 3, where employee 1 has a holiday on 2024-01-03.
+    fn get_fixed_schedule() -> (ScheduleSolutionScoreCalculator, ScheduleSolution) {
+        let employees: Vec<Employee> = (1..=3).map(|id| Employee { id }).collect();
+        let employee_to_holidays = HashMap::from([(
+            employees[0],
+            HashSet::from([Holiday(NaiveDate::from_ymd_opt(2024, 1, 3).unwrap())]),
+        )]);
+        let solution = ScheduleSolution {
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 1, 14).unwrap(),
+            date_to_employee: [1, 2, 1, 3, 2, 3, 1, 2, 3, 1, 2, 3, 3, 1]
+                .into_iter()
+                .map(|id| Employee { id })
+                .collect(),
+            employees,
+        };
+        (
+            ScheduleSolutionScoreCalculator::new(employee_to_holidays),
+            solution,
+        )
+    }
+
+    #[test]
+    fn test_fixed_schedule_score() {
+        let (calculator, solution) = get_fixed_schedule();
+        let scored = calculator.get_scored_solution(solution);
+        assert_eq!(
+            (7.0, 15.0),
+            (scored.score.get_hard_score(), scored.score.get_soft_score())
+        );
+    }
+
+    #[test]
+    fn test_fixed_schedule_constraint_scores() {
+        let (calculator, solution) = get_fixed_schedule();
+        let constraint_scores = calculator.get_constraint_scores(&solution);
+        assert_eq!(
+            vec![
+                // Employee 1 on their holiday.
+                ("holidays", ConstraintLevel::Hard, 1.0),
+                // Employee 3 on Friday and Saturday of the second week.
+                ("no consecutive days", ConstraintLevel::Hard, 1.0),
+                // Employees 1 and 3 on both weekends.
+                ("no consecutive weekends", ConstraintLevel::Hard, 2.0),
+                // Every employee works 4 or 5 of the 14 days.
+                ("at most 3 days per 14 days", ConstraintLevel::Hard, 3.0),
+                // Every 7 days one employee works 3 of them.
+                ("at most 2 days per 7 days", ConstraintLevel::Soft, 8.0),
+                // Mondays, Tuesdays, Thursdays and Fridays are split between two employees.
+                ("same weekdays", ConstraintLevel::Soft, 4.0),
+                // Employee 2 works 4 days, the others 5.
+                ("balanced days", ConstraintLevel::Soft, 1.0),
+                // Employee 2 works no weekend day, the others 2.
+                ("balanced weekends", ConstraintLevel::Soft, 2.0),
+            ],
+            constraint_scores
+                .iter()
+                .map(|constraint_score| (
+                    constraint_score.name.as_str(),
+                    constraint_score.level,
+                    constraint_score.score
+                ))
+                .collect::<Vec<(&str, ConstraintLevel, f64)>>()
+        );
+    }
+
+    #[test]
+    fn test_fixed_schedule_score_contributions() {
+        let (calculator, solution) = get_fixed_schedule();
+        let contributions = calculator.get_score_contributions(&solution);
+        assert_eq!(14, contributions.len());
+
+        // Wednesday 2024-01-03: the holiday, a fifth of employee 1's days in 14, a third of their days in the first 7,
+        // and a 14th of the unbalanced days.
+        assert!((contributions[2].get_hard_score() - (1.0 + 1.0 / 5.0)).abs() < 1e-9);
+        assert!((contributions[2].get_soft_score() - (1.0 / 3.0 + 1.0 / 14.0)).abs() < 1e-9);
+        // Sunday 2024-01-14: half of a consecutive weekend, a fifth of employee 1's days in 14, a 14th of the
+        // unbalanced days and a quarter of the unbalanced weekends.
+        assert!((contributions[13].get_hard_score() - (1.0 / 2.0 + 1.0 / 5.0)).abs() < 1e-9);
+        assert!((contributions[13].get_soft_score() - (1.0 / 14.0 + 2.0 / 4.0)).abs() < 1e-9);
+
+        let score = calculator.get_scored_solution(solution).score;
+        let hard_sum: f64 = contributions
+            .iter()
+            .map(|contribution| contribution.get_hard_score())
+            .sum();
+        let soft_sum: f64 = contributions
+            .iter()
+            .map(|contribution| contribution.get_soft_score())
+            .sum();
+        assert!((score.get_hard_score() - hard_sum).abs() < 1e-9);
+        assert!((score.get_soft_score() - soft_sum).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_initial_solution_generator_needs_employees() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let generator: Result<ScheduleInitialSolutionGenerator, _> =
+            ScheduleInitialSolutionGenerator::new(date, date, vec![], HashMap::new());
+        assert!(matches!(generator, Err(SolverError::EmptyProblem(_))));
+    }
+
+    #[test]
+    fn test_move_proposer_keeps_to_the_domain() {
+        let start_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let end_date = NaiveDate::from_ymd_opt(2024, 1, 14).unwrap();
+        let employees: Vec<Employee> = (1..=3).map(|id| Employee { id }).collect();
+        let holiday = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        let employee_to_holidays = HashMap::from([(employees[0], HashSet::from([Holiday(holiday)]))]);
+        let generator: ScheduleInitialSolutionGenerator =
+            ScheduleInitialSolutionGenerator::new(start_date, end_date, employees, employee_to_holidays)
+                .unwrap();
+        let mut rng = Seed::from("42").get_rng();
+        let start = generator.generate_initial_solution(&mut rng);
+        let move_proposer: ScheduleRandomMoveProposer =
+            ScheduleRandomMoveProposer::with_domain(generator.get_domain());
+        for neighbor in move_proposer.iter_local_moves(&start, &mut rng).take(1_000) {
+            assert_ne!(Employee { id: 1 }, neighbor.date_to_employee[2]);
+        }
+    }
+}
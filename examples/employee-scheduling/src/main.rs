@@ -4,6 +4,7 @@ use chrono::{Datelike, Duration, NaiveDate};
 use itertools::Itertools;
 
 use employee_scheduling::{get_ils, Employee, MainArgs};
+use local_search::config::SolverConfig;
 
 fn main() {
     println!("employee scheduling local search example");
@@ -22,33 +23,44 @@ fn main() {
     let employee_to_holidays = HashMap::new();
 
     let seed = "42";
-    let local_search_max_iterations = 1_000;
-    let window_size = 100;
-    let best_solutions_capacity = 64;
-    let all_solutions_capacity = 100_000;
-    let all_solution_iteration_expiry = 1_000;
-    let iterated_local_search_max_iterations = 250;
-    let max_allow_no_improvement_for = 20;
-
-    let mut iterated_local_search = get_ils(MainArgs {
+
+    // The solver settings are read from the config file given as the first argument, or else from solver.toml.
+    let solver_config = match std::env::args().nth(1) {
+        Some(path) => SolverConfig::load(path),
+        None => SolverConfig::from_toml_str(include_str!("../solver.toml")),
+    };
+    let solver_config = match solver_config {
+        Ok(solver_config) => solver_config,
+        Err(error) => {
+            eprintln!("failed to load the solver config: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let iterated_local_search = get_ils(MainArgs {
         start_date,
         end_date,
         employees,
         employee_to_holidays,
         seed,
-        local_search_max_iterations,
-        window_size,
-        best_solutions_capacity,
-        all_solutions_capacity,
-        all_solution_iteration_expiry,
-        iterated_local_search_max_iterations,
-        max_allow_no_improvement_for,
+        constraint_weights: solver_config
+            .get_constraint_weights()
+            .expect("constraint weights are checked when the config is loaded"),
+        solver_config,
     });
-
-    while !iterated_local_search.is_finished() {
-        iterated_local_search.execute_round();
-    }
-    let result = iterated_local_search.get_best_solution();
+    let result = iterated_local_search.and_then(|mut iterated_local_search| {
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round()?;
+        }
+        iterated_local_search.try_get_best_solution()
+    });
+    let result = match result {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("failed to solve: {}", error);
+            std::process::exit(1);
+        }
+    };
 
     println!("result.solution:\n{:?}", result.solution);
     println!("result.score: {:?}", result.score);
@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+use local_search::local_search::{InitialSolutionGenerator, LocalSearchBuilder};
+use nqueens::{NQueensInitialSolutionGenerator, NQueensMoveProposer, NQueensSolutionScoreCalculator};
+
+fn local_search_execute_nqueens(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LocalSearch::execute - N-Queens");
+    group.sample_size(10);
+    for board_size in [64, 256, 1024] {
+        let start = NQueensInitialSolutionGenerator::new(board_size)
+            .generate_initial_solution(&mut ChaCha20Rng::seed_from_u64(42));
+        group.bench_with_input(BenchmarkId::from_parameter(board_size), &start, |b, start| {
+            b.iter(|| {
+                let mut local_search = LocalSearchBuilder::new(
+                    NQueensMoveProposer::new(board_size),
+                    NQueensSolutionScoreCalculator::default(),
+                    ChaCha20Rng::seed_from_u64(42),
+                )
+                .max_iterations(10)
+                .window_size(16)
+                .build();
+                black_box(local_search.execute(start.clone(), u64::MAX))
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, local_search_execute_nqueens);
+criterion_main!(benches);
@@ -2,13 +2,18 @@
 extern crate derivative;
 
 use std::collections::HashSet;
+use std::marker::PhantomData;
 
-use local_search::iterated_local_search::Perturbation;
-use local_search::local_search::{
-    InitialSolutionGenerator, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
-};
+use local_search::analysis::ScoreContributionCalculator;
+use local_search::assignment::{Assignment, Domain};
+use local_search::branch_and_bound::BoundedScoreCalculator;
+use local_search::error::{check_weights, SolverError};
+use local_search::incremental::IncrementalScoreCalculator;
+use local_search::memetic::Recombinator;
+use local_search::moves::ChangeValueMove;
+use local_search::prelude::*;
+use local_search::rng::DefaultRng;
 use rand::prelude::SliceRandom;
-use rand::Rng;
 
 type Integer = i64;
 
@@ -22,6 +27,36 @@ pub struct NQueensSolution {
 
 impl Solution for NQueensSolution {}
 
+/// Each column is a decision variable whose value is the row of the queen in that column.
+impl Assignment for NQueensSolution {
+    type Value = Integer;
+
+    fn get_variable_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn get_value(&self, variable: usize) -> Self::Value {
+        self.rows[variable]
+    }
+
+    fn set_value(&mut self, variable: usize, value: Self::Value) {
+        self.rows[variable] = value;
+    }
+}
+
+/// Any queen can be placed on any row of the board.
+#[derive(Derivative)]
+#[derivative(Default)]
+pub struct NQueensDomain {}
+
+impl Domain for NQueensDomain {
+    type _Solution = NQueensSolution;
+
+    fn get_values(&self, solution: &Self::_Solution, _variable: usize) -> Vec<Integer> {
+        (0..solution.rows.len() as Integer).collect()
+    }
+}
+
 // Print out solutions, useful for small solutions, nice-to-have.
 impl std::fmt::Debug for NQueensSolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -70,6 +105,17 @@ impl Score for NQueensScore {
     }
 }
 
+/// Every conflict is a hard constraint violation, there are no preferences.
+impl HardSoftScoring for NQueensScore {
+    fn get_hard_score(&self) -> f64 {
+        self.0 as f64
+    }
+
+    fn get_soft_score(&self) -> f64 {
+        0.0
+    }
+}
+
 /// Get conflict per column.
 fn get_col_scores(solution: &NQueensSolution) -> Vec<Integer> {
     let mut result = vec![0; solution.rows.len()];
@@ -117,6 +163,83 @@ mod get_col_scores_tests {
         assert_eq!(0, *scores.get(2).unwrap());
         assert_eq!(0, *scores.get(3).unwrap());
     }
+
+    #[test]
+    fn test_score_contributions_sum_to_score() {
+        let solution = NQueensSolution {
+            rows: vec![0, 2, 1, 3],
+        };
+        let calculator = NQueensSolutionScoreCalculator::default();
+        let contributions = calculator.get_score_contributions(&solution);
+        let score = calculator.get_scored_solution(solution).score;
+        assert_eq!(
+            score.0,
+            contributions
+                .iter()
+                .map(|contribution| contribution.0)
+                .sum::<i64>()
+        );
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            local_search::analysis::get_worst_variables(&contributions, 4)
+        );
+    }
+
+    #[test]
+    fn test_verify_reports_conflicts() {
+        let calculator = NQueensSolutionScoreCalculator::default();
+        assert_eq!(
+            Ok(()),
+            calculator.verify(&NQueensSolution {
+                rows: vec![1, 3, 0, 2]
+            })
+        );
+        let violations = calculator
+            .verify(&NQueensSolution {
+                rows: vec![0, 0, 0, 0],
+            })
+            .unwrap_err();
+        assert_eq!(1, violations.len());
+        assert_eq!(12.0, violations[0].score);
+    }
+}
+
+#[cfg(test)]
+mod repair_tests {
+    use super::*;
+    use local_search::repair::{GreedyReassignmentRepair, RandomFeasibilityRepair, RepairOperator};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_repairs_reduce_conflicts() {
+        let board_size = 16;
+        let calculator = NQueensSolutionScoreCalculator::default();
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = NQueensSolution {
+            rows: vec![0; board_size],
+        };
+        let start_score = calculator.get_scored_solution(start.clone()).score;
+
+        let mut greedy = GreedyReassignmentRepair::new(
+            NQueensSolutionScoreCalculator::default(),
+            NQueensDomain::default(),
+            board_size,
+        );
+        let greedy_score = calculator
+            .get_scored_solution(greedy.repair(start.clone(), &mut rng))
+            .score;
+        assert!(greedy_score < start_score, "{:?} {:?}", greedy_score, start_score);
+
+        let mut random = RandomFeasibilityRepair::new(
+            NQueensSolutionScoreCalculator::default(),
+            NQueensDomain::default(),
+            board_size * 10,
+        );
+        let random_score = calculator
+            .get_scored_solution(random.repair(start, &mut rng))
+            .score;
+        assert!(random_score < start_score, "{:?} {:?}", random_score, start_score);
+    }
 }
 
 #[derive(Derivative)]
@@ -132,25 +255,79 @@ impl SolutionScoreCalculator for NQueensSolutionScoreCalculator {
         solution: Self::_Solution,
     ) -> ScoredSolution<Self::_Solution, Self::_Score> {
         let row_scores = get_col_scores(&solution);
-        ScoredSolution {
-            score: NQueensScore(row_scores.iter().sum()),
-            solution,
-        }
+        ScoredSolution::new(solution, NQueensScore(row_scores.iter().sum()))
+    }
+}
+
+/// Conflicts between the queens of the first assigned columns can only be joined by more conflicts once the remaining
+/// queens are placed.
+impl BoundedScoreCalculator for NQueensSolutionScoreCalculator {
+    fn get_lower_bound(&self, partial: &Self::_Solution, assigned: usize) -> Self::_Score {
+        let placed = NQueensSolution {
+            rows: partial.rows[..assigned].to_vec(),
+        };
+        NQueensScore(get_col_scores(&placed).iter().sum())
+    }
+}
+
+/// Each column contributes the number of conflicts its queen takes part in. Every conflict involves two queens so it
+/// is counted in both columns, exactly like the total score.
+impl ScoreContributionCalculator for NQueensSolutionScoreCalculator {
+    fn get_score_contributions(&self, solution: &Self::_Solution) -> Vec<Self::_Score> {
+        get_col_scores(solution).into_iter().map(NQueensScore).collect()
+    }
+}
+
+/// Get the number of queens other than the one in col that would attack a queen in col and row.
+fn get_conflicts_at(solution: &NQueensSolution, col: usize, row: Integer) -> Integer {
+    solution
+        .rows
+        .iter()
+        .enumerate()
+        .filter(|(other_col, other_row)| {
+            *other_col != col
+                && (**other_row == row
+                    || (**other_row - row).abs() == (*other_col as Integer - col as Integer).abs())
+        })
+        .count() as Integer
+}
+
+/// Moving a queen only changes the conflicts of that queen, which are counted twice in the score like every conflict.
+impl IncrementalScoreCalculator for NQueensSolutionScoreCalculator {
+    type _Solution = NQueensSolution;
+    type _Score = NQueensScore;
+    type _Move = ChangeValueMove<NQueensSolution>;
+    type _ScoreDelta = Integer;
+
+    fn delta_for_move(&self, solution: &Self::_Solution, move_: &Self::_Move) -> Integer {
+        2 * (get_conflicts_at(solution, move_.variable, move_.to)
+            - get_conflicts_at(solution, move_.variable, move_.from))
+    }
+
+    fn apply_delta(&self, score: &Self::_Score, delta: &Integer) -> Self::_Score {
+        NQueensScore(score.0 + delta)
     }
 }
 
-pub struct NQueensInitialSolutionGenerator {
+pub struct NQueensInitialSolutionGenerator<_R = DefaultRng> {
     board_size: usize,
+    phantom_r: PhantomData<_R>,
 }
 
-impl NQueensInitialSolutionGenerator {
+impl<_R> NQueensInitialSolutionGenerator<_R> {
     pub fn new(board_size: usize) -> Self {
-        NQueensInitialSolutionGenerator { board_size }
+        NQueensInitialSolutionGenerator {
+            board_size,
+            phantom_r: PhantomData,
+        }
     }
 }
 
-impl InitialSolutionGenerator for NQueensInitialSolutionGenerator {
-    type R = rand_chacha::ChaCha20Rng;
+impl<_R> InitialSolutionGenerator for NQueensInitialSolutionGenerator<_R>
+where
+    _R: rand::Rng,
+{
+    type R = _R;
     type Solution = NQueensSolution;
 
     fn generate_initial_solution(&self, rng: &mut Self::R) -> Self::Solution {
@@ -160,18 +337,25 @@ impl InitialSolutionGenerator for NQueensInitialSolutionGenerator {
     }
 }
 
-pub struct NQueensMoveProposer {
+pub struct NQueensMoveProposer<_R = DefaultRng> {
     board_size: usize,
+    phantom_r: PhantomData<_R>,
 }
 
-impl NQueensMoveProposer {
+impl<_R> NQueensMoveProposer<_R> {
     pub fn new(board_size: usize) -> Self {
-        Self { board_size }
+        Self {
+            board_size,
+            phantom_r: PhantomData,
+        }
     }
 }
 
-impl MoveProposer for NQueensMoveProposer {
-    type R = rand_chacha::ChaCha20Rng;
+impl<_R> MoveProposer for NQueensMoveProposer<_R>
+where
+    _R: rand::Rng,
+{
+    type R = _R;
     type Solution = NQueensSolution;
 
     fn iter_local_moves(
@@ -261,29 +445,49 @@ pub enum NQueensPerturbationStrategy {
     DoNothing,
 }
 
-pub struct NQueensPerturbation {
+pub struct NQueensPerturbation<_R = DefaultRng> {
     strategy: Vec<(NQueensPerturbationStrategy, u64)>,
+    strength: PerturbationStrength<NQueensScore>,
+    pinned_cols: HashSet<usize>,
+    phantom_r: PhantomData<_R>,
 }
 
-impl NQueensPerturbation {
-    pub fn new(strategy: Vec<(NQueensPerturbationStrategy, u64)>) -> Self {
-        Self { strategy }
+impl<_R> NQueensPerturbation<_R> {
+    /// Pick a strategy per round, proportionally to its weight. Fails unless a strategy has a positive weight.
+    pub fn new(strategy: Vec<(NQueensPerturbationStrategy, u64)>) -> Result<Self, SolverError> {
+        check_weights("strategy", &strategy)?;
+        Ok(Self {
+            strategy,
+            ..Self::default()
+        })
+    }
+
+    /// Never move the queens of pinned_cols, e.g. queens that were placed by hand.
+    pub fn with_pinned_cols(mut self, pinned_cols: impl IntoIterator<Item = usize>) -> Self {
+        self.pinned_cols = pinned_cols.into_iter().collect();
+        self
     }
 }
 
-impl Default for NQueensPerturbation {
+impl<_R> Default for NQueensPerturbation<_R> {
     fn default() -> Self {
         Self {
             strategy: vec![
                 (NQueensPerturbationStrategy::ChangeSubset, 100),
                 (NQueensPerturbationStrategy::DoNothing, 10),
             ],
+            strength: PerturbationStrength::default(),
+            pinned_cols: HashSet::new(),
+            phantom_r: PhantomData,
         }
     }
 }
 
-impl Perturbation for NQueensPerturbation {
-    type _R = rand_chacha::ChaCha20Rng;
+impl<_R> Perturbation for NQueensPerturbation<_R>
+where
+    _R: rand::Rng,
+{
+    type _R = _R;
     type _Solution = NQueensSolution;
     type _Score = NQueensScore;
     type _SSC = NQueensSolutionScoreCalculator;
@@ -294,19 +498,23 @@ impl Perturbation for NQueensPerturbation {
         history: &local_search::local_search::History<Self::_R, Self::_Solution, Self::_Score>,
         rng: &mut Self::_R,
     ) -> Self::_Solution {
-        let current_strategy = self.strategy.choose_weighted(rng, |s| s.1).unwrap().0.clone();
+        self.strength.observe(history);
+        let current_strategy = self
+            .strategy
+            .choose_weighted(rng, |s| s.1)
+            .expect("strategy weights are checked in new")
+            .0
+            .clone();
         let mut new_solution = current.solution.clone();
         match current_strategy {
             NQueensPerturbationStrategy::ChangeSubset => {
                 let board_size = current.solution.rows.len() as u64;
-                let mut rows: Vec<u64> = (0..board_size).collect();
+                let mut rows: Vec<u64> = (0..board_size)
+                    .filter(|col| !self.pinned_cols.contains(&(*col as usize)))
+                    .collect();
                 rows.shuffle(rng);
-                let number_of_rows_to_alter = match history.is_best_solution(current.clone()) {
-                    true => rng.gen_range(1..=(board_size / 20).clamp(1, board_size)),
-                    false => rng.gen_range(1..=(board_size / 2).clamp(1, board_size)),
-                };
-                let rows_to_alter: Vec<u64> =
-                    rows.into_iter().take(number_of_rows_to_alter as usize).collect();
+                let number_of_rows_to_alter = rng.gen_range(1..=self.strength.get_size(board_size as usize));
+                let rows_to_alter: Vec<u64> = rows.into_iter().take(number_of_rows_to_alter).collect();
                 for i in rows_to_alter {
                     let new_col = rng.gen_range(0..board_size) as Integer;
                     new_solution.rows[i as usize] = new_col;
@@ -318,3 +526,544 @@ impl Perturbation for NQueensPerturbation {
         }
     }
 }
+
+/// Builds a child column by column, taking the queen from whichever parent has fewer conflicts in that column, with
+/// ties broken randomly. Columns that are conflict free in a parent are therefore likely to survive.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct NQueensCrossover<_R = DefaultRng> {
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R> Recombinator for NQueensCrossover<_R>
+where
+    _R: rand::Rng,
+{
+    type _R = _R;
+    type _Solution = NQueensSolution;
+
+    fn recombine(
+        &self,
+        first: &NQueensSolution,
+        second: &NQueensSolution,
+        rng: &mut Self::_R,
+    ) -> NQueensSolution {
+        let first_scores = get_col_scores(first);
+        let second_scores = get_col_scores(second);
+        let rows = (0..first.rows.len())
+            .map(|col| match first_scores[col].cmp(&second_scores[col]) {
+                std::cmp::Ordering::Less => first.rows[col],
+                std::cmp::Ordering::Greater => second.rows[col],
+                std::cmp::Ordering::Equal => *[first.rows[col], second.rows[col]].choose(rng).unwrap(),
+            })
+            .collect();
+        NQueensSolution { rows }
+    }
+}
+
+#[cfg(test)]
+mod conformance_tests {
+    use super::*;
+    use local_search_testkit::{
+        assert_move_proposer_is_valid, assert_perturbation_preserves_pinned,
+        assert_score_calculator_is_deterministic, assert_solution_consistency,
+    };
+    use rand::SeedableRng;
+
+    const BOARD_SIZE: usize = 8;
+
+    #[test]
+    fn test_solution_consistency() {
+        assert_solution_consistency(&NQueensInitialSolutionGenerator::<rand_chacha::ChaCha20Rng>::new(
+            BOARD_SIZE,
+        ));
+    }
+
+    #[test]
+    fn test_move_proposer_is_valid() {
+        assert_move_proposer_is_valid(
+            &NQueensInitialSolutionGenerator::<rand_chacha::ChaCha20Rng>::new(BOARD_SIZE),
+            &NQueensMoveProposer::new(BOARD_SIZE),
+            BOARD_SIZE * BOARD_SIZE,
+            |solution| {
+                solution.rows.len() == BOARD_SIZE
+                    && solution
+                        .rows
+                        .iter()
+                        .all(|row| (0..BOARD_SIZE as Integer).contains(row))
+            },
+        );
+    }
+
+    #[test]
+    fn test_score_calculator_is_deterministic() {
+        assert_score_calculator_is_deterministic(
+            &NQueensInitialSolutionGenerator::<rand_chacha::ChaCha20Rng>::new(BOARD_SIZE),
+            &NQueensSolutionScoreCalculator::default(),
+        );
+    }
+
+    #[test]
+    fn test_perturbation_needs_a_strategy_with_a_positive_weight() {
+        let error = Err(SolverError::InvalidConfiguration(
+            "strategy must have a positive weight".to_string(),
+        ));
+        assert_eq!(
+            error,
+            NQueensPerturbation::<rand_chacha::ChaCha20Rng>::new(vec![]).map(|_| ())
+        );
+        assert_eq!(
+            error,
+            NQueensPerturbation::<rand_chacha::ChaCha20Rng>::new(vec![
+                (NQueensPerturbationStrategy::ChangeSubset, 0),
+                (NQueensPerturbationStrategy::DoNothing, 0),
+            ])
+            .map(|_| ())
+        );
+    }
+
+    #[test]
+    fn test_change_subset_perturbation_preserves_pinned_cols() {
+        let pinned: Vec<usize> = (0..BOARD_SIZE / 2).collect();
+        let new_perturbation = || {
+            NQueensPerturbation::<rand_chacha::ChaCha20Rng>::new(vec![(
+                NQueensPerturbationStrategy::ChangeSubset,
+                1,
+            )])
+            .unwrap()
+            .with_pinned_cols(pinned.clone())
+        };
+        let initial_solution_generator =
+            NQueensInitialSolutionGenerator::<rand_chacha::ChaCha20Rng>::new(BOARD_SIZE);
+        let calculator = NQueensSolutionScoreCalculator::default();
+        assert_perturbation_preserves_pinned(
+            &initial_solution_generator,
+            &calculator,
+            &mut new_perturbation(),
+            &pinned,
+        );
+
+        // The columns that are not pinned do move.
+        let mut perturbation = new_perturbation();
+        let history = History::default();
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let changed_cols: HashSet<usize> = (0..20)
+            .flat_map(|_| {
+                let current = calculator
+                    .get_scored_solution(initial_solution_generator.generate_initial_solution(&mut rng));
+                let perturbed = perturbation.propose_new_starting_solution(&current, &history, &mut rng);
+                (0..BOARD_SIZE)
+                    .filter(|col| current.solution.rows[*col] != perturbed.rows[*col])
+                    .collect::<Vec<usize>>()
+            })
+            .collect();
+        assert!(!changed_cols.is_empty());
+        assert!(changed_cols.iter().all(|col| !pinned.contains(col)));
+    }
+}
+
+#[cfg(test)]
+mod modeling_tests {
+    use super::*;
+    use local_search::adaptive_large_neighborhood_search::AdaptiveLargeNeighborhoodSearch;
+    use local_search::iterated_local_search::{
+        DefaultAcceptanceCriterion, IteratedLocalSearch, IteratedLocalSearchBuilder,
+    };
+    use local_search::large_neighborhood_search::{LargeNeighborhoodSearch, RandomDestroy};
+    use local_search::local_search::{History, LocalSearch};
+    use local_search::memetic::MemeticSearch;
+    use local_search::modeling::{ConflictMoveProposer, ConflictStrategy, ListDomain};
+    use local_search::moves::{ChangeValueMoveGenerator, Move, MoveGenerator, MoveLocalSearch};
+    use local_search::path_relinking::PathRelinkingPerturbation;
+    use local_search::repair::{GreedyReassignmentRepair, RandomFeasibilityRepair};
+    use local_search::tabu::{AttributeTabuList, ChangedVariables};
+    use local_search::termination::MaxIterations;
+    use rand::SeedableRng;
+
+    type NQueensConflictMoveProposer = ConflictMoveProposer<
+        rand_chacha::ChaCha20Rng,
+        NQueensSolutionScoreCalculator,
+        ListDomain<NQueensSolution>,
+    >;
+
+    fn new_move_proposer(board_size: usize) -> NQueensConflictMoveProposer {
+        ConflictMoveProposer::new(
+            NQueensSolutionScoreCalculator::default(),
+            ListDomain::uniform(board_size, (0..board_size as Integer).collect()),
+            vec![
+                (ConflictStrategy::MinConflict, 1),
+                (ConflictStrategy::MaxMinConflict, 1),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_conflict_move_proposer_needs_a_strategy_with_a_positive_weight() {
+        let new_move_proposer = |strategy| -> Result<NQueensConflictMoveProposer, SolverError> {
+            ConflictMoveProposer::new(
+                NQueensSolutionScoreCalculator::default(),
+                ListDomain::uniform(4, (0..4).collect()),
+                strategy,
+            )
+        };
+        assert!(matches!(
+            new_move_proposer(vec![]),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            new_move_proposer(vec![(ConflictStrategy::MinConflict, 0)]),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+        assert!(new_move_proposer(vec![(ConflictStrategy::MinConflict, 1)]).is_ok());
+    }
+
+    #[test]
+    fn test_moves_change_one_conflicting_column_fewest_conflicts_first() {
+        let board_size = 8;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = NQueensSolutionScoreCalculator::default();
+        let start = NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let start_contributions = calculator.get_score_contributions(&start);
+
+        let moves: Vec<NQueensSolution> = new_move_proposer(board_size)
+            .iter_local_moves(&start, &mut rng)
+            .collect();
+        assert_eq!(board_size - 1, moves.len());
+        let changed: Vec<usize> = (0..board_size)
+            .filter(|col| moves[0].rows[*col] != start.rows[*col])
+            .collect();
+        assert_eq!(1, changed.len());
+        let col = changed[0];
+        assert_ne!(0, start_contributions[col].0);
+        assert!(moves.iter().all(|candidate| (0..board_size)
+            .all(|other| other == col || candidate.rows[other] == start.rows[other])));
+        let col_contributions: Vec<Integer> = moves
+            .iter()
+            .map(|candidate| calculator.get_score_contributions(candidate)[col].0)
+            .collect();
+        assert!(col_contributions.windows(2).all(|window| window[0] <= window[1]));
+    }
+
+    type NQueensConflictLocalSearch = LocalSearch<
+        rand_chacha::ChaCha20Rng,
+        NQueensSolution,
+        NQueensScore,
+        NQueensSolutionScoreCalculator,
+        NQueensConflictMoveProposer,
+    >;
+
+    fn new_local_search(board_size: usize) -> NQueensConflictLocalSearch {
+        LocalSearch::new(
+            new_move_proposer(board_size),
+            NQueensSolutionScoreCalculator::default(),
+            1_000,
+            board_size * 5,
+            32,
+            10_000,
+            1_000,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+    }
+
+    fn solve(board_size: usize, local_search: NQueensConflictLocalSearch) -> NQueensScore {
+        let mut iterated_local_search: IteratedLocalSearch<
+            rand_chacha::ChaCha20Rng,
+            NQueensSolution,
+            NQueensScore,
+            NQueensSolutionScoreCalculator,
+            NQueensConflictMoveProposer,
+            NQueensInitialSolutionGenerator,
+            NQueensPerturbation,
+        > = IteratedLocalSearch::new(
+            NQueensInitialSolutionGenerator::new(board_size),
+            NQueensSolutionScoreCalculator::default(),
+            local_search,
+            NQueensPerturbation::default(),
+            History::default(),
+            DefaultAcceptanceCriterion::default(),
+            200,
+            5,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        iterated_local_search.get_best_solution().score
+    }
+
+    #[test]
+    fn test_iterated_local_search_with_conflict_move_proposer() {
+        let board_size = 8;
+        assert_eq!(NQueensScore(0), solve(board_size, new_local_search(board_size)));
+    }
+
+    #[test]
+    fn test_iterated_local_search_with_changed_column_tabu() {
+        let board_size = 8;
+        let mut local_search = new_local_search(board_size);
+        local_search.set_move_tabu(Box::new(AttributeTabuList::new(ChangedVariables::default(), 3)));
+        assert_eq!(NQueensScore(0), solve(board_size, local_search));
+    }
+
+    #[test]
+    fn test_min_conflicts_with_random_walk() {
+        let board_size = 16;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let mut local_search = new_local_search(board_size);
+        local_search.set_random_walk_probability(0.1);
+        let result = local_search.execute_until(start, &MaxIterations(10_000));
+        println!("{:?}", result);
+        assert_eq!(NQueensScore(0), result.score);
+    }
+
+    #[test]
+    fn test_iterated_local_search_with_path_relinking() {
+        let board_size = 8;
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            NQueensInitialSolutionGenerator::new(board_size),
+            NQueensSolutionScoreCalculator::default(),
+            new_local_search(board_size),
+            PathRelinkingPerturbation::new(
+                NQueensPerturbation::default(),
+                NQueensSolutionScoreCalculator::default(),
+                0.5,
+            ),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(200)
+        .build();
+        assert_eq!(
+            NQueensScore(0),
+            iterated_local_search.execute(&MaxIterations(200)).unwrap().score
+        );
+    }
+
+    #[test]
+    fn test_large_neighborhood_search() {
+        let board_size = 8;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let initial_solution =
+            NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let mut large_neighborhood_search = LargeNeighborhoodSearch::new(
+            initial_solution,
+            NQueensSolutionScoreCalculator::default(),
+            RandomDestroy::new(NQueensDomain::default(), 0.25),
+            GreedyReassignmentRepair::new(
+                NQueensSolutionScoreCalculator::default(),
+                NQueensDomain::default(),
+                board_size,
+            ),
+            DefaultAcceptanceCriterion::default(),
+            History::default(),
+            rng,
+        );
+        assert_eq!(
+            NQueensScore(0),
+            large_neighborhood_search.execute(&MaxIterations(500)).score
+        );
+    }
+
+    #[test]
+    fn test_adaptive_large_neighborhood_search() {
+        let board_size = 8;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let initial_solution =
+            NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let mut adaptive_large_neighborhood_search = AdaptiveLargeNeighborhoodSearch::new(
+            initial_solution,
+            NQueensSolutionScoreCalculator::default(),
+            vec![
+                Box::new(RandomDestroy::new(NQueensDomain::default(), 0.25)),
+                Box::new(RandomDestroy::new(NQueensDomain::default(), 0.75)),
+            ],
+            vec![
+                Box::new(GreedyReassignmentRepair::new(
+                    NQueensSolutionScoreCalculator::default(),
+                    NQueensDomain::default(),
+                    board_size,
+                )),
+                Box::new(RandomFeasibilityRepair::new(
+                    NQueensSolutionScoreCalculator::default(),
+                    NQueensDomain::default(),
+                    board_size * 10,
+                )),
+            ],
+            DefaultAcceptanceCriterion::default(),
+            rng,
+        );
+        adaptive_large_neighborhood_search.set_segment(10, 0.5);
+        assert_eq!(
+            NQueensScore(0),
+            adaptive_large_neighborhood_search
+                .execute(&MaxIterations(500))
+                .score
+        );
+        let stats = adaptive_large_neighborhood_search.get_stats();
+        assert_eq!(4, stats.operator_weights.len());
+        assert_eq!(
+            stats.proposed_moves * 2,
+            stats
+                .operator_weights
+                .iter()
+                .map(|operator| operator.uses)
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_memetic_search_with_crossover() {
+        let board_size = 8;
+        let mut memetic_search = MemeticSearch::new(
+            NQueensInitialSolutionGenerator::new(board_size),
+            new_local_search(board_size),
+            NQueensCrossover::default(),
+            8,
+            5,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        assert_eq!(NQueensScore(0), memetic_search.execute(&MaxIterations(50)).score);
+    }
+
+    #[test]
+    fn test_move_local_search_solves_without_cloning_candidates() {
+        let board_size = 8;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let mut local_search = MoveLocalSearch::new(
+            ChangeValueMoveGenerator::new(NQueensDomain::default(), 2),
+            NQueensSolutionScoreCalculator::default(),
+            board_size * 2,
+            rng,
+        );
+        let result = local_search.execute_until(start, &MaxIterations(10_000));
+        println!("{:?}", result);
+        assert_eq!(NQueensScore(0), result.score);
+    }
+
+    #[test]
+    fn test_incremental_scores_match_rescoring() {
+        let board_size = 12;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = NQueensSolutionScoreCalculator::default();
+        let start = NQueensInitialSolutionGenerator::new(board_size).generate_initial_solution(&mut rng);
+        let start_score = calculator.get_scored_solution(start.clone()).score;
+        let generator = ChangeValueMoveGenerator::new(NQueensDomain::default(), board_size);
+        for change in generator.iter_moves(&start, &mut rng) {
+            let mut moved = start.clone();
+            change.apply(&mut moved);
+            assert_eq!(
+                calculator.get_scored_solution(moved).score,
+                calculator.apply_delta(&start_score, &calculator.delta_for_move(&start, &change))
+            );
+        }
+
+        let mut local_search = MoveLocalSearch::new(
+            generator,
+            NQueensSolutionScoreCalculator::default(),
+            board_size * 2,
+            rng,
+        );
+        local_search.set_incremental_score_calculator(NQueensSolutionScoreCalculator::default());
+        assert_eq!(
+            NQueensScore(0),
+            local_search.execute_until(start, &MaxIterations(10_000)).score
+        );
+    }
+}
+
+#[cfg(test)]
+mod branch_and_bound_tests {
+    use super::*;
+    use local_search::branch_and_bound::{BranchAndBound, ExactSolver};
+    use local_search::iterated_local_search::{DefaultAcceptanceCriterion, IteratedLocalSearch};
+    use local_search::local_search::{History, LocalSearch};
+    use rand::SeedableRng;
+
+    fn get_brute_force_best_score(board_size: usize) -> NQueensScore {
+        let calculator = NQueensSolutionScoreCalculator::default();
+        let mut best = None;
+        for index in 0..board_size.pow(board_size as u32) {
+            let rows = (0..board_size)
+                .map(|col| ((index / board_size.pow(col as u32)) % board_size) as Integer)
+                .collect();
+            let score = calculator.get_scored_solution(NQueensSolution { rows }).score;
+            if best.as_ref().is_none_or(|best| score < *best) {
+                best = Some(score);
+            }
+        }
+        best.unwrap()
+    }
+
+    #[test]
+    fn test_branch_and_bound_matches_brute_force() {
+        for board_size in 1..=5 {
+            let branch_and_bound = BranchAndBound::new(
+                NQueensSolutionScoreCalculator::default(),
+                NQueensDomain::default(),
+                12,
+                12,
+            );
+            let template = NQueensSolution {
+                rows: vec![0; board_size],
+            };
+            assert!(branch_and_bound.is_small_enough(&template));
+            assert_eq!(
+                get_brute_force_best_score(board_size),
+                branch_and_bound.solve(template).score,
+                "board size {}",
+                board_size
+            );
+        }
+    }
+
+    #[test]
+    fn test_iterated_local_search_uses_exact_solver_for_small_boards() {
+        let board_size = 8;
+        let local_search: LocalSearch<
+            rand_chacha::ChaCha20Rng,
+            NQueensSolution,
+            NQueensScore,
+            NQueensSolutionScoreCalculator,
+            NQueensMoveProposer,
+        > = LocalSearch::new(
+            NQueensMoveProposer::new(board_size),
+            NQueensSolutionScoreCalculator::default(),
+            1_000,
+            board_size * 5,
+            32,
+            10_000,
+            1_000,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        let mut iterated_local_search: IteratedLocalSearch<
+            rand_chacha::ChaCha20Rng,
+            NQueensSolution,
+            NQueensScore,
+            NQueensSolutionScoreCalculator,
+            NQueensMoveProposer,
+            NQueensInitialSolutionGenerator,
+            NQueensPerturbation,
+        > = IteratedLocalSearch::new(
+            NQueensInitialSolutionGenerator::new(board_size),
+            NQueensSolutionScoreCalculator::default(),
+            local_search,
+            NQueensPerturbation::default(),
+            History::default(),
+            DefaultAcceptanceCriterion::default(),
+            1_000,
+            5,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        iterated_local_search.set_exact_solver(Box::new(BranchAndBound::new(
+            NQueensSolutionScoreCalculator::default(),
+            NQueensDomain::default(),
+            12,
+            12,
+        )));
+        iterated_local_search.execute_round().unwrap();
+        assert!(iterated_local_search.is_finished());
+        assert_eq!(NQueensScore(0), iterated_local_search.get_best_solution().score);
+    }
+}
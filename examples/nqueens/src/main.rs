@@ -1,17 +1,18 @@
-use blake2::{digest::consts::U32, Blake2b, Digest};
-use local_search::iterated_local_search::AcceptanceCriterion;
-use local_search::iterated_local_search::IteratedLocalSearch;
-use local_search::local_search::LocalSearch;
-use local_search::local_search::{History, ScoredSolution};
+use local_search::branch_and_bound::BranchAndBound;
+use local_search::convergence::ConvergenceRecorder;
+use local_search::prelude::*;
+use local_search::rng::Seed;
+use nqueens::NQueensDomain;
 use nqueens::NQueensInitialSolutionGenerator;
 use nqueens::NQueensMoveProposer;
 use nqueens::NQueensPerturbation;
 use nqueens::NQueensScore;
 use nqueens::NQueensSolution;
 use nqueens::NQueensSolutionScoreCalculator;
-use rand::SeedableRng;
 
-type Blake2b256 = Blake2b<U32>;
+/// Boards with fewer queens than this are solved exactly with branch and bound instead of iterated local search, unless
+/// --exact-below says otherwise.
+const DEFAULT_EXACT_BELOW: &str = "13";
 
 struct MainArgs<'a> {
     board_size: u64,
@@ -23,20 +24,17 @@ struct MainArgs<'a> {
     all_solution_iteration_expiry: u64,
     iterated_local_search_max_iterations: u64,
     max_allow_no_improvement_for: u64,
-}
+    convergence_csv: Option<&'a str>,
 
-fn hash_str(input: &str) -> [u8; 32] {
-    let mut hasher = Blake2b256::new();
-    hasher.update(input.as_bytes());
-    let seed = hasher.finalize();
-    seed.into()
+    /// Solve boards with fewer queens than this exactly, see DEFAULT_EXACT_BELOW. Zero never does.
+    exact_below: usize,
 }
 
 fn get_solution(args: MainArgs) -> ScoredSolution<NQueensSolution, NQueensScore> {
-    let seed = hash_str(args.seed);
+    let seed = Seed::from(args.seed);
     let move_proposer = NQueensMoveProposer::new(args.board_size as usize);
     let solution_score_calculator = NQueensSolutionScoreCalculator::default();
-    let solver_rng = rand_chacha::ChaCha20Rng::from_seed(seed);
+    let solver_rng = seed.get_rng();
     let local_search: LocalSearch<
         rand_chacha::ChaCha20Rng,
         NQueensSolution,
@@ -62,8 +60,8 @@ fn get_solution(args: MainArgs) -> ScoredSolution<NQueensSolution, NQueensScore>
         args.all_solutions_capacity,
         args.all_solution_iteration_expiry,
     );
-    let acceptance_criterion = AcceptanceCriterion::default();
-    let iterated_local_search_rng = rand_chacha::ChaCha20Rng::from_seed(seed);
+    let acceptance_criterion = DefaultAcceptanceCriterion::default();
+    let iterated_local_search_rng = seed.get_rng();
     let iterated_local_search_max_iterations = args.iterated_local_search_max_iterations;
     let max_allow_no_improvement_for = args.max_allow_no_improvement_for;
     let mut iterated_local_search: IteratedLocalSearch<
@@ -86,8 +84,23 @@ fn get_solution(args: MainArgs) -> ScoredSolution<NQueensSolution, NQueensScore>
         iterated_local_search_rng,
     );
 
+    if args.exact_below > 0 {
+        iterated_local_search.set_exact_solver(Box::new(BranchAndBound::new(
+            NQueensSolutionScoreCalculator::default(),
+            NQueensDomain::default(),
+            args.exact_below - 1,
+            args.exact_below - 1,
+        )));
+    }
+    let mut convergence_recorder = args.convergence_csv.map(|_| ConvergenceRecorder::every_round());
     while !iterated_local_search.is_finished() {
-        iterated_local_search.execute_round();
+        iterated_local_search.execute_round().unwrap();
+        if let Some(convergence_recorder) = convergence_recorder.as_mut() {
+            convergence_recorder.observe(&iterated_local_search);
+        }
+    }
+    if let (Some(convergence_recorder), Some(path)) = (convergence_recorder, args.convergence_csv) {
+        convergence_recorder.write_csv_file(path).unwrap();
     }
     iterated_local_search.get_best_solution()
 }
@@ -122,10 +135,35 @@ fn main() {
                     Ok(())
                 }),
         )
+        .arg(
+            clap::Arg::new("convergence_csv")
+                .long("convergence-csv")
+                .value_name("PATH")
+                .help("Write the best score after every round to this CSV file")
+                .required(false)
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::new("exact_below")
+                .long("exact-below")
+                .value_name("INT")
+                .help("Solve boards with fewer queens exactly with branch and bound, 0 never does")
+                .required(false)
+                .default_value(DEFAULT_EXACT_BELOW)
+                .takes_value(true)
+                .validator(|input| {
+                    if let Err(err) = input.parse::<usize>() {
+                        return Err(err.to_string());
+                    }
+                    Ok(())
+                }),
+        )
         .get_matches();
 
     let seed = matches.value_of("seed").unwrap();
     let board_size = matches.value_of("board_size").unwrap().parse::<u64>().unwrap();
+    let convergence_csv = matches.value_of("convergence_csv");
+    let exact_below = matches.value_of("exact_below").unwrap().parse::<usize>().unwrap();
     let local_search_max_iterations = 10_000;
     let window_size = board_size * 5;
     let best_solutions_capacity = 32;
@@ -143,6 +181,8 @@ fn main() {
         all_solution_iteration_expiry,
         iterated_local_search_max_iterations,
         max_allow_no_improvement_for,
+        convergence_csv,
+        exact_below,
     });
 
     println!("result.solution:\n{:?}", result.solution);
@@ -178,6 +218,8 @@ mod nqueens_example_tests {
                         all_solution_iteration_expiry,
                         iterated_local_search_max_iterations,
                         max_allow_no_improvement_for,
+                        convergence_csv: None,
+                        exact_below: 0,
                     })
                 })
                 .collect();
@@ -198,4 +240,22 @@ mod nqueens_example_tests {
             );
         }
     }
+
+    #[test]
+    fn exact_solves_small_boards() {
+        let result = get_solution(MainArgs {
+            board_size: 8,
+            seed: "42",
+            local_search_max_iterations: 10_000,
+            window_size: 40,
+            best_solutions_capacity: 32,
+            all_solutions_capacity: 100_000,
+            all_solution_iteration_expiry: 1_000,
+            iterated_local_search_max_iterations: 10_000,
+            max_allow_no_improvement_for: 5,
+            convergence_csv: None,
+            exact_below: DEFAULT_EXACT_BELOW.parse().unwrap(),
+        });
+        assert_eq!(0, result.score.0);
+    }
 }
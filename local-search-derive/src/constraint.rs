@@ -0,0 +1,147 @@
+//! constraint expands the constraint attribute and derives ConstraintScoreCalculator, see
+//! local_search_derive::constraint and local_search_derive::ConstraintScoreCalculator.
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{Data, DeriveInput, Expr, FnArg, ItemFn, LitStr, Path, Token, Type};
+
+/// The arguments of the constraint attribute, e.g. `#[constraint(soft, weight = 2.0)]`.
+#[derive(Default)]
+pub(crate) struct ConstraintArgs {
+    level: Option<TokenStream2>,
+    name: Option<LitStr>,
+    weight: Option<Expr>,
+    is_matches: bool,
+}
+
+impl ConstraintArgs {
+    pub(crate) fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        let level = quote!(::local_search::constraints::ConstraintLevel);
+        if meta.path.is_ident("hard") || meta.path.is_ident("soft") {
+            if self.level.is_some() {
+                return Err(meta.error("a constraint is either hard or soft"));
+            }
+            self.level = Some(match meta.path.is_ident("hard") {
+                true => quote!(#level::Hard),
+                false => quote!(#level::Soft),
+            });
+        } else if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("weight") {
+            self.weight = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("matches") {
+            self.is_matches = true;
+        } else {
+            return Err(meta.error(
+                "unknown constraint attribute, expected `hard`, `soft`, `name`, `weight` or `matches`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Keep function, and add a function named after it with a _constraint suffix that creates its Constraint.
+pub(crate) fn expand_constraint(args: ConstraintArgs, function: ItemFn) -> syn::Result<TokenStream2> {
+    let ident = &function.sig.ident;
+    let level = args.level.ok_or_else(|| {
+        syn::Error::new_spanned(ident, "expected `#[constraint(hard)]` or `#[constraint(soft)]`")
+    })?;
+    let solution = match function.sig.inputs.first() {
+        Some(FnArg::Typed(input)) if function.sig.inputs.len() == 1 => match &*input.ty {
+            Type::Reference(reference) => Some(reference.elem.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| syn::Error::new_spanned(&function.sig, "a constraint takes one argument, a &Solution"))?;
+    let name = args
+        .name
+        .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+    let create = match args.is_matches {
+        true => quote!(::local_search::constraints::constraint_matches),
+        false => quote!(::local_search::constraints::constraint),
+    };
+    let with_weight = args.weight.map(|weight| quote!(.with_weight(#weight)));
+    let vis = &function.vis;
+    let constraint_ident = format_ident!("{}_constraint", ident);
+    let doc = format!(
+        "Create the constraint of {}, see the constraint attribute.",
+        ident
+    );
+
+    Ok(quote! {
+        #function
+
+        #[doc = #doc]
+        #vis fn #constraint_ident() -> ::local_search::constraints::Constraint<#solution> {
+            #create(#name, #level, #ident) #with_weight
+        }
+    })
+}
+
+pub(crate) fn expand_constraint_score_calculator(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let field = match &input.data {
+        Data::Struct(data) if data.fields.len() == 1 => data.fields.iter().next().unwrap(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "ConstraintScoreCalculator can only be derived for structs with one field, a ConstraintScoreCalculator",
+            ))
+        }
+    };
+    let member = match &field.ident {
+        Some(field_ident) => quote!(#field_ident),
+        None => quote!(0),
+    };
+    let field_type = &field.ty;
+
+    let mut constraints: Vec<Path> = vec![];
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("constraints"))
+    {
+        constraints.extend(attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?);
+    }
+    for constraint in constraints.iter_mut() {
+        let last = constraint.segments.last_mut().unwrap();
+        last.ident = format_ident!("{}_constraint", last.ident);
+    }
+
+    let calculator = quote!(<#field_type as ::local_search::local_search::SolutionScoreCalculator>);
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::std::default::Default for #ident #type_generics #where_clause {
+            fn default() -> Self {
+                let mut constraints: #field_type = ::std::default::Default::default();
+                #(constraints.add_constraint(#constraints());)*
+                Self { #member: constraints }
+            }
+        }
+
+        impl #impl_generics ::local_search::local_search::SolutionScoreCalculator for #ident #type_generics #where_clause {
+            type _Solution = #calculator::_Solution;
+            type _Score = #calculator::_Score;
+
+            fn get_scored_solution(
+                &self,
+                solution: Self::_Solution,
+            ) -> ::local_search::local_search::ScoredSolution<Self::_Solution, Self::_Score> {
+                ::local_search::local_search::SolutionScoreCalculator::get_scored_solution(&self.#member, solution)
+            }
+
+            fn verify(
+                &self,
+                solution: &Self::_Solution,
+            ) -> ::std::result::Result<(), ::std::vec::Vec<::local_search::constraints::ConstraintViolation>>
+            where
+                Self::_Score: ::local_search::score::HardSoftScoring,
+            {
+                ::local_search::local_search::SolutionScoreCalculator::verify(&self.#member, solution)
+            }
+        }
+    })
+}
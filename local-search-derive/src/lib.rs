@@ -0,0 +1,61 @@
+//! local-search-derive contains the procedural macros of local-search. Use them through local-search, which re-exports
+//! them next to the traits and types they implement, e.g. `use local_search::local_search::Solution;` and
+//! `use local_search::constraints::{constraint, ConstraintScoreCalculator};`.
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+mod constraint;
+mod solution;
+
+/// Derive Solution together with the Clone, PartialEq, Eq, PartialOrd, Ord and Hash that it requires. Fields marked
+/// `#[solution(skip)]` are cloned but otherwise ignored, which suits data that is the same for every solution of a
+/// problem, e.g. its start and end dates. Fields are ordered by their declaration order. Debug is not derived, so that
+/// it can print the solution in the problem's own terms; use `#[derive(Debug)]` to print every field.
+#[proc_macro_derive(Solution, attributes(solution))]
+pub fn derive_solution(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match solution::expand_solution(input) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Turn a function that measures how much a solution violates a rule, e.g.
+/// `#[constraint(hard)] fn no_consecutive_days(solution: &ScheduleSolution) -> f64`, into a constraint, see the
+/// constraints module of local-search. The function is kept as it is, and a function named after it with a
+/// _constraint suffix, e.g. no_consecutive_days_constraint, creates its Constraint. The arguments are:
+///
+/// - `hard` or `soft`, the level of the constraint, which is required.
+/// - `name = "..."`, the name of the constraint, which defaults to the name of the function.
+/// - `weight = ...`, the weight of the constraint, which defaults to 1.
+/// - `matches`, for functions that list their violations as a Vec<ConstraintMatch>, see constraint_matches.
+#[proc_macro_attribute]
+pub fn constraint(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut constraint_args = constraint::ConstraintArgs::default();
+    let parser = syn::meta::parser(|meta| constraint_args.parse(meta));
+    parse_macro_input!(args with parser);
+    let function = parse_macro_input!(input as ItemFn);
+    match constraint::expand_constraint(constraint_args, function) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Derive Default and SolutionScoreCalculator for a struct whose one field is a ConstraintScoreCalculator, so that
+/// the constraints listed by `#[constraints(...)]`, functions with the constraint attribute, make up its score:
+///
+/// ```ignore
+/// #[derive(ConstraintScoreCalculator)]
+/// #[constraints(no_consecutive_days, fair_weekends)]
+/// struct ScheduleScoreCalculator(ConstraintScoreCalculator<ScheduleSolution, ScheduleScore>);
+/// ```
+///
+/// The field keeps the bookkeeping per constraint, e.g. get_constraint_scores and explain.
+#[proc_macro_derive(ConstraintScoreCalculator, attributes(constraints))]
+pub fn derive_constraint_score_calculator(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match constraint::expand_constraint_score_calculator(input) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
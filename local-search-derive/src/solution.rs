@@ -0,0 +1,114 @@
+//! solution derives Solution, see local_search_derive::Solution.
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Field, Fields, Index};
+
+pub(crate) fn expand_solution(mut input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Solution can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut members = vec![];
+    let mut compared_members = vec![];
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(index);
+                quote!(#index)
+            }
+        };
+        if !is_skipped(field)? {
+            compared_members.push(member.clone());
+        }
+        members.push(member);
+    }
+
+    let clone_body = match fields {
+        Fields::Named(_) => quote!(Self { #(#members: ::std::clone::Clone::clone(&self.#members)),* }),
+        Fields::Unnamed(_) => quote!(Self(#(::std::clone::Clone::clone(&self.#members)),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let type_params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+    for type_param in type_params {
+        where_clause.predicates.push(parse_quote!(
+            #type_param: ::std::clone::Clone
+                + ::std::marker::Send
+                + ::std::cmp::Ord
+                + ::std::hash::Hash
+                + ::std::fmt::Debug
+        ));
+    }
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let state = format_ident!("__state");
+
+    Ok(quote! {
+        impl #impl_generics ::std::clone::Clone for #ident #type_generics #where_clause {
+            fn clone(&self) -> Self {
+                #clone_body
+            }
+        }
+
+        impl #impl_generics ::std::cmp::PartialEq for #ident #type_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#compared_members == other.#compared_members)*
+            }
+        }
+
+        impl #impl_generics ::std::cmp::Eq for #ident #type_generics #where_clause {}
+
+        impl #impl_generics ::std::cmp::PartialOrd for #ident #type_generics #where_clause {
+            fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                ::std::option::Option::Some(::std::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl #impl_generics ::std::cmp::Ord for #ident #type_generics #where_clause {
+            fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                #(
+                    match ::std::cmp::Ord::cmp(&self.#compared_members, &other.#compared_members) {
+                        ::std::cmp::Ordering::Equal => {}
+                        ordering => return ordering,
+                    }
+                )*
+                ::std::cmp::Ordering::Equal
+            }
+        }
+
+        impl #impl_generics ::std::hash::Hash for #ident #type_generics #where_clause {
+            fn hash<__H: ::std::hash::Hasher>(&self, #state: &mut __H) {
+                #(::std::hash::Hash::hash(&self.#compared_members, #state);)*
+            }
+        }
+
+        impl #impl_generics ::local_search::local_search::Solution for #ident #type_generics #where_clause {}
+    })
+}
+
+/// Is field marked `#[solution(skip)]`.
+fn is_skipped(field: &Field) -> syn::Result<bool> {
+    let mut is_skipped = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("solution")) {
+        attr.parse_nested_meta(|meta| match meta.path.is_ident("skip") {
+            true => {
+                is_skipped = true;
+                Ok(())
+            }
+            false => Err(meta.error("unknown solution attribute, expected `skip`")),
+        })?;
+    }
+    Ok(is_skipped)
+}
@@ -0,0 +1,100 @@
+use local_search::constraints::ConstraintLevel;
+use local_search::constraints::{constraint, ConstraintMatch, ConstraintScore, ConstraintScoreCalculator};
+use local_search::local_search::{Solution, SolutionScoreCalculator};
+use local_search::score::{FloatHardSoft, HardSoftScoring};
+
+/// How many days in a row each employee works.
+#[derive(Debug, Solution)]
+struct Streaks(Vec<u8>);
+
+#[constraint(hard)]
+fn at_most_five_days_in_a_row(solution: &Streaks) -> f64 {
+    solution.0.iter().map(|days| days.saturating_sub(5) as f64).sum()
+}
+
+#[constraint(soft, name = "short streaks", weight = 0.5)]
+fn short_streaks(solution: &Streaks) -> f64 {
+    solution.0.iter().map(|days| *days as f64).sum()
+}
+
+#[constraint(soft, matches)]
+fn no_single_days(solution: &Streaks) -> Vec<ConstraintMatch> {
+    (0..solution.0.len())
+        .filter(|index| solution.0[*index] == 1)
+        .map(|index| ConstraintMatch::new(vec![index]))
+        .collect()
+}
+
+mod rules {
+    use local_search::constraints::constraint;
+
+    use crate::Streaks;
+
+    #[constraint(soft, weight = 10.0)]
+    pub fn no_idle_employees(solution: &Streaks) -> f64 {
+        solution.0.iter().filter(|days| **days == 0).count() as f64
+    }
+}
+
+#[derive(ConstraintScoreCalculator)]
+#[constraints(
+    at_most_five_days_in_a_row,
+    short_streaks,
+    no_single_days,
+    rules::no_idle_employees
+)]
+struct StreaksScoreCalculator {
+    constraints: ConstraintScoreCalculator<Streaks, FloatHardSoft>,
+}
+
+#[test]
+fn constraint_keeps_function() {
+    assert_eq!(2.0, at_most_five_days_in_a_row(&Streaks(vec![7, 2])));
+    let constraint = short_streaks_constraint();
+    assert_eq!("short streaks", constraint.get_name());
+    assert_eq!(ConstraintLevel::Soft, constraint.get_level());
+    assert_eq!(4.5, constraint.evaluate(&Streaks(vec![7, 2])));
+}
+
+#[test]
+fn derived_score_calculator_adds_up_constraints() {
+    let calculator = StreaksScoreCalculator::default();
+    let solution = Streaks(vec![7, 1, 0]);
+    let score = calculator.get_scored_solution(solution.clone()).score;
+    assert_eq!(2.0, score.get_hard_score());
+    assert_eq!(4.0 + 1.0 + 10.0, score.get_soft_score());
+    assert_eq!(
+        vec![
+            ConstraintScore {
+                name: "at_most_five_days_in_a_row".to_string(),
+                level: ConstraintLevel::Hard,
+                score: 2.0,
+            },
+            ConstraintScore {
+                name: "short streaks".to_string(),
+                level: ConstraintLevel::Soft,
+                score: 4.0,
+            },
+            ConstraintScore {
+                name: "no_single_days".to_string(),
+                level: ConstraintLevel::Soft,
+                score: 1.0,
+            },
+            ConstraintScore {
+                name: "no_idle_employees".to_string(),
+                level: ConstraintLevel::Soft,
+                score: 10.0,
+            },
+        ],
+        calculator.constraints.get_constraint_scores(&solution)
+    );
+    assert_eq!(
+        vec!["at_most_five_days_in_a_row".to_string()],
+        calculator
+            .verify(&solution)
+            .unwrap_err()
+            .into_iter()
+            .map(|violation| violation.name)
+            .collect::<Vec<_>>()
+    );
+}
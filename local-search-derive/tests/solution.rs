@@ -0,0 +1,54 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use local_search::local_search::Solution;
+
+/// A solution with data that is the same for every solution of its problem.
+#[derive(Debug, Solution)]
+struct Days {
+    #[solution(skip)]
+    name: String,
+    days: Vec<u8>,
+    count: u8,
+}
+
+/// A generic tuple struct solution.
+#[derive(Debug, Solution)]
+struct Pair<_T>(_T, #[solution(skip)] u8);
+
+fn days(name: &str, days: Vec<u8>, count: u8) -> Days {
+    Days {
+        name: name.to_string(),
+        days,
+        count,
+    }
+}
+
+fn get_hash<_T: Hash>(value: &_T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn assert_solution<_Solution: Solution>(_solution: &_Solution) {}
+
+#[test]
+fn derived_solution_ignores_skipped_fields() {
+    let solution = days("a", vec![1, 2], 3);
+    assert_solution(&solution);
+    assert_eq!("a", solution.clone().name);
+    assert_eq!(solution, days("b", vec![1, 2], 3));
+    assert_eq!(get_hash(&solution), get_hash(&days("b", vec![1, 2], 3)));
+    assert_ne!(solution, days("a", vec![1, 2], 4));
+    assert!(solution < days("a", vec![2], 0));
+    assert!(solution > days("a", vec![1, 2], 2));
+}
+
+#[test]
+fn derived_solution_of_generic_tuple_struct() {
+    let solution = Pair("a", 1);
+    assert_solution(&solution);
+    assert_eq!(1, solution.clone().1);
+    assert_eq!(solution, Pair("a", 2));
+    assert!(solution < Pair("b", 0));
+}
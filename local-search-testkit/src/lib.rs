@@ -0,0 +1,189 @@
+//! local-search-testkit checks that the types a new problem domain plugs into local-search satisfy the invariants the
+//! framework relies on. Every check is property-based: solutions are generated from many random seeds with the
+//! domain's own InitialSolutionGenerator, and a failure panics with the minimal failing seed so that it can be
+//! reproduced. Call the checks from a #[test] in the problem domain's crate. The number of cases can be changed with
+//! the PROPTEST_CASES environment variable.
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use local_search::assignment::Assignment;
+use local_search::iterated_local_search::Perturbation;
+use local_search::local_search::{
+    History, InitialSolutionGenerator, MoveProposer, Score, Solution, SolutionScoreCalculator,
+};
+use proptest::prelude::*;
+use proptest::test_runner::{TestCaseResult, TestRunner};
+use rand::SeedableRng;
+
+fn generate<_ISG>(initial_solution_generator: &_ISG, seed: u64) -> _ISG::Solution
+where
+    _ISG: InitialSolutionGenerator,
+    _ISG::R: SeedableRng,
+{
+    let mut rng = _ISG::R::seed_from_u64(seed);
+    initial_solution_generator.generate_initial_solution(&mut rng)
+}
+
+fn get_hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn run<S, F>(strategy: &S, test: F)
+where
+    S: Strategy,
+    S::Value: std::fmt::Debug,
+    F: Fn(S::Value) -> TestCaseResult,
+{
+    if let Err(err) = TestRunner::default().run(strategy, test) {
+        panic!("{}", err);
+    }
+}
+
+/// Check that Eq, Ord and Hash agree with each other for pairs of generated solutions, and that clones are equal to
+/// the original. History and tabu lists silently misbehave when they don't.
+pub fn assert_solution_consistency<_ISG>(initial_solution_generator: &_ISG)
+where
+    _ISG: InitialSolutionGenerator,
+    _ISG::R: SeedableRng,
+{
+    run(&(any::<u64>(), any::<u64>()), |(left_seed, right_seed)| {
+        let left = generate(initial_solution_generator, left_seed);
+        let right = generate(initial_solution_generator, right_seed);
+        let left_clone = left.clone();
+
+        prop_assert_eq!(&left, &left_clone, "clone is not equal to the original");
+        prop_assert_eq!(
+            Ordering::Equal,
+            left.cmp(&left_clone),
+            "clone does not compare equal"
+        );
+        prop_assert_eq!(
+            get_hash(&left),
+            get_hash(&left_clone),
+            "clone has a different hash"
+        );
+
+        prop_assert_eq!(
+            left == right,
+            left.cmp(&right) == Ordering::Equal,
+            "Eq and Ord disagree for {:?} and {:?}",
+            &left,
+            &right
+        );
+        prop_assert_eq!(
+            Some(left.cmp(&right)),
+            left.partial_cmp(&right),
+            "Ord and PartialOrd disagree"
+        );
+        prop_assert_eq!(
+            left.cmp(&right),
+            right.cmp(&left).reverse(),
+            "Ord is not antisymmetric"
+        );
+        if left == right {
+            prop_assert_eq!(
+                get_hash(&left),
+                get_hash(&right),
+                "equal solutions have different hashes"
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Check that iter_local_moves yields at most max_moves solutions, so that local search can always exhaust a
+/// neighborhood, and that every yielded solution satisfies is_valid.
+pub fn assert_move_proposer_is_valid<_R, _Solution, _ISG, _MP, _V>(
+    initial_solution_generator: &_ISG,
+    move_proposer: &_MP,
+    max_moves: usize,
+    is_valid: _V,
+) where
+    _R: rand::Rng + SeedableRng,
+    _Solution: Solution,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _V: Fn(&_Solution) -> bool,
+{
+    run(&any::<u64>(), |seed| {
+        let start = generate(initial_solution_generator, seed);
+        let mut rng = _R::seed_from_u64(seed);
+        let mut count = 0;
+        for proposed in move_proposer.iter_local_moves(&start, &mut rng) {
+            count += 1;
+            prop_assert!(
+                count <= max_moves,
+                "move iterator yielded more than {} moves",
+                max_moves
+            );
+            prop_assert!(
+                is_valid(&proposed),
+                "invalid move from {:?} to {:?}",
+                &start,
+                &proposed
+            );
+        }
+        Ok(())
+    });
+}
+
+/// Check that scoring the same solution twice gives the same score, and that scoring doesn't change the solution.
+pub fn assert_score_calculator_is_deterministic<_Solution, _Score, _ISG, _SSC>(
+    initial_solution_generator: &_ISG,
+    solution_score_calculator: &_SSC,
+) where
+    _Solution: Solution,
+    _Score: Score,
+    _ISG: InitialSolutionGenerator<Solution = _Solution>,
+    _ISG::R: SeedableRng,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+{
+    run(&any::<u64>(), |seed| {
+        let solution = generate(initial_solution_generator, seed);
+        let first = solution_score_calculator.get_scored_solution(solution.clone());
+        let second = solution_score_calculator.get_scored_solution(solution.clone());
+        prop_assert_eq!(&first.score, &second.score, "scores differ for {:?}", &solution);
+        prop_assert_eq!(&first.solution, &solution, "scoring changed the solution");
+        Ok(())
+    });
+}
+
+/// Check that the perturbation never changes the value of the pinned variables, e.g. days that were already agreed
+/// on with employees.
+pub fn assert_perturbation_preserves_pinned<_R, _Solution, _Score, _ISG, _SSC, _P>(
+    initial_solution_generator: &_ISG,
+    solution_score_calculator: &_SSC,
+    perturbation: &mut _P,
+    pinned: &[usize],
+) where
+    _R: rand::Rng + SeedableRng,
+    _Solution: Assignment,
+    _Score: Score,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+{
+    let perturbation = RefCell::new(perturbation);
+    run(&any::<u64>(), |seed| {
+        let current =
+            solution_score_calculator.get_scored_solution(generate(initial_solution_generator, seed));
+        let history = History::<_R, _Solution, _Score>::default();
+        let mut rng = _R::seed_from_u64(seed);
+        let perturbed = perturbation
+            .borrow_mut()
+            .propose_new_starting_solution(&current, &history, &mut rng);
+        for variable in pinned {
+            prop_assert_eq!(
+                current.solution.get_value(*variable),
+                perturbed.get_value(*variable),
+                "pinned variable {} changed",
+                variable
+            );
+        }
+        Ok(())
+    });
+}
@@ -0,0 +1,104 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ordered_float::OrderedFloat;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use local_search::local_search::{History, Score, ScoredSolution, Solution};
+
+/// Counts the bytes that are allocated and not freed yet, to report how much memory a History holds on to.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct Point(Vec<OrderedFloat<f64>>);
+impl Solution for Point {}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct Energy(OrderedFloat<f64>);
+impl Score for Energy {
+    fn is_best(&self) -> bool {
+        false
+    }
+}
+
+fn get_scored_solutions(count: usize, dimensions: usize) -> Vec<ScoredSolution<Point, Energy>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(42);
+    (0..count)
+        .map(|_| {
+            let point = Point((0..dimensions).map(|_| OrderedFloat(rng.gen())).collect());
+            let energy = Energy(OrderedFloat(point.0.iter().map(|x| x.0 * x.0).sum()));
+            ScoredSolution::new(point, energy)
+        })
+        .collect()
+}
+
+/// Every solution is seen, as LocalSearch sees them, and then chosen, as IteratedLocalSearch chooses them, so that
+/// the history keeps all of them as recently seen solutions and a best solutions capacity's worth as best solutions.
+/// The capacities and the expiry are those of examples/employee-scheduling/solver.toml, so nothing expires yet.
+fn fill_history(scored_solutions: &[ScoredSolution<Point, Energy>]) -> History<ChaCha20Rng, Point, Energy> {
+    let mut history = History::new(16, 10_000, 1_000);
+    for scored_solution in scored_solutions {
+        history.seen_solution(scored_solution.clone());
+        history.local_search_chose_solution(scored_solution.clone());
+    }
+    history
+}
+
+/// Get what create returns and the bytes it allocated and did not free.
+fn get_allocated<T>(create: impl FnOnce() -> T) -> (T, usize) {
+    let allocated_before = ALLOCATED.load(Ordering::Relaxed);
+    let created = create();
+    (created, ALLOCATED.load(Ordering::Relaxed) - allocated_before)
+}
+
+fn history_memory_and_time(c: &mut Criterion) {
+    let mut group =
+        c.benchmark_group("History::seen_solution and local_search_chose_solution - 1,000 solutions");
+    group.sample_size(20);
+    for dimensions in [10, 1_000] {
+        let scored_solutions = get_scored_solutions(1_000, dimensions);
+        let (history, history_bytes) = get_allocated(|| fill_history(&scored_solutions));
+        drop(history);
+        // The baseline is a single copy of every solution, the least any layout holds. A History that copies every
+        // solution into both its queue and its lookup holds about twice as much.
+        let (copies, solution_bytes) = get_allocated(|| scored_solutions.to_vec());
+        drop(copies);
+        println!(
+            "History of 1,000 solutions of {} dimensions holds {} KiB, {:.2} times the {} KiB of the solutions",
+            dimensions,
+            history_bytes / 1_024,
+            history_bytes as f64 / solution_bytes as f64,
+            solution_bytes / 1_024
+        );
+        group.bench_with_input(
+            BenchmarkId::from_parameter(dimensions),
+            &scored_solutions,
+            |b, scored_solutions| {
+                b.iter(|| black_box(fill_history(scored_solutions)));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, history_memory_and_time);
+criterion_main!(benches);
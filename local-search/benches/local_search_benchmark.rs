@@ -0,0 +1,147 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use math_util::ackley::AckleyFunction;
+use ordered_float::OrderedFloat;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use local_search::local_search::{
+    History, LocalSearchBuilder, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
+    TabuStorage,
+};
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct Point(Vec<OrderedFloat<f64>>);
+impl Solution for Point {}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+struct AckleyScore(OrderedFloat<f64>);
+impl Score for AckleyScore {
+    fn is_best(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Default)]
+struct AckleyScoreCalculator(AckleyFunction);
+impl SolutionScoreCalculator for AckleyScoreCalculator {
+    type _Solution = Point;
+    type _Score = AckleyScore;
+
+    fn get_scored_solution(&self, solution: Point) -> ScoredSolution<Point, AckleyScore> {
+        let score = AckleyScore(OrderedFloat(self.0.calculate(&solution.0)));
+        ScoredSolution::new(solution, score)
+    }
+}
+
+/// Moves every dimension up and down by the same random step.
+struct AckleyMoveProposer;
+impl MoveProposer for AckleyMoveProposer {
+    type R = ChaCha20Rng;
+    type Solution = Point;
+
+    fn iter_local_moves(&self, start: &Point, rng: &mut ChaCha20Rng) -> Box<dyn Iterator<Item = Point>> {
+        let step = rng.gen_range(1e-3..0.5);
+        let start = start.clone();
+        Box::new((0..start.0.len() * 2).map(move |move_| {
+            let mut moved = start.clone();
+            let dimension = move_ / 2;
+            moved.0[dimension] = match move_ % 2 {
+                0 => OrderedFloat(moved.0[dimension].0 + step),
+                _ => OrderedFloat(moved.0[dimension].0 - step),
+            };
+            moved
+        }))
+    }
+}
+
+fn get_random_point(dimensions: usize, rng: &mut ChaCha20Rng) -> Point {
+    Point(
+        (0..dimensions)
+            .map(|_| OrderedFloat(rng.gen_range(-32.768..32.768)))
+            .collect(),
+    )
+}
+
+fn local_search_execute_ackley(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LocalSearch::execute - Ackley");
+    for dimensions in [2, 10, 50] {
+        let start = get_random_point(dimensions, &mut ChaCha20Rng::seed_from_u64(42));
+        group.bench_with_input(BenchmarkId::from_parameter(dimensions), &start, |b, start| {
+            b.iter(|| {
+                let mut local_search = LocalSearchBuilder::new(
+                    AckleyMoveProposer,
+                    AckleyScoreCalculator::default(),
+                    ChaCha20Rng::seed_from_u64(42),
+                )
+                .max_iterations(100)
+                .window_size(2 * dimensions)
+                .build();
+                black_box(local_search.execute(start.clone(), u64::MAX))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn history_seen_solution(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(42);
+    let scored_solutions: Vec<ScoredSolution<Point, AckleyScore>> = (0..10_000)
+        .map(|_| AckleyScoreCalculator::default().get_scored_solution(get_random_point(10, &mut rng)))
+        .collect();
+    let mut group = c.benchmark_group("History::seen_solution and is_solution_tabu - 10,000 solutions");
+    for (name, tabu_storage) in [
+        ("solutions", TabuStorage::Solutions),
+        ("hashes", TabuStorage::Hashes),
+        (
+            "bloom filter",
+            TabuStorage::BloomFilter {
+                false_positive_rate: 0.01,
+            },
+        ),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &tabu_storage,
+            |b, tabu_storage| {
+                b.iter(|| {
+                    let mut history = History::<ChaCha20Rng, Point, AckleyScore>::new(16, 1_000, 10_000)
+                        .with_tabu_storage(tabu_storage.clone());
+                    for scored_solution in scored_solutions.iter() {
+                        history.seen_solution(scored_solution.clone());
+                    }
+                    black_box(
+                        scored_solutions
+                            .iter()
+                            .filter(|scored_solution| history.is_solution_tabu(&scored_solution.solution))
+                            .count(),
+                    )
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn history_local_search_chose_solution(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(42);
+    let scored_solutions: Vec<ScoredSolution<Point, AckleyScore>> = (0..10_000)
+        .map(|_| AckleyScoreCalculator::default().get_scored_solution(get_random_point(10, &mut rng)))
+        .collect();
+    c.bench_function("History::local_search_chose_solution - 10,000 solutions", |b| {
+        b.iter(|| {
+            let mut history = History::<ChaCha20Rng, Point, AckleyScore>::default();
+            for scored_solution in scored_solutions.iter() {
+                history.local_search_chose_solution(scored_solution.clone());
+            }
+            black_box(history.get_best())
+        });
+    });
+}
+
+criterion_group!(
+    benches,
+    local_search_execute_ackley,
+    history_seen_solution,
+    history_local_search_chose_solution
+);
+criterion_main!(benches);
@@ -12,25 +12,51 @@ use math_util::ackley::AckleyFunction;
 use ordered_float::OrderedFloat;
 use rand::{prelude::SliceRandom, Rng};
 use rand_distr::Distribution;
+use serde::{Deserialize, Serialize};
 
+use crate::assignment::Assignment;
+#[cfg(test)]
+use crate::error::{check_weights, SolverError};
 use crate::iterated_local_search::Perturbation;
 use crate::local_search::{
     InitialSolutionGenerator, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
 };
+use crate::score::{FromHardSoftScore, HardSoftScoring};
+use crate::step_size::OneFifthRule;
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct AckleySolution {
     x: Vec<OrderedFloat<f64>>,
 }
 impl Solution for AckleySolution {}
+impl Assignment for AckleySolution {
+    type Value = OrderedFloat<f64>;
+
+    fn get_variable_count(&self) -> usize {
+        self.x.len()
+    }
+
+    fn get_value(&self, variable: usize) -> OrderedFloat<f64> {
+        self.x[variable]
+    }
+
+    fn set_value(&mut self, variable: usize, value: OrderedFloat<f64>) {
+        self.x[variable] = value;
+    }
+}
 impl AckleySolution {
     #[cfg(test)]
     pub fn new(x: Vec<OrderedFloat<f64>>) -> Self {
         AckleySolution { x }
     }
+
+    #[cfg(test)]
+    pub fn get_x(&self) -> &[OrderedFloat<f64>] {
+        &self.x
+    }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct AckleyScore(OrderedFloat<f64>);
 impl Score for AckleyScore {
     /// We know the best score is 0.0, so let's say we're best at a certain epsilon.
@@ -38,7 +64,26 @@ impl Score for AckleyScore {
         abs_diff_eq!(self.0 .0, 0.0, epsilon = 1e-2)
     }
 }
+impl HardSoftScoring for AckleyScore {
+    fn get_hard_score(&self) -> f64 {
+        0.0
+    }
+
+    fn get_soft_score(&self) -> f64 {
+        self.0 .0
+    }
+}
+impl FromHardSoftScore for AckleyScore {
+    fn from_hard_soft_score(_hard_score: f64, soft_score: f64) -> Self {
+        AckleyScore(OrderedFloat(soft_score))
+    }
+}
 impl AckleyScore {
+    #[cfg(test)]
+    pub fn new(score: f64) -> Self {
+        AckleyScore(OrderedFloat(score))
+    }
+
     #[cfg(test)]
     pub fn get_score(&self) -> f64 {
         self.0 .0
@@ -70,10 +115,7 @@ impl SolutionScoreCalculator for AckleySolutionScoreCalculator {
         solution: Self::_Solution,
     ) -> ScoredSolution<Self::_Solution, Self::_Score> {
         let score = self.ackley_function.calculate(&solution.x);
-        ScoredSolution {
-            score: AckleyScore(OrderedFloat(score)),
-            solution,
-        }
+        ScoredSolution::new(solution, AckleyScore(OrderedFloat(score)))
     }
 }
 
@@ -107,6 +149,7 @@ pub struct AckleyMoveProposer {
     dimensions: usize,
     min_move_size: f64,
     max_move_size: f64,
+    one_fifth_rule: Option<OneFifthRule>,
 }
 
 impl AckleyMoveProposer {
@@ -116,8 +159,16 @@ impl AckleyMoveProposer {
             dimensions,
             min_move_size,
             max_move_size,
+            one_fifth_rule: None,
         }
     }
+
+    /// Take the move size from one_fifth_rule instead of sampling it between the min and max move size.
+    #[cfg(test)]
+    pub fn with_one_fifth_rule(mut self, one_fifth_rule: OneFifthRule) -> Self {
+        self.one_fifth_rule = Some(one_fifth_rule);
+        self
+    }
 }
 
 impl Default for AckleyMoveProposer {
@@ -126,6 +177,7 @@ impl Default for AckleyMoveProposer {
             dimensions: 2,
             min_move_size: 1e-6,
             max_move_size: 0.1,
+            one_fifth_rule: None,
         }
     }
 }
@@ -183,7 +235,10 @@ impl MoveProposer for AckleyMoveProposer {
 
         let mut dimension_schedule: Vec<usize> = (0..self.dimensions).collect();
         dimension_schedule.shuffle(rng);
-        let move_size = rng.gen_range(self.min_move_size..self.max_move_size);
+        let move_size = match self.one_fifth_rule.as_ref() {
+            Some(one_fifth_rule) => one_fifth_rule.get_step_size(),
+            None => rng.gen_range(self.min_move_size..self.max_move_size),
+        };
         Box::new(MoveIterator {
             dimension_schedule,
             current_dimension: 0,
@@ -207,8 +262,9 @@ pub struct AckleyPerturbation {
 
 impl AckleyPerturbation {
     #[cfg(test)]
-    pub fn new(strategy: Vec<(AckleyPerturbationStrategy, u64)>) -> Self {
-        Self { strategy }
+    pub fn new(strategy: Vec<(AckleyPerturbationStrategy, u64)>) -> Result<Self, SolverError> {
+        check_weights("strategy", &strategy)?;
+        Ok(Self { strategy })
     }
 }
 
@@ -237,7 +293,12 @@ impl Perturbation for AckleyPerturbation {
     ) -> Self::_Solution {
         let x_min = -32.768;
         let x_max = 32.768;
-        let current_strategy = self.strategy.choose_weighted(rng, |s| s.1).unwrap().0.clone();
+        let current_strategy = self
+            .strategy
+            .choose_weighted(rng, |s| s.1)
+            .expect("strategy weights are checked in new")
+            .0
+            .clone();
         match current_strategy {
             AckleyPerturbationStrategy::ChangeSubset => {
                 let mut new_solution = current.solution.clone();
@@ -260,3 +321,148 @@ impl Perturbation for AckleyPerturbation {
         }
     }
 }
+
+/// AckleyCrossover takes every coordinate from either parent with equal probability.
+#[cfg(test)]
+#[derive(Default)]
+pub struct AckleyCrossover {}
+
+#[cfg(test)]
+impl crate::memetic::Recombinator for AckleyCrossover {
+    type _R = rand_chacha::ChaCha20Rng;
+    type _Solution = AckleySolution;
+
+    fn recombine(
+        &self,
+        first: &AckleySolution,
+        second: &AckleySolution,
+        rng: &mut Self::_R,
+    ) -> AckleySolution {
+        AckleySolution {
+            x: first
+                .x
+                .iter()
+                .zip(second.x.iter())
+                .map(|(first, second)| if rng.gen_bool(0.5) { *first } else { *second })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub type AckleyLocalSearch = crate::local_search::LocalSearch<
+    rand_chacha::ChaCha20Rng,
+    AckleySolution,
+    AckleyScore,
+    AckleySolutionScoreCalculator,
+    AckleyMoveProposer,
+>;
+
+#[cfg(test)]
+pub type AckleyAcceptanceCriterion = crate::iterated_local_search::DefaultAcceptanceCriterion<
+    rand_chacha::ChaCha20Rng,
+    AckleySolution,
+    AckleyScore,
+>;
+
+#[cfg(test)]
+pub type AckleyIteratedLocalSearch<_AC = AckleyAcceptanceCriterion> =
+    crate::iterated_local_search::IteratedLocalSearch<
+        rand_chacha::ChaCha20Rng,
+        AckleySolution,
+        AckleyScore,
+        AckleySolutionScoreCalculator,
+        AckleyMoveProposer,
+        AckleyInitialSolutionGenerator,
+        AckleyPerturbation,
+        _AC,
+    >;
+
+/// Wire up a local search over the Ackley function with the same parameters the iterated local search tests use, so
+/// that tests for other modules don't have to repeat the setup.
+#[cfg(test)]
+pub fn new_ackley_local_search(dimensions: usize, seed: u64) -> AckleyLocalSearch {
+    use rand::SeedableRng;
+
+    crate::local_search::LocalSearch::new(
+        AckleyMoveProposer::new(dimensions, 1e-3, 0.5),
+        AckleySolutionScoreCalculator::default(),
+        100_000,
+        500,
+        16,
+        10_000,
+        10_000,
+        rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+    )
+}
+
+/// Wire up an iterated local search over the Ackley function that runs for max_iterations rounds.
+#[cfg(test)]
+pub fn new_ackley_iterated_local_search(
+    dimensions: usize,
+    seed: u64,
+    max_iterations: u64,
+) -> AckleyIteratedLocalSearch {
+    new_ackley_iterated_local_search_with_acceptance_criterion(
+        dimensions,
+        seed,
+        max_iterations,
+        AckleyAcceptanceCriterion::default(),
+    )
+}
+
+/// Like new_ackley_iterated_local_search, but with rounds of only 20 local search iterations in 10 dimensions, so that
+/// it keeps executing rounds for a while instead of finding the best possible solution, which finishes the solver,
+/// within the first few rounds.
+#[cfg(test)]
+pub fn new_slow_ackley_iterated_local_search(seed: u64, max_iterations: u64) -> AckleyIteratedLocalSearch {
+    use rand::SeedableRng;
+
+    let local_search = crate::local_search::LocalSearchBuilder::new(
+        AckleyMoveProposer::new(10, 1e-3, 0.5),
+        AckleySolutionScoreCalculator::default(),
+        rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+    )
+    .max_iterations(20)
+    .window_size(10)
+    .build();
+    crate::iterated_local_search::IteratedLocalSearchBuilder::new(
+        AckleyInitialSolutionGenerator::new(10),
+        AckleySolutionScoreCalculator::default(),
+        local_search,
+        AckleyPerturbation::default(),
+        rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+    )
+    .max_iterations(max_iterations)
+    .max_allow_no_improvement_for(5)
+    .build()
+}
+
+#[cfg(test)]
+pub fn new_ackley_iterated_local_search_with_acceptance_criterion<_AC>(
+    dimensions: usize,
+    seed: u64,
+    max_iterations: u64,
+    acceptance_criterion: _AC,
+) -> AckleyIteratedLocalSearch<_AC>
+where
+    _AC: crate::iterated_local_search::AcceptanceCriterion<
+        _R = rand_chacha::ChaCha20Rng,
+        _Solution = AckleySolution,
+        _Score = AckleyScore,
+    >,
+{
+    use rand::SeedableRng;
+
+    crate::iterated_local_search::IteratedLocalSearchBuilder::new(
+        AckleyInitialSolutionGenerator::new(dimensions),
+        AckleySolutionScoreCalculator::default(),
+        new_ackley_local_search(dimensions, seed),
+        AckleyPerturbation::default(),
+        rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+    )
+    .acceptance_criterion(acceptance_criterion)
+    .max_iterations(max_iterations)
+    .max_allow_no_improvement_for(5)
+    .build()
+}
@@ -0,0 +1,369 @@
+//! adaptive_large_neighborhood_search extends large_neighborhood_search to several destroy and repair operators, and
+//! learns which of them work for the instance at hand, see [1]. Operators are sampled proportionally to their weights,
+//! earn a score every round depending on the outcome, and at the end of every segment of rounds their weights move
+//! towards their average score in that segment.
+//!
+//! [1] Ropke, Stefan and David Pisinger. "An Adaptive Large Neighborhood Search Heuristic for the Pickup and Delivery
+//! Problem with Time Windows." Transportation Science 40.4 (2006): 455-472.
+use std::time::Instant;
+
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::iterated_local_search::{AcceptanceCriterion, DefaultAcceptanceCriterion};
+use crate::large_neighborhood_search::DestroyOperator;
+use crate::local_search::{History, Score, ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::repair::RepairOperator;
+use crate::stats::{OperatorWeight, SolverStats};
+use crate::termination::{Termination, TerminationState};
+
+/// OutcomeScores are the scores an operator pair earns for a round. Only the highest applicable score is earned. The
+/// defaults are the values of [1].
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutcomeScores {
+    /// The repaired solution is a new best solution.
+    pub new_best: f64,
+
+    /// The repaired solution is better than the current solution.
+    pub improved: f64,
+
+    /// The repaired solution is not better than the current solution, but was accepted.
+    pub accepted: f64,
+}
+
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self {
+            new_best: 33.0,
+            improved: 9.0,
+            accepted: 13.0,
+        }
+    }
+}
+
+/// AdaptiveWeights keeps the selection weights of a list of operators.
+#[derive(Clone, Debug)]
+struct AdaptiveWeights {
+    weights: Vec<f64>,
+    segment_scores: Vec<f64>,
+    segment_uses: Vec<u64>,
+    uses: Vec<u64>,
+}
+
+impl AdaptiveWeights {
+    fn new(operators: usize) -> Self {
+        Self {
+            weights: vec![1.0; operators],
+            segment_scores: vec![0.0; operators],
+            segment_uses: vec![0; operators],
+            uses: vec![0; operators],
+        }
+    }
+
+    /// Select an operator proportionally to its weight, or uniformly once no operator has a positive weight, e.g.
+    /// after a segment in which none of them earned a score.
+    fn select<_R: rand::Rng>(&mut self, rng: &mut _R) -> usize {
+        let sum = self.weights.iter().sum::<f64>();
+        let selected = match sum > 0.0 {
+            true => {
+                let mut target = rng.gen_range(0.0..sum);
+                self.weights
+                    .iter()
+                    .position(|weight| {
+                        target -= weight;
+                        target < 0.0
+                    })
+                    .unwrap_or(self.weights.len() - 1)
+            }
+            false => rng.gen_range(0..self.weights.len()),
+        };
+        self.uses[selected] += 1;
+        self.segment_uses[selected] += 1;
+        selected
+    }
+
+    fn reward(&mut self, operator: usize, score: f64) {
+        self.segment_scores[operator] += score;
+    }
+
+    /// Move the weight of every operator that was used in this segment towards its average score, by reaction_factor.
+    fn end_segment(&mut self, reaction_factor: f64) {
+        for operator in 0..self.weights.len() {
+            if self.segment_uses[operator] > 0 {
+                let average_score = self.segment_scores[operator] / self.segment_uses[operator] as f64;
+                self.weights[operator] =
+                    (1.0 - reaction_factor) * self.weights[operator] + reaction_factor * average_score;
+            }
+            self.segment_scores[operator] = 0.0;
+            self.segment_uses[operator] = 0;
+        }
+    }
+}
+
+type DestroyOperators<_R, _Solution> = Vec<Box<dyn DestroyOperator<_R = _R, _Solution = _Solution> + Send>>;
+type RepairOperators<_R, _Solution> = Vec<Box<dyn RepairOperator<_R = _R, _Solution = _Solution> + Send>>;
+
+/// AdaptiveLargeNeighborhoodSearch selects one destroy and one repair operator per round by their adaptive weights.
+/// Every segment_length rounds the weights are updated with reaction_factor, where 0 keeps the weights as they are
+/// and 1 replaces them with the average scores of the last segment, see set_segment.
+pub struct AdaptiveLargeNeighborhoodSearch<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _AC = DefaultAcceptanceCriterion<_R, _Solution, _Score>,
+> where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    solution_score_calculator: _SSC,
+    destroy_operators: DestroyOperators<_R, _Solution>,
+    repair_operators: RepairOperators<_R, _Solution>,
+    destroy_weights: AdaptiveWeights,
+    repair_weights: AdaptiveWeights,
+    outcome_scores: OutcomeScores,
+    segment_length: u64,
+    reaction_factor: f64,
+    acceptance_criterion: _AC,
+    history: History<_R, _Solution, _Score>,
+    rng: _R,
+    current: ScoredSolution<_Solution, _Score>,
+    iteration: u64,
+    rounds_without_improvement: u64,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+    stats: SolverStats<_Score>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _AC> AdaptiveLargeNeighborhoodSearch<_R, _Solution, _Score, _SSC, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    pub fn new(
+        initial_solution: _Solution,
+        solution_score_calculator: _SSC,
+        destroy_operators: DestroyOperators<_R, _Solution>,
+        repair_operators: RepairOperators<_R, _Solution>,
+        acceptance_criterion: _AC,
+        rng: _R,
+    ) -> Self {
+        assert!(
+            !destroy_operators.is_empty(),
+            "destroy_operators must not be empty"
+        );
+        assert!(!repair_operators.is_empty(), "repair_operators must not be empty");
+        let current = solution_score_calculator.get_scored_solution(initial_solution);
+        let mut history = History::default();
+        history.local_search_chose_solution(current.clone());
+        let best_solution_handle = BestSolutionHandle::new();
+        best_solution_handle.offer(&current);
+        Self {
+            solution_score_calculator,
+            destroy_weights: AdaptiveWeights::new(destroy_operators.len()),
+            repair_weights: AdaptiveWeights::new(repair_operators.len()),
+            destroy_operators,
+            repair_operators,
+            outcome_scores: OutcomeScores::default(),
+            segment_length: 100,
+            reaction_factor: 0.1,
+            acceptance_criterion,
+            history,
+            rng,
+            current,
+            iteration: 0,
+            rounds_without_improvement: 0,
+            best_solution_handle,
+            stats: SolverStats::default(),
+        }
+    }
+
+    /// Update the weights every segment_length rounds, with reaction_factor. Defaults to 100 rounds and 0.1.
+    pub fn set_segment(&mut self, segment_length: u64, reaction_factor: f64) {
+        assert!(segment_length > 0, "segment_length must be positive");
+        assert!(
+            (0.0..=1.0).contains(&reaction_factor),
+            "reaction_factor must be between 0 and 1"
+        );
+        self.segment_length = segment_length;
+        self.reaction_factor = reaction_factor;
+    }
+
+    /// Defaults to OutcomeScores::default(). Scores must not be negative, so that weights never are.
+    pub fn set_outcome_scores(&mut self, outcome_scores: OutcomeScores) {
+        assert!(
+            [
+                outcome_scores.new_best,
+                outcome_scores.improved,
+                outcome_scores.accepted
+            ]
+            .iter()
+            .all(|score| *score >= 0.0),
+            "outcome scores must not be negative"
+        );
+        self.outcome_scores = outcome_scores;
+    }
+
+    pub fn get_best_solution(&self) -> ScoredSolution<_Solution, _Score> {
+        self.history.get_best().unwrap()
+    }
+
+    /// Get a handle that always holds the best solution found so far, see BestSolutionHandle.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    /// Get the statistics of every round so far. Every round proposes one repaired solution, and operator_weights
+    /// lists the destroy operators followed by the repair operators.
+    pub fn get_stats(&self) -> SolverStats<_Score> {
+        let destroy_names = self.destroy_operators.iter().map(|operator| operator.get_name());
+        let repair_names = self.repair_operators.iter().map(|operator| operator.get_name());
+        let operator_weights = destroy_names
+            .zip(
+                self.destroy_weights
+                    .weights
+                    .iter()
+                    .zip(&self.destroy_weights.uses),
+            )
+            .chain(repair_names.zip(self.repair_weights.weights.iter().zip(&self.repair_weights.uses)))
+            .map(|(name, (weight, uses))| OperatorWeight {
+                name: name.to_string(),
+                weight: *weight,
+                uses: *uses,
+            })
+            .collect();
+        SolverStats {
+            operator_weights,
+            ..self.stats.clone()
+        }
+    }
+
+    pub fn execute_round(&mut self) {
+        self.iteration += 1;
+        let _span = tracing::debug_span!(
+            "adaptive_large_neighborhood_search_round",
+            iteration = self.iteration
+        )
+        .entered();
+        let destroy = self.destroy_weights.select(&mut self.rng);
+        let repair = self.repair_weights.select(&mut self.rng);
+        let destroyed = self.destroy_operators[destroy].destroy(&self.current.solution, &mut self.rng);
+        let repaired = self
+            .solution_score_calculator
+            .get_scored_solution(self.repair_operators[repair].repair(destroyed, &mut self.rng));
+        self.stats.proposed_moves += 1;
+
+        let is_new_best = self
+            .history
+            .get_best()
+            .is_none_or(|best| repaired.score < best.score);
+        let is_improved = repaired.score < self.current.score;
+        self.history.local_search_chose_solution(repaired.clone());
+        self.best_solution_handle.offer(&repaired);
+        let chosen = self
+            .acceptance_criterion
+            .choose(&self.current, &repaired, &self.history, &mut self.rng);
+        let is_accepted = chosen == repaired;
+
+        let score = if is_new_best {
+            self.outcome_scores.new_best
+        } else if is_improved {
+            self.outcome_scores.improved
+        } else if is_accepted {
+            self.outcome_scores.accepted
+        } else {
+            0.0
+        };
+        tracing::trace!(destroy, repair, score, "scored operators");
+        self.destroy_weights.reward(destroy, score);
+        self.repair_weights.reward(repair, score);
+        if self.iteration % self.segment_length == 0 {
+            self.destroy_weights.end_segment(self.reaction_factor);
+            self.repair_weights.end_segment(self.reaction_factor);
+        }
+
+        if is_accepted {
+            self.stats.accepted_moves += 1;
+        }
+        if is_accepted && is_improved {
+            self.stats.improving_moves += 1;
+        }
+        match is_new_best {
+            true => self.rounds_without_improvement = 0,
+            false => self.rounds_without_improvement += 1,
+        }
+        self.current = chosen;
+    }
+
+    /// Execute rounds until termination says to stop, see the termination module, or until a best possible solution
+    /// is found, and return the best solution found. At least one round is always executed.
+    pub fn execute<_T>(&mut self, termination: &_T) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let started = Instant::now();
+        let first_iteration = self.iteration;
+        loop {
+            self.execute_round();
+            let best = self.get_best_solution();
+            let state = TerminationState {
+                iteration: self.iteration - first_iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&best.score),
+                iterations_without_improvement: self.rounds_without_improvement,
+            };
+            if best.score.is_best() || termination.is_terminated(&state) {
+                return best;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::adaptive_large_neighborhood_search::AdaptiveWeights;
+
+    #[test]
+    fn weights_move_towards_segment_scores() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut weights = AdaptiveWeights::new(2);
+        for _ in 0..100 {
+            let operator = weights.select(&mut rng);
+            weights.reward(operator, if operator == 0 { 10.0 } else { 0.0 });
+        }
+        assert_eq!(100, weights.uses.iter().sum::<u64>());
+        weights.end_segment(0.5);
+        assert_eq!(vec![5.5, 0.5], weights.weights);
+
+        let selected_first = (0..1_000).filter(|_| weights.select(&mut rng) == 0).count();
+        assert!(
+            selected_first > 850,
+            "selected the first operator {} times",
+            selected_first
+        );
+    }
+
+    #[test]
+    fn segment_without_rewards_falls_back_to_uniform_selection() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut weights = AdaptiveWeights::new(2);
+        for _ in 0..10 {
+            let operator = weights.select(&mut rng);
+            weights.reward(operator, 0.0);
+        }
+        weights.end_segment(1.0);
+        assert_eq!(vec![0.0, 0.0], weights.weights);
+
+        let selected_first = (0..1_000).filter(|_| weights.select(&mut rng) == 0).count();
+        assert!(
+            (400..600).contains(&selected_first),
+            "selected the first operator {} times",
+            selected_first
+        );
+    }
+}
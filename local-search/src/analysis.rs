@@ -0,0 +1,57 @@
+//! analysis attributes the score of a solution to its individual decision variables, e.g. per day for schedules or per
+//! column for n-queens. This powers "show me the worst days" views and lets move proposers target the variables that
+//! are responsible for most of the score.
+use crate::local_search::{Score, SolutionScoreCalculator};
+
+/// ScoreContributionCalculator is a SolutionScoreCalculator that can also split the score of a solution across the
+/// decision variables of the solution. Contributions use the solution's own Score type, so a contribution that
+/// is_best() means that the variable is not involved in any violation.
+///
+/// How a violation that involves several variables is split is up to the implementation, but splitting it evenly
+/// keeps the contributions summing to the score, which makes them easy to reason about.
+pub trait ScoreContributionCalculator: SolutionScoreCalculator {
+    /// Get one contribution per decision variable, in the solution's own variable order.
+    fn get_score_contributions(&self, solution: &Self::_Solution) -> Vec<Self::_Score>;
+}
+
+/// Get the indices of the count variables with the worst contributions, worst first. Variables that are not involved
+/// in any violation are never returned, so the result can be shorter than count.
+pub fn get_worst_variables<_Score>(contributions: &[_Score], count: usize) -> Vec<usize>
+where
+    _Score: Score,
+{
+    let mut variables: Vec<usize> = (0..contributions.len())
+        .filter(|variable| !contributions[*variable].is_best())
+        .collect();
+    // Stable sort, so variables with equal contributions keep their order.
+    variables.sort_by(|left, right| contributions[*right].cmp(&contributions[*left]));
+    variables.truncate(count);
+    variables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_worst_variables;
+    use crate::local_search::Score;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Conflicts(u64);
+    impl Score for Conflicts {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    #[test]
+    fn worst_variables_are_sorted_and_skip_satisfied_variables() {
+        let contributions = vec![
+            Conflicts(1),
+            Conflicts(0),
+            Conflicts(3),
+            Conflicts(1),
+            Conflicts(0),
+        ];
+        assert_eq!(vec![2, 0], get_worst_variables(&contributions, 2));
+        assert_eq!(vec![2, 0, 3], get_worst_variables(&contributions, 10));
+    }
+}
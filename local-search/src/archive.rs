@@ -0,0 +1,216 @@
+//! archive appends every new best solution of a History to a newline-delimited JSON file, with its score and when it
+//! was found, so that earlier good solutions can be recovered after a crash or a perturbation that wrecked the current
+//! one. Unlike a checkpoint, which only keeps the latest state of the solver, the archive keeps every best solution,
+//! also across runs, and load_archive reads them back. See History::set_best_solution_archive.
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::local_search::{Score, ScoredSolution, Solution};
+
+/// ArchivedSolution is one line of the archive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedSolution<_Solution, _Score> {
+    pub solution: _Solution,
+    pub score: _Score,
+
+    /// When the solution was found, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+
+type ArchiveSolution<_Solution, _Score> =
+    Box<dyn FnMut(&ScoredSolution<_Solution, _Score>) -> std::io::Result<()> + Send>;
+
+/// BestSolutionArchive writes one ArchivedSolution per line, and flushes after every line so that a crash loses at
+/// most the line being written.
+pub struct BestSolutionArchive<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    archive_solution: ArchiveSolution<_Solution, _Score>,
+}
+
+impl<_Solution, _Score> BestSolutionArchive<_Solution, _Score>
+where
+    _Solution: Solution + Serialize,
+    _Score: Score + Serialize,
+{
+    pub fn new<_W>(mut writer: _W) -> Self
+    where
+        _W: Write + Send + 'static,
+    {
+        Self {
+            archive_solution: Box::new(move |solution| {
+                let timestamp_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64;
+                let archived = ArchivedSolution {
+                    solution: &solution.solution,
+                    score: &solution.score,
+                    timestamp_ms,
+                };
+                serde_json::to_writer(&mut writer, &archived)?;
+                writer.write_all(b"\n")?;
+                writer.flush()
+            }),
+        }
+    }
+
+    /// Open the archive file at path for appending, creating it if it doesn't exist, so that the solutions of
+    /// earlier runs are kept.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<_Solution, _Score> BestSolutionArchive<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    pub fn archive(&mut self, solution: &ScoredSolution<_Solution, _Score>) -> std::io::Result<()> {
+        (self.archive_solution)(solution)
+    }
+}
+
+/// Read back every solution of the archive file at path, oldest first.
+pub fn load_archive<_Solution, _Score, P>(
+    path: P,
+) -> std::io::Result<Vec<ArchivedSolution<_Solution, _Score>>>
+where
+    _Solution: DeserializeOwned,
+    _Score: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let mut solutions = vec![];
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        solutions.push(serde_json::from_str(&line)?);
+    }
+    Ok(solutions)
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleyPerturbation, AckleyScore, AckleySolution,
+        AckleySolutionScoreCalculator,
+    };
+    use crate::archive::{load_archive, BestSolutionArchive};
+    use crate::iterated_local_search::IteratedLocalSearchBuilder;
+    use crate::local_search::{History, LocalSearchBuilder};
+
+    #[test]
+    fn ackley_archives_every_new_best_solution() {
+        let path = std::env::temp_dir().join(format!("local-search-archive-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut archived_before = 0;
+        for seed in [1, 2] {
+            let local_search = LocalSearchBuilder::new(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            )
+            .max_iterations(20)
+            .build();
+            let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+                AckleyInitialSolutionGenerator::new(2),
+                AckleySolutionScoreCalculator::default(),
+                local_search,
+                AckleyPerturbation::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            )
+            .history(History::default().with_best_solution_archive(BestSolutionArchive::open(&path).unwrap()))
+            .max_iterations(20)
+            .build();
+            while !iterated_local_search.is_finished() {
+                iterated_local_search.execute_round().unwrap();
+            }
+
+            // The archive keeps the solutions of earlier runs, and every run only appends improvements.
+            let archived = load_archive::<AckleySolution, AckleyScore, _>(&path).unwrap();
+            let run = &archived[archived_before..];
+            assert!(!run.is_empty());
+            assert!(run.windows(2).all(|pair| pair[1].score < pair[0].score));
+            assert!(run
+                .windows(2)
+                .all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+            // Without the serde feature floats don't round trip exactly.
+            assert_abs_diff_eq!(
+                iterated_local_search.get_best_solution().score.get_score(),
+                run.last().unwrap().score.get_score(),
+                epsilon = 1e-12
+            );
+            archived_before = archived.len();
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ackley_keeps_archiving_after_resuming_from_checkpoint() {
+        let path = std::env::temp_dir().join(format!(
+            "local-search-resumed-archive-{}.jsonl",
+            std::process::id()
+        ));
+        let checkpoint_path = std::env::temp_dir().join(format!(
+            "local-search-resumed-archive-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let new_iterated_local_search = |seed| {
+            let local_search = LocalSearchBuilder::new(
+                AckleyMoveProposer::new(10, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            )
+            .max_iterations(20)
+            .window_size(10)
+            .build();
+            IteratedLocalSearchBuilder::new(
+                AckleyInitialSolutionGenerator::new(10),
+                AckleySolutionScoreCalculator::default(),
+                local_search,
+                AckleyPerturbation::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            )
+            .history(History::default().with_best_solution_archive(BestSolutionArchive::open(&path).unwrap()))
+            .max_iterations(u64::MAX)
+            .build()
+        };
+        let mut iterated_local_search = new_iterated_local_search(0);
+        iterated_local_search
+            .execute(&crate::termination::MaxIterations(5))
+            .unwrap();
+        iterated_local_search.write_checkpoint(&checkpoint_path).unwrap();
+        let checkpoint_best = iterated_local_search.get_best_solution();
+
+        let mut resumed = new_iterated_local_search(1);
+        resumed.resume_from_checkpoint(&checkpoint_path).unwrap();
+        let archived_before = load_archive::<AckleySolution, AckleyScore, _>(&path)
+            .unwrap()
+            .len();
+        resumed.execute(&crate::termination::MaxIterations(20)).unwrap();
+
+        // The restored history still archives, and only what improves on the best solution of the checkpoint.
+        let archived = load_archive::<AckleySolution, AckleyScore, _>(&path).unwrap();
+        let run = &archived[archived_before..];
+        assert!(!run.is_empty());
+        assert!(run[0].score < checkpoint_best.score);
+        assert!(run.windows(2).all(|pair| pair[1].score < pair[0].score));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&checkpoint_path).unwrap();
+    }
+}
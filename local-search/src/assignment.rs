@@ -0,0 +1,81 @@
+//! assignment describes solutions that are made of a fixed number of decision variables, each assigned one value from
+//! its domain. Knowing this structure lets the framework offer generic operators (repair, construction, propagation)
+//! that would otherwise have to be rewritten for every problem domain.
+use crate::local_search::Solution;
+
+/// Assignment is a Solution made of get_variable_count() decision variables, addressed by index. For n-queens the
+/// variables are the columns and the values the rows, for a schedule the variables are the days and the values the
+/// employees.
+pub trait Assignment: Solution {
+    type Value: Clone + Eq + std::hash::Hash + std::fmt::Debug + Send;
+
+    fn get_variable_count(&self) -> usize;
+    fn get_value(&self, variable: usize) -> Self::Value;
+    fn set_value(&mut self, variable: usize, value: Self::Value);
+}
+
+/// Domain lists the values that a decision variable may be assigned. The solution is passed in so that domains can
+/// depend on problem facts stored in the solution, e.g. the employees of a schedule.
+pub trait Domain {
+    type _Solution: Assignment;
+
+    fn get_values(
+        &self,
+        solution: &Self::_Solution,
+        variable: usize,
+    ) -> Vec<<Self::_Solution as Assignment>::Value>;
+}
+
+/// PartialAssignment is an Assignment whose variables can be left unassigned, which every Assignment with Option
+/// values is, e.g. a schedule with an Option<Employee> per day. Construction heuristics and repair operators can then
+/// build a solution a few variables at a time, instead of always starting from a complete, random one. By convention
+/// every unassigned variable counts as one hard violation, see constraints::unassigned_constraint, so that the search
+/// prefers complete solutions.
+pub trait PartialAssignment: Assignment {
+    fn is_assigned(&self, variable: usize) -> bool;
+    fn unassign(&mut self, variable: usize);
+
+    fn get_unassigned_variables(&self) -> Vec<usize> {
+        (0..self.get_variable_count())
+            .filter(|variable| !self.is_assigned(*variable))
+            .collect()
+    }
+}
+
+impl<_Solution, _T> PartialAssignment for _Solution
+where
+    _Solution: Assignment<Value = Option<_T>>,
+{
+    fn is_assigned(&self, variable: usize) -> bool {
+        self.get_value(variable).is_some()
+    }
+
+    fn unassign(&mut self, variable: usize) {
+        self.set_value(variable, None);
+    }
+}
+
+/// Changes lists the decision variables that an assignment changed, with their new values, in order of variable.
+pub type Changes<_Solution> = Vec<(usize, <_Solution as Assignment>::Value)>;
+
+/// Get the variables whose values differ between from and to, with their values in to.
+pub fn get_changes<_Solution: Assignment>(from: &_Solution, to: &_Solution) -> Changes<_Solution> {
+    assert_eq!(
+        from.get_variable_count(),
+        to.get_variable_count(),
+        "solutions must have the same variables"
+    );
+    (0..to.get_variable_count())
+        .map(|variable| (variable, to.get_value(variable)))
+        .filter(|(variable, value)| from.get_value(*variable) != *value)
+        .collect()
+}
+
+/// Get a copy of from with changes applied.
+pub fn apply_changes<_Solution: Assignment>(from: &_Solution, changes: &Changes<_Solution>) -> _Solution {
+    let mut to = from.clone();
+    for (variable, value) in changes.iter() {
+        to.set_value(*variable, value.clone());
+    }
+    to
+}
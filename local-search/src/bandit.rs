@@ -0,0 +1,225 @@
+//! bandit adaptively selects among operators, e.g. move types or perturbations, instead of using fixed weights. Each
+//! operator is an arm of a multi-armed bandit that is rewarded by the score improvements it leads to, see [1] for
+//! UCB1 and epsilon-greedy.
+//!
+//! [1] Auer, Peter, Nicolò Cesa-Bianchi and Paul Fischer. "Finite-time Analysis of the Multiarmed Bandit Problem."
+//! Machine Learning 47.2 (2002): 235-256.
+use rand::Rng;
+
+use crate::iterated_local_search::Perturbation;
+use crate::local_search::{History, ScoredSolution};
+
+/// BanditStrategy decides how OperatorBandit trades off exploring rarely used operators against exploiting the best
+/// operator so far.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BanditStrategy {
+    /// Select the operator with the highest upper confidence bound of its mean reward, where exploration scales the
+    /// confidence term. Auer et al. use an exploration of sqrt(2).
+    Ucb1 { exploration: f64 },
+
+    /// Select a uniformly random operator with probability epsilon, else the operator with the highest mean reward.
+    EpsilonGreedy { epsilon: f64 },
+}
+
+impl Default for BanditStrategy {
+    fn default() -> Self {
+        BanditStrategy::Ucb1 {
+            exploration: std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/// OperatorBandit holds a list of operators and their observed rewards. Call select to get the index of the next
+/// operator to use and reward once its outcome is known. Rewards should be in [0, 1] for UCB1 to balance well.
+#[derive(Clone, Debug)]
+pub struct OperatorBandit<_Operator> {
+    operators: Vec<_Operator>,
+    strategy: BanditStrategy,
+    uses: Vec<u64>,
+    rewards: Vec<f64>,
+}
+
+impl<_Operator> OperatorBandit<_Operator> {
+    pub fn new(operators: Vec<_Operator>, strategy: BanditStrategy) -> Self {
+        assert!(!operators.is_empty(), "operators must not be empty");
+        match strategy {
+            BanditStrategy::Ucb1 { exploration } => {
+                assert!(exploration >= 0.0, "exploration must not be negative")
+            }
+            BanditStrategy::EpsilonGreedy { epsilon } => {
+                assert!((0.0..=1.0).contains(&epsilon), "epsilon must be between 0 and 1")
+            }
+        }
+        let operator_count = operators.len();
+        Self {
+            operators,
+            strategy,
+            uses: vec![0; operator_count],
+            rewards: vec![0.0; operator_count],
+        }
+    }
+
+    /// Select the next operator. Every operator is selected once before the strategy applies.
+    pub fn select<_R: Rng>(&mut self, rng: &mut _R) -> usize {
+        let selected = match self.uses.iter().position(|uses| *uses == 0) {
+            Some(unused) => unused,
+            None => match self.strategy {
+                BanditStrategy::Ucb1 { exploration } => {
+                    let total_uses = self.uses.iter().sum::<u64>() as f64;
+                    self.argmax(|operator| {
+                        exploration * (total_uses.ln() / self.uses[operator] as f64).sqrt()
+                    })
+                }
+                BanditStrategy::EpsilonGreedy { epsilon } if rng.gen_bool(epsilon) => {
+                    rng.gen_range(0..self.operators.len())
+                }
+                BanditStrategy::EpsilonGreedy { .. } => self.argmax(|_| 0.0),
+            },
+        };
+        self.uses[selected] += 1;
+        selected
+    }
+
+    pub fn reward(&mut self, operator: usize, reward: f64) {
+        self.rewards[operator] += reward;
+    }
+
+    pub fn get_operator(&self, operator: usize) -> &_Operator {
+        &self.operators[operator]
+    }
+
+    pub fn get_operator_mut(&mut self, operator: usize) -> &mut _Operator {
+        &mut self.operators[operator]
+    }
+
+    pub fn get_uses(&self) -> &[u64] {
+        &self.uses
+    }
+
+    /// Get the mean reward of every operator, zero for operators that weren't used yet.
+    pub fn get_mean_rewards(&self) -> Vec<f64> {
+        (0..self.operators.len())
+            .map(|operator| self.get_mean_reward(operator))
+            .collect()
+    }
+
+    fn get_mean_reward(&self, operator: usize) -> f64 {
+        match self.uses[operator] {
+            0 => 0.0,
+            uses => self.rewards[operator] / uses as f64,
+        }
+    }
+
+    /// Get the operator with the highest mean reward plus bonus, the first one on ties.
+    fn argmax(&self, bonus: impl Fn(usize) -> f64) -> usize {
+        (0..self.operators.len())
+            .map(|operator| (operator, self.get_mean_reward(operator) + bonus(operator)))
+            .fold((0, f64::NEG_INFINITY), |best, candidate| {
+                match candidate.1 > best.1 {
+                    true => candidate,
+                    false => best,
+                }
+            })
+            .0
+    }
+}
+
+/// BanditPerturbation selects one of several perturbations with an OperatorBandit. A perturbation earns a reward of 1
+/// if the best solution of the History improved by the time the next perturbation is proposed, i.e. if the local search
+/// that started from its solution found a new best solution, and 0 otherwise.
+pub struct BanditPerturbation<_P>
+where
+    _P: Perturbation,
+{
+    bandit: OperatorBandit<_P>,
+    last_selected: Option<(usize, Option<_P::_Score>)>,
+}
+
+impl<_P> BanditPerturbation<_P>
+where
+    _P: Perturbation,
+{
+    pub fn new(perturbations: Vec<_P>, strategy: BanditStrategy) -> Self {
+        Self {
+            bandit: OperatorBandit::new(perturbations, strategy),
+            last_selected: None,
+        }
+    }
+
+    pub fn get_bandit(&self) -> &OperatorBandit<_P> {
+        &self.bandit
+    }
+}
+
+impl<_P> Perturbation for BanditPerturbation<_P>
+where
+    _P: Perturbation,
+{
+    type _R = _P::_R;
+    type _Solution = _P::_Solution;
+    type _Score = _P::_Score;
+    type _SSC = _P::_SSC;
+
+    fn propose_new_starting_solution(
+        &mut self,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        history: &History<Self::_R, Self::_Solution, Self::_Score>,
+        rng: &mut Self::_R,
+    ) -> Self::_Solution {
+        let best_score = history.get_best().map(|best| best.score);
+        if let Some((operator, best_score_when_selected)) = self.last_selected.take() {
+            let improved = match (&best_score, &best_score_when_selected) {
+                (Some(best_score), Some(best_score_when_selected)) => best_score < best_score_when_selected,
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            self.bandit.reward(operator, if improved { 1.0 } else { 0.0 });
+        }
+        let operator = self.bandit.select(rng);
+        tracing::trace!(operator, "bandit selected perturbation");
+        self.last_selected = Some((operator, best_score));
+        self.bandit
+            .get_operator_mut(operator)
+            .propose_new_starting_solution(current, history, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::bandit::{BanditStrategy, OperatorBandit};
+
+    fn select_rewarding_first(strategy: BanditStrategy) -> OperatorBandit<&'static str> {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut bandit = OperatorBandit::new(vec!["good", "bad", "worse"], strategy);
+        for _ in 0..1_000 {
+            let operator = bandit.select(&mut rng);
+            let reward = match operator {
+                0 => 0.8,
+                1 => 0.3,
+                _ => 0.0,
+            };
+            bandit.reward(operator, reward);
+        }
+        bandit
+    }
+
+    #[test]
+    fn ucb1_mostly_selects_the_best_operator() {
+        let bandit = select_rewarding_first(BanditStrategy::default());
+        println!("uses: {:?}", bandit.get_uses());
+        assert_eq!(1_000, bandit.get_uses().iter().sum::<u64>());
+        assert!(bandit.get_uses()[0] > 900);
+        assert!(bandit.get_uses().iter().all(|uses| *uses > 0));
+        assert!((bandit.get_mean_rewards()[0] - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn epsilon_greedy_mostly_selects_the_best_operator() {
+        let bandit = select_rewarding_first(BanditStrategy::EpsilonGreedy { epsilon: 0.1 });
+        println!("uses: {:?}", bandit.get_uses());
+        assert!(bandit.get_uses()[0] > 900);
+        assert!(bandit.get_uses()[2] > 10);
+    }
+}
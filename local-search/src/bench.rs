@@ -0,0 +1,233 @@
+//! bench runs one solver configuration over many seeds and summarizes how it did, so that parameter settings can be
+//! compared on the distribution of their results instead of by eyeballing single runs. Runs execute one after another,
+//! so that their times are comparable.
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
+use crate::local_search::{InitialSolutionGenerator, MoveProposer, Solution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+use crate::termination::{ScoreThreshold, Termination};
+
+/// Summary statistics of a non-empty sample.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+
+    /// The sample standard deviation, or 0 for a single sample.
+    pub standard_deviation: f64,
+
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    /// Summarize samples, or None if there are none.
+    pub fn new(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let median = match count % 2 {
+            0 => (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0,
+            _ => sorted[count / 2],
+        };
+        let standard_deviation = match count {
+            1 => 0.0,
+            _ => {
+                (sorted.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (count - 1) as f64).sqrt()
+            }
+        };
+        Some(Self {
+            count,
+            mean,
+            median,
+            standard_deviation,
+            min: sorted[0],
+            max: sorted[count - 1],
+        })
+    }
+}
+
+/// RunResult is how the solver did for one seed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RunResult<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub seed: u64,
+    pub best_score: _Score,
+    pub elapsed: Duration,
+
+    /// Rounds the solver executed, see IterationInfo::current.
+    pub iterations: u64,
+
+    /// When the best solution first reached the target of the experiment, or None if it never did or there was no
+    /// target.
+    pub time_to_target: Option<Duration>,
+}
+
+/// ExperimentReport summarizes the runs of run_experiment. Scores are summarized by their energy, see
+/// HardSoftScoring::get_energy, and times in seconds.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExperimentReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub runs: Vec<RunResult<_Score>>,
+    pub final_energy: Summary,
+    pub elapsed_seconds: Summary,
+    pub iterations: Summary,
+
+    /// Only over the runs that reached the target, or None if none did.
+    pub time_to_target_seconds: Option<Summary>,
+}
+
+impl<_Score> ExperimentReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// Fraction of runs that reached the target.
+    pub fn get_target_reached_rate(&self) -> f64 {
+        let reached = self
+            .runs
+            .iter()
+            .filter(|run| run.time_to_target.is_some())
+            .count();
+        reached as f64 / self.runs.len() as f64
+    }
+}
+
+/// Run the solver that create_solver creates for every seed until termination says to stop, and summarize the
+/// results. With a target, also measure how long every run took to first reach it. Fails if there are no seeds, and
+/// stops at the first run that fails.
+pub fn run_experiment<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC, _T, F>(
+    seeds: &[u64],
+    termination: &_T,
+    target: Option<&ScoreThreshold<_Score>>,
+    mut create_solver: F,
+) -> Result<ExperimentReport<_Score>, SolverError>
+where
+    _R: rand::Rng,
+    _Score: HardSoftScoring,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    _T: Termination<_Score>,
+    F: FnMut(u64) -> IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+{
+    let no_seeds = || SolverError::InvalidConfiguration("seeds must not be empty".to_string());
+    if seeds.is_empty() {
+        return Err(no_seeds());
+    }
+    let runs: Vec<RunResult<_Score>> = seeds
+        .iter()
+        .map(|seed| {
+            let _span = tracing::info_span!("experiment_run", seed).entered();
+            let mut solver = create_solver(*seed);
+            let started = Instant::now();
+            let (best, stats) = solver.execute_with_stats(termination)?;
+            let elapsed = started.elapsed();
+            let time_to_target = target.and_then(|target| {
+                stats
+                    .best_score_timeline
+                    .iter()
+                    .find(|sample| target.is_reached(&sample.best_score))
+                    .map(|sample| sample.elapsed)
+            });
+            Ok(RunResult {
+                seed: *seed,
+                best_score: best.score,
+                elapsed,
+                iterations: solver.get_iteration_info().iteration,
+                time_to_target,
+            })
+        })
+        .collect::<Result<_, SolverError>>()?;
+    let final_energies: Vec<f64> = runs.iter().map(|run| run.best_score.get_energy()).collect();
+    let elapsed_seconds: Vec<f64> = runs.iter().map(|run| run.elapsed.as_secs_f64()).collect();
+    let iterations: Vec<f64> = runs.iter().map(|run| run.iterations as f64).collect();
+    let time_to_target_seconds: Vec<f64> = runs
+        .iter()
+        .filter_map(|run| {
+            run.time_to_target
+                .map(|time_to_target| time_to_target.as_secs_f64())
+        })
+        .collect();
+    Ok(ExperimentReport {
+        final_energy: Summary::new(&final_energies).ok_or_else(no_seeds)?,
+        elapsed_seconds: Summary::new(&elapsed_seconds).ok_or_else(no_seeds)?,
+        iterations: Summary::new(&iterations).ok_or_else(no_seeds)?,
+        time_to_target_seconds: Summary::new(&time_to_target_seconds),
+        runs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::Summary;
+
+    #[test]
+    fn summary() {
+        assert_eq!(None, Summary::new(&[]));
+        let summary = Summary::new(&[4.0, 1.0, 3.0, 2.0]).unwrap();
+        assert_eq!(4, summary.count);
+        assert_abs_diff_eq!(2.5, summary.mean);
+        assert_abs_diff_eq!(2.5, summary.median);
+        assert_abs_diff_eq!(1.290_994, summary.standard_deviation, epsilon = 1e-6);
+        assert_abs_diff_eq!(1.0, summary.min);
+        assert_abs_diff_eq!(4.0, summary.max);
+        assert_abs_diff_eq!(0.0, Summary::new(&[7.0]).unwrap().standard_deviation);
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use crate::ackley::{new_slow_ackley_iterated_local_search, AckleyScore};
+    use crate::bench::run_experiment;
+    use crate::error::SolverError;
+    use crate::score::HardSoftScoring;
+    use crate::termination::{MaxIterations, ScoreThreshold};
+
+    #[test]
+    fn ackley_experiment() {
+        let target = ScoreThreshold::new(|score: &AckleyScore| score.get_soft_score() < 20.0);
+        let report = run_experiment(&[0, 1, 2], &MaxIterations(3), Some(&target), |seed| {
+            new_slow_ackley_iterated_local_search(seed, u64::MAX)
+        })
+        .unwrap();
+        assert_eq!(
+            vec![0, 1, 2],
+            report.runs.iter().map(|run| run.seed).collect::<Vec<u64>>()
+        );
+        assert_eq!(3, report.final_energy.count);
+        assert!(report.final_energy.min <= report.final_energy.median);
+        assert!(report.final_energy.median <= report.final_energy.max);
+        assert_abs_diff_eq!(3.0, report.iterations.max);
+        assert_eq!(1.0, report.get_target_reached_rate());
+        assert_eq!(3, report.time_to_target_seconds.unwrap().count);
+    }
+
+    #[test]
+    fn experiment_needs_seeds() {
+        let report = run_experiment(&[], &MaxIterations(3), None, |seed| {
+            new_slow_ackley_iterated_local_search(seed, u64::MAX)
+        });
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "seeds must not be empty".to_string()
+            )),
+            report.err()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! best_solution_handle lets other threads (a UI, an HTTP handler) read the best solution a solver has found so far
+//! without stopping the solve and without owning the solver.
+use std::sync::{Arc, Mutex};
+
+use crate::local_search::{Score, ScoredSolution, Solution};
+
+/// BestSolutionHandle is a cheap to clone, thread-safe view of the best solution found so far. The solver publishes to
+/// it whenever its best solution improves, and every clone of the handle sees the update.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Default(bound = ""))]
+pub struct BestSolutionHandle<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    best: Arc<Mutex<Option<ScoredSolution<_Solution, _Score>>>>,
+}
+
+impl<_Solution, _Score> BestSolutionHandle<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    pub fn new() -> Self {
+        Self {
+            best: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Offer a candidate to the handle. The candidate replaces the current best if it is strictly better, using the
+    /// same ordering as History so that the handle agrees with the solver's own notion of best. Returns whether the
+    /// candidate became the new best.
+    pub fn offer(&self, candidate: &ScoredSolution<_Solution, _Score>) -> bool {
+        let mut best = self.best.lock().unwrap();
+        match &*best {
+            Some(existing) if existing <= candidate => false,
+            _ => {
+                *best = Some(candidate.clone());
+                true
+            }
+        }
+    }
+
+    /// Get a copy of the best solution found so far, or None if the solver has not finished a round yet.
+    pub fn get(&self) -> Option<ScoredSolution<_Solution, _Score>> {
+        self.best.lock().unwrap().clone()
+    }
+
+    /// Get only the best score found so far, which avoids cloning the solution when that's all a caller needs.
+    pub fn get_score(&self) -> Option<_Score> {
+        self.best.lock().unwrap().as_ref().map(|best| best.score.clone())
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use crate::ackley::new_ackley_iterated_local_search;
+
+    #[test]
+    fn handle_tracks_best_solution_from_another_thread() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 42, 50);
+        let handle = iterated_local_search.get_best_solution_handle();
+        assert!(
+            handle.get().is_none(),
+            "expected no best solution before the first round"
+        );
+
+        let reader_handle = handle.clone();
+        let reader = std::thread::spawn(move || {
+            let mut scores = vec![];
+            while scores.len() < 1_000 {
+                if let Some(score) = reader_handle.get_score() {
+                    scores.push(score);
+                }
+                std::thread::yield_now();
+            }
+            scores
+        });
+
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+            assert_eq!(Some(iterated_local_search.get_best_solution()), handle.get());
+        }
+
+        let scores = reader.join().unwrap();
+        assert!(
+            scores.windows(2).all(|window| window[1] <= window[0]),
+            "expected the best score seen through the handle to never get worse"
+        );
+    }
+}
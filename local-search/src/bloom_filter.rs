@@ -0,0 +1,93 @@
+//! bloom_filter is a set that only remembers a fixed number of bits, see [1]. It never forgets an item that was
+//! inserted, but may claim to contain an item that was not, with a false positive rate that depends on how many items
+//! it was sized for. History can use it to remember seen solutions in bounded memory, see TabuStorage::BloomFilter.
+//!
+//! [1] Bloom, Burton H. "Space/Time Trade-offs in Hash Coding with Allowable Errors." Communications of the ACM 13.7
+//! (1970): 422-426.
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// BloomFilter sized for expected_items items at false_positive_rate. Inserting more items than expected raises the
+/// false positive rate.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u64,
+}
+
+impl BloomFilter {
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(expected_items > 0, "expected_items must be positive");
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false_positive_rate must be between 0 and 1"
+        );
+        let ln_2 = std::f64::consts::LN_2;
+        let bit_count = (-(expected_items as f64) * false_positive_rate.ln() / (ln_2 * ln_2))
+            .ceil()
+            .max(64.0) as u64;
+        let hash_count = ((bit_count as f64 / expected_items as f64) * ln_2)
+            .round()
+            .max(1.0) as u64;
+        Self {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hash_count,
+        }
+    }
+
+    pub fn insert<_T: Hash + ?Sized>(&mut self, item: &_T) {
+        for bit in self.get_bits(item) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    pub fn contains<_T: Hash + ?Sized>(&self, item: &_T) -> bool {
+        self.get_bits(item)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// Get the bits of item from one 64-bit hash, stretched into hash_count pseudo-random numbers with SplitMix64.
+    fn get_bits<_T: Hash + ?Sized>(&self, item: &_T) -> impl Iterator<Item = u64> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let mut state = hasher.finish();
+        let bit_count = self.bit_count;
+        (0..self.hash_count).map(move |_| {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut bits = state;
+            bits = (bits ^ (bits >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            bits = (bits ^ (bits >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            (bits ^ (bits >> 31)) % bit_count
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bloom_filter::BloomFilter;
+
+    #[test]
+    fn contains_inserted_items_and_few_others() {
+        let mut bloom_filter = BloomFilter::new(1_000, 0.01);
+        for item in 0..1_000 {
+            bloom_filter.insert(&item);
+        }
+        assert!((0..1_000).all(|item| bloom_filter.contains(&item)));
+        let false_positives = (1_000..11_000).filter(|item| bloom_filter.contains(item)).count();
+        println!("false positives: {}", false_positives);
+        assert!(false_positives < 200);
+
+        bloom_filter.clear();
+        assert!(!bloom_filter.contains(&0));
+    }
+}
@@ -0,0 +1,109 @@
+//! branch_and_bound is an exact alternative to local search for instances that are small enough to enumerate. It
+//! returns provably optimal solutions, which also makes it an oracle for checking how good the heuristics are.
+use crate::assignment::{Assignment, Domain};
+use crate::local_search::{Score, ScoredSolution, Solution, SolutionScoreCalculator};
+
+/// BoundedScoreCalculator can bound the score of every solution that shares the values of the first assigned
+/// variables with a partial solution. The values of the remaining variables are arbitrary and must be ignored. The
+/// bound must never be worse than the score of any such solution, otherwise branch and bound is no longer exact.
+pub trait BoundedScoreCalculator: SolutionScoreCalculator
+where
+    Self::_Solution: Assignment,
+{
+    fn get_lower_bound(&self, partial: &Self::_Solution, assigned: usize) -> Self::_Score;
+}
+
+/// ExactSolver solves instances to optimality. IteratedLocalSearch hands an instance over to an ExactSolver if
+/// is_small_enough says so, see IteratedLocalSearch::set_exact_solver.
+pub trait ExactSolver {
+    type _Solution: Solution;
+    type _Score: Score;
+
+    fn is_small_enough(&self, solution: &Self::_Solution) -> bool;
+
+    /// Find the best solution that has the same shape (and problem facts) as template.
+    fn solve(&self, template: Self::_Solution) -> ScoredSolution<Self::_Solution, Self::_Score>;
+}
+
+/// BranchAndBound assigns variables in order, depth first, trying every value of the domain, and prunes a branch as
+/// soon as its lower bound can't beat the best solution found so far. Instances with at most max_variables variables
+/// and at most max_values values per variable are considered small enough.
+pub struct BranchAndBound<_SSC, _D>
+where
+    _SSC: BoundedScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    solution_score_calculator: _SSC,
+    domain: _D,
+    max_variables: usize,
+    max_values: usize,
+}
+
+impl<_SSC, _D> BranchAndBound<_SSC, _D>
+where
+    _SSC: BoundedScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    pub fn new(solution_score_calculator: _SSC, domain: _D, max_variables: usize, max_values: usize) -> Self {
+        Self {
+            solution_score_calculator,
+            domain,
+            max_variables,
+            max_values,
+        }
+    }
+
+    fn search(
+        &self,
+        partial: &mut _SSC::_Solution,
+        assigned: usize,
+        best: &mut Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>>,
+    ) {
+        if let Some(best) = best.as_ref() {
+            if best.score.is_best()
+                || self.solution_score_calculator.get_lower_bound(partial, assigned) >= best.score
+            {
+                return;
+            }
+        }
+        if assigned == partial.get_variable_count() {
+            let candidate = self
+                .solution_score_calculator
+                .get_scored_solution(partial.clone());
+            if best.as_ref().is_none_or(|best| candidate < *best) {
+                *best = Some(candidate);
+            }
+            return;
+        }
+        for value in self.domain.get_values(partial, assigned) {
+            partial.set_value(assigned, value);
+            self.search(partial, assigned + 1, best);
+        }
+    }
+}
+
+impl<_SSC, _D> ExactSolver for BranchAndBound<_SSC, _D>
+where
+    _SSC: BoundedScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    type _Solution = _SSC::_Solution;
+    type _Score = _SSC::_Score;
+
+    fn is_small_enough(&self, solution: &Self::_Solution) -> bool {
+        solution.get_variable_count() <= self.max_variables
+            && (0..solution.get_variable_count())
+                .all(|variable| self.domain.get_values(solution, variable).len() <= self.max_values)
+    }
+
+    fn solve(&self, template: Self::_Solution) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let mut partial = template.clone();
+        let mut best = None;
+        self.search(&mut partial, 0, &mut best);
+        // Only empty domains leave nothing to enumerate, in which case the template is all there is.
+        best.unwrap_or_else(|| self.solution_score_calculator.get_scored_solution(template))
+    }
+}
@@ -0,0 +1,163 @@
+//! cache wraps a SolutionScoreCalculator so that solutions it has scored recently are not scored again. Perturbations
+//! that sometimes do nothing, and local searches that revisit their neighbors, resubmit identical solutions often, and
+//! scoring those from scratch every time is wasted work for expensive calculators.
+use std::sync::Mutex;
+
+use hashlink::LruCache;
+
+use crate::constraints::{ConstraintId, ConstraintViolation};
+use crate::local_search::{get_hash, ScoredSolution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+
+/// CachedScoreCalculator remembers the scores and constraint violations of the capacity most recently scored solutions,
+/// keyed by the hash of the solution. Like TabuStorage::Hashes, two different solutions with the same 64-bit hash
+/// share a score, which is rare enough not to matter for the search. The wrapped calculator must be deterministic, else
+/// the cache changes results.
+pub struct CachedScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+{
+    solution_score_calculator: _SSC,
+    cache: Mutex<ScoreCache<_SSC::_Score>>,
+}
+
+/// The score and constraint violations of a cached solution, see ScoredSolution.
+type CachedScore<_Score> = (_Score, Option<Vec<(ConstraintId, u64)>>);
+
+struct ScoreCache<_Score> {
+    scores: LruCache<u64, CachedScore<_Score>>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<_SSC> CachedScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+{
+    pub fn new(solution_score_calculator: _SSC, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            solution_score_calculator,
+            cache: Mutex::new(ScoreCache {
+                scores: LruCache::new(capacity),
+                hits: 0,
+                misses: 0,
+            }),
+        }
+    }
+
+    pub fn get_solution_score_calculator(&self) -> &_SSC {
+        &self.solution_score_calculator
+    }
+
+    pub fn into_inner(self) -> _SSC {
+        self.solution_score_calculator
+    }
+
+    /// Get how many solutions were scored from the cache and how many by the wrapped calculator.
+    pub fn get_hits_and_misses(&self) -> (u64, u64) {
+        let cache = self.cache.lock().unwrap();
+        (cache.hits, cache.misses)
+    }
+}
+
+impl<_SSC> SolutionScoreCalculator for CachedScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+{
+    type _Solution = _SSC::_Solution;
+    type _Score = _SSC::_Score;
+
+    fn get_scored_solution(
+        &self,
+        solution: Self::_Solution,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let hash = get_hash(&solution);
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some((score, constraint_violations)) = cache.scores.get(&hash).cloned() {
+                cache.hits += 1;
+                return ScoredSolution {
+                    solution,
+                    score,
+                    constraint_violations,
+                };
+            }
+            cache.misses += 1;
+        }
+        let scored_solution = self.solution_score_calculator.get_scored_solution(solution);
+        self.cache.lock().unwrap().scores.insert(
+            hash,
+            (
+                scored_solution.score.clone(),
+                scored_solution.constraint_violations.clone(),
+            ),
+        );
+        scored_solution
+    }
+
+    /// Verifies with the wrapped calculator, which may list its violated constraints one by one.
+    fn verify(&self, solution: &Self::_Solution) -> Result<(), Vec<ConstraintViolation>>
+    where
+        Self::_Score: HardSoftScoring,
+    {
+        self.solution_score_calculator.verify(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::CachedScoreCalculator;
+    use crate::fixtures::{assert_verify_lists_no_ones, new_no_ones_score_calculator, Bits};
+    use crate::local_search::SolutionScoreCalculator;
+
+    #[test]
+    fn hits_keep_the_constraint_violations() {
+        let mut score_calculator = new_no_ones_score_calculator();
+        score_calculator.set_count_violations(true);
+        let cached_score_calculator = CachedScoreCalculator::new(score_calculator, 2);
+        let miss = cached_score_calculator.get_scored_solution(Bits(vec![1, 0, 1]));
+        let hit = cached_score_calculator.get_scored_solution(Bits(vec![1, 0, 1]));
+        assert_eq!((1, 1), cached_score_calculator.get_hits_and_misses());
+        assert_eq!(Some(vec![("no ones".to_string(), 1)]), miss.constraint_violations);
+        assert_eq!(miss.constraint_violations, hit.constraint_violations);
+    }
+
+    #[test]
+    fn verify_lists_the_violations_of_the_wrapped_calculator() {
+        assert_verify_lists_no_ones(&CachedScoreCalculator::new(new_no_ones_score_calculator(), 2));
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use ordered_float::OrderedFloat;
+
+    use crate::ackley::{AckleySolution, AckleySolutionScoreCalculator};
+    use crate::cache::CachedScoreCalculator;
+    use crate::local_search::SolutionScoreCalculator;
+
+    #[test]
+    fn rescores_only_evicted_solutions() {
+        let cached_score_calculator = CachedScoreCalculator::new(AckleySolutionScoreCalculator::default(), 2);
+        let solutions: Vec<AckleySolution> = (0..3)
+            .map(|x| AckleySolution::new(vec![OrderedFloat(x as f64), OrderedFloat(1.0)]))
+            .collect();
+        let first = cached_score_calculator.get_scored_solution(solutions[0].clone());
+        assert_eq!(
+            AckleySolutionScoreCalculator::default().get_scored_solution(solutions[0].clone()),
+            first
+        );
+        assert_eq!(
+            first,
+            cached_score_calculator.get_scored_solution(solutions[0].clone())
+        );
+        assert_eq!((1, 1), cached_score_calculator.get_hits_and_misses());
+
+        cached_score_calculator.get_scored_solution(solutions[1].clone());
+        cached_score_calculator.get_scored_solution(solutions[2].clone());
+        cached_score_calculator.get_scored_solution(solutions[2].clone());
+        cached_score_calculator.get_scored_solution(solutions[0].clone());
+        assert_eq!((2, 4), cached_score_calculator.get_hits_and_misses());
+    }
+}
@@ -0,0 +1,99 @@
+//! checkpoint decides when IteratedLocalSearch writes its state to disk, so that an hours-long solve that is stopped,
+//! e.g. on a spot instance that is reclaimed, can resume where it was instead of starting over. Needs the serde
+//! feature, see IteratedLocalSearch::set_checkpoint_policy and IteratedLocalSearch::resume_from_checkpoint.
+use std::num::NonZeroU64;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// CheckpointPolicy says how often to checkpoint and where to. A checkpoint is due after every_iterations rounds or
+/// after every_duration, whichever comes first, counted from the previous checkpoint.
+#[derive(Clone, Debug)]
+pub struct CheckpointPolicy {
+    path_template: String,
+    every_iterations: Option<NonZeroU64>,
+    every_duration: Option<Duration>,
+    last_iteration: u64,
+    last_checkpoint: Option<Instant>,
+}
+
+impl CheckpointPolicy {
+    /// Write checkpoints to path_template, where {iteration} is replaced by the round the checkpoint was taken after,
+    /// e.g. "checkpoints/round-{iteration}.json". Without {iteration} every checkpoint overwrites the previous one. No
+    /// checkpoint is due until every_iterations or every_duration is set.
+    pub fn new(path_template: impl Into<String>) -> Self {
+        Self {
+            path_template: path_template.into(),
+            every_iterations: None,
+            every_duration: None,
+            last_iteration: 0,
+            last_checkpoint: None,
+        }
+    }
+
+    /// Checkpoint every this many rounds.
+    pub fn every_iterations(mut self, every_iterations: NonZeroU64) -> Self {
+        self.every_iterations = Some(every_iterations);
+        self
+    }
+
+    /// Checkpoint once this much time passed since the previous checkpoint, checked at the end of every round.
+    pub fn every_duration(mut self, every_duration: Duration) -> Self {
+        self.every_duration = Some(every_duration);
+        self
+    }
+
+    /// Is a checkpoint due after round iteration, at now. Remembers that it is taken if it is due.
+    pub fn is_due(&mut self, iteration: u64, now: Instant) -> bool {
+        let last_checkpoint = *self.last_checkpoint.get_or_insert(now);
+        let is_due = self
+            .every_iterations
+            .is_some_and(|every_iterations| iteration >= self.last_iteration + every_iterations.get())
+            || self
+                .every_duration
+                .is_some_and(|every_duration| now >= last_checkpoint + every_duration);
+        if is_due {
+            self.last_iteration = iteration;
+            self.last_checkpoint = Some(now);
+        }
+        is_due
+    }
+
+    /// Get the round the previous checkpoint was taken after, or 0 before the first checkpoint.
+    pub fn get_last_iteration(&self) -> u64 {
+        self.last_iteration
+    }
+
+    /// Count every_iterations from round last_iteration, e.g. the round of the checkpoint a solve resumes from.
+    pub fn set_last_iteration(&mut self, last_iteration: u64) {
+        self.last_iteration = last_iteration;
+    }
+
+    /// Get the path of the checkpoint taken after round iteration.
+    pub fn get_path(&self, iteration: u64) -> PathBuf {
+        PathBuf::from(self.path_template.replace("{iteration}", &iteration.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    use crate::checkpoint::CheckpointPolicy;
+
+    #[test]
+    fn due_every_iterations_or_duration() {
+        let started = Instant::now();
+        let mut checkpoint_policy = CheckpointPolicy::new("round-{iteration}.json")
+            .every_iterations(NonZeroU64::new(3).unwrap())
+            .every_duration(Duration::from_secs(60));
+        let due: Vec<u64> = (1..=7)
+            .filter(|iteration| checkpoint_policy.is_due(*iteration, started))
+            .collect();
+        assert_eq!(vec![3, 6], due);
+        assert!(checkpoint_policy.is_due(7, started + Duration::from_secs(60)));
+        assert!(!checkpoint_policy.is_due(8, started + Duration::from_secs(61)));
+        assert_eq!(PathBuf::from("round-7.json"), checkpoint_policy.get_path(7));
+    }
+}
@@ -0,0 +1,275 @@
+//! compare runs a handful of solver configurations side by side on the same problem and over the same seeds, and
+//! tabulates how they did: the energy of their best solutions, how long they took to first find a feasible solution,
+//! i.e. one with a hard score of zero, and how many rounds they executed. Use it to decide between candidate settings,
+//! e.g. the config file before and after a change, and the tuning module to search a space of settings instead.
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::bench::{run_experiment, ExperimentReport};
+use crate::config::SolverConfig;
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
+use crate::local_search::{InitialSolutionGenerator, MoveProposer, Solution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+use crate::termination::{ScoreThreshold, Termination};
+
+/// Contender is how one named configuration did over the seeds of the comparison. The target of its report is the
+/// first feasible solution.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Contender<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub name: String,
+    pub config: SolverConfig,
+    pub report: ExperimentReport<_Score>,
+}
+
+/// ComparisonReport is every contender of a comparison, in the order of the configurations. Display formats it as a
+/// table with a row per contender, where energies are those of the best solutions, the time to feasible is the median
+/// over the runs that found a feasible solution, and iterations and elapsed times are means.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ComparisonReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub contenders: Vec<Contender<_Score>>,
+}
+
+impl<_Score> ComparisonReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// Get the contender with the lowest mean energy of its best solutions, or None if there are no contenders. Ties go
+    /// to the earliest configuration.
+    pub fn get_best_contender(&self) -> Option<&Contender<_Score>> {
+        self.contenders.iter().min_by(|left, right| {
+            left.report
+                .final_energy
+                .mean
+                .total_cmp(&right.report.final_energy.mean)
+        })
+    }
+}
+
+impl<_Score> fmt::Display for ComparisonReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            "configuration",
+            "mean energy",
+            "median energy",
+            "min energy",
+            "feasible",
+            "time to feasible",
+            "iterations",
+            "elapsed",
+        ]
+        .map(String::from);
+        let rows: Vec<[String; 8]> = self
+            .contenders
+            .iter()
+            .map(|contender| {
+                let report = &contender.report;
+                let feasible_runs = report
+                    .runs
+                    .iter()
+                    .filter(|run| run.time_to_target.is_some())
+                    .count();
+                [
+                    contender.name.clone(),
+                    format!("{:.3}", report.final_energy.mean),
+                    format!("{:.3}", report.final_energy.median),
+                    format!("{:.3}", report.final_energy.min),
+                    format!("{}/{}", feasible_runs, report.runs.len()),
+                    report
+                        .time_to_target_seconds
+                        .as_ref()
+                        .map_or("-".to_string(), |seconds| format!("{:.3}s", seconds.median)),
+                    format!("{:.1}", report.iterations.mean),
+                    format!("{:.3}s", report.elapsed_seconds.mean),
+                ]
+            })
+            .collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].len())
+                    .chain([header[column].len()])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+        for row in [header].iter().chain(rows.iter()) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, width))| match column {
+                    0 => format!("{:<width$}", cell, width = width),
+                    _ => format!("{:>width$}", cell, width = width),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Run the solver that create_solver creates for every configuration and seed until termination says to stop, and
+/// report how every configuration did. create_solver is the problem definition: it builds the solver for the problem
+/// from a configuration, e.g. with LocalSearch::from_config and IteratedLocalSearch::from_config, and a seed, so that
+/// every configuration solves the same problem from the same seeds. Fails if there are no configurations or two of
+/// them have the same name, and stops at the first run that fails.
+pub fn compare<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC, _T, F>(
+    configs: &[(&str, SolverConfig)],
+    seeds: &[u64],
+    termination: &_T,
+    mut create_solver: F,
+) -> Result<ComparisonReport<_Score>, SolverError>
+where
+    _R: rand::Rng,
+    _Score: HardSoftScoring + 'static,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    _T: Termination<_Score>,
+    F: FnMut(&SolverConfig, u64) -> IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+{
+    if configs.is_empty() {
+        return Err(SolverError::InvalidConfiguration(
+            "configs must not be empty".to_string(),
+        ));
+    }
+    for (index, (name, _config)) in configs.iter().enumerate() {
+        if configs[..index].iter().any(|(other, _config)| other == name) {
+            return Err(SolverError::InvalidConfiguration(format!(
+                "configuration names must be unique, {} is already used",
+                name
+            )));
+        }
+    }
+    let feasible = ScoreThreshold::new(|score: &_Score| score.get_hard_score() == 0.0);
+    let contenders = configs
+        .iter()
+        .map(|(name, config)| {
+            let _span = tracing::info_span!("compare_configuration", name).entered();
+            let report = run_experiment(seeds, termination, Some(&feasible), |seed| {
+                create_solver(config, seed)
+            })?;
+            Ok(Contender {
+                name: name.to_string(),
+                config: config.clone(),
+                report,
+            })
+        })
+        .collect::<Result<Vec<_>, SolverError>>()?;
+    Ok(ComparisonReport { contenders })
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyIteratedLocalSearch, AckleyMoveProposer, AckleyPerturbation,
+        AckleySolutionScoreCalculator,
+    };
+    use crate::compare::compare;
+    use crate::config::SolverConfig;
+    use crate::error::SolverError;
+    use crate::iterated_local_search::IteratedLocalSearch;
+    use crate::local_search::LocalSearch;
+    use crate::termination::MaxIterations;
+
+    fn new_solver(config: &SolverConfig, seed: u64) -> AckleyIteratedLocalSearch {
+        let local_search = LocalSearch::from_config(
+            AckleyMoveProposer::new(10, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            &config.local_search,
+        )
+        .unwrap();
+        IteratedLocalSearch::from_config(
+            AckleyInitialSolutionGenerator::new(10),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+            &config.iterated_local_search,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn ackley_compare() {
+        let narrow =
+            SolverConfig::from_toml_str("[local_search]\nmax_iterations = 20\nwindow_size = 1").unwrap();
+        let wide =
+            SolverConfig::from_toml_str("[local_search]\nmax_iterations = 20\nwindow_size = 50").unwrap();
+        let report = compare(
+            &[("narrow", narrow.clone()), ("wide", wide)],
+            &[0, 1],
+            &MaxIterations(3),
+            new_solver,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["narrow", "wide"],
+            report
+                .contenders
+                .iter()
+                .map(|contender| contender.name.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(narrow, report.contenders[0].config);
+        for contender in &report.contenders {
+            assert!(contender.report.runs.iter().map(|run| run.seed).eq([0, 1]));
+            // Ackley has no hard constraints, so every run is feasible from its first round on.
+            assert_eq!(1.0, contender.report.get_target_reached_rate());
+        }
+        assert!(report.contenders.iter().all(|contender| report
+            .get_best_contender()
+            .unwrap()
+            .report
+            .final_energy
+            .mean
+            <= contender.report.final_energy.mean));
+
+        let table = report.to_string();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("configuration"));
+        assert!(lines[1].starts_with("narrow"));
+        assert!(lines[2].contains("2/2"));
+    }
+
+    #[test]
+    fn compare_rejects_empty_and_duplicate_configurations() {
+        let config = SolverConfig::default();
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "configs must not be empty".to_string()
+            )),
+            compare(&[], &[0], &MaxIterations(3), new_solver).err()
+        );
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "configuration names must be unique, narrow is already used".to_string()
+            )),
+            compare(
+                &[("narrow", config.clone()), ("narrow", config)],
+                &[0],
+                &MaxIterations(3),
+                new_solver
+            )
+            .err()
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! config collects the settings of a solver into SolverConfig, which applications load from a TOML or JSON file
+//! instead of hard-coding them in main(), so that trying other settings doesn't require recompiling. Every setting
+//! that a file leaves out keeps the default of the builders, see LocalSearchBuilder and IteratedLocalSearchBuilder,
+//! and unknown settings are an error, so that a typo doesn't silently fall back to a default.
+//!
+//! Wire a solver up from a SolverConfig with LocalSearch::from_config and IteratedLocalSearch::from_config, or apply
+//! the settings to a builder with LocalSearchBuilder::config and IteratedLocalSearchBuilder::config to combine them
+//! with settings that aren't plain numbers, e.g. a restart policy. The weights of the acceptance criterion and of the
+//! perturbation strategies go through DefaultAcceptanceCriterion::from_config and
+//! SolverConfig::get_perturbation_weights, since perturbations are problem specific.
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constraints::ConstraintWeights;
+use crate::error::{check_weights, SolverError};
+
+/// LocalSearchConfig is the settings of a LocalSearchBuilder.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalSearchConfig {
+    pub max_iterations: u64,
+    pub window_size: usize,
+    pub best_solutions_capacity: usize,
+    pub all_solutions_capacity: usize,
+    pub all_solution_iteration_expiry: u64,
+    pub random_walk_probability: f64,
+    pub estimated_candidates: Option<usize>,
+}
+
+impl Default for LocalSearchConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 10_000,
+            window_size: 100,
+            best_solutions_capacity: 16,
+            all_solutions_capacity: 10_000,
+            all_solution_iteration_expiry: 100_000,
+            random_walk_probability: 0.0,
+            estimated_candidates: None,
+        }
+    }
+}
+
+/// IteratedLocalSearchConfig is the settings of an IteratedLocalSearchBuilder. The capacities are those of its
+/// History.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IteratedLocalSearchConfig {
+    pub max_iterations: u64,
+    pub max_allow_no_improvement_for: u64,
+    pub best_solutions_capacity: usize,
+    pub all_solutions_capacity: usize,
+    pub all_solution_iteration_expiry: u64,
+    pub initial_population_size: usize,
+    pub improve_initial_population: bool,
+}
+
+impl Default for IteratedLocalSearchConfig {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1_000,
+            max_allow_no_improvement_for: 5,
+            best_solutions_capacity: 16,
+            all_solutions_capacity: 10_000,
+            all_solution_iteration_expiry: 100_000,
+            initial_population_size: 1,
+            improve_initial_population: false,
+        }
+    }
+}
+
+/// AcceptanceConfig is the weights of a DefaultAcceptanceCriterion, see DefaultAcceptanceCriterion::with_weights.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AcceptanceConfig {
+    pub existing_weight: u64,
+    pub new_weight: u64,
+    pub random_best_weight: u64,
+}
+
+impl Default for AcceptanceConfig {
+    fn default() -> Self {
+        Self {
+            existing_weight: 1,
+            new_weight: 5,
+            random_best_weight: 1,
+        }
+    }
+}
+
+/// SolverConfig is the settings of an IteratedLocalSearch, its LocalSearch and its DefaultAcceptanceCriterion, the
+/// weights of the strategies of its perturbation by strategy name, and the weights of the constraints of its score
+/// calculator by constraint name, see ConstraintWeights. In TOML:
+///
+/// ```toml
+/// [local_search]
+/// max_iterations = 1000
+/// window_size = 100
+///
+/// [iterated_local_search]
+/// max_iterations = 250
+/// max_allow_no_improvement_for = 20
+///
+/// [acceptance]
+/// new_weight = 10
+///
+/// [perturbation_weights]
+/// do_nothing = 0
+///
+/// [constraint_weights]
+/// "balanced days" = 2.0
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SolverConfig {
+    pub local_search: LocalSearchConfig,
+    pub iterated_local_search: IteratedLocalSearchConfig,
+    pub acceptance: AcceptanceConfig,
+    pub perturbation_weights: HashMap<String, u64>,
+    pub constraint_weights: HashMap<String, f64>,
+}
+
+impl SolverConfig {
+    /// Fails on settings that aren't valid TOML for SolverConfig, and on negative or NaN constraint weights.
+    pub fn from_toml_str(toml: &str) -> Result<Self, SolverError> {
+        let config: Self =
+            toml::from_str(toml).map_err(|error| SolverError::InvalidConfiguration(error.to_string()))?;
+        config.get_constraint_weights()?;
+        Ok(config)
+    }
+
+    /// Fails on settings that aren't valid JSON for SolverConfig, and on negative or NaN constraint weights.
+    pub fn from_json_str(json: &str) -> Result<Self, SolverError> {
+        let config: Self = serde_json::from_str(json)
+            .map_err(|error| SolverError::InvalidConfiguration(error.to_string()))?;
+        config.get_constraint_weights()?;
+        Ok(config)
+    }
+
+    /// Load the config file at path, as JSON if its extension is json and as TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SolverError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|error| {
+            SolverError::InvalidConfiguration(format!("failed to read {}: {}", path.display(), error))
+        })?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_toml_str(&contents),
+        }
+    }
+
+    /// Get the constraint weights as ConstraintWeights, to set on a ConstraintScoreCalculator, or fail if a weight is
+    /// negative or NaN.
+    pub fn get_constraint_weights(&self) -> Result<ConstraintWeights, SolverError> {
+        let weights = ConstraintWeights::new();
+        for (name, weight) in &self.constraint_weights {
+            weights.try_set_weight(name.clone(), *weight)?;
+        }
+        Ok(weights)
+    }
+
+    /// Override the weights of the strategies of a perturbation, given as (name, strategy, default weight), with
+    /// perturbation_weights by name. Fails if perturbation_weights names a strategy that isn't one of strategies, so
+    /// that a typo doesn't silently keep the default, or if no strategy is left with a positive weight.
+    pub fn get_perturbation_weights<_T>(
+        &self,
+        strategies: Vec<(&str, _T, u64)>,
+    ) -> Result<Vec<(_T, u64)>, SolverError> {
+        if let Some(unknown) = self.perturbation_weights.keys().find(|name| {
+            strategies
+                .iter()
+                .all(|(strategy_name, _, _)| strategy_name != name)
+        }) {
+            return Err(SolverError::InvalidConfiguration(format!(
+                "unknown perturbation strategy {}",
+                unknown
+            )));
+        }
+        let weights: Vec<(_T, u64)> = strategies
+            .into_iter()
+            .map(|(name, strategy, weight)| {
+                let weight = self.perturbation_weights.get(name).copied().unwrap_or(weight);
+                (strategy, weight)
+            })
+            .collect();
+        check_weights("perturbation strategy", &weights)?;
+        Ok(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{AcceptanceConfig, LocalSearchConfig, SolverConfig};
+    use crate::error::SolverError;
+
+    #[test]
+    fn toml_and_json_fill_in_defaults() {
+        let from_toml = SolverConfig::from_toml_str(
+            r#"
+            [local_search]
+            window_size = 10
+
+            [iterated_local_search]
+            max_iterations = 250
+
+            [acceptance]
+            new_weight = 10
+
+            [perturbation_weights]
+            do_nothing = 0
+
+            [constraint_weights]
+            "balanced days" = 2.0
+            "#,
+        )
+        .unwrap();
+        let from_json = SolverConfig::from_json_str(
+            r#"{
+                "local_search": {"window_size": 10},
+                "iterated_local_search": {"max_iterations": 250},
+                "acceptance": {"new_weight": 10},
+                "perturbation_weights": {"do_nothing": 0},
+                "constraint_weights": {"balanced days": 2.0}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(from_toml, from_json);
+        assert_eq!(
+            LocalSearchConfig {
+                window_size: 10,
+                ..LocalSearchConfig::default()
+            },
+            from_toml.local_search
+        );
+        assert_eq!(250, from_toml.iterated_local_search.max_iterations);
+        assert_eq!(5, from_toml.iterated_local_search.max_allow_no_improvement_for);
+        assert_eq!(
+            AcceptanceConfig {
+                new_weight: 10,
+                ..AcceptanceConfig::default()
+            },
+            from_toml.acceptance
+        );
+        assert_eq!(
+            Ok(vec![("do nothing", 0), ("change", 100)]),
+            from_toml
+                .get_perturbation_weights(vec![("do_nothing", "do nothing", 10), ("change", "change", 100)])
+        );
+        assert_eq!(
+            Some(2.0),
+            from_toml
+                .get_constraint_weights()
+                .unwrap()
+                .get_weight("balanced days")
+        );
+        assert_eq!(SolverConfig::default(), SolverConfig::from_toml_str("").unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        assert!(matches!(
+            SolverConfig::from_toml_str("[local_search]\nwindow = 10"),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            SolverConfig::load("does-not-exist.toml"),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_weights() {
+        let error = Err(SolverError::InvalidConfiguration(
+            "weight of balanced days must not be negative, got -1".to_string(),
+        ));
+        assert_eq!(
+            error,
+            SolverConfig::from_toml_str("[constraint_weights]\n\"balanced days\" = -1.0")
+        );
+        assert_eq!(
+            error,
+            SolverConfig::from_json_str(r#"{"constraint_weights": {"balanced days": -1.0}}"#)
+        );
+        assert!(matches!(
+            SolverConfig::from_toml_str("[constraint_weights]\n\"balanced days\" = nan"),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+
+        let config =
+            SolverConfig::from_toml_str("[perturbation_weights]\ndo_nothing = 0\nchange = 0").unwrap();
+        assert_eq!(
+            Err(SolverError::InvalidConfiguration(
+                "unknown perturbation strategy change".to_string()
+            )),
+            config.get_perturbation_weights(vec![("do_nothing", (), 10)])
+        );
+        assert_eq!(
+            Err(SolverError::InvalidConfiguration(
+                "perturbation strategy must have a positive weight".to_string()
+            )),
+            config.get_perturbation_weights(vec![("do_nothing", (), 10), ("change", (), 100)])
+        );
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleyPerturbation, AckleyScore, AckleySolution,
+        AckleySolutionScoreCalculator,
+    };
+    use crate::config::SolverConfig;
+    use crate::error::SolverError;
+    use crate::iterated_local_search::{DefaultAcceptanceCriterion, IteratedLocalSearch};
+    use crate::local_search::{
+        LocalSearch, LocalSearchBuilder, NeighborhoodExploration, StepAcceptance, TabuStorage,
+    };
+
+    #[test]
+    fn ackley_from_config() {
+        let config = SolverConfig::from_toml_str(
+            "[local_search]\nmax_iterations = 20\n\n[iterated_local_search]\nmax_iterations = 5",
+        )
+        .unwrap();
+        let local_search = LocalSearch::from_config(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            &config.local_search,
+        )
+        .unwrap();
+        let mut iterated_local_search = IteratedLocalSearch::from_config(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            &config.iterated_local_search,
+        )
+        .unwrap();
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        assert_eq!(5, iterated_local_search.get_iteration_info().iteration);
+
+        let mut invalid = config;
+        invalid.local_search.window_size = 0;
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "window_size must be positive".to_string()
+            )),
+            LocalSearch::from_config(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+                &invalid.local_search,
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn invalid_settings_are_errors_not_panics() {
+        let config = SolverConfig::from_toml_str("[local_search]\nrandom_walk_probability = 1.5").unwrap();
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "random_walk_probability must be between 0 and 1".to_string()
+            )),
+            LocalSearch::from_config(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+                &config.local_search,
+            )
+            .err()
+        );
+
+        // Settings that configs don't have go through the same check when they are set on a builder.
+        let config = SolverConfig::default();
+        let new_builder = || {
+            LocalSearchBuilder::new(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            )
+            .config(&config.local_search)
+        };
+        for (builder, message) in [
+            (
+                new_builder().step_acceptance(StepAcceptance::LateAcceptance { length: 0 }),
+                "late acceptance length must be positive",
+            ),
+            (
+                new_builder().neighborhood_exploration(NeighborhoodExploration::BestOfWindow { size: 0 }),
+                "window size must be positive",
+            ),
+            (
+                new_builder().neighborhood_exploration(NeighborhoodExploration::ReservoirSample { size: 0 }),
+                "window size must be positive",
+            ),
+            (
+                new_builder().adaptive_window_size(0, 8),
+                "min window size must be positive",
+            ),
+            (
+                new_builder().adaptive_window_size(8, 2),
+                "min window size must be at most max window size",
+            ),
+            (
+                new_builder().tabu_storage(TabuStorage::BloomFilter {
+                    false_positive_rate: 0.0,
+                }),
+                "false_positive_rate must be between 0 and 1",
+            ),
+        ] {
+            assert_eq!(
+                Some(SolverError::InvalidConfiguration(message.to_string())),
+                builder.try_build().err()
+            );
+        }
+
+        let config =
+            SolverConfig::from_toml_str("[iterated_local_search]\nbest_solutions_capacity = 0").unwrap();
+        let local_search = LocalSearch::from_config(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            &config.local_search,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "best_solutions_capacity must be positive".to_string()
+            )),
+            IteratedLocalSearch::from_config(
+                AckleyInitialSolutionGenerator::new(2),
+                AckleySolutionScoreCalculator::default(),
+                local_search,
+                AckleyPerturbation::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+                &config.iterated_local_search,
+            )
+            .err()
+        );
+
+        let config =
+            SolverConfig::from_toml_str("[acceptance]\nexisting_weight = 0\nnew_weight = 0").unwrap();
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "existing_weight or new_weight must have a positive weight".to_string()
+            )),
+            DefaultAcceptanceCriterion::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::from_config(
+                &config.acceptance
+            )
+            .err()
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! constraints lets you declare a score as a list of named constraints instead of one hand-rolled score function.
+//! Every constraint is a closure that measures how much a solution violates it, and ConstraintScoreCalculator adds
+//! the weighted measures up into the hard and soft parts of a score. Because the constraints are named, the score can
+//! also be reported per constraint, see ConstraintScoreCalculator::get_constraint_scores. Constraints that list their
+//! matches, see constraint_matches, can also say which entities take part in every violation, see
+//! ConstraintScoreCalculator::explain. ConstraintWeights overrides the weights of constraints by name at runtime, e.g.
+//! to let users tune the trade-off between soft constraints without recompiling.
+//!
+//! The constraint attribute and the ConstraintScoreCalculator derive declare the same constraints with less code: every
+//! constraint is a function, and a new rule is one more function.
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::assignment::PartialAssignment;
+use crate::error::SolverError;
+use crate::local_search::{ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::score::FromHardSoftScore;
+
+/// Declare constraints as functions with `#[constraint(hard)]` or `#[constraint(soft)]`, and collect them into a score
+/// calculator with `#[derive(ConstraintScoreCalculator)]`, see local_search_derive.
+pub use local_search_derive::{constraint, ConstraintScoreCalculator};
+
+/// ConstraintId identifies a constraint, by its name, which is unique within a ConstraintScoreCalculator.
+pub type ConstraintId = String;
+
+/// ConstraintLevel says whether violating a constraint makes a solution unusable (hard) or just less preferable (soft).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ConstraintLevel {
+    Hard,
+    Soft,
+}
+
+/// ConstraintMatch is one violation of a constraint, with the entities that take part in it, e.g. the indices of
+/// decision variables, and how much it counts before the weight of the constraint is applied.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConstraintMatch {
+    pub entities: Vec<usize>,
+    pub weight: f64,
+}
+
+impl ConstraintMatch {
+    /// Create a match with a weight of 1.
+    pub fn new(entities: Vec<usize>) -> Self {
+        Self {
+            entities,
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+type GetMatches<_Solution> = Box<dyn Fn(&_Solution) -> Vec<ConstraintMatch> + Send + Sync>;
+
+enum Evaluate<_Solution> {
+    Total(Box<dyn Fn(&_Solution) -> f64 + Send + Sync>),
+    Matches(GetMatches<_Solution>),
+}
+
+/// Constraint is a named measure of how much a solution violates a rule, e.g. the number of violations. Zero means the
+/// constraint holds. The measure is multiplied by the weight, 1 by default, before it is added to the score.
+pub struct Constraint<_Solution>
+where
+    _Solution: Solution,
+{
+    name: String,
+    level: ConstraintLevel,
+    weight: f64,
+    evaluate: Evaluate<_Solution>,
+}
+
+/// Create a constraint with a weight of 1.
+pub fn constraint<_Solution, _F>(
+    name: impl Into<String>,
+    level: ConstraintLevel,
+    evaluate: _F,
+) -> Constraint<_Solution>
+where
+    _Solution: Solution,
+    _F: Fn(&_Solution) -> f64 + Send + Sync + 'static,
+{
+    Constraint {
+        name: name.into(),
+        level,
+        weight: 1.0,
+        evaluate: Evaluate::Total(Box::new(evaluate)),
+    }
+}
+
+/// Create a constraint with a weight of 1 that lists its violations. The measure of the constraint is the sum of the
+/// weights of its matches.
+pub fn constraint_matches<_Solution, _F>(
+    name: impl Into<String>,
+    level: ConstraintLevel,
+    get_matches: _F,
+) -> Constraint<_Solution>
+where
+    _Solution: Solution,
+    _F: Fn(&_Solution) -> Vec<ConstraintMatch> + Send + Sync + 'static,
+{
+    Constraint {
+        name: name.into(),
+        level,
+        weight: 1.0,
+        evaluate: Evaluate::Matches(Box::new(get_matches)),
+    }
+}
+
+/// Create the hard constraint of the PartialAssignment convention, named "unassigned", which has one match, of weight
+/// 1, for every unassigned variable.
+pub fn unassigned_constraint<_Solution>() -> Constraint<_Solution>
+where
+    _Solution: PartialAssignment,
+{
+    constraint_matches("unassigned", ConstraintLevel::Hard, |solution: &_Solution| {
+        solution
+            .get_unassigned_variables()
+            .into_iter()
+            .map(|variable| ConstraintMatch::new(vec![variable]))
+            .collect()
+    })
+}
+
+impl<_Solution> Constraint<_Solution>
+where
+    _Solution: Solution,
+{
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        assert!(weight >= 0.0, "weight must not be negative");
+        self.weight = weight;
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_level(&self) -> ConstraintLevel {
+        self.level
+    }
+
+    pub fn get_weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// Get the weighted violation of solution.
+    pub fn evaluate(&self, solution: &_Solution) -> f64 {
+        self.evaluate_with_weight(solution, self.weight)
+    }
+
+    /// Get the violations of solution with their weights multiplied by the weight of the constraint. Constraints that
+    /// don't list their matches report one match without entities for their whole violation.
+    pub fn get_matches(&self, solution: &_Solution) -> Vec<ConstraintMatch> {
+        self.get_matches_with_weight(solution, self.weight)
+    }
+
+    fn evaluate_with_weight(&self, solution: &_Solution, weight: f64) -> f64 {
+        let measure = match &self.evaluate {
+            Evaluate::Total(evaluate) => evaluate(solution),
+            Evaluate::Matches(get_matches) => get_matches(solution).iter().map(|found| found.weight).sum(),
+        };
+        weight * measure
+    }
+
+    fn get_matches_with_weight(&self, solution: &_Solution, weight: f64) -> Vec<ConstraintMatch> {
+        let matches = match &self.evaluate {
+            Evaluate::Total(evaluate) => match evaluate(solution) {
+                0.0 => vec![],
+                measure => vec![ConstraintMatch::new(vec![]).with_weight(measure)],
+            },
+            Evaluate::Matches(get_matches) => get_matches(solution),
+        };
+        matches
+            .into_iter()
+            .map(|found| ConstraintMatch {
+                weight: weight * found.weight,
+                ..found
+            })
+            .collect()
+    }
+}
+
+/// ConstraintWeights overrides the weights that constraints were created with, by constraint name. Clones share the
+/// same weights, so a caller can keep a clone and change weights between solves without rebuilding the solver.
+/// Constraints without an override keep their own weight.
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintWeights(Arc<RwLock<HashMap<String, f64>>>);
+
+impl ConstraintWeights {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_weight(&self, name: impl Into<String>, weight: f64) {
+        assert!(weight >= 0.0, "weight must not be negative");
+        self.0.write().unwrap().insert(name.into(), weight);
+    }
+
+    /// Like set_weight, but fails instead of panicking on a negative or NaN weight, for weights that come from users,
+    /// e.g. a config file.
+    pub fn try_set_weight(&self, name: impl Into<String>, weight: f64) -> Result<(), SolverError> {
+        let name = name.into();
+        if weight.is_nan() || weight < 0.0 {
+            return Err(SolverError::InvalidConfiguration(format!(
+                "weight of {} must not be negative, got {}",
+                name, weight
+            )));
+        }
+        self.set_weight(name, weight);
+        Ok(())
+    }
+
+    pub fn get_weight(&self, name: &str) -> Option<f64> {
+        self.0.read().unwrap().get(name).copied()
+    }
+
+    /// Remove the override for name so that the constraint uses its own weight again.
+    pub fn remove_weight(&self, name: &str) {
+        self.0.write().unwrap().remove(name);
+    }
+
+    pub fn get_weights(&self) -> HashMap<String, f64> {
+        self.0.read().unwrap().clone()
+    }
+}
+
+/// ConstraintScore is how much one constraint contributes to the score of a solution.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConstraintScore {
+    pub name: String,
+    pub level: ConstraintLevel,
+    pub score: f64,
+}
+
+/// ConstraintViolation is a violated constraint in a ScoreExplanation. score is the sum of the weights of matches.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConstraintViolation {
+    pub name: String,
+    pub level: ConstraintLevel,
+    pub score: f64,
+    pub matches: Vec<ConstraintMatch>,
+}
+
+/// ScoreExplanation breaks the score of a solution down into the violated constraints, e.g. to tell users which rules
+/// a solution with a non-zero hard score breaks.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ScoreExplanation {
+    pub hard_score: f64,
+    pub soft_score: f64,
+    pub violations: Vec<ConstraintViolation>,
+}
+
+/// ConstraintScoreCalculator scores solutions by the sum of the weighted violations of its constraints, separately for
+/// the hard and the soft constraints. The weights of its ConstraintWeights take precedence over the weights of the
+/// constraints.
+pub struct ConstraintScoreCalculator<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: FromHardSoftScore,
+{
+    constraints: Vec<Constraint<_Solution>>,
+    weights: ConstraintWeights,
+    count_violations: bool,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_Solution, _Score> Default for ConstraintScoreCalculator<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: FromHardSoftScore,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<_Solution, _Score> ConstraintScoreCalculator<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: FromHardSoftScore,
+{
+    pub fn new() -> Self {
+        Self {
+            constraints: vec![],
+            weights: ConstraintWeights::default(),
+            count_violations: false,
+            phantom_score: PhantomData,
+        }
+    }
+
+    /// Use weights instead of the weights the constraints were created with. Keep a clone of weights to change them
+    /// later.
+    pub fn set_constraint_weights(&mut self, weights: ConstraintWeights) {
+        self.weights = weights;
+    }
+
+    pub fn get_constraint_weights(&self) -> &ConstraintWeights {
+        &self.weights
+    }
+
+    /// Count the violations of every constraint in the constraint_violations of scored solutions, see
+    /// get_violation_counts. Off by default, because it allocates for every scored solution.
+    pub fn set_count_violations(&mut self, count_violations: bool) {
+        self.count_violations = count_violations;
+    }
+
+    pub fn add_constraint(&mut self, constraint: Constraint<_Solution>) {
+        assert!(
+            self.constraints.iter().all(|other| other.name != constraint.name),
+            "constraint names must be unique, {} is already used",
+            constraint.name
+        );
+        self.constraints.push(constraint);
+    }
+
+    pub fn get_constraints(&self) -> &[Constraint<_Solution>] {
+        &self.constraints
+    }
+
+    /// Get the contribution of every constraint to the score of solution, in the order the constraints were added.
+    pub fn get_constraint_scores(&self, solution: &_Solution) -> Vec<ConstraintScore> {
+        let weights = self.weights.0.read().unwrap();
+        self.constraints
+            .iter()
+            .map(|constraint| ConstraintScore {
+                name: constraint.name.clone(),
+                level: constraint.level,
+                score: constraint.evaluate_with_weight(solution, get_weight(&weights, constraint)),
+            })
+            .collect()
+    }
+
+    pub fn get_score(&self, solution: &_Solution) -> _Score {
+        let weights = self.weights.0.read().unwrap();
+        let mut hard_score = 0.0;
+        let mut soft_score = 0.0;
+        for constraint in &self.constraints {
+            let score = constraint.evaluate_with_weight(solution, get_weight(&weights, constraint));
+            match constraint.level {
+                ConstraintLevel::Hard => hard_score += score,
+                ConstraintLevel::Soft => soft_score += score,
+            }
+        }
+        _Score::from_hard_soft_score(hard_score, soft_score)
+    }
+
+    /// Get the score of solution together with the number of matches of every violated constraint, see get_matches, in
+    /// the order the constraints were added.
+    pub fn get_score_with_violation_counts(
+        &self,
+        solution: &_Solution,
+    ) -> (_Score, Vec<(ConstraintId, u64)>) {
+        let weights = self.weights.0.read().unwrap();
+        let mut hard_score = 0.0;
+        let mut soft_score = 0.0;
+        let mut violation_counts = vec![];
+        for constraint in &self.constraints {
+            let matches = constraint.get_matches_with_weight(solution, get_weight(&weights, constraint));
+            if matches.is_empty() {
+                continue;
+            }
+            let score: f64 = matches.iter().map(|found| found.weight).sum();
+            match constraint.level {
+                ConstraintLevel::Hard => hard_score += score,
+                ConstraintLevel::Soft => soft_score += score,
+            }
+            violation_counts.push((constraint.name.clone(), matches.len() as u64));
+        }
+        (
+            _Score::from_hard_soft_score(hard_score, soft_score),
+            violation_counts,
+        )
+    }
+
+    /// Get the number of matches of every violated constraint, see get_score_with_violation_counts.
+    pub fn get_violation_counts(&self, solution: &_Solution) -> Vec<(ConstraintId, u64)> {
+        self.get_score_with_violation_counts(solution).1
+    }
+
+    /// Explain the score of solution by its violated constraints, in the order the constraints were added.
+    pub fn explain(&self, solution: &_Solution) -> ScoreExplanation {
+        let weights = self.weights.0.read().unwrap();
+        let mut explanation = ScoreExplanation {
+            hard_score: 0.0,
+            soft_score: 0.0,
+            violations: vec![],
+        };
+        for constraint in &self.constraints {
+            let matches = constraint.get_matches_with_weight(solution, get_weight(&weights, constraint));
+            let score: f64 = matches.iter().map(|found| found.weight).sum();
+            if score == 0.0 {
+                continue;
+            }
+            match constraint.level {
+                ConstraintLevel::Hard => explanation.hard_score += score,
+                ConstraintLevel::Soft => explanation.soft_score += score,
+            }
+            explanation.violations.push(ConstraintViolation {
+                name: constraint.name.clone(),
+                level: constraint.level,
+                score,
+                matches,
+            });
+        }
+        explanation
+    }
+}
+
+/// Get the weight of constraint, overridden by weights if they have one for it.
+fn get_weight<_Solution: Solution>(
+    weights: &HashMap<String, f64>,
+    constraint: &Constraint<_Solution>,
+) -> f64 {
+    weights
+        .get(&constraint.name)
+        .copied()
+        .unwrap_or(constraint.weight)
+}
+
+impl<_Solution, _Score> SolutionScoreCalculator for ConstraintScoreCalculator<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: FromHardSoftScore,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    /// Counts the violations of every constraint if set_count_violations is on.
+    fn get_scored_solution(&self, solution: _Solution) -> ScoredSolution<_Solution, _Score> {
+        if !self.count_violations {
+            let score = self.get_score(&solution);
+            return ScoredSolution::new(solution, score);
+        }
+        let (score, violation_counts) = self.get_score_with_violation_counts(&solution);
+        ScoredSolution::new(solution, score).with_constraint_violations(violation_counts)
+    }
+
+    /// Lists every violated hard constraint, see explain.
+    fn verify(&self, solution: &_Solution) -> Result<(), Vec<ConstraintViolation>> {
+        let violations: Vec<ConstraintViolation> = self
+            .explain(solution)
+            .violations
+            .into_iter()
+            .filter(|violation| violation.level == ConstraintLevel::Hard)
+            .collect();
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::{
+        constraint, constraint_matches, ConstraintLevel, ConstraintMatch, ConstraintScore,
+        ConstraintScoreCalculator, ConstraintViolation, ConstraintWeights,
+    };
+    use crate::local_search::{History, Score, Solution, SolutionScoreCalculator};
+    use crate::score::{FromHardSoftScore, HardSoftScoring};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Days(Vec<u8>);
+    impl Solution for Days {}
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(u64, u64);
+    impl Score for Cost {
+        fn is_best(&self) -> bool {
+            self.0 == 0 && self.1 == 0
+        }
+    }
+    impl HardSoftScoring for Cost {
+        fn get_hard_score(&self) -> f64 {
+            self.0 as f64
+        }
+
+        fn get_soft_score(&self) -> f64 {
+            self.1 as f64
+        }
+    }
+    impl FromHardSoftScore for Cost {
+        fn from_hard_soft_score(hard_score: f64, soft_score: f64) -> Self {
+            Cost(hard_score as u64, soft_score as u64)
+        }
+    }
+
+    #[test]
+    fn constraints_add_up_by_level() {
+        let mut calculator = ConstraintScoreCalculator::<Days, Cost>::new();
+        calculator.add_constraint(constraint(
+            "no consecutive days",
+            ConstraintLevel::Hard,
+            |days: &Days| days.0.windows(2).filter(|window| window[0] == window[1]).count() as f64,
+        ));
+        calculator.add_constraint(
+            constraint("prefer employee 0", ConstraintLevel::Soft, |days: &Days| {
+                days.0.iter().filter(|employee| **employee != 0).count() as f64
+            })
+            .with_weight(10.0),
+        );
+
+        let scored = calculator.get_scored_solution(Days(vec![0, 1, 1, 1, 0]));
+        assert_eq!(Cost(2, 30), scored.score);
+        assert_eq!(
+            vec![
+                ConstraintScore {
+                    name: "no consecutive days".to_string(),
+                    level: ConstraintLevel::Hard,
+                    score: 2.0,
+                },
+                ConstraintScore {
+                    name: "prefer employee 0".to_string(),
+                    level: ConstraintLevel::Soft,
+                    score: 30.0,
+                },
+            ],
+            calculator.get_constraint_scores(&scored.solution)
+        );
+    }
+
+    #[test]
+    fn explanation_lists_violated_constraints_and_their_entities() {
+        let mut calculator = ConstraintScoreCalculator::<Days, Cost>::new();
+        calculator.add_constraint(constraint_matches(
+            "no consecutive days",
+            ConstraintLevel::Hard,
+            |days: &Days| {
+                (0..days.0.len() - 1)
+                    .filter(|day| days.0[*day] == days.0[day + 1])
+                    .map(|day| ConstraintMatch::new(vec![day, day + 1]))
+                    .collect()
+            },
+        ));
+        calculator.add_constraint(constraint(
+            "no employee 2",
+            ConstraintLevel::Hard,
+            |days: &Days| days.0.iter().filter(|employee| **employee == 2).count() as f64,
+        ));
+        calculator.add_constraint(
+            constraint("prefer employee 0", ConstraintLevel::Soft, |days: &Days| {
+                days.0.iter().filter(|employee| **employee != 0).count() as f64
+            })
+            .with_weight(10.0),
+        );
+
+        let solution = Days(vec![1, 1, 0, 0, 1]);
+        assert_eq!(Ok(()), calculator.verify(&Days(vec![0, 1, 0])));
+        assert_eq!(
+            Err(vec![ConstraintViolation {
+                name: "no consecutive days".to_string(),
+                level: ConstraintLevel::Hard,
+                score: 2.0,
+                matches: vec![ConstraintMatch::new(vec![0, 1]), ConstraintMatch::new(vec![2, 3])],
+            }]),
+            calculator.verify(&solution)
+        );
+        let explanation = calculator.explain(&solution);
+        assert_eq!(Cost(2, 30), calculator.get_score(&solution));
+        assert_eq!(2.0, explanation.hard_score);
+        assert_eq!(30.0, explanation.soft_score);
+        assert_eq!(
+            vec![
+                ConstraintViolation {
+                    name: "no consecutive days".to_string(),
+                    level: ConstraintLevel::Hard,
+                    score: 2.0,
+                    matches: vec![ConstraintMatch::new(vec![0, 1]), ConstraintMatch::new(vec![2, 3])],
+                },
+                ConstraintViolation {
+                    name: "prefer employee 0".to_string(),
+                    level: ConstraintLevel::Soft,
+                    score: 30.0,
+                    matches: vec![ConstraintMatch::new(vec![]).with_weight(30.0)],
+                },
+            ],
+            explanation.violations
+        );
+    }
+
+    #[test]
+    fn constraint_weights_override_weights_between_calls() {
+        let mut calculator = ConstraintScoreCalculator::<Days, Cost>::new();
+        calculator.add_constraint(
+            constraint("prefer employee 0", ConstraintLevel::Soft, |days: &Days| {
+                days.0.iter().filter(|employee| **employee != 0).count() as f64
+            })
+            .with_weight(10.0),
+        );
+        calculator.add_constraint(constraint(
+            "prefer employee 1",
+            ConstraintLevel::Soft,
+            |days: &Days| days.0.iter().filter(|employee| **employee != 1).count() as f64,
+        ));
+        let weights = ConstraintWeights::new();
+        calculator.set_constraint_weights(weights.clone());
+        let solution = Days(vec![0, 1, 1]);
+        assert_eq!(Cost(0, 21), calculator.get_score(&solution));
+
+        weights.set_weight("prefer employee 0", 1.0);
+        weights.set_weight("prefer employee 1", 5.0);
+        assert_eq!(Cost(0, 7), calculator.get_score(&solution));
+        assert_eq!(5.0, calculator.explain(&solution).violations[1].score);
+
+        weights.remove_weight("prefer employee 0");
+        assert_eq!(Cost(0, 25), calculator.get_score(&solution));
+        assert_eq!(20.0, calculator.get_constraint_scores(&solution)[0].score);
+    }
+
+    #[test]
+    fn scored_solutions_count_violations_through_history() {
+        let mut calculator = ConstraintScoreCalculator::<Days, Cost>::new();
+        calculator.add_constraint(constraint_matches(
+            "no consecutive days",
+            ConstraintLevel::Hard,
+            |days: &Days| {
+                (0..days.0.len() - 1)
+                    .filter(|day| days.0[*day] == days.0[day + 1])
+                    .map(|day| ConstraintMatch::new(vec![day, day + 1]))
+                    .collect()
+            },
+        ));
+        calculator.add_constraint(
+            constraint("prefer employee 0", ConstraintLevel::Soft, |days: &Days| {
+                days.0.iter().filter(|employee| **employee != 0).count() as f64
+            })
+            .with_weight(10.0),
+        );
+        calculator.add_constraint(constraint("no days", ConstraintLevel::Soft, |_days: &Days| 0.0));
+        assert_eq!(
+            None,
+            calculator
+                .get_scored_solution(Days(vec![1, 1]))
+                .constraint_violations
+        );
+
+        calculator.set_count_violations(true);
+        let mut history = History::<rand_chacha::ChaCha20Rng, Days, Cost>::default();
+        assert_eq!(None, history.get_best_constraint_violations());
+        let scored = calculator.get_scored_solution(Days(vec![0, 1, 1, 1, 0]));
+        assert_eq!(Cost(2, 30), scored.score);
+        assert_eq!(calculator.get_score(&scored.solution), scored.score);
+        history.local_search_chose_solution(scored);
+        assert_eq!(
+            Some(
+                &[
+                    ("no consecutive days".to_string(), 2),
+                    ("prefer employee 0".to_string(), 1),
+                ][..]
+            ),
+            history.get_best_constraint_violations()
+        );
+
+        history.local_search_chose_solution(calculator.get_scored_solution(Days(vec![0, 0])));
+        assert_eq!(
+            Some(&[("no consecutive days".to_string(), 1)][..]),
+            history.get_best_constraint_violations()
+        );
+    }
+}
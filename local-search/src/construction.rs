@@ -0,0 +1,318 @@
+//! construction builds initial solutions greedily, one decision variable at a time, instead of drawing them at random.
+//! Random initial solutions of tightly constrained problems, e.g. rosters, are so far from feasibility that the first
+//! rounds of a search do little more than repair them. A ConstructionHeuristic starts the search closer to
+//! feasibility; ConstructionInitialSolutionGenerator lets an IteratedLocalSearch use one wherever it takes an
+//! InitialSolutionGenerator.
+use std::marker::PhantomData;
+
+use rand::prelude::SliceRandom;
+
+use crate::assignment::{Assignment, Domain, PartialAssignment};
+use crate::local_search::{InitialSolutionGenerator, ScoredSolution, Solution, SolutionScoreCalculator};
+
+/// ConstructionHeuristic completes a start solution, e.g. a template or a random solution, into one that is hopefully
+/// close to feasibility.
+pub trait ConstructionHeuristic {
+    type _R: rand::Rng;
+    type _Solution: Solution;
+
+    fn construct(&self, start: Self::_Solution, rng: &mut Self::_R) -> Self::_Solution;
+}
+
+/// ConstructionStrategy decides which placement GreedyConstruction makes next. A placement assigns a value to a
+/// variable.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConstructionStrategy {
+    /// Visit the variables in order, and assign each the first value of its domain that improves the score. The
+    /// cheapest strategy, but the order of the domains matters.
+    FirstFit,
+
+    /// Visit the variables in order, and assign each the value of its domain that gives the best score.
+    BestFit,
+
+    /// Out of all variables not yet placed and all their values, make the placement that gives the best score, until
+    /// every variable is placed. The most expensive strategy, a factor of the variable count slower than BestFit,
+    /// but it places the most constrained variables while they still have good values left.
+    CheapestInsertion,
+}
+
+/// GreedyConstruction places every variable of the start solution once, following a ConstructionStrategy, and ranks
+/// placements with a score calculator, typically the one of the search. Variables that have not been placed yet keep
+/// their start values and count towards the score, so start from values that cost little, e.g. unassigned ones, see
+/// PartialAssignment. Ties between equally good values of a variable are broken randomly, except by FirstFit.
+pub struct GreedyConstruction<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    solution_score_calculator: _SSC,
+    domain: _D,
+    strategy: ConstructionStrategy,
+    get_unplaced_variables: fn(&_SSC::_Solution) -> Vec<usize>,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _SSC, _D> GreedyConstruction<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    pub fn new(solution_score_calculator: _SSC, domain: _D, strategy: ConstructionStrategy) -> Self {
+        Self {
+            solution_score_calculator,
+            domain,
+            strategy,
+            get_unplaced_variables: |solution| (0..solution.get_variable_count()).collect(),
+            phantom_r: PhantomData,
+        }
+    }
+
+    /// Place only the unassigned variables of a partially assigned start solution, and keep the values of the
+    /// assigned ones, e.g. of shifts that were fixed by hand.
+    pub fn with_unassigned_only(mut self) -> Self
+    where
+        _SSC::_Solution: PartialAssignment,
+    {
+        self.get_unplaced_variables = |solution| solution.get_unassigned_variables();
+        self
+    }
+
+    fn place(
+        &self,
+        current: &ScoredSolution<_SSC::_Solution, _SSC::_Score>,
+        variable: usize,
+        value: <_SSC::_Solution as Assignment>::Value,
+    ) -> ScoredSolution<_SSC::_Solution, _SSC::_Score> {
+        let mut candidate = current.solution.clone();
+        candidate.set_value(variable, value);
+        self.solution_score_calculator.get_scored_solution(candidate)
+    }
+
+    fn get_first_fit(
+        &self,
+        current: &ScoredSolution<_SSC::_Solution, _SSC::_Score>,
+        variable: usize,
+    ) -> Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>> {
+        self.domain
+            .get_values(&current.solution, variable)
+            .into_iter()
+            .map(|value| self.place(current, variable, value))
+            .find(|candidate| candidate.score < current.score)
+    }
+
+    fn get_best_fit(
+        &self,
+        current: &ScoredSolution<_SSC::_Solution, _SSC::_Score>,
+        variable: usize,
+        rng: &mut _R,
+    ) -> Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>> {
+        let mut values = self.domain.get_values(&current.solution, variable);
+        values.shuffle(rng);
+        values
+            .into_iter()
+            .map(|value| self.place(current, variable, value))
+            .min_by(|left, right| left.score.cmp(&right.score))
+    }
+}
+
+impl<_R, _SSC, _D> ConstructionHeuristic for GreedyConstruction<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    type _R = _R;
+    type _Solution = _SSC::_Solution;
+
+    fn construct(&self, start: Self::_Solution, rng: &mut Self::_R) -> Self::_Solution {
+        let mut current = self.solution_score_calculator.get_scored_solution(start);
+        let mut unplaced = (self.get_unplaced_variables)(&current.solution);
+        while !unplaced.is_empty() {
+            let placement = match self.strategy {
+                ConstructionStrategy::FirstFit => self
+                    .get_first_fit(&current, unplaced[0])
+                    .map(|placement| (0, placement)),
+                ConstructionStrategy::BestFit => self
+                    .get_best_fit(&current, unplaced[0], rng)
+                    .map(|placement| (0, placement)),
+                ConstructionStrategy::CheapestInsertion => unplaced
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, variable)| {
+                        self.get_best_fit(&current, *variable, rng)
+                            .map(|placement| (index, placement))
+                    })
+                    .min_by(|(_, left), (_, right)| left.score.cmp(&right.score)),
+            };
+            match placement {
+                Some((index, placement)) => {
+                    current = placement;
+                    unplaced.remove(index);
+                }
+                // No value of the next variable fits, so it keeps its start value.
+                None => {
+                    unplaced.remove(0);
+                }
+            }
+        }
+        current.solution
+    }
+}
+
+/// ConstructionInitialSolutionGenerator generates initial solutions by completing the solutions of another
+/// InitialSolutionGenerator with a ConstructionHeuristic. To construct solutions from scratch instead of in addition to
+/// generating them, use a generator that returns a fixed template.
+pub struct ConstructionInitialSolutionGenerator<_ISG, _CH>
+where
+    _ISG: InitialSolutionGenerator,
+    _CH: ConstructionHeuristic<_R = _ISG::R, _Solution = _ISG::Solution>,
+{
+    initial_solution_generator: _ISG,
+    construction_heuristic: _CH,
+}
+
+impl<_ISG, _CH> ConstructionInitialSolutionGenerator<_ISG, _CH>
+where
+    _ISG: InitialSolutionGenerator,
+    _CH: ConstructionHeuristic<_R = _ISG::R, _Solution = _ISG::Solution>,
+{
+    pub fn new(initial_solution_generator: _ISG, construction_heuristic: _CH) -> Self {
+        Self {
+            initial_solution_generator,
+            construction_heuristic,
+        }
+    }
+}
+
+impl<_ISG, _CH> InitialSolutionGenerator for ConstructionInitialSolutionGenerator<_ISG, _CH>
+where
+    _ISG: InitialSolutionGenerator,
+    _CH: ConstructionHeuristic<_R = _ISG::R, _Solution = _ISG::Solution>,
+{
+    type R = _ISG::R;
+    type Solution = _ISG::Solution;
+
+    fn generate_initial_solution(&self, rng: &mut Self::R) -> Self::Solution {
+        let start = self.initial_solution_generator.generate_initial_solution(rng);
+        self.construction_heuristic.construct(start, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::assignment::PartialAssignment;
+    use crate::constraints::{constraint, unassigned_constraint, ConstraintLevel, ConstraintScoreCalculator};
+    use crate::construction::{
+        ConstructionHeuristic, ConstructionInitialSolutionGenerator, ConstructionStrategy, GreedyConstruction,
+    };
+    use crate::fixtures::{Bits, PartialBits};
+    use crate::local_search::{InitialSolutionGenerator, ScoredSolution, SolutionScoreCalculator};
+    use crate::modeling::{DomainInitialSolutionGenerator, ListDomain};
+    use crate::score::{HardSoftScoring, IntegerHardSoft};
+
+    /// Color a cycle: neighbors must differ, and lower colors are cheaper.
+    #[derive(Default)]
+    struct CycleScoreCalculator;
+    impl SolutionScoreCalculator for CycleScoreCalculator {
+        type _Solution = Bits;
+        type _Score = IntegerHardSoft;
+
+        fn get_scored_solution(&self, solution: Bits) -> ScoredSolution<Bits, IntegerHardSoft> {
+            let count = solution.0.len();
+            let conflicts = (0..count)
+                .filter(|variable| solution.0[*variable] == solution.0[(variable + 1) % count])
+                .count();
+            let colors = solution.0.iter().map(|color| *color as i64).sum();
+            ScoredSolution::new(solution, IntegerHardSoft::new(conflicts as i64, colors))
+        }
+    }
+
+    #[test]
+    fn every_strategy_constructs_a_feasible_solution() {
+        for strategy in [
+            ConstructionStrategy::FirstFit,
+            ConstructionStrategy::BestFit,
+            ConstructionStrategy::CheapestInsertion,
+        ] {
+            let construction = GreedyConstruction::new(
+                CycleScoreCalculator,
+                ListDomain::uniform(7, vec![0, 1, 2]),
+                strategy.clone(),
+            );
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+            let solution = construction.construct(Bits(vec![0; 7]), &mut rng);
+            let scored = CycleScoreCalculator.get_scored_solution(solution);
+            assert_eq!(0, scored.score.hard_score, "{:?}", strategy);
+            assert!(scored.score.soft_score < 7 * 2, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn constructs_the_unassigned_variables_of_partial_solutions() {
+        let mut calculator = ConstraintScoreCalculator::<PartialBits, IntegerHardSoft>::new();
+        calculator.add_constraint(unassigned_constraint());
+        calculator.add_constraint(constraint(
+            "neighbors differ",
+            ConstraintLevel::Hard,
+            |solution: &PartialBits| {
+                solution
+                    .0
+                    .windows(2)
+                    .filter(|window| window[0].is_some() && window[0] == window[1])
+                    .count() as f64
+            },
+        ));
+        let start = PartialBits(vec![None, Some(2), None, None, Some(0), None]);
+        assert_eq!(vec![0, 2, 3, 5], start.get_unassigned_variables());
+        assert_eq!(
+            4.0,
+            calculator
+                .get_scored_solution(start.clone())
+                .score
+                .get_hard_score()
+        );
+
+        let construction = GreedyConstruction::new(
+            calculator,
+            ListDomain::uniform(6, vec![Some(0), Some(1), Some(2)]),
+            ConstructionStrategy::FirstFit,
+        )
+        .with_unassigned_only();
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let solution = construction.construct(start, &mut rng);
+        assert_eq!(
+            PartialBits(vec![Some(0), Some(2), Some(0), Some(1), Some(0), Some(1)]),
+            solution
+        );
+    }
+
+    #[test]
+    fn generates_constructed_initial_solutions() {
+        let generator = ConstructionInitialSolutionGenerator::new(
+            DomainInitialSolutionGenerator::new(Bits(vec![0; 9]), ListDomain::uniform(9, vec![0, 1, 2])),
+            GreedyConstruction::new(
+                CycleScoreCalculator,
+                ListDomain::uniform(9, vec![0, 1, 2]),
+                ConstructionStrategy::BestFit,
+            ),
+        );
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        for _ in 0..10 {
+            let solution = generator.generate_initial_solution(&mut rng);
+            assert_eq!(
+                0,
+                CycleScoreCalculator
+                    .get_scored_solution(solution)
+                    .score
+                    .hard_score
+            );
+        }
+    }
+}
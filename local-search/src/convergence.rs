@@ -0,0 +1,251 @@
+//! convergence samples the current and best score of an IteratedLocalSearch over time, so that convergence curves of
+//! different configurations can be exported to CSV or JSON and compared without adding instrumentation to every
+//! problem domain. A ConvergenceRecorder either observes a solver from the outside, see ConvergenceRecorder::observe,
+//! or is kept by the solver itself, see IteratedLocalSearch::set_record_convergence_trace.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Serializer};
+
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
+use crate::local_search::{InitialSolutionGenerator, MoveProposer, Score, Solution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConvergenceSample<_Score>
+where
+    _Score: Score,
+{
+    #[serde(rename = "elapsed_seconds", serialize_with = "serialize_seconds")]
+    pub elapsed: Duration,
+    pub iteration: u64,
+
+    /// The score of the current solution, at the end of the round as chosen by the acceptance criterion.
+    pub current_score: _Score,
+    pub best_score: _Score,
+}
+
+fn serialize_seconds<S: Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64())
+}
+
+/// ConvergenceRecorder keeps a ConvergenceSample for every sample_every-th round it observes. Wall time is measured
+/// from when the recorder is created, unless the caller of record measures it.
+#[derive(Clone, Debug)]
+pub struct ConvergenceRecorder<_Score>
+where
+    _Score: Score,
+{
+    started: Instant,
+    sample_every: u64,
+    samples: Vec<ConvergenceSample<_Score>>,
+}
+
+impl<_Score> ConvergenceRecorder<_Score>
+where
+    _Score: Score,
+{
+    /// Sample every sample_every-th round, or fail if sample_every is 0. A sample_every of 1 samples every round, see
+    /// every_round.
+    pub fn new(sample_every: u64) -> Result<Self, SolverError> {
+        if sample_every == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "sample_every must be positive".to_string(),
+            ));
+        }
+        Ok(Self::with_sample_every(sample_every))
+    }
+
+    /// Sample every round.
+    pub fn every_round() -> Self {
+        Self::with_sample_every(1)
+    }
+
+    fn with_sample_every(sample_every: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            sample_every,
+            samples: vec![],
+        }
+    }
+
+    /// Call after every execute_round. Rounds that aren't sampled, and rounds before any best solution exists, are
+    /// ignored.
+    pub fn observe<_R, _Solution, _SSC, _MP, _ISG, _P, _AC>(
+        &mut self,
+        iterated_local_search: &IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    ) where
+        _R: rand::Rng,
+        _Solution: Solution,
+        _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+        _MP: MoveProposer<R = _R, Solution = _Solution>,
+        _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+        _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+        _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    {
+        let iteration_info = iterated_local_search.get_iteration_info();
+        if let Some(best_score) = iteration_info.best_score.as_ref() {
+            self.record(
+                iteration_info.iteration,
+                self.started.elapsed(),
+                &iteration_info.current_score,
+                best_score,
+            );
+        }
+    }
+
+    /// Keep a sample of round iteration, if it is sampled, with elapsed as its wall time.
+    pub fn record(&mut self, iteration: u64, elapsed: Duration, current_score: &_Score, best_score: &_Score) {
+        if iteration % self.sample_every != 0 {
+            return;
+        }
+        self.samples.push(ConvergenceSample {
+            elapsed,
+            iteration,
+            current_score: current_score.clone(),
+            best_score: best_score.clone(),
+        });
+    }
+
+    pub fn get_samples(&self) -> &[ConvergenceSample<_Score>] {
+        &self.samples
+    }
+}
+
+impl<_Score> ConvergenceRecorder<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// Write the samples as CSV with a header row, one row per sample. Scores are split into their hard and soft
+    /// parts.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "elapsed_seconds,iteration,current_hard_score,current_soft_score,best_hard_score,best_soft_score"
+        )?;
+        for sample in &self.samples {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                sample.elapsed.as_secs_f64(),
+                sample.iteration,
+                sample.current_score.get_hard_score(),
+                sample.current_score.get_soft_score(),
+                sample.best_score.get_hard_score(),
+                sample.best_score.get_soft_score()
+            )?;
+        }
+        writer.flush()
+    }
+
+    pub fn write_csv_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.write_csv(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<_Score> ConvergenceRecorder<_Score>
+where
+    _Score: Score + Serialize,
+{
+    /// Serialize the samples as a JSON array, with scores in their own serde representation.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&self.samples)
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use crate::ackley::{new_slow_ackley_iterated_local_search, AckleyScore};
+    use crate::convergence::ConvergenceRecorder;
+    use crate::error::SolverError;
+    use crate::termination::MaxIterations;
+
+    #[test]
+    fn samples_are_monotonic_and_written_as_csv() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(42, 10);
+        let mut recorder = ConvergenceRecorder::new(2).unwrap();
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+            recorder.observe(&iterated_local_search);
+        }
+
+        let samples = recorder.get_samples();
+        let iterations: Vec<u64> = samples.iter().map(|sample| sample.iteration).collect();
+        assert_eq!(vec![2, 4, 6, 8, 10], iterations);
+        assert!(samples
+            .windows(2)
+            .all(|window| window[1].best_score <= window[0].best_score
+                && window[1].elapsed >= window[0].elapsed));
+        assert!(samples
+            .iter()
+            .all(|sample| sample.best_score <= sample.current_score));
+
+        let mut csv = vec![];
+        recorder.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            "elapsed_seconds,iteration,current_hard_score,current_soft_score,best_hard_score,best_soft_score",
+            lines[0]
+        );
+        assert_eq!(samples.len() + 1, lines.len());
+        assert!(lines[1].split(',').nth(1) == Some("2"));
+    }
+
+    #[test]
+    fn trace_records_every_round_and_is_written_as_csv_and_json() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(42, 10);
+        iterated_local_search.set_record_convergence_trace(true);
+        iterated_local_search.execute(&MaxIterations(5)).unwrap();
+
+        let samples = iterated_local_search
+            .get_convergence_trace()
+            .unwrap()
+            .get_samples();
+        // Rounds after the global minimum is found end early and aren't recorded.
+        assert_eq!(1, samples[0].iteration);
+        assert!(samples
+            .windows(2)
+            .all(|window| window[1].iteration > window[0].iteration));
+        assert!(samples
+            .iter()
+            .all(|sample| sample.best_score <= sample.current_score));
+        assert!(samples
+            .windows(2)
+            .all(|window| window[1].best_score <= window[0].best_score
+                && window[1].elapsed >= window[0].elapsed));
+
+        let path = std::env::temp_dir().join(format!(
+            "local-search-convergence-trace-{}.csv",
+            std::process::id()
+        ));
+        iterated_local_search.write_trace_csv(&path).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            "elapsed_seconds,iteration,current_hard_score,current_soft_score,best_hard_score,best_soft_score",
+            lines[0]
+        );
+        assert_eq!(samples.len() + 1, lines.len());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&iterated_local_search.to_trace_json().unwrap()).unwrap();
+        assert_eq!(samples.len(), json.as_array().unwrap().len());
+        assert_eq!(1, json[0]["iteration"]);
+        assert!(json[0]["elapsed_seconds"].is_f64());
+    }
+
+    #[test]
+    fn sample_every_must_be_positive() {
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "sample_every must be positive".to_string()
+            )),
+            ConvergenceRecorder::<AckleyScore>::new(0).err()
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! diversity keeps the best solutions of a History from filling up with trivially different variants of one local
+//! optimum. With a SolutionDistance, History treats best solutions that are closer than a minimum distance as
+//! duplicates and only keeps the better one, see History::set_solution_distance.
+use std::marker::PhantomData;
+
+use crate::assignment::Assignment;
+use crate::local_search::Solution;
+
+/// SolutionDistance measures how different two solutions are. Distances must be non-negative, zero for equal
+/// solutions, and symmetric.
+pub trait SolutionDistance {
+    type _Solution: Solution;
+
+    fn get_distance(&self, first: &Self::_Solution, second: &Self::_Solution) -> f64;
+}
+
+/// HammingDistance counts the decision variables that have different values.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct HammingDistance<_Solution>
+where
+    _Solution: Assignment,
+{
+    phantom_solution: PhantomData<_Solution>,
+}
+
+impl<_Solution> SolutionDistance for HammingDistance<_Solution>
+where
+    _Solution: Assignment,
+{
+    type _Solution = _Solution;
+
+    fn get_distance(&self, first: &_Solution, second: &_Solution) -> f64 {
+        (0..first.get_variable_count())
+            .filter(|variable| first.get_value(*variable) != second.get_value(*variable))
+            .count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diversity::{HammingDistance, SolutionDistance};
+    use crate::fixtures::Bits;
+    use crate::local_search::{History, Score, ScoredSolution};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(u64);
+    impl Score for Cost {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    fn scored(bits: Vec<u8>, cost: u64) -> ScoredSolution<Bits, Cost> {
+        ScoredSolution::new(Bits(bits), Cost(cost))
+    }
+
+    #[test]
+    fn hamming_distance_counts_differing_variables() {
+        let distance = HammingDistance::default();
+        assert_eq!(
+            0.0,
+            distance.get_distance(&Bits(vec![0, 1, 1]), &Bits(vec![0, 1, 1]))
+        );
+        assert_eq!(
+            2.0,
+            distance.get_distance(&Bits(vec![0, 1, 1]), &Bits(vec![1, 1, 0]))
+        );
+    }
+
+    #[test]
+    fn history_keeps_only_the_best_of_near_duplicates() {
+        let mut history = History::<rand_chacha::ChaCha20Rng, Bits, Cost>::new(3, 100, 100);
+        history.set_solution_distance(Box::new(HammingDistance::default()), 2.0);
+
+        history.local_search_chose_solution(scored(vec![0, 0, 0, 0], 5));
+        history.local_search_chose_solution(scored(vec![0, 0, 0, 1], 6));
+        history.local_search_chose_solution(scored(vec![1, 1, 1, 1], 7));
+        assert_eq!(
+            Some(vec![scored(vec![0, 0, 0, 0], 5), scored(vec![1, 1, 1, 1], 7)]),
+            history.get_best_multiple(3)
+        );
+
+        // A better near duplicate replaces the one it is close to.
+        history.local_search_chose_solution(scored(vec![1, 1, 1, 0], 4));
+        assert_eq!(
+            Some(vec![scored(vec![1, 1, 1, 0], 4), scored(vec![0, 0, 0, 0], 5)]),
+            history.get_best_multiple(3)
+        );
+    }
+}
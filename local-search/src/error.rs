@@ -0,0 +1,54 @@
+//! error contains SolverError, so that a misconfigured solver or a problem without anything to search surfaces as an
+//! Err that the application can report, instead of as a panic deep inside a round.
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SolverError {
+    /// A setting of the solver is out of range, e.g. max_iterations is zero.
+    InvalidConfiguration(String),
+
+    /// The problem has nothing to search, e.g. a schedule without employees.
+    EmptyProblem(String),
+
+    /// The solver has no solution to return yet, e.g. because it has not executed a round.
+    NoSolution,
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::InvalidConfiguration(message) => write!(f, "{}", message),
+            SolverError::EmptyProblem(message) => write!(f, "empty problem: {}", message),
+            SolverError::NoSolution => write!(f, "no solution found yet"),
+        }
+    }
+}
+
+impl std::error::Error for SolverError {}
+
+/// Check that a weighted list, e.g. the strategies of a perturbation, can be sampled from with choose_weighted, i.e. that
+/// it has a positive weight, so that constructors can reject it instead of a round panicking on it.
+pub fn check_weights<_T>(name: &str, weights: &[(_T, u64)]) -> Result<(), SolverError> {
+    match weights.iter().any(|(_item, weight)| *weight > 0) {
+        true => Ok(()),
+        false => Err(SolverError::InvalidConfiguration(format!(
+            "{} must have a positive weight",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{check_weights, SolverError};
+
+    #[test]
+    fn weights_need_a_positive_weight() {
+        assert_eq!(Ok(()), check_weights("strategy", &[("first", 0), ("second", 1)]));
+        let error = Err(SolverError::InvalidConfiguration(
+            "strategy must have a positive weight".to_string(),
+        ));
+        assert_eq!(error, check_weights::<&str>("strategy", &[]));
+        assert_eq!(error, check_weights("strategy", &[("first", 0), ("second", 0)]));
+    }
+}
@@ -0,0 +1,155 @@
+//! event_log records what IteratedLocalSearch did to its current solution, i.e. every restart, perturbation, local
+//! search step, new best and accepted solution, so that a run can be replayed step by step from its initial solution
+//! to the final result. This answers "why did the solver produce this schedule" questions after the fact, without
+//! rerunning the solver under a debugger. An event log is a trace, see the trace module, that keeps every round and
+//! also observes the solver and its local search, so it is written by a JsonLinesTraceRecorder and replayed with
+//! TraceReplay like any other trace.
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use crate::assignment::Assignment;
+use crate::score::HardSoftScoring;
+use crate::trace::JsonLinesTraceRecorder;
+
+/// EventLog is a JsonLinesTraceRecorder that is shared between an IteratedLocalSearch and its LocalSearch. Add it to
+/// both before the first round:
+///
+/// ```ignore
+/// let event_log = new_event_log(writer);
+/// local_search.add_observer(Box::new(JsonLinesTraceRecorder::observe_steps(event_log.clone())));
+/// iterated_local_search.add_observer(Box::new(event_log.clone()));
+/// iterated_local_search.set_trace_sink(Box::new(event_log.clone()));
+/// ```
+pub type EventLog<_W, _Solution, _Score> = Arc<Mutex<JsonLinesTraceRecorder<_W, _Solution, _Score>>>;
+
+/// Get an EventLog that writes every event of every round to writer.
+pub fn new_event_log<_W, _Solution, _Score>(writer: _W) -> EventLog<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    Arc::new(Mutex::new(JsonLinesTraceRecorder::every_round(writer)))
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use std::sync::{Arc, Mutex};
+
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        new_ackley_local_search, AckleyInitialSolutionGenerator, AckleyPerturbation, AckleyScore,
+        AckleySolution, AckleySolutionScoreCalculator,
+    };
+    use crate::event_log::{new_event_log, EventLog};
+    use crate::iterated_local_search::IteratedLocalSearchBuilder;
+    use crate::local_search::ScoredSolution;
+    use crate::observer::Observer;
+    use crate::restart::FixedIntervalRestart;
+    use crate::trace::{JsonLinesTraceRecorder, TraceEvent, TraceReplay};
+
+    /// Keeps a copy of every solution that the solver accepted, to check the replay against.
+    #[derive(Default)]
+    struct AcceptedSolutions(Vec<ScoredSolution<AckleySolution, AckleyScore>>);
+    impl Observer for AcceptedSolutions {
+        type _Solution = AckleySolution;
+        type _Score = AckleyScore;
+
+        fn on_iteration(&mut self, _iteration: u64, current: &ScoredSolution<AckleySolution, AckleyScore>) {
+            self.0.push(current.clone());
+        }
+    }
+
+    type AckleyEventLog = EventLog<Vec<u8>, AckleySolution, AckleyScore>;
+
+    /// Run max_iterations rounds on 10 dimensions, restarting every restart_interval rounds, with an event log
+    /// observing the rounds and steps, and return the best solution and the log.
+    fn execute_logged(
+        max_iterations: u64,
+        restart_interval: u64,
+        accepted_solutions: &Arc<Mutex<AcceptedSolutions>>,
+    ) -> (ScoredSolution<AckleySolution, AckleyScore>, Vec<u8>) {
+        let event_log: AckleyEventLog = new_event_log(vec![]);
+        let mut local_search = new_ackley_local_search(10, 0);
+        local_search.add_observer(Box::new(JsonLinesTraceRecorder::observe_steps(event_log.clone())));
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(10),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(0),
+        )
+        .max_iterations(max_iterations)
+        .restart_policy(Box::new(FixedIntervalRestart(restart_interval)))
+        .build();
+        iterated_local_search.add_observer(Box::new(event_log.clone()));
+        iterated_local_search.set_trace_sink(Box::new(event_log.clone()));
+        iterated_local_search.add_observer(Box::new(accepted_solutions.clone()));
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+
+        let mut event_log = event_log.lock().unwrap();
+        event_log.finish().unwrap();
+        (
+            iterated_local_search.get_best_solution(),
+            event_log.get_ref().clone(),
+        )
+    }
+
+    fn replay(event_log: &[u8]) -> TraceReplay<AckleySolution, AckleyScore> {
+        TraceReplay::load(event_log).unwrap()
+    }
+
+    #[test]
+    fn replay_reproduces_final_result() {
+        let accepted_solutions = Arc::new(Mutex::new(AcceptedSolutions::default()));
+        let (best, event_log) = execute_logged(4, 2, &accepted_solutions);
+        let mut replay = replay(&event_log);
+
+        assert!(matches!(replay.get_events()[0], TraceEvent::Start { .. }));
+        // Ackley moves change a single dimension, so every step holds one change instead of the whole solution.
+        let steps: Vec<usize> = replay
+            .get_events()
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::Step { changes, .. } => Some(changes.len()),
+                _ => None,
+            })
+            .collect();
+        assert!(!steps.is_empty());
+        assert!(steps.iter().all(|changes| *changes == 1));
+
+        let restart = replay
+            .step_until(|event| matches!(event, TraceEvent::Restart { .. }))
+            .cloned();
+        assert!(matches!(restart, Some(TraceEvent::Restart { iteration: 2, .. })));
+        let mut replay = self::replay(&event_log);
+        let mut replayed = vec![];
+        while let Some(event) = replay.step() {
+            if matches!(event, TraceEvent::Accepted { .. }) {
+                replayed.push(replay.get_current_solution().unwrap().clone());
+            }
+        }
+        assert_eq!(accepted_solutions.lock().unwrap().0, replayed);
+        assert_eq!(best, replay.get_best_solution().unwrap().clone());
+    }
+
+    #[test]
+    fn start_is_recorded_before_a_restart_in_the_first_round() {
+        let accepted_solutions = Arc::new(Mutex::new(AcceptedSolutions::default()));
+        let (_, event_log) = execute_logged(2, 1, &accepted_solutions);
+        let mut replay = replay(&event_log);
+
+        assert!(matches!(replay.get_events()[0], TraceEvent::Start { .. }));
+        assert!(matches!(
+            replay.get_events()[1],
+            TraceEvent::Restart { iteration: 1, .. }
+        ));
+        assert_eq!(accepted_solutions.lock().unwrap().0.last(), replay.finish());
+    }
+}
@@ -0,0 +1,72 @@
+//! fixtures holds the small problems that the tests of several modules share.
+use crate::assignment::Assignment;
+use crate::constraints::{constraint, ConstraintLevel, ConstraintScoreCalculator};
+use crate::local_search::{Solution, SolutionScoreCalculator};
+use crate::score::{HardSoftScoring, IntegerHardSoft};
+
+/// Bits is a solution with a small u8 value, e.g. a bit or a color, per variable.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Bits(pub Vec<u8>);
+impl Solution for Bits {}
+impl Assignment for Bits {
+    type Value = u8;
+
+    fn get_variable_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get_value(&self, variable: usize) -> u8 {
+        self.0[variable]
+    }
+
+    fn set_value(&mut self, variable: usize, value: u8) {
+        self.0[variable] = value;
+    }
+}
+
+/// Score Bits with a single hard constraint, "no ones", that counts the variables set to 1.
+pub fn new_no_ones_score_calculator() -> ConstraintScoreCalculator<Bits, IntegerHardSoft> {
+    let mut calculator = ConstraintScoreCalculator::new();
+    calculator.add_constraint(constraint("no ones", ConstraintLevel::Hard, |solution: &Bits| {
+        solution.0.iter().filter(|value| **value == 1).count() as f64
+    }));
+    calculator
+}
+
+/// Check that score_calculator, which wraps the calculator of new_no_ones_score_calculator, lets verify list the
+/// violations of the wrapped calculator.
+pub fn assert_verify_lists_no_ones<_SSC>(score_calculator: &_SSC)
+where
+    _SSC: SolutionScoreCalculator<_Solution = Bits>,
+    _SSC::_Score: HardSoftScoring,
+{
+    assert_eq!(Ok(()), score_calculator.verify(&Bits(vec![0, 0, 0])));
+    let violations = score_calculator.verify(&Bits(vec![1, 0, 1])).unwrap_err();
+    assert_eq!(
+        vec![("no ones".to_string(), 2.0)],
+        violations
+            .into_iter()
+            .map(|violation| (violation.name, violation.score))
+            .collect::<Vec<(String, f64)>>()
+    );
+}
+
+/// PartialBits is Bits where a variable may not be assigned yet.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PartialBits(pub Vec<Option<u8>>);
+impl Solution for PartialBits {}
+impl Assignment for PartialBits {
+    type Value = Option<u8>;
+
+    fn get_variable_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get_value(&self, variable: usize) -> Option<u8> {
+        self.0[variable]
+    }
+
+    fn set_value(&mut self, variable: usize, value: Option<u8>) {
+        self.0[variable] = value;
+    }
+}
@@ -0,0 +1,219 @@
+//! incremental lets a problem domain score a move from the change it makes instead of rescoring the whole solution.
+//! Most moves only affect a few constraints, so the delta can usually be computed in time proportional to the affected
+//! constraints. MoveLocalSearch and LocalSearch use an IncrementalScoreCalculator when they are given one, see
+//! MoveLocalSearch::set_incremental_score_calculator and LocalSearch::set_incremental_score_calculator, and fall back
+//! to the SolutionScoreCalculator otherwise.
+use crate::local_search::{Score, Solution};
+use crate::moves::{Move, NeighborMove};
+
+/// IncrementalScoreCalculator computes how a move would change the score of a solution without making it. The score of
+/// a solution after a move must equal what the SolutionScoreCalculator computes for it.
+pub trait IncrementalScoreCalculator {
+    type _Solution: Solution;
+    type _Score: Score;
+    type _Move: Move<_Solution = Self::_Solution>;
+    type _ScoreDelta;
+
+    /// Get the change in score that making move on solution would cause. solution is the solution before the move.
+    fn delta_for_move(&self, solution: &Self::_Solution, move_: &Self::_Move) -> Self::_ScoreDelta;
+
+    /// Get the score that results from changing score by delta.
+    fn apply_delta(&self, score: &Self::_Score, delta: &Self::_ScoreDelta) -> Self::_Score;
+}
+
+/// MoveScoreCalculator hides the _ScoreDelta of an IncrementalScoreCalculator so that it can be boxed.
+pub(crate) trait MoveScoreCalculator {
+    type _Solution: Solution;
+    type _Score: Score;
+    type _Move: Move<_Solution = Self::_Solution>;
+
+    fn get_move_score(
+        &self,
+        solution: &Self::_Solution,
+        score: &Self::_Score,
+        move_: &Self::_Move,
+    ) -> Self::_Score;
+}
+
+impl<_ISC> MoveScoreCalculator for _ISC
+where
+    _ISC: IncrementalScoreCalculator,
+{
+    type _Solution = _ISC::_Solution;
+    type _Score = _ISC::_Score;
+    type _Move = _ISC::_Move;
+
+    fn get_move_score(
+        &self,
+        solution: &Self::_Solution,
+        score: &Self::_Score,
+        move_: &Self::_Move,
+    ) -> Self::_Score {
+        self.apply_delta(score, &self.delta_for_move(solution, move_))
+    }
+}
+
+/// NeighborScoreCalculator scores the neighbors that a MoveProposer yields with an IncrementalScoreCalculator, for
+/// LocalSearch, which gets whole neighbors instead of moves. It hides the _Move and _ScoreDelta so that it can be
+/// boxed.
+pub(crate) trait NeighborScoreCalculator {
+    type _Solution: Solution;
+    type _Score: Score;
+
+    /// Get the score of neighbor from solution and its score, or None if neighbor isn't a single move away from
+    /// solution, and has to be scored in full.
+    fn get_neighbor_score(
+        &self,
+        solution: &Self::_Solution,
+        score: &Self::_Score,
+        neighbor: &Self::_Solution,
+    ) -> Option<Self::_Score>;
+}
+
+impl<_ISC> NeighborScoreCalculator for _ISC
+where
+    _ISC: IncrementalScoreCalculator,
+    _ISC::_Move: NeighborMove,
+{
+    type _Solution = _ISC::_Solution;
+    type _Score = _ISC::_Score;
+
+    fn get_neighbor_score(
+        &self,
+        solution: &Self::_Solution,
+        score: &Self::_Score,
+        neighbor: &Self::_Solution,
+    ) -> Option<Self::_Score> {
+        let move_ = _ISC::_Move::from_neighbor(solution, neighbor)?;
+        Some(self.get_move_score(solution, score, &move_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use rand::SeedableRng;
+
+    use crate::fixtures::Bits;
+    use crate::incremental::IncrementalScoreCalculator;
+    use crate::local_search::{
+        LocalSearchBuilder, MoveProposer, Score, ScoredSolution, SolutionScoreCalculator,
+    };
+    use crate::modeling::ListDomain;
+    use crate::moves::{ChangeValueMove, ChangeValueMoveGenerator, Move, MoveGenerator, MoveLocalSearch};
+    use crate::termination::MaxIterations;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Zeros(u64);
+    impl Score for Zeros {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    /// Best with all bits set.
+    struct CountZeros;
+    impl SolutionScoreCalculator for CountZeros {
+        type _Solution = Bits;
+        type _Score = Zeros;
+
+        fn get_scored_solution(&self, solution: Bits) -> ScoredSolution<Bits, Zeros> {
+            let zeros = solution.0.iter().filter(|bit| **bit == 0).count() as u64;
+            ScoredSolution::new(solution, Zeros(zeros))
+        }
+    }
+
+    impl IncrementalScoreCalculator for CountZeros {
+        type _Solution = Bits;
+        type _Score = Zeros;
+        type _Move = ChangeValueMove<Bits>;
+        type _ScoreDelta = i64;
+
+        fn delta_for_move(&self, _solution: &Bits, move_: &ChangeValueMove<Bits>) -> i64 {
+            (move_.to == 0) as i64 - (move_.from == 0) as i64
+        }
+
+        fn apply_delta(&self, score: &Zeros, delta: &i64) -> Zeros {
+            Zeros(score.0.checked_add_signed(*delta).unwrap())
+        }
+    }
+
+    #[test]
+    fn deltas_match_rescoring() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let solution = Bits(vec![0, 1, 2, 0, 1]);
+        let score = CountZeros.get_scored_solution(solution.clone()).score;
+        let generator = ChangeValueMoveGenerator::new(ListDomain::uniform(5, vec![0, 1, 2]), 5);
+        for move_ in generator.iter_moves(&solution, &mut rng) {
+            let incremental = CountZeros.apply_delta(&score, &CountZeros.delta_for_move(&solution, &move_));
+            let mut moved = solution.clone();
+            move_.apply(&mut moved);
+            assert_eq!(CountZeros.get_scored_solution(moved).score, incremental);
+        }
+    }
+
+    /// CountZeros that counts how many solutions it scored in full.
+    struct CountingCountZeros(Rc<Cell<usize>>);
+    impl SolutionScoreCalculator for CountingCountZeros {
+        type _Solution = Bits;
+        type _Score = Zeros;
+
+        fn get_scored_solution(&self, solution: Bits) -> ScoredSolution<Bits, Zeros> {
+            self.0.set(self.0.get() + 1);
+            CountZeros.get_scored_solution(solution)
+        }
+    }
+
+    #[test]
+    fn move_local_search_uses_the_incremental_score_calculator() {
+        let full_scorings = Rc::new(Cell::new(0));
+        let mut local_search = MoveLocalSearch::new(
+            ChangeValueMoveGenerator::new(ListDomain::uniform(16, vec![0, 1]), 16),
+            CountingCountZeros(full_scorings.clone()),
+            16,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        local_search.set_incremental_score_calculator(CountZeros);
+        let result = local_search.execute_until(Bits(vec![0; 16]), &MaxIterations(100));
+        assert_eq!(Zeros(0), result.score);
+        assert_eq!(Bits(vec![1; 16]), result.solution);
+        assert_eq!(1, full_scorings.get());
+    }
+
+    /// Sets one zero of a solution to one, for every zero.
+    struct SetOneMoveProposer;
+    impl MoveProposer for SetOneMoveProposer {
+        type R = rand_chacha::ChaCha20Rng;
+        type Solution = Bits;
+
+        fn iter_local_moves(&self, start: &Bits, _rng: &mut Self::R) -> Box<dyn Iterator<Item = Bits>> {
+            let neighbors: Vec<Bits> = (0..start.0.len())
+                .filter(|index| start.0[*index] == 0)
+                .map(|index| {
+                    let mut neighbor = start.clone();
+                    neighbor.0[index] = 1;
+                    neighbor
+                })
+                .collect();
+            Box::new(neighbors.into_iter())
+        }
+    }
+
+    #[test]
+    fn local_search_uses_the_incremental_score_calculator() {
+        let full_scorings = Rc::new(Cell::new(0));
+        let mut local_search = LocalSearchBuilder::new(
+            SetOneMoveProposer,
+            CountingCountZeros(full_scorings.clone()),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .build();
+        local_search.set_incremental_score_calculator(CountZeros);
+        let result = local_search.execute(Bits(vec![0; 16]), 5);
+        assert_eq!(Zeros(0), result.score);
+        assert_eq!(Bits(vec![1; 16]), result.solution);
+        assert_eq!(1, full_scorings.get());
+    }
+}
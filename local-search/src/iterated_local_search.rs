@@ -2,53 +2,153 @@
 ///
 /// [1] Lourenço, Helena Ramalhinho, Olivier C. Martin and Thomas Stützle. "Iterated Local Search: Framework and
 /// Applications." (2010).
+use std::collections::VecDeque;
+use std::future::Future;
 use std::marker::PhantomData;
+use std::num::NonZeroU64;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::mpsc::Sender;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::branch_and_bound::ExactSolver;
+#[cfg(feature = "serde")]
+use crate::checkpoint::CheckpointPolicy;
+use crate::config::{AcceptanceConfig, IteratedLocalSearchConfig};
+use crate::constraints::ConstraintId;
+use crate::convergence::ConvergenceRecorder;
+use crate::error::{check_weights, SolverError};
 use crate::local_search::History;
 use crate::local_search::InitialSolutionGenerator;
 use crate::local_search::LocalSearch;
+#[cfg(feature = "serde")]
+use crate::local_search::LocalSearchState;
 use crate::local_search::MoveProposer;
 use crate::local_search::Score;
 use crate::local_search::ScoredSolution;
 use crate::local_search::Solution;
 use crate::local_search::SolutionScoreCalculator;
+use crate::observer::Observer;
+use crate::progress::ProgressEvent;
+use crate::repair::RepairOperator;
+use crate::restart::{FixedIntervalRestart, RestartPolicy, RestartState};
+use crate::score::HardSoftScoring;
+use crate::stats::{BestScoreSample, SolverStats};
+use crate::termination::{CancellationToken, MinImprovement, ScoreThreshold, Termination, TerminationState};
+use crate::trace::TraceSink;
 use rand::prelude::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde::ser::SerializeStruct;
 use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serializer};
+#[cfg(feature = "serde")]
+use std::fs::File;
+#[cfg(feature = "serde")]
+use std::io::{BufReader, BufWriter, Write};
 
 /// AcceptanceCriterion takes the old local minima and new local minima, combines it with the history, and determines
-/// which one to use.
-#[derive(Derivative)]
-#[derivative(Default)]
-pub struct AcceptanceCriterion<_R, _Solution, _Score, _SSC>
+/// which one to use. DefaultAcceptanceCriterion is used unless IteratedLocalSearch is given another implementation,
+/// e.g. SimulatedAnnealingAcceptanceCriterion.
+pub trait AcceptanceCriterion {
+    type _R: rand::Rng;
+    type _Solution: Solution;
+    type _Score: Score;
+
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<Self::_Solution, Self::_Score>,
+        new_local_minima: &ScoredSolution<Self::_Solution, Self::_Score>,
+        history: &History<Self::_R, Self::_Solution, Self::_Score>,
+        rng: &mut Self::_R,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score>;
+}
+
+/// DefaultAcceptanceCriterion is a weighted random pick that favours the new local minima, but sometimes keeps the
+/// existing one or jumps back to one of the best solutions in the history. The weights default to 1 for the existing
+/// local minima, 5 for the new one and 1 for a random best solution, see with_weights.
+pub struct DefaultAcceptanceCriterion<_R, _Solution, _Score>
 where
     _R: rand::Rng,
     _Solution: Solution,
     _Score: Score,
-    _SSC: SolutionScoreCalculator,
 {
+    existing_weight: u64,
+    new_weight: u64,
+    random_best_weight: u64,
     phantom_r: PhantomData<_R>,
     phantom_solution: PhantomData<_Solution>,
     phantom_score: PhantomData<_Score>,
-    phantom_ssc: PhantomData<_SSC>,
 }
 
-impl<_R, _Solution, _Score, _SSC> AcceptanceCriterion<_R, _Solution, _Score, _SSC>
+impl<_R, _Solution, _Score> Default for DefaultAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<_R, _Solution, _Score> DefaultAcceptanceCriterion<_R, _Solution, _Score>
 where
     _R: rand::Rng,
     _Solution: Solution,
     _Score: Score,
-    _SSC: SolutionScoreCalculator,
 {
     pub fn new() -> Self {
-        Self {
+        Self::with_weights(1, 5, 1).expect("the default weights are positive")
+    }
+
+    /// Pick the existing local minima, the new local minima, or a random best solution from the history in proportion
+    /// to these weights, e.g. a higher new_weight intensifies the search around the latest local minima. The history
+    /// may not have a best solution yet, so it fails if existing_weight and new_weight are both zero.
+    pub fn with_weights(
+        existing_weight: u64,
+        new_weight: u64,
+        random_best_weight: u64,
+    ) -> Result<Self, SolverError> {
+        check_weights(
+            "existing_weight or new_weight",
+            &[("existing", existing_weight), ("new", new_weight)],
+        )?;
+        Ok(Self {
+            existing_weight,
+            new_weight,
+            random_best_weight,
             phantom_r: PhantomData,
             phantom_solution: PhantomData,
             phantom_score: PhantomData,
-            phantom_ssc: PhantomData,
-        }
+        })
+    }
+
+    /// Create with the weights of config, see the config module. Shorthand for with_weights.
+    pub fn from_config(config: &AcceptanceConfig) -> Result<Self, SolverError> {
+        Self::with_weights(
+            config.existing_weight,
+            config.new_weight,
+            config.random_best_weight,
+        )
     }
+}
 
-    pub fn choose(
+impl<_R, _Solution, _Score> AcceptanceCriterion for DefaultAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn choose(
         &mut self,
         existing_local_minima: &ScoredSolution<_Solution, _Score>,
         new_local_minima: &ScoredSolution<_Solution, _Score>,
@@ -61,13 +161,182 @@ where
         let maybe_random_best_solution = history.get_random_best_solution(rng);
         let choices = match maybe_random_best_solution {
             Some(ref random_best_solution) => vec![
-                (existing_local_minima, 1),
-                (new_local_minima, 5),
-                (random_best_solution, 1),
+                (existing_local_minima, self.existing_weight),
+                (new_local_minima, self.new_weight),
+                (random_best_solution, self.random_best_weight),
             ],
-            None => vec![(existing_local_minima, 1), (new_local_minima, 5)],
+            None => vec![
+                (existing_local_minima, self.existing_weight),
+                (new_local_minima, self.new_weight),
+            ],
+        };
+        choices
+            .choose_weighted(rng, |item| item.1)
+            .expect("existing_weight and new_weight are checked in with_weights")
+            .0
+            .clone()
+    }
+}
+
+/// AcceptanceFn is an acceptance criterion made from a closure with the arguments of AcceptanceCriterion::choose, for
+/// quick experiments that don't warrant a new type, e.g.
+/// `AcceptanceFn::new(|existing, new, _history, _rng| std::cmp::min(existing, new).clone())`.
+pub struct AcceptanceFn<_R, _Solution, _Score, F>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    F: FnMut(
+        &ScoredSolution<_Solution, _Score>,
+        &ScoredSolution<_Solution, _Score>,
+        &History<_R, _Solution, _Score>,
+        &mut _R,
+    ) -> ScoredSolution<_Solution, _Score>,
+{
+    choose: F,
+    phantom_r: PhantomData<_R>,
+    phantom_solution: PhantomData<_Solution>,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_R, _Solution, _Score, F> AcceptanceFn<_R, _Solution, _Score, F>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    F: FnMut(
+        &ScoredSolution<_Solution, _Score>,
+        &ScoredSolution<_Solution, _Score>,
+        &History<_R, _Solution, _Score>,
+        &mut _R,
+    ) -> ScoredSolution<_Solution, _Score>,
+{
+    pub fn new(choose: F) -> Self {
+        Self {
+            choose,
+            phantom_r: PhantomData,
+            phantom_solution: PhantomData,
+            phantom_score: PhantomData,
+        }
+    }
+}
+
+impl<_R, _Solution, _Score, F> AcceptanceCriterion for AcceptanceFn<_R, _Solution, _Score, F>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    F: FnMut(
+        &ScoredSolution<_Solution, _Score>,
+        &ScoredSolution<_Solution, _Score>,
+        &History<_R, _Solution, _Score>,
+        &mut _R,
+    ) -> ScoredSolution<_Solution, _Score>,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<_Solution, _Score>,
+        new_local_minima: &ScoredSolution<_Solution, _Score>,
+        history: &History<_R, _Solution, _Score>,
+        rng: &mut _R,
+    ) -> ScoredSolution<_Solution, _Score> {
+        (self.choose)(existing_local_minima, new_local_minima, history, rng)
+    }
+}
+
+/// HomebaseStrategy decides which solution the next round perturbs, its homebase, once a round ends, following the
+/// homebase strategies of the ILS literature, see [1] at the top of this module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HomebaseStrategy {
+    /// Continue from the new local minima if it is at least as good as the existing one, and from the existing one
+    /// otherwise.
+    AcceptFromCurrent,
+
+    /// Always continue from the best solution found so far, which intensifies the search around it.
+    RestartToBest,
+
+    /// Continue from a random one of the best solutions of the history, its elite, which intensifies around good
+    /// solutions while keeping some diversity.
+    RestartToRandomElite,
+}
+
+type HomebaseBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P> = IteratedLocalSearchBuilder<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _MP,
+    _ISG,
+    _P,
+    HomebaseAcceptanceCriterion<_R, _Solution, _Score>,
+>;
+
+/// HomebaseAcceptanceCriterion is the acceptance criterion of a HomebaseStrategy, see
+/// IteratedLocalSearchBuilder::homebase_strategy. Unlike DefaultAcceptanceCriterion it doesn't mix strategies at random.
+pub struct HomebaseAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    strategy: HomebaseStrategy,
+    phantom_r: PhantomData<_R>,
+    phantom_solution: PhantomData<_Solution>,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_R, _Solution, _Score> HomebaseAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    pub fn new(strategy: HomebaseStrategy) -> Self {
+        Self {
+            strategy,
+            phantom_r: PhantomData,
+            phantom_solution: PhantomData,
+            phantom_score: PhantomData,
+        }
+    }
+
+    pub fn get_strategy(&self) -> &HomebaseStrategy {
+        &self.strategy
+    }
+}
+
+impl<_R, _Solution, _Score> AcceptanceCriterion for HomebaseAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    /// The history already holds new_local_minima, so RestartToBest and RestartToRandomElite may pick it too.
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<_Solution, _Score>,
+        new_local_minima: &ScoredSolution<_Solution, _Score>,
+        history: &History<_R, _Solution, _Score>,
+        rng: &mut _R,
+    ) -> ScoredSolution<_Solution, _Score> {
+        let homebase = match self.strategy {
+            HomebaseStrategy::AcceptFromCurrent => None,
+            HomebaseStrategy::RestartToBest => history.get_best(),
+            HomebaseStrategy::RestartToRandomElite => history.get_random_best_solution(rng),
         };
-        choices.choose_weighted(rng, |item| item.1).unwrap().0.clone()
+        match homebase {
+            Some(homebase) => homebase,
+            None if new_local_minima.score <= existing_local_minima.score => new_local_minima.clone(),
+            None => existing_local_minima.clone(),
+        }
     }
 }
 
@@ -85,16 +354,208 @@ pub trait Perturbation {
         history: &History<Self::_R, Self::_Solution, Self::_Score>,
         rng: &mut Self::_R,
     ) -> Self::_Solution;
+
+    /// Name the perturbation, e.g. in IterationInfo::perturbation_used. Perturbations that choose between several
+    /// strategies can name the one they used last. Defaults to the type name.
+    fn get_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
 }
 
-#[derive(Serialize)]
-pub struct IterationInfo {
-    pub current: u64,
-    pub total: u64,
+/// Boxed perturbations are perturbations, so that e.g. BanditPerturbation can select among perturbations of different
+/// types.
+impl<_P> Perturbation for Box<_P>
+where
+    _P: Perturbation + ?Sized,
+{
+    type _R = _P::_R;
+    type _Solution = _P::_Solution;
+    type _Score = _P::_Score;
+    type _SSC = _P::_SSC;
+
+    fn propose_new_starting_solution(
+        &mut self,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        history: &History<Self::_R, Self::_Solution, Self::_Score>,
+        rng: &mut Self::_R,
+    ) -> Self::_Solution {
+        (**self).propose_new_starting_solution(current, history, rng)
+    }
 }
 
-pub struct IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P>
+/// PerturbationStrength decides how much of a solution a Perturbation changes. Right after the best solution improved
+/// it is min, so that the search keeps exploring near the new best solution, and every round without improvement it
+/// grows linearly, reaching max after rounds_to_max rounds, so that the search kicks harder the longer it is stuck.
+/// min and max are fractions of the solution, e.g. of the days of a schedule.
+///
+/// Call observe with the history once at the start of every propose_new_starting_solution, then get_size for how many
+/// parts of the solution to change.
+#[derive(Clone, Debug)]
+pub struct PerturbationStrength<_Score>
+where
+    _Score: Score,
+{
+    min: f64,
+    max: f64,
+    rounds_to_max: u64,
+    non_improving_rounds: u64,
+    best_score: Option<_Score>,
+}
+
+/// Start at 5% of the solution and grow to 50% over 10 rounds without improvement.
+impl<_Score> Default for PerturbationStrength<_Score>
+where
+    _Score: Score,
+{
+    fn default() -> Self {
+        Self {
+            min: 0.05,
+            max: 0.5,
+            rounds_to_max: 10,
+            non_improving_rounds: 0,
+            best_score: None,
+        }
+    }
+}
+
+impl<_Score> PerturbationStrength<_Score>
+where
+    _Score: Score,
+{
+    /// Fail unless 0 < min <= max <= 1 and rounds_to_max is positive.
+    pub fn new(min: f64, max: f64, rounds_to_max: u64) -> Result<Self, SolverError> {
+        if min.is_nan() || min <= 0.0 {
+            return Err(SolverError::InvalidConfiguration(
+                "min must be positive".to_string(),
+            ));
+        }
+        if max.is_nan() || min > max {
+            return Err(SolverError::InvalidConfiguration(
+                "min must be at most max".to_string(),
+            ));
+        }
+        if max > 1.0 {
+            return Err(SolverError::InvalidConfiguration(
+                "max must be at most 1".to_string(),
+            ));
+        }
+        if rounds_to_max == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "rounds_to_max must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            min,
+            max,
+            rounds_to_max,
+            ..Self::default()
+        })
+    }
+
+    /// Count the round as non-improving unless the best solution in history is better than at the last call.
+    pub fn observe<_R, _Solution>(&mut self, history: &History<_R, _Solution, _Score>)
+    where
+        _R: rand::Rng,
+        _Solution: Solution,
+    {
+        match history.get_best() {
+            Some(best) if self.best_score.as_ref().is_none_or(|score| best.score < *score) => {
+                self.non_improving_rounds = 0;
+                self.best_score = Some(best.score);
+            }
+            _ => self.non_improving_rounds += 1,
+        }
+    }
+
+    pub fn get_non_improving_rounds(&self) -> u64 {
+        self.non_improving_rounds
+    }
+
+    /// The current strength, between min and max.
+    pub fn get_strength(&self) -> f64 {
+        let progress = self.non_improving_rounds.min(self.rounds_to_max) as f64 / self.rounds_to_max as f64;
+        self.min + (self.max - self.min) * progress
+    }
+
+    /// How many of total parts of a solution to change at the current strength, at least 1 and at most total.
+    pub fn get_size(&self, total: usize) -> usize {
+        ((self.get_strength() * total as f64).ceil() as usize).clamp(1, total.max(1))
+    }
+}
+
+type BuildResult<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> =
+    Result<IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>, SolverError>;
+
+type RepairOperatorBox<_R, _Solution> = Box<dyn RepairOperator<_R = _R, _Solution = _Solution> + Send>;
+
+/// WriteCheckpoint writes an IteratedLocalSearch to a path. set_checkpoint_policy keeps one, because only there the
+/// state of the solver is known to be serializable.
+#[cfg(feature = "serde")]
+type WriteCheckpoint<_ILS> = fn(&_ILS, &Path) -> std::io::Result<()>;
+
+/// YieldNow is a future that is pending once, to hand control back to the executor, see
+/// IteratedLocalSearch::solve_async. It wakes itself right away, so it works with any executor.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        context.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// StopCondition finishes IteratedLocalSearch once it returns true, see IteratedLocalSearchBuilder::with_stop_condition.
+type StopCondition<_Score> = Box<dyn Fn(&IterationInfo<_Score>) -> bool + Send>;
+
+/// IterationInfo is how far IteratedLocalSearch is, see get_iteration_info, and, kept for every round, what happened in
+/// the round, see set_iteration_history_capacity.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct IterationInfo<_Score>
 where
+    _Score: Score,
+{
+    /// Rounds executed so far, i.e. the iteration of the last round.
+    pub iteration: u64,
+
+    /// The max_iterations rounds after which the solver is finished.
+    pub total: u64,
+
+    /// The score of the best solution so far, or None before the first round.
+    pub best_score: Option<_Score>,
+
+    /// The score of the current solution, at the end of a round as chosen by the acceptance criterion.
+    pub current_score: _Score,
+
+    /// Time since the first round started, zero before the first round.
+    pub elapsed: Duration,
+
+    /// The name of the perturbation that proposed the starting solution of the last round, see
+    /// Perturbation::get_name, or None before the first perturbation.
+    pub perturbation_used: Option<String>,
+
+    /// Restarts so far, including one at the start of the last round.
+    pub restarts: u64,
+
+    /// The constraint violations of the best solution so far, see History::get_best_constraint_violations.
+    pub best_constraint_violations: Option<Vec<(ConstraintId, u64)>>,
+}
+
+pub struct IteratedLocalSearch<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _MP,
+    _ISG,
+    _P,
+    _AC = DefaultAcceptanceCriterion<_R, _Solution, _Score>,
+> where
     _R: rand::Rng,
     _Score: Score,
     _Solution: Solution,
@@ -102,18 +563,44 @@ where
     _MP: MoveProposer<R = _R, Solution = _Solution>,
     _ISG: InitialSolutionGenerator,
     _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
 {
     initial_solution_generator: _ISG,
     solution_score_calculator: _SSC,
     local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
     perturbation: _P,
     history: History<_R, _Solution, _Score>,
-    acceptance_criterion: AcceptanceCriterion<_R, _Solution, _Score, _SSC>,
+    acceptance_criterion: _AC,
     iteration: u64,
     max_iterations: u64,
     max_allow_no_improvement_for: u64,
     rng: _R,
     current: ScoredSolution<_Solution, _Score>,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+    trace_sink: Option<Box<dyn TraceSink<_Solution = _Solution, _Score = _Score> + Send>>,
+    exact_solver: Option<Box<dyn ExactSolver<_Solution = _Solution, _Score = _Score> + Send>>,
+    deadline: Option<Instant>,
+    rounds_without_improvement: u64,
+    min_improvement: Option<MinImprovement<_Score>>,
+
+    /// The best score when an improvement was last counted, see set_min_improvement.
+    plateau_score: Option<_Score>,
+    restart_policy: Box<dyn RestartPolicy + Send>,
+    repair_operator: Option<RepairOperatorBox<_R, _Solution>>,
+    rounds_since_restart: u64,
+    score_threshold: Option<ScoreThreshold<_Score>>,
+    stop_condition: Option<StopCondition<_Score>>,
+    cancellation_token: Option<CancellationToken>,
+    observers: Vec<Box<dyn Observer<_Solution = _Solution, _Score = _Score> + Send>>,
+    progress_sender: Option<Sender<ProgressEvent<_Score>>>,
+    started: Option<Instant>,
+    stats: SolverStats<_Score>,
+    convergence_trace: Option<ConvergenceRecorder<_Score>>,
+    iteration_history: VecDeque<IterationInfo<_Score>>,
+    iteration_history_capacity: usize,
+    perturbation_used: Option<String>,
+    #[cfg(feature = "serde")]
+    checkpoint: Option<(CheckpointPolicy, WriteCheckpoint<Self>)>,
 }
 
 impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P>
@@ -126,6 +613,40 @@ where
     _MP: MoveProposer<R = _R, Solution = _Solution>,
     _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
     _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+{
+    /// Create from the settings of config, see the config module, with DefaultAcceptanceCriterion. Shorthand for
+    /// IteratedLocalSearchBuilder::new(...).config(config).try_build().
+    pub fn from_config(
+        initial_solution_generator: _ISG,
+        solution_score_calculator: _SSC,
+        local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+        perturbation: _P,
+        rng: _R,
+        config: &IteratedLocalSearchConfig,
+    ) -> Result<Self, SolverError> {
+        IteratedLocalSearchBuilder::new(
+            initial_solution_generator,
+            solution_score_calculator,
+            local_search,
+            perturbation,
+            rng,
+        )
+        .config(config)
+        .try_build()
+    }
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
 {
     pub fn new(
         initial_solution_generator: _ISG,
@@ -133,97 +654,1076 @@ where
         local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
         perturbation: _P,
         history: History<_R, _Solution, _Score>,
-        acceptance_criterion: AcceptanceCriterion<_R, _Solution, _Score, _SSC>,
+        acceptance_criterion: _AC,
         max_iterations: u64,
         max_allow_no_improvement_for: u64,
-        mut rng: _R,
+        rng: _R,
     ) -> Self {
-        let current = solution_score_calculator
-            .get_scored_solution(initial_solution_generator.generate_initial_solution(&mut rng));
-        IteratedLocalSearch {
+        Self::from_builder(IteratedLocalSearchBuilder {
             initial_solution_generator,
             solution_score_calculator,
             local_search,
             perturbation,
+            rng,
             history,
             acceptance_criterion,
-            iteration: 0,
             max_iterations,
             max_allow_no_improvement_for,
+            start_solution: None,
+            initial_population_size: 1,
+            improve_initial_population: false,
+            restart_policy: Box::new(FixedIntervalRestart(50)),
+            repair_operator: None,
+            stop_condition: None,
+        })
+    }
+
+    /// Start from the start_solution of builder if it has one, else from the best of its initial population, see
+    /// IteratedLocalSearchBuilder::initial_population_size. A start_solution or an improved initial solution is also
+    /// added to the history, see set_start_solution.
+    fn from_builder(
+        builder: IteratedLocalSearchBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    ) -> Self {
+        let mut rng = builder.rng;
+        let mut local_search = builder.local_search;
+        let imports_start = builder.start_solution.is_some() || builder.improve_initial_population;
+        let current = match builder.start_solution {
+            Some(start_solution) => builder
+                .solution_score_calculator
+                .get_scored_solution(start_solution),
+            None => (0..builder.initial_population_size)
+                .map(|_| {
+                    let initial_solution = builder
+                        .initial_solution_generator
+                        .generate_initial_solution(&mut rng);
+                    match builder.improve_initial_population {
+                        true => local_search.execute(initial_solution, builder.max_allow_no_improvement_for),
+                        false => builder
+                            .solution_score_calculator
+                            .get_scored_solution(initial_solution),
+                    }
+                })
+                .min()
+                .unwrap(),
+        };
+        let mut iterated_local_search = IteratedLocalSearch {
+            initial_solution_generator: builder.initial_solution_generator,
+            solution_score_calculator: builder.solution_score_calculator,
+            local_search,
+            perturbation: builder.perturbation,
+            history: builder.history,
+            acceptance_criterion: builder.acceptance_criterion,
+            iteration: 0,
+            max_iterations: builder.max_iterations,
+            max_allow_no_improvement_for: builder.max_allow_no_improvement_for,
             rng,
             current,
+            best_solution_handle: BestSolutionHandle::new(),
+            trace_sink: None,
+            exact_solver: None,
+            deadline: None,
+            rounds_without_improvement: 0,
+            min_improvement: None,
+            plateau_score: None,
+            restart_policy: builder.restart_policy,
+            repair_operator: builder.repair_operator,
+            rounds_since_restart: 0,
+            score_threshold: None,
+            stop_condition: builder.stop_condition,
+            cancellation_token: None,
+            observers: vec![],
+            progress_sender: None,
+            started: None,
+            stats: SolverStats::default(),
+            convergence_trace: None,
+            iteration_history: VecDeque::new(),
+            iteration_history_capacity: 0,
+            perturbation_used: None,
+            #[cfg(feature = "serde")]
+            checkpoint: None,
+        };
+        if imports_start {
+            iterated_local_search.import_solution(iterated_local_search.current.clone());
         }
+        iterated_local_search
     }
 
-    pub fn get_iteration_info(&self) -> IterationInfo {
+    /// Get how many rounds were executed so far out of how many. Together with execute_round and is_finished this lets
+    /// a caller drive the solver one round at a time, e.g. to report progress from an event loop or a web worker:
+    /// `while !solver.is_finished() { solver.execute_round(); report(solver.get_iteration_info()); }`.
+    pub fn get_iteration_info(&self) -> IterationInfo<_Score> {
         IterationInfo {
-            current: self.iteration,
+            iteration: self.iteration,
             total: self.max_iterations,
+            best_score: self.best_solution_handle.get_score(),
+            current_score: self.current.score.clone(),
+            elapsed: self.started.map_or(Duration::ZERO, |started| started.elapsed()),
+            perturbation_used: self.perturbation_used.clone(),
+            restarts: self.stats.restarts,
+            best_constraint_violations: self
+                .history
+                .get_best_constraint_violations()
+                .map(|violations| violations.to_vec()),
         }
     }
 
+    /// Get the best solution found so far. Panics before the first round, see try_get_best_solution.
     pub fn get_best_solution(&self) -> ScoredSolution<_Solution, _Score> {
-        self.history.get_best().unwrap()
+        self.try_get_best_solution()
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    pub fn is_finished(&self) -> bool {
-        self.iteration >= self.max_iterations
+    /// Get the best solution found so far, or SolverError::NoSolution before the first round.
+    pub fn try_get_best_solution(&self) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        self.history.get_best().ok_or(SolverError::NoSolution)
     }
 
-    pub fn execute_round(&mut self) {
-        self.iteration += 1;
-        if let Some(best) = self.history.get_best() {
-            println!(
-                "iterated local search best score: {:?}, current score {:?}",
-                &best.score, &self.current.score
-            );
-            if best.score.is_best() {
-                println!("iterated local search found best possible solution and is terminating");
-                return;
-            }
-        }
-        if self.iteration > 0 && self.iteration % 50 == 0 {
-            println!("reset from random");
-            self.current = self.solution_score_calculator.get_scored_solution(
-                self.initial_solution_generator
-                    .generate_initial_solution(&mut self.rng),
-            );
-        }
-        let perturbed =
-            self.perturbation
-                .propose_new_starting_solution(&self.current, &self.history, &mut self.rng);
-        let new = self
-            .local_search
-            .execute(perturbed, self.max_allow_no_improvement_for);
-        self.history.local_search_chose_solution(new.clone());
-        self.current = self
-            .acceptance_criterion
-            .choose(&self.current, &new, &self.history, &mut self.rng);
+    /// The Pareto front of the local minima found so far, see History::set_pareto_archive, or None if the history
+    /// has no Pareto archive.
+    pub fn get_pareto_front(&self) -> Option<Vec<ScoredSolution<_Solution, _Score>>> {
+        self.history.get_pareto_front()
     }
-}
 
-#[cfg(test)]
-mod ackley_tests {
-    use approx::assert_abs_diff_eq;
-    use rand::SeedableRng;
+    /// How many rounds in a row have not improved on the best solution, or only insignificantly, see
+    /// set_min_improvement.
+    pub fn get_rounds_without_improvement(&self) -> u64 {
+        self.rounds_without_improvement
+    }
 
-    use crate::ackley::AckleyPerturbation;
-    use crate::ackley::{
-        AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleyScore, AckleySolution,
-        AckleySolutionScoreCalculator,
-    };
-    use crate::iterated_local_search::AcceptanceCriterion;
-    use crate::iterated_local_search::History;
-    use crate::iterated_local_search::IteratedLocalSearch;
-    use crate::local_search::LocalSearch;
-    use crate::local_search::ScoredSolution;
+    /// Only count rounds that improve the best solution significantly according to min_improvement, see
+    /// MinImprovement, so that e.g. Stagnation stops execute on a plateau of tiny improvements. Defaults to counting
+    /// every improvement. Set one on the LocalSearch too to end its rounds on plateaus.
+    pub fn set_min_improvement(&mut self, min_improvement: Option<MinImprovement<_Score>>) {
+        self.min_improvement = min_improvement;
+    }
 
-    fn _ackley(dimensions: usize, seed: u64) -> ScoredSolution<AckleySolution, AckleyScore> {
-        let min_move_size = 1e-3;
-        let max_move_size = 0.5;
-        let local_search_max_iterations = 100_000;
-        let window_size = 500;
-        let best_solutions_capacity = 16;
+    /// Add a solution that was found elsewhere, e.g. by another solver, to the history, where perturbation and
+    /// acceptance can pick it up. It becomes the best solution if it is better than every solution found so far.
+    pub fn import_solution(&mut self, solution: ScoredSolution<_Solution, _Score>) {
+        self.best_solution_handle.offer(&solution);
+        self.history.local_search_chose_solution(solution);
+    }
+
+    /// Continue from solution, e.g. a previously saved one, instead of from the current solution. solution is also
+    /// added to the history like an imported solution, so the best solution found is never worse than it.
+    pub fn set_start_solution(&mut self, solution: _Solution) {
+        let start = self.solution_score_calculator.get_scored_solution(solution);
+        self.import_solution(start.clone());
+        self.current = start;
+    }
+
+    /// Like execute, but start from solution instead of the current solution, see set_start_solution.
+    pub fn execute_from<_T>(
+        &mut self,
+        solution: _Solution,
+        termination: &_T,
+    ) -> Result<ScoredSolution<_Solution, _Score>, SolverError>
+    where
+        _T: Termination<_Score>,
+    {
+        self.set_start_solution(solution);
+        self.execute(termination)
+    }
+
+    /// Get a handle that always holds the best solution found so far. The handle can be moved to another thread and
+    /// read while this solver keeps executing rounds.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    /// Record every round to trace_sink from now on, see the trace module. Tracing is off by default.
+    pub fn set_trace_sink(
+        &mut self,
+        trace_sink: Box<dyn TraceSink<_Solution = _Solution, _Score = _Score> + Send>,
+    ) {
+        self.trace_sink = Some(trace_sink);
+    }
+
+    /// If the instance is small enough for exact_solver, the first round solves it exactly instead of running local
+    /// search, and the search is finished afterwards.
+    pub fn set_exact_solver(
+        &mut self,
+        exact_solver: Box<dyn ExactSolver<_Solution = _Solution, _Score = _Score> + Send>,
+    ) {
+        self.exact_solver = Some(exact_solver);
+    }
+
+    /// Finish once time_limit has passed from now, or after max_iterations rounds, whichever comes first. The
+    /// local search of a round that is running at the deadline stops early too.
+    pub fn set_time_limit(&mut self, time_limit: Duration) {
+        let deadline = Instant::now() + time_limit;
+        self.deadline = Some(deadline);
+        self.local_search.set_deadline(Some(deadline));
+    }
+
+    /// Execute rounds for at most time_limit, see set_time_limit, and return the best solution found. At least one
+    /// round is always executed so that there is a best solution to return.
+    pub fn solve_for(
+        &mut self,
+        time_limit: Duration,
+    ) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        self.set_time_limit(time_limit);
+        loop {
+            self.execute_round()?;
+            if self.is_finished() {
+                break;
+            }
+        }
+        self.try_get_best_solution()
+    }
+
+    /// Execute rounds until termination says to stop, see the termination module, or until is_finished, and return
+    /// the best solution found. Iterations are rounds, and at least one round is always executed so that there is a
+    /// best solution to return.
+    pub fn execute<_T>(&mut self, termination: &_T) -> Result<ScoredSolution<_Solution, _Score>, SolverError>
+    where
+        _T: Termination<_Score>,
+    {
+        let started = Instant::now();
+        let first_iteration = self.iteration;
+        loop {
+            self.execute_round()?;
+            if self.is_terminated(termination, started, first_iteration) {
+                break;
+            }
+        }
+        self.try_get_best_solution()
+    }
+
+    /// Like execute, but as a future that yields to the executor every yield_every rounds, so that the solver can run
+    /// as a task of an async runtime, e.g. inside a tokio service, without starving other tasks and without a
+    /// dedicated blocking thread. A round itself is never interrupted, so the time between yield points is that of
+    /// yield_every rounds.
+    pub async fn solve_async<_T>(
+        &mut self,
+        termination: &_T,
+        yield_every: NonZeroU64,
+    ) -> Result<ScoredSolution<_Solution, _Score>, SolverError>
+    where
+        _T: Termination<_Score>,
+    {
+        let started = Instant::now();
+        let first_iteration = self.iteration;
+        loop {
+            self.execute_round()?;
+            if self.is_terminated(termination, started, first_iteration) {
+                break;
+            }
+            if (self.iteration - first_iteration) % yield_every.get() == 0 {
+                YieldNow(false).await;
+            }
+        }
+        self.try_get_best_solution()
+    }
+
+    /// Is the solver finished or does termination say to stop, for rounds since first_iteration that started at
+    /// started.
+    fn is_terminated<_T>(&self, termination: &_T, started: Instant, first_iteration: u64) -> bool
+    where
+        _T: Termination<_Score>,
+    {
+        let best = self.history.get_best();
+        let state = TerminationState {
+            iteration: self.iteration - first_iteration,
+            elapsed: started.elapsed(),
+            best_score: best.as_ref().map(|best| &best.score),
+            iterations_without_improvement: self.rounds_without_improvement,
+        };
+        self.is_finished() || termination.is_terminated(&state)
+    }
+
+    /// Decide when to restart from a new initial solution with restart_policy, see the restart module. Defaults to
+    /// FixedIntervalRestart(50).
+    pub fn set_restart_policy(&mut self, restart_policy: Box<dyn RestartPolicy + Send>) {
+        self.restart_policy = restart_policy;
+    }
+
+    /// Repair every perturbed solution with repair_operator before local search starts from it, see the repair
+    /// module, so that local search does not spend its steps on the gross violations a perturbation can cause. No
+    /// repair by default.
+    pub fn set_repair_operator(&mut self, repair_operator: RepairOperatorBox<_R, _Solution>) {
+        self.repair_operator = Some(repair_operator);
+    }
+
+    /// Finish as soon as the best solution reaches score_threshold, e.g. ScoreThreshold::feasible() to stop at the
+    /// first solution without hard constraint violations. The local search of each round stops there too.
+    pub fn set_score_threshold(&mut self, score_threshold: Option<ScoreThreshold<_Score>>) {
+        self.local_search.set_score_threshold(score_threshold.clone());
+        self.score_threshold = score_threshold;
+    }
+
+    /// Finish once stop_condition returns true for the iteration info after a round, see
+    /// IteratedLocalSearchBuilder::with_stop_condition.
+    pub fn set_stop_condition(&mut self, stop_condition: Option<StopCondition<_Score>>) {
+        self.stop_condition = stop_condition;
+    }
+
+    /// Finish once cancellation_token is cancelled. The local search of a round that is running at that moment stops
+    /// early too, so the best solution found so far is available promptly.
+    pub fn set_cancellation_token(&mut self, cancellation_token: Option<CancellationToken>) {
+        self.local_search
+            .set_cancellation_token(cancellation_token.clone());
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Call observer on every round from now on, see the observer module. To also observe the steps of the local
+    /// search, add an observer to the LocalSearch before building this solver.
+    pub fn add_observer(
+        &mut self,
+        observer: Box<dyn Observer<_Solution = _Solution, _Score = _Score> + Send>,
+    ) {
+        self.observers.push(observer);
+    }
+
+    /// Send a ProgressEvent at the end of every round from now on, or stop sending with None. Sending never blocks,
+    /// and stops by itself once the receiver is dropped.
+    pub fn set_progress_sender(&mut self, progress_sender: Option<Sender<ProgressEvent<_Score>>>) {
+        self.progress_sender = progress_sender;
+    }
+
+    /// Keep a ConvergenceSample for every round from now on, with wall time measured from the first round, or stop and
+    /// discard the trace with false. Recording is off by default.
+    pub fn set_record_convergence_trace(&mut self, record_convergence_trace: bool) {
+        self.convergence_trace = match record_convergence_trace {
+            true => Some(
+                self.convergence_trace
+                    .take()
+                    .unwrap_or_else(ConvergenceRecorder::every_round),
+            ),
+            false => None,
+        };
+    }
+
+    /// Get the trace recorded so far, or None if recording is off.
+    pub fn get_convergence_trace(&self) -> Option<&ConvergenceRecorder<_Score>> {
+        self.convergence_trace.as_ref()
+    }
+
+    /// Keep the IterationInfo at the end of each of the last iteration_history_capacity rounds from now on, or none
+    /// with 0, the default. Unlike the convergence trace the history stays bounded however long the solver runs.
+    pub fn set_iteration_history_capacity(&mut self, iteration_history_capacity: usize) {
+        self.iteration_history_capacity = iteration_history_capacity;
+        while self.iteration_history.len() > iteration_history_capacity {
+            self.iteration_history.pop_front();
+        }
+    }
+
+    /// Get the IterationInfo of the last rounds, oldest first, see set_iteration_history_capacity.
+    pub fn get_iteration_history(&self) -> &VecDeque<IterationInfo<_Score>> {
+        &self.iteration_history
+    }
+
+    /// Like execute, but also return the statistics of every round so far, including the moves of the local search.
+    pub fn execute_with_stats<_T>(
+        &mut self,
+        termination: &_T,
+    ) -> Result<(ScoredSolution<_Solution, _Score>, SolverStats<_Score>), SolverError>
+    where
+        _T: Termination<_Score>,
+    {
+        let best = self.execute(termination)?;
+        Ok((best, self.get_stats()))
+    }
+
+    /// Get the statistics of every round so far, including the moves of the local search.
+    pub fn get_stats(&self) -> SolverStats<_Score> {
+        let local_search_stats = self.local_search.get_stats();
+        SolverStats {
+            proposed_moves: local_search_stats.proposed_moves,
+            accepted_moves: local_search_stats.accepted_moves,
+            improving_moves: local_search_stats.improving_moves,
+            ..self.stats.clone()
+        }
+    }
+
+    /// Is the solver finished, i.e. did it execute max_iterations rounds, pass its deadline, find the best possible
+    /// solution, see Score::is_best, reach its score threshold, meet its stop condition or get cancelled.
+    /// execute_round still executes a round when it is finished, so callers that step the solver check is_finished
+    /// first.
+    pub fn is_finished(&self) -> bool {
+        self.iteration >= self.max_iterations
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            || self
+                .best_solution_handle
+                .get_score()
+                .is_some_and(|best_score| best_score.is_best())
+            || self.is_score_threshold_reached()
+            || self
+                .stop_condition
+                .as_ref()
+                .is_some_and(|stop_condition| stop_condition(&self.get_iteration_info()))
+            || self
+                .cancellation_token
+                .as_ref()
+                .is_some_and(|cancellation_token| cancellation_token.is_cancelled())
+    }
+
+    fn is_score_threshold_reached(&self) -> bool {
+        match self.score_threshold.as_ref() {
+            Some(score_threshold) => self
+                .best_solution_handle
+                .get_score()
+                .is_some_and(|best_score| score_threshold.is_reached(&best_score)),
+            None => false,
+        }
+    }
+
+    /// Execute one round: maybe restart, perturb the current solution, run the local search from it, and choose the
+    /// next current solution with the acceptance criterion. The best solution so far is available afterwards from
+    /// get_best_solution. A round does nothing once the best possible solution has been found.
+    pub fn execute_round(&mut self) -> Result<(), SolverError> {
+        self.iteration += 1;
+        let _span = tracing::debug_span!("iterated_local_search_round", iteration = self.iteration).entered();
+        let started = *self.started.get_or_insert_with(Instant::now);
+        if self.iteration == 1 {
+            for observer in self.observers.iter_mut() {
+                observer.on_start(&self.current);
+            }
+            if let Some(exact_solver) = self.exact_solver.as_ref() {
+                if exact_solver.is_small_enough(&self.current.solution) {
+                    tracing::info!("instance is small, solving exactly");
+                    let optimal = exact_solver.solve(self.current.solution.clone());
+                    self.history.local_search_chose_solution(optimal.clone());
+                    self.best_solution_handle.offer(&optimal);
+                    self.record_new_best(started, &optimal);
+                    self.current = optimal;
+                    self.iteration = self.max_iterations;
+                    return Ok(());
+                }
+            }
+        }
+        if let Some(best) = self.history.get_best() {
+            tracing::debug!(best_score = ?best.score, current_score = ?self.current.score, "round started");
+            if best.score.is_best() {
+                tracing::info!("found best possible solution, terminating");
+                return Ok(());
+            }
+        }
+        let restart_state = RestartState {
+            iteration: self.iteration,
+            rounds_since_restart: self.rounds_since_restart,
+            rounds_without_improvement: self.rounds_without_improvement,
+        };
+        self.rounds_since_restart += 1;
+        if self.restart_policy.should_restart(&restart_state) {
+            tracing::debug!("restarting from a new initial solution");
+            self.rounds_since_restart = 1;
+            self.current = self.solution_score_calculator.get_scored_solution(
+                self.initial_solution_generator
+                    .generate_initial_solution(&mut self.rng),
+            );
+            self.stats.restarts += 1;
+            for observer in self.observers.iter_mut() {
+                observer.on_restart(self.iteration, &self.current);
+            }
+        }
+        let perturbed =
+            self.perturbation
+                .propose_new_starting_solution(&self.current, &self.history, &mut self.rng);
+        if self.perturbation_used.as_deref() != Some(self.perturbation.get_name()) {
+            self.perturbation_used = Some(self.perturbation.get_name().to_string());
+        }
+        self.stats.perturbations += 1;
+        for observer in self.observers.iter_mut() {
+            observer.on_perturbation(self.iteration, &self.current, &perturbed);
+        }
+        let perturbed = match self.repair_operator.as_mut() {
+            Some(repair_operator) => repair_operator.repair(perturbed, &mut self.rng),
+            None => perturbed,
+        };
+        let new = self
+            .local_search
+            .execute(perturbed, self.max_allow_no_improvement_for);
+        match self.history.get_best() {
+            Some(best) if new.score >= best.score => self.rounds_without_improvement += 1,
+            _ => {
+                let is_significant = match (self.min_improvement.as_ref(), self.plateau_score.as_ref()) {
+                    (Some(min_improvement), Some(plateau_score)) => {
+                        min_improvement.is_significant(&new.score, plateau_score)
+                    }
+                    _ => true,
+                };
+                match is_significant {
+                    true => {
+                        self.rounds_without_improvement = 0;
+                        self.plateau_score = Some(new.score.clone());
+                    }
+                    false => self.rounds_without_improvement += 1,
+                }
+                self.record_new_best(started, &new);
+            }
+        }
+        self.history.local_search_chose_solution(new.clone());
+        self.best_solution_handle.offer(&new);
+        let best_score = self.try_get_best_solution()?.score;
+        let chosen = self
+            .acceptance_criterion
+            .choose(&self.current, &new, &self.history, &mut self.rng);
+        if let Some(trace_sink) = self.trace_sink.as_mut() {
+            if let Err(err) = trace_sink.record(self.iteration, &self.current, &new, chosen == new) {
+                tracing::warn!(%err, "failed to record trace event, disabling tracing");
+                self.trace_sink = None;
+            }
+        }
+        for observer in self.observers.iter_mut() {
+            observer.on_iteration(self.iteration, &chosen);
+        }
+        if let Some(convergence_trace) = self.convergence_trace.as_mut() {
+            convergence_trace.record(self.iteration, started.elapsed(), &chosen.score, &best_score);
+        }
+        if let Some(progress_sender) = self.progress_sender.as_ref() {
+            let event = ProgressEvent {
+                iteration: self.iteration,
+                current_score: chosen.score.clone(),
+                best_score,
+                elapsed: started.elapsed(),
+            };
+            if progress_sender.send(event).is_err() {
+                self.progress_sender = None;
+            }
+        }
+        self.current = chosen;
+        if self.iteration_history_capacity > 0 {
+            if self.iteration_history.len() == self.iteration_history_capacity {
+                self.iteration_history.pop_front();
+            }
+            self.iteration_history.push_back(self.get_iteration_info());
+        }
+        #[cfg(feature = "serde")]
+        self.write_due_checkpoint();
+        Ok(())
+    }
+
+    /// Write a checkpoint if the checkpoint policy says one is due, see set_checkpoint_policy. A checkpoint that fails
+    /// to write is logged and skipped, the search goes on.
+    #[cfg(feature = "serde")]
+    fn write_due_checkpoint(&mut self) {
+        let (path, write_checkpoint) = match self.checkpoint.as_mut() {
+            Some((checkpoint_policy, write_checkpoint)) => {
+                if !checkpoint_policy.is_due(self.iteration, Instant::now()) {
+                    return;
+                }
+                (checkpoint_policy.get_path(self.iteration), *write_checkpoint)
+            }
+            None => return,
+        };
+        tracing::debug!(path = %path.display(), "writing checkpoint");
+        if let Err(err) = write_checkpoint(self, &path) {
+            tracing::warn!(%err, path = %path.display(), "failed to write checkpoint");
+        }
+    }
+
+    fn record_new_best(&mut self, started: Instant, best: &ScoredSolution<_Solution, _Score>) {
+        self.stats.best_score_timeline.push(BestScoreSample {
+            iteration: self.iteration,
+            elapsed: started.elapsed(),
+            best_score: best.score.clone(),
+        });
+        for observer in self.observers.iter_mut() {
+            observer.on_new_best(self.iteration, best);
+        }
+    }
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Score: HardSoftScoring + Serialize,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    /// Write the convergence trace to path as CSV, see set_record_convergence_trace and ConvergenceRecorder::write_csv.
+    /// Writes only the header row if recording is off.
+    pub fn write_trace_csv<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        match self.convergence_trace.as_ref() {
+            Some(convergence_trace) => convergence_trace.write_csv_file(path),
+            None => ConvergenceRecorder::<_Score>::every_round().write_csv_file(path),
+        }
+    }
+
+    /// Serialize the convergence trace as a JSON array, see set_record_convergence_trace. The array is empty if
+    /// recording is off.
+    pub fn to_trace_json(&self) -> serde_json::Result<String> {
+        match self.convergence_trace.as_ref() {
+            Some(convergence_trace) => convergence_trace.to_json(),
+            None => ConvergenceRecorder::<_Score>::every_round().to_json(),
+        }
+    }
+}
+
+/// IteratedLocalSearchState is what IteratedLocalSearch serializes to with the serde feature, see
+/// IteratedLocalSearch::restore.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct IteratedLocalSearchState<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    iteration: u64,
+    current: ScoredSolution<_Solution, _Score>,
+    history: History<_R, _Solution, _Score>,
+    local_search: LocalSearchState<_R, _Solution, _Score>,
+    rounds_without_improvement: u64,
+    rounds_since_restart: u64,
+    stats: SolverStats<_Score>,
+    rng: _R,
+
+    /// The Pareto front of the history, which History doesn't serialize itself. Missing in checkpoints of solvers
+    /// without a Pareto archive.
+    pareto_front: Option<Vec<ScoredSolution<_Solution, _Score>>>,
+
+    /// The round of the previous checkpoint of the checkpoint policy, see CheckpointPolicy::get_last_iteration. Missing
+    /// in checkpoints of solvers without a checkpoint policy.
+    last_checkpoint_iteration: Option<u64>,
+}
+
+/// With the serde feature IteratedLocalSearch serializes the state that changes from round to round, including that
+/// of its LocalSearch, the Pareto front of its History and the round of its previous checkpoint, so that it can be
+/// checkpointed and resumed, see restore. Its
+/// settings and the components it was built with are not serialized, nor is the state of components, e.g. the
+/// temperature of a SimulatedAnnealingAcceptanceCriterion.
+#[cfg(feature = "serde")]
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> Serialize
+    for IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng + Serialize,
+    _Score: Score + Serialize,
+    _Solution: Solution + Serialize,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("IteratedLocalSearch", 10)?;
+        state.serialize_field("iteration", &self.iteration)?;
+        state.serialize_field("current", &self.current)?;
+        state.serialize_field("history", &self.history)?;
+        state.serialize_field("local_search", &self.local_search)?;
+        state.serialize_field("rounds_without_improvement", &self.rounds_without_improvement)?;
+        state.serialize_field("rounds_since_restart", &self.rounds_since_restart)?;
+        state.serialize_field("stats", &self.stats)?;
+        state.serialize_field("rng", &self.rng)?;
+        state.serialize_field("pareto_front", &self.history.get_pareto_front())?;
+        state.serialize_field(
+            "last_checkpoint_iteration",
+            &self
+                .checkpoint
+                .as_ref()
+                .map(|(checkpoint_policy, _)| checkpoint_policy.get_last_iteration()),
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    /// Replace the state of this solver with one it was serialized to, e.g. a checkpoint written by a previous run, so
+    /// that it continues exactly where the serialized solver stopped. The solver must have been created with the same
+    /// settings and components as the serialized one for the search to continue the same way. Elapsed time restarts
+    /// from zero.
+    pub fn restore<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+        _R: Deserialize<'de>,
+        _Solution: Deserialize<'de>,
+        _Score: Deserialize<'de>,
+    {
+        let state = IteratedLocalSearchState::deserialize(deserializer)?;
+        if let Some(best) = state.history.get_best() {
+            self.best_solution_handle.offer(&best);
+        }
+        self.history.replace_with(state.history);
+        self.history
+            .replace_pareto_front(state.pareto_front.unwrap_or_default());
+        self.iteration = state.iteration;
+        self.current = state.current;
+        self.local_search.set_state(state.local_search);
+        self.rounds_without_improvement = state.rounds_without_improvement;
+        self.plateau_score = self.history.get_best().map(|best| best.score);
+        self.rounds_since_restart = state.rounds_since_restart;
+        self.stats = state.stats;
+        self.rng = state.rng;
+        if let (Some((checkpoint_policy, _)), Some(last_checkpoint_iteration)) =
+            (self.checkpoint.as_mut(), state.last_checkpoint_iteration)
+        {
+            checkpoint_policy.set_last_iteration(last_checkpoint_iteration);
+        }
+        self.started = None;
+        self.perturbation_used = None;
+        Ok(())
+    }
+
+    /// Restore the state of this solver from a checkpoint written by write_checkpoint, e.g. one that a checkpoint
+    /// policy wrote before the solve was stopped, see restore.
+    pub fn resume_from_checkpoint<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()>
+    where
+        _R: DeserializeOwned,
+        _Solution: DeserializeOwned,
+        _Score: DeserializeOwned,
+    {
+        let reader = BufReader::new(File::open(path)?);
+        self.restore(&mut serde_json::Deserializer::from_reader(reader))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng + Serialize,
+    _Score: Score + Serialize,
+    _Solution: Solution + Serialize,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    /// Write a checkpoint at the end of every round that checkpoint_policy says one is due from now on, see the
+    /// checkpoint module, or stop writing checkpoints with None. Set it before resuming from a checkpoint, so that it
+    /// counts rounds from the checkpoint's previous checkpoint, see restore.
+    pub fn set_checkpoint_policy(&mut self, checkpoint_policy: Option<CheckpointPolicy>) {
+        self.checkpoint = checkpoint_policy
+            .map(|checkpoint_policy| (checkpoint_policy, Self::write_checkpoint as WriteCheckpoint<Self>));
+    }
+
+    /// Write the state of this solver to path as JSON, see restore and resume_from_checkpoint. The checkpoint is
+    /// written next to path first and then renamed, so that path always holds a complete checkpoint.
+    pub fn write_checkpoint(&self, path: &Path) -> std::io::Result<()> {
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        let mut writer = BufWriter::new(File::create(&temporary_path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        std::fs::rename(temporary_path, path)
+    }
+}
+
+/// IteratedLocalSearchBuilder creates an IteratedLocalSearch with named settings instead of IteratedLocalSearch::new's
+/// positional arguments. Settings that aren't set keep their defaults, and build checks that they make sense.
+pub struct IteratedLocalSearchBuilder<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _MP,
+    _ISG,
+    _P,
+    _AC = DefaultAcceptanceCriterion<_R, _Solution, _Score>,
+> where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    initial_solution_generator: _ISG,
+    solution_score_calculator: _SSC,
+    local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+    perturbation: _P,
+    rng: _R,
+    history: History<_R, _Solution, _Score>,
+    acceptance_criterion: _AC,
+    max_iterations: u64,
+    max_allow_no_improvement_for: u64,
+    start_solution: Option<_Solution>,
+    initial_population_size: usize,
+    improve_initial_population: bool,
+    restart_policy: Box<dyn RestartPolicy + Send>,
+    repair_operator: Option<RepairOperatorBox<_R, _Solution>>,
+    stop_condition: Option<StopCondition<_Score>>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P>
+    IteratedLocalSearchBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+{
+    pub fn new(
+        initial_solution_generator: _ISG,
+        solution_score_calculator: _SSC,
+        local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+        perturbation: _P,
+        rng: _R,
+    ) -> Self {
+        Self {
+            initial_solution_generator,
+            solution_score_calculator,
+            local_search,
+            perturbation,
+            rng,
+            history: History::default(),
+            acceptance_criterion: DefaultAcceptanceCriterion::default(),
+            max_iterations: 1_000,
+            max_allow_no_improvement_for: 5,
+            start_solution: None,
+            initial_population_size: 1,
+            improve_initial_population: false,
+            restart_policy: Box::new(FixedIntervalRestart(50)),
+            repair_operator: None,
+            stop_condition: None,
+        }
+    }
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearchBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    /// Defaults to History::default().
+    pub fn history(mut self, history: History<_R, _Solution, _Score>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Shorthand for history(History::new(...)).
+    pub fn history_capacities(
+        self,
+        best_solutions_capacity: usize,
+        all_solutions_capacity: usize,
+        all_solution_iteration_expiry: u64,
+    ) -> Self {
+        self.history(History::new(
+            best_solutions_capacity,
+            all_solutions_capacity,
+            all_solution_iteration_expiry,
+        ))
+    }
+
+    /// Defaults to DefaultAcceptanceCriterion.
+    pub fn acceptance_criterion<_AC2>(
+        self,
+        acceptance_criterion: _AC2,
+    ) -> IteratedLocalSearchBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC2>
+    where
+        _AC2: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    {
+        IteratedLocalSearchBuilder {
+            initial_solution_generator: self.initial_solution_generator,
+            solution_score_calculator: self.solution_score_calculator,
+            local_search: self.local_search,
+            perturbation: self.perturbation,
+            rng: self.rng,
+            history: self.history,
+            acceptance_criterion,
+            max_iterations: self.max_iterations,
+            max_allow_no_improvement_for: self.max_allow_no_improvement_for,
+            start_solution: self.start_solution,
+            initial_population_size: self.initial_population_size,
+            improve_initial_population: self.improve_initial_population,
+            restart_policy: self.restart_policy,
+            repair_operator: self.repair_operator,
+            stop_condition: self.stop_condition,
+        }
+    }
+
+    /// Choose the homebase of every round with strategy, see HomebaseStrategy, instead of with
+    /// DefaultAcceptanceCriterion. Shorthand for acceptance_criterion(HomebaseAcceptanceCriterion::new(strategy)).
+    pub fn homebase_strategy(
+        self,
+        strategy: HomebaseStrategy,
+    ) -> HomebaseBuilder<_R, _Solution, _Score, _SSC, _MP, _ISG, _P> {
+        self.acceptance_criterion(HomebaseAcceptanceCriterion::new(strategy))
+    }
+
+    /// Apply every setting of config, see the config module. Replaces the history with an empty one with the
+    /// capacities of config.
+    pub fn config(self, config: &IteratedLocalSearchConfig) -> Self {
+        let mut builder = self.history_capacities(
+            config.best_solutions_capacity,
+            config.all_solutions_capacity,
+            config.all_solution_iteration_expiry,
+        );
+        builder.max_iterations = config.max_iterations;
+        builder.max_allow_no_improvement_for = config.max_allow_no_improvement_for;
+        builder.initial_population_size = config.initial_population_size;
+        builder.improve_initial_population = config.improve_initial_population;
+        builder
+    }
+
+    /// Number of rounds. Defaults to 1,000.
+    pub fn max_iterations(mut self, max_iterations: u64) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// How many steps in a row the local search of a round may fail to improve before the round ends. Defaults to 5.
+    pub fn max_allow_no_improvement_for(mut self, max_allow_no_improvement_for: u64) -> Self {
+        self.max_allow_no_improvement_for = max_allow_no_improvement_for;
+        self
+    }
+
+    /// Start from start_solution, e.g. a previously saved one, instead of a solution of the InitialSolutionGenerator.
+    /// Defaults to None.
+    pub fn start_solution(mut self, start_solution: Option<_Solution>) -> Self {
+        self.start_solution = start_solution;
+        self
+    }
+
+    /// Generate initial_population_size initial solutions and start from the best one, since a single random start
+    /// can land in a poor region of the search space. Ignored if there is a start_solution. Defaults to 1.
+    pub fn initial_population_size(mut self, initial_population_size: usize) -> Self {
+        self.initial_population_size = initial_population_size;
+        self
+    }
+
+    /// Run the local search on every initial solution and start from the best result, instead of from the best
+    /// initial solution as generated. Defaults to false.
+    pub fn improve_initial_population(mut self, improve_initial_population: bool) -> Self {
+        self.improve_initial_population = improve_initial_population;
+        self
+    }
+
+    /// Decide when to restart from a new initial solution, see the restart module. Defaults to
+    /// FixedIntervalRestart(50).
+    pub fn restart_policy(mut self, restart_policy: Box<dyn RestartPolicy + Send>) -> Self {
+        self.restart_policy = restart_policy;
+        self
+    }
+
+    /// Repair every perturbed solution before local search starts from it, see
+    /// IteratedLocalSearch::set_repair_operator. Defaults to no repair.
+    pub fn repair_operator(mut self, repair_operator: RepairOperatorBox<_R, _Solution>) -> Self {
+        self.repair_operator = Some(repair_operator);
+        self
+    }
+
+    /// Finish once stop_condition returns true for the iteration info after a round, on top of max_iterations, e.g.
+    /// `|info| info.best_score.as_ref().is_some_and(|score| score.get_hard_score() == 0.0 && score.get_soft_score() <
+    /// 5.0)`. Lighter than a Termination for one-off conditions. No stop condition by default.
+    pub fn with_stop_condition<F>(mut self, stop_condition: F) -> Self
+    where
+        F: Fn(&IterationInfo<_Score>) -> bool + Send + 'static,
+    {
+        self.stop_condition = Some(Box::new(stop_condition));
+        self
+    }
+
+    /// Build the solver. Panics if a setting is out of range, see try_build.
+    pub fn build(self) -> IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> {
+        self.try_build().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like build, but return SolverError::InvalidConfiguration instead of panicking if a setting is out of range,
+    /// e.g. for settings that come from user input. Every setting is checked here, so a solver that was built doesn't
+    /// panic later because of its settings.
+    pub fn try_build(self) -> BuildResult<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> {
+        if self.max_iterations == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "max_iterations must be positive".to_string(),
+            ));
+        }
+        if self.max_allow_no_improvement_for == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "max_allow_no_improvement_for must be positive".to_string(),
+            ));
+        }
+        if self.initial_population_size == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "initial_population_size must be positive".to_string(),
+            ));
+        }
+        if self.history.get_best_solutions_capacity() == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "best_solutions_capacity must be positive".to_string(),
+            ));
+        }
+        Ok(IteratedLocalSearch::from_builder(self))
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use approx::assert_abs_diff_eq;
+    use ordered_float::OrderedFloat;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use std::future::Future;
+    use std::num::NonZeroU64;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::ackley::new_ackley_iterated_local_search;
+    use crate::ackley::new_ackley_iterated_local_search_with_acceptance_criterion;
+    use crate::ackley::new_ackley_local_search;
+    use crate::ackley::new_slow_ackley_iterated_local_search;
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyIteratedLocalSearch, AckleyMoveProposer, AckleyScore,
+        AckleySolution, AckleySolutionScoreCalculator,
+    };
+    use crate::ackley::{AckleyPerturbation, AckleyPerturbationStrategy};
+    #[cfg(feature = "serde")]
+    use crate::checkpoint::CheckpointPolicy;
+    use crate::error::SolverError;
+    use crate::iterated_local_search::History;
+    use crate::iterated_local_search::IteratedLocalSearch;
+    use crate::iterated_local_search::IteratedLocalSearchBuilder;
+    use crate::iterated_local_search::IterationInfo;
+    use crate::iterated_local_search::{
+        AcceptanceCriterion, AcceptanceFn, DefaultAcceptanceCriterion, HomebaseAcceptanceCriterion,
+        HomebaseStrategy, PerturbationStrength,
+    };
+    use crate::local_search::ScoredSolution;
+    use crate::local_search::SolutionScoreCalculator;
+    use crate::local_search::{LocalSearch, LocalSearchBuilder};
+    use crate::pareto::ParetoArchive;
+    use crate::record_to_record::{Deviation, RecordToRecordTravelAcceptanceCriterion};
+    use crate::repair::RepairOperator;
+    use crate::restart::{FixedIntervalRestart, NeverRestart};
+    use crate::score::HardSoftScoring;
+    use crate::simulated_annealing::{SimulatedAnnealingAcceptanceCriterion, TemperatureSchedule};
+    use crate::termination::{
+        CancellationToken, MaxIterations, Or, ScoreReached, ScoreThreshold, Stagnation,
+    };
+
+    fn _ackley(dimensions: usize, seed: u64) -> ScoredSolution<AckleySolution, AckleyScore> {
+        let min_move_size = 1e-3;
+        let max_move_size = 0.5;
+        let local_search_max_iterations = 100_000;
+        let window_size = 500;
+        let best_solutions_capacity = 16;
         let all_solutions_capacity = 10_000;
         let all_solution_iteration_expiry = 10_000;
         let move_proposer = AckleyMoveProposer::new(dimensions, min_move_size, max_move_size);
@@ -250,7 +1750,7 @@ mod ackley_tests {
         let solution_score_calculator = AckleySolutionScoreCalculator::default();
         let perturbation = AckleyPerturbation::default();
         let history = History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::default();
-        let acceptance_criterion = AcceptanceCriterion::default();
+        let acceptance_criterion = DefaultAcceptanceCriterion::default();
         let iterated_local_search_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
         let iterated_local_search_max_iterations = 10_000;
         let max_allow_no_improvement_for = 5;
@@ -275,11 +1775,750 @@ mod ackley_tests {
         );
 
         while !iterated_local_search.is_finished() {
-            iterated_local_search.execute_round();
+            iterated_local_search.execute_round().unwrap();
         }
         iterated_local_search.get_best_solution()
     }
 
+    /// Always moves on to the new local minima, to check that IteratedLocalSearch accepts user implementations.
+    struct AlwaysAcceptNew;
+
+    impl AcceptanceCriterion for AlwaysAcceptNew {
+        type _R = rand_chacha::ChaCha20Rng;
+        type _Solution = AckleySolution;
+        type _Score = AckleyScore;
+
+        fn choose(
+            &mut self,
+            _existing_local_minima: &ScoredSolution<AckleySolution, AckleyScore>,
+            new_local_minima: &ScoredSolution<AckleySolution, AckleyScore>,
+            _history: &History<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>,
+            _rng: &mut rand_chacha::ChaCha20Rng,
+        ) -> ScoredSolution<AckleySolution, AckleyScore> {
+            new_local_minima.clone()
+        }
+    }
+
+    #[test]
+    fn ackley_custom_acceptance_criterion() {
+        let mut iterated_local_search =
+            new_ackley_iterated_local_search_with_acceptance_criterion(2, 0, 50, AlwaysAcceptNew);
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        assert_abs_diff_eq!(
+            0.0,
+            iterated_local_search.get_best_solution().score.get_score(),
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn ackley_acceptance_fn() {
+        let mut choices = 0;
+        let mut iterated_local_search = new_ackley_iterated_local_search_with_acceptance_criterion(
+            2,
+            0,
+            50,
+            AcceptanceFn::new(|existing, new, _history, _rng| {
+                choices += 1;
+                std::cmp::min(existing, new).clone()
+            }),
+        );
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        assert_abs_diff_eq!(
+            0.0,
+            iterated_local_search.get_best_solution().score.get_score(),
+            epsilon = 1e-2
+        );
+        drop(iterated_local_search);
+        assert!(choices > 0);
+    }
+
+    #[test]
+    fn ackley_perturbation_needs_a_strategy_with_a_positive_weight() {
+        assert!(matches!(
+            AckleyPerturbation::new(vec![]),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+        assert!(matches!(
+            AckleyPerturbation::new(vec![
+                (AckleyPerturbationStrategy::ChangeSubset, 0),
+                (AckleyPerturbationStrategy::DoNothing, 0),
+            ]),
+            Err(SolverError::InvalidConfiguration(_))
+        ));
+        assert!(AckleyPerturbation::new(vec![(AckleyPerturbationStrategy::DoNothing, 1)]).is_ok());
+    }
+
+    #[test]
+    fn homebase_strategies() {
+        let scored = |x: f64| {
+            AckleySolutionScoreCalculator::default()
+                .get_scored_solution(AckleySolution::new(vec![OrderedFloat(x)]))
+        };
+        let mut history = History::<ChaCha20Rng, AckleySolution, AckleyScore>::default();
+        let (best, existing, new) = (scored(0.01), scored(1.0), scored(2.0));
+        for solution in [&best, &existing, &new] {
+            history.local_search_chose_solution(solution.clone());
+        }
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let mut choose =
+            |strategy: HomebaseStrategy, existing: &ScoredSolution<_, _>, new: &ScoredSolution<_, _>| {
+                HomebaseAcceptanceCriterion::new(strategy).choose(existing, new, &history, &mut rng)
+            };
+        assert_eq!(
+            existing,
+            choose(HomebaseStrategy::AcceptFromCurrent, &existing, &new)
+        );
+        assert_eq!(
+            existing,
+            choose(HomebaseStrategy::AcceptFromCurrent, &new, &existing)
+        );
+        assert_eq!(best, choose(HomebaseStrategy::RestartToBest, &existing, &new));
+        for _ in 0..10 {
+            let elite = choose(HomebaseStrategy::RestartToRandomElite, &existing, &new);
+            assert!([&best, &existing, &new].contains(&&elite));
+        }
+    }
+
+    #[test]
+    fn ackley_restart_to_best() {
+        let local_search = LocalSearchBuilder::new(
+            AckleyMoveProposer::new(10, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(20)
+        .window_size(10)
+        .build();
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(10),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(20)
+        .homebase_strategy(HomebaseStrategy::RestartToBest)
+        .build();
+        iterated_local_search.set_iteration_history_capacity(20);
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        let iteration_history = iterated_local_search.get_iteration_history();
+        assert_eq!(20, iteration_history.len());
+        assert!(iteration_history
+            .iter()
+            .all(|info| info.best_score.as_ref() == Some(&info.current_score)));
+    }
+
+    #[test]
+    fn perturbation_strength_grows_until_improvement() {
+        let solution_score_calculator = AckleySolutionScoreCalculator::default();
+        let mut history = History::<ChaCha20Rng, AckleySolution, AckleyScore>::default();
+        let mut strength = PerturbationStrength::new(0.05, 0.5, 10).unwrap();
+        history.local_search_chose_solution(
+            solution_score_calculator.get_scored_solution(AckleySolution::new(vec![OrderedFloat(1.0)])),
+        );
+        strength.observe(&history);
+        assert_eq!(0, strength.get_non_improving_rounds());
+        assert_eq!(5, strength.get_size(100));
+        for _ in 0..5 {
+            strength.observe(&history);
+        }
+        assert_abs_diff_eq!(0.275, strength.get_strength());
+        for _ in 0..10 {
+            strength.observe(&history);
+        }
+        assert_eq!(50, strength.get_size(100));
+        assert_eq!(1, strength.get_size(1));
+
+        history.local_search_chose_solution(
+            solution_score_calculator.get_scored_solution(AckleySolution::new(vec![OrderedFloat(0.0)])),
+        );
+        strength.observe(&history);
+        assert_eq!(0, strength.get_non_improving_rounds());
+        assert_abs_diff_eq!(0.05, strength.get_strength());
+    }
+
+    #[test]
+    fn perturbation_strength_rejects_invalid_bounds() {
+        for (min, max, rounds_to_max, message) in [
+            (0.0, 0.5, 10, "min must be positive"),
+            (f64::NAN, 0.5, 10, "min must be positive"),
+            (0.5, 0.05, 10, "min must be at most max"),
+            (0.05, f64::NAN, 10, "min must be at most max"),
+            (0.05, 1.5, 10, "max must be at most 1"),
+            (0.05, 0.5, 0, "rounds_to_max must be positive"),
+        ] {
+            assert_eq!(
+                Some(SolverError::InvalidConfiguration(message.to_string())),
+                PerturbationStrength::<AckleyScore>::new(min, max, rounds_to_max).err()
+            );
+        }
+    }
+
+    #[test]
+    fn default_acceptance_criterion_weights() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+        let history = History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::default();
+        let existing = ScoredSolution::new(
+            AckleySolution::new(vec![OrderedFloat(1.0)]),
+            AckleyScore::new(1.0),
+        );
+        let new = ScoredSolution::new(
+            AckleySolution::new(vec![OrderedFloat(2.0)]),
+            AckleyScore::new(2.0),
+        );
+        let mut only_existing = DefaultAcceptanceCriterion::with_weights(1, 0, 1).unwrap();
+        let mut only_new = DefaultAcceptanceCriterion::with_weights(0, 1, 0).unwrap();
+        for _ in 0..100 {
+            assert_eq!(
+                existing,
+                only_existing.choose(&existing, &new, &history, &mut rng)
+            );
+            assert_eq!(new, only_new.choose(&existing, &new, &history, &mut rng));
+        }
+    }
+
+    #[test]
+    fn default_acceptance_criterion_rejects_zero_weights() {
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "existing_weight or new_weight must have a positive weight".to_string()
+            )),
+            DefaultAcceptanceCriterion::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::with_weights(
+                0, 0, 1,
+            )
+            .err()
+        );
+    }
+
+    #[test]
+    fn ackley_record_to_record_travel() {
+        let acceptance_criterion = RecordToRecordTravelAcceptanceCriterion::new(Deviation::Relative(0.1));
+        let mut iterated_local_search =
+            new_ackley_iterated_local_search_with_acceptance_criterion(10, 0, 200, acceptance_criterion);
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        assert_abs_diff_eq!(
+            0.0,
+            iterated_local_search.get_best_solution().score.get_score(),
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn ackley_solve_for_respects_time_limit() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(20, 0, u64::MAX);
+        let started = Instant::now();
+        let solution = iterated_local_search
+            .solve_for(Duration::from_millis(200))
+            .unwrap();
+        let elapsed = started.elapsed();
+        println!("solve_for took {:?}, best: {:?}", elapsed, solution.score);
+        assert!(elapsed < Duration::from_secs(2), "solve_for took {:?}", elapsed);
+        assert!(iterated_local_search.is_finished());
+    }
+
+    #[test]
+    fn ackley_execute_until_terminated() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 0, u64::MAX);
+        let solution = iterated_local_search
+            .execute(&Or(MaxIterations(1_000), ScoreReached(AckleyScore::new(0.5))))
+            .unwrap();
+        println!(
+            "execute stopped after {:?}, best: {:?}",
+            iterated_local_search.get_iteration_info(),
+            solution
+        );
+        assert!(solution.score.get_score() <= 0.5);
+        assert!(iterated_local_search.get_iteration_info().iteration < 1_000);
+
+        let mut iterated_local_search = new_ackley_iterated_local_search(20, 0, u64::MAX);
+        iterated_local_search
+            .execute(&Or(Stagnation(3), MaxIterations(20)))
+            .unwrap();
+        assert!(iterated_local_search.get_iteration_info().iteration <= 20);
+    }
+
+    #[test]
+    fn ackley_score_threshold() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(10, 0, u64::MAX);
+        iterated_local_search.set_score_threshold(Some(ScoreThreshold::at_most(AckleyScore::new(1.0))));
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        let best = iterated_local_search.get_best_solution();
+        println!(
+            "stopped after {:?} at {:?}",
+            iterated_local_search.get_iteration_info(),
+            best
+        );
+        assert!(best.score.get_score() <= 1.0);
+    }
+
+    #[test]
+    fn ackley_finishes_at_best_possible_solution() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 42, u64::MAX);
+        let optimum = AckleySolution::new(vec![OrderedFloat(0.0); 2]);
+        let best = iterated_local_search
+            .execute_from(optimum.clone(), &MaxIterations(u64::MAX))
+            .unwrap();
+        assert_eq!(optimum, best.solution);
+        assert!(iterated_local_search.is_finished());
+        assert_eq!(1, iterated_local_search.get_iteration_info().iteration);
+    }
+
+    #[test]
+    fn ackley_stop_condition() {
+        let local_search = LocalSearchBuilder::new(
+            AckleyMoveProposer::new(10, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(20)
+        .window_size(10)
+        .build();
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(10),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(u64::MAX)
+        .with_stop_condition(|info: &IterationInfo<AckleyScore>| {
+            info.iteration >= 1_000
+                || info
+                    .best_score
+                    .as_ref()
+                    .is_some_and(|best_score| best_score.get_score() < 1.0)
+        })
+        .build();
+        iterated_local_search.set_iteration_history_capacity(1_000);
+        assert!(!iterated_local_search.is_finished());
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+
+        // The solver stops after the first round that reaches the condition.
+        let iteration_info = iterated_local_search.get_iteration_info();
+        assert!(iteration_info.iteration > 1);
+        assert!(iteration_info.best_score.unwrap().get_score() < 1.0);
+        let iteration_history = iterated_local_search.get_iteration_history();
+        assert!(iteration_history
+            .iter()
+            .take(iteration_history.len() - 1)
+            .all(|info| info.best_score.as_ref().unwrap().get_score() >= 1.0));
+    }
+
+    #[test]
+    fn ackley_cancellation_token() {
+        let cancellation_token = CancellationToken::new();
+        let mut iterated_local_search = new_ackley_iterated_local_search(20, 0, u64::MAX);
+        iterated_local_search.set_cancellation_token(Some(cancellation_token.clone()));
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            cancellation_token.cancel();
+        });
+        let started = Instant::now();
+        let solution = iterated_local_search.execute(&MaxIterations(u64::MAX)).unwrap();
+        canceller.join().unwrap();
+        println!(
+            "cancelled after {:?}, best: {:?}",
+            started.elapsed(),
+            solution.score
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(iterated_local_search.is_finished());
+    }
+
+    #[test]
+    #[should_panic(expected = "max_iterations must be positive")]
+    fn builder_rejects_zero_max_iterations() {
+        IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            new_ackley_local_search(2, 0),
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(0),
+        )
+        .max_iterations(0)
+        .build();
+    }
+
+    #[test]
+    fn builder_try_build_returns_invalid_configuration() {
+        let result = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            new_ackley_local_search(2, 0),
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(0),
+        )
+        .initial_population_size(0)
+        .try_build();
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "initial_population_size must be positive".to_string()
+            )),
+            result.err()
+        );
+    }
+
+    #[test]
+    fn try_get_best_solution_before_the_first_round() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 0, 1);
+        assert_eq!(
+            Some(SolverError::NoSolution),
+            iterated_local_search.try_get_best_solution().err()
+        );
+        let best = iterated_local_search.execute(&MaxIterations(1)).unwrap();
+        assert_eq!(Ok(best), iterated_local_search.try_get_best_solution());
+    }
+
+    #[test]
+    fn ackley_warm_start() {
+        let optimum = AckleySolution::new(vec![OrderedFloat(0.0); 10]);
+        let iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(10),
+            AckleySolutionScoreCalculator::default(),
+            new_ackley_local_search(10, 0),
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(0),
+        )
+        .start_solution(Some(optimum.clone()))
+        .max_iterations(1)
+        .build();
+        assert_eq!(optimum, iterated_local_search.get_best_solution().solution);
+
+        let mut iterated_local_search = new_ackley_iterated_local_search(10, 0, u64::MAX);
+        let first = iterated_local_search.execute(&MaxIterations(20)).unwrap();
+        let mut iterated_local_search = new_ackley_iterated_local_search(10, 1, u64::MAX);
+        let continued = iterated_local_search
+            .execute_from(first.solution, &MaxIterations(1))
+            .unwrap();
+        assert!(continued.score <= first.score);
+    }
+
+    #[test]
+    fn ackley_initial_population() {
+        let build = |initial_population_size, improve_initial_population| {
+            IteratedLocalSearchBuilder::new(
+                AckleyInitialSolutionGenerator::new(10),
+                AckleySolutionScoreCalculator::default(),
+                new_ackley_local_search(10, 0),
+                AckleyPerturbation::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(0),
+            )
+            .initial_population_size(initial_population_size)
+            .improve_initial_population(improve_initial_population)
+            .build()
+        };
+        let single = build(1, false);
+        let population = build(16, false);
+        let improved_population = build(16, true);
+        println!(
+            "single: {:?}, population: {:?}, improved population: {:?}",
+            single.current.score, population.current.score, improved_population.current.score
+        );
+        assert!(population.current.score <= single.current.score);
+        assert!(improved_population.current.score < population.current.score);
+        assert_eq!(
+            improved_population.current,
+            improved_population.get_best_solution()
+        );
+    }
+
+    #[test]
+    fn ackley_iteration_history_keeps_the_last_rounds() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(0, u64::MAX);
+        iterated_local_search.set_iteration_history_capacity(3);
+        iterated_local_search.execute(&MaxIterations(5)).unwrap();
+        let history = iterated_local_search.get_iteration_history();
+        assert_eq!(
+            vec![3, 4, 5],
+            history.iter().map(|info| info.iteration).collect::<Vec<u64>>()
+        );
+        for info in history {
+            assert!(info.best_score.as_ref().unwrap() <= &info.current_score);
+            assert!(info
+                .perturbation_used
+                .as_ref()
+                .unwrap()
+                .ends_with("AckleyPerturbation"));
+        }
+        assert_eq!(
+            Some(iterated_local_search.get_best_solution().score),
+            history.back().unwrap().best_score
+        );
+        // Between rounds get_iteration_info is the last round, apart from the time that passed since.
+        let iteration_info = iterated_local_search.get_iteration_info();
+        let last_round = history.back().unwrap();
+        assert!(iteration_info.elapsed >= last_round.elapsed);
+        assert_eq!(
+            *last_round,
+            IterationInfo {
+                elapsed: last_round.elapsed,
+                ..iteration_info
+            }
+        );
+        iterated_local_search.set_iteration_history_capacity(1);
+        assert_eq!(1, iterated_local_search.get_iteration_history().len());
+    }
+
+    #[test]
+    fn ackley_restart_policy() {
+        // A local search of a single step never reaches the best possible solution, which would end every round early.
+        let build = |restart_policy| {
+            IteratedLocalSearchBuilder::new(
+                AckleyInitialSolutionGenerator::new(20),
+                AckleySolutionScoreCalculator::default(),
+                LocalSearchBuilder::new(
+                    AckleyMoveProposer::new(20, 1e-3, 0.5),
+                    AckleySolutionScoreCalculator::default(),
+                    rand_chacha::ChaCha20Rng::seed_from_u64(0),
+                )
+                .max_iterations(1)
+                .build(),
+                AckleyPerturbation::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(0),
+            )
+            .restart_policy(restart_policy)
+            .build()
+        };
+        let (_, stats) = build(Box::new(NeverRestart))
+            .execute_with_stats(&MaxIterations(60))
+            .unwrap();
+        assert_eq!(0, stats.restarts);
+        let (_, stats) = build(Box::new(FixedIntervalRestart(10)))
+            .execute_with_stats(&MaxIterations(30))
+            .unwrap();
+        assert_eq!(3, stats.restarts);
+    }
+
+    fn new_ackley_pareto_iterated_local_search(seed: u64) -> AckleyIteratedLocalSearch {
+        IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            new_ackley_local_search(2, seed),
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        )
+        .history(History::default().with_pareto_archive(ParetoArchive::new(
+            |score: &AckleyScore| vec![score.get_hard_score(), score.get_soft_score()],
+            8,
+        )))
+        .build()
+    }
+
+    #[test]
+    fn ackley_pareto_front() {
+        let mut iterated_local_search = new_ackley_pareto_iterated_local_search(42);
+        assert_eq!(Some(vec![]), iterated_local_search.get_pareto_front());
+        let best = iterated_local_search.execute(&MaxIterations(5)).unwrap();
+        // Ackley has no hard score, so the front of its single real objective is the best solution.
+        assert_eq!(Some(vec![best]), iterated_local_search.get_pareto_front());
+    }
+
+    /// Repairs every solution to the origin, the global minimum of Ackley.
+    struct OriginRepair(usize);
+    impl RepairOperator for OriginRepair {
+        type _R = ChaCha20Rng;
+        type _Solution = AckleySolution;
+
+        fn repair(&mut self, _solution: AckleySolution, _rng: &mut ChaCha20Rng) -> AckleySolution {
+            AckleySolution::new(vec![OrderedFloat(0.0); self.0])
+        }
+    }
+
+    #[test]
+    fn ackley_repair_operator_runs_after_perturbation() {
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(5),
+            AckleySolutionScoreCalculator::default(),
+            new_ackley_local_search(5, 42),
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .repair_operator(Box::new(OriginRepair(5)))
+        .build();
+        let best = iterated_local_search.execute(&MaxIterations(1)).unwrap();
+        assert_eq!(AckleySolution::new(vec![OrderedFloat(0.0); 5]), best.solution);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ackley_restore_continues_exactly() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(0, u64::MAX);
+        iterated_local_search.execute(&MaxIterations(3)).unwrap();
+        let checkpoint = serde_json::to_string(&iterated_local_search).unwrap();
+        let continued = iterated_local_search.execute(&MaxIterations(3)).unwrap();
+
+        let mut restored = new_slow_ackley_iterated_local_search(1, u64::MAX);
+        restored
+            .restore(&mut serde_json::Deserializer::from_str(&checkpoint))
+            .unwrap();
+        let resumed = restored.execute(&MaxIterations(3)).unwrap();
+        assert_eq!(continued, resumed);
+        assert_eq!(iterated_local_search.current, restored.current);
+        assert_eq!(6, restored.get_iteration_info().iteration);
+        assert_eq!(
+            iterated_local_search.get_stats().proposed_moves,
+            restored.get_stats().proposed_moves
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ackley_restore_replaces_pareto_front() {
+        let mut iterated_local_search = new_ackley_pareto_iterated_local_search(42);
+        iterated_local_search.execute(&MaxIterations(3)).unwrap();
+        let checkpoint = serde_json::to_string(&iterated_local_search).unwrap();
+
+        let mut restored = new_ackley_pareto_iterated_local_search(1);
+        restored.execute(&MaxIterations(3)).unwrap();
+        restored
+            .restore(&mut serde_json::Deserializer::from_str(&checkpoint))
+            .unwrap();
+        assert_eq!(
+            iterated_local_search.get_pareto_front(),
+            restored.get_pareto_front()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ackley_checkpoint_policy() {
+        let directory = std::env::temp_dir().join(format!("local-search-checkpoints-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path_template = directory.join("round-{iteration}.json");
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(0, u64::MAX);
+        iterated_local_search.set_checkpoint_policy(Some(
+            CheckpointPolicy::new(path_template.to_str().unwrap())
+                .every_iterations(NonZeroU64::new(2).unwrap()),
+        ));
+        iterated_local_search.execute(&MaxIterations(4)).unwrap();
+        assert!(!directory.join("round-1.json").exists());
+        assert!(directory.join("round-4.json").exists());
+
+        let mut resumed = new_slow_ackley_iterated_local_search(1, u64::MAX);
+        let resumed_path_template = directory.join("resumed-{iteration}.json");
+        resumed.set_checkpoint_policy(Some(
+            CheckpointPolicy::new(resumed_path_template.to_str().unwrap())
+                .every_iterations(NonZeroU64::new(2).unwrap()),
+        ));
+        resumed
+            .resume_from_checkpoint(directory.join("round-2.json"))
+            .unwrap();
+        assert_eq!(2, resumed.get_iteration_info().iteration);
+        resumed.execute(&MaxIterations(2)).unwrap();
+        assert!(!directory.join("resumed-3.json").exists());
+        assert!(directory.join("resumed-4.json").exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    /// Run future to completion on this thread, and count how often it yielded.
+    fn block_on<_F: Future>(future: _F) -> (_F::Output, u64) {
+        struct ThreadWaker(thread::Thread);
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        let mut yields = 0;
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return (output, yields),
+                Poll::Pending => {
+                    yields += 1;
+                    thread::park();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ackley_solve_async_yields() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(0, u64::MAX);
+        let (best, yields) =
+            block_on(iterated_local_search.solve_async(&MaxIterations(10), NonZeroU64::new(3).unwrap()));
+        assert_eq!(3, yields);
+        assert_eq!(10, iterated_local_search.get_iteration_info().iteration);
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(0, u64::MAX);
+        assert_eq!(
+            iterated_local_search.execute(&MaxIterations(10)).unwrap(),
+            best.unwrap()
+        );
+    }
+
+    #[test]
+    fn ackley_progress_events() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 0, 10);
+        iterated_local_search.set_progress_sender(Some(sender));
+        let solution = iterated_local_search.execute(&MaxIterations(10)).unwrap();
+        drop(iterated_local_search);
+
+        let events: Vec<_> = receiver.iter().collect();
+        assert!(!events.is_empty());
+        assert!(events
+            .windows(2)
+            .all(|window| window[0].iteration < window[1].iteration
+                && window[1].best_score <= window[0].best_score
+                && window[0].elapsed <= window[1].elapsed));
+        assert_eq!(solution.score, events.last().unwrap().best_score);
+    }
+
+    #[test]
+    fn ackley_stats() {
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 0, 10);
+        let (best, stats) = iterated_local_search
+            .execute_with_stats(&MaxIterations(10))
+            .unwrap();
+        println!("stats: {:?}", stats);
+        assert!(stats.perturbations > 0);
+        assert!(stats.proposed_moves >= stats.accepted_moves);
+        assert!(stats.accepted_moves >= stats.improving_moves);
+        assert!(stats.improving_moves > 0);
+        assert!(stats.get_acceptance_rate() > 0.0 && stats.get_acceptance_rate() <= 1.0);
+        assert!(stats
+            .best_score_timeline
+            .windows(2)
+            .all(|window| window[1].best_score < window[0].best_score));
+        assert_eq!(best.score, stats.best_score_timeline.last().unwrap().best_score);
+    }
+
+    #[test]
+    fn ackley_simulated_annealing() {
+        let acceptance_criterion = SimulatedAnnealingAcceptanceCriterion::new(
+            TemperatureSchedule::Geometric {
+                initial_temperature: 1.0,
+                alpha: 0.95,
+            },
+            1e-3,
+        );
+        let mut iterated_local_search =
+            new_ackley_iterated_local_search_with_acceptance_criterion(10, 0, 200, acceptance_criterion);
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        assert_abs_diff_eq!(
+            0.0,
+            iterated_local_search.get_best_solution().score.get_score(),
+            epsilon = 1e-2
+        );
+    }
+
     #[test]
     fn ackley() {
         let dimensions = 2;
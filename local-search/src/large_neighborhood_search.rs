@@ -0,0 +1,230 @@
+//! large_neighborhood_search escapes deep local optima by ruin and recreate instead of small moves: every round
+//! destroys a part of the current solution, repairs it, and lets an AcceptanceCriterion decide whether to move to the
+//! result, see [1]. A single round can change many variables at once, which a MoveProposer neighborhood can't.
+//!
+//! [1] Shaw, Paul. "Using Constraint Programming and Local Search Methods to Solve Vehicle Routing Problems."
+//! International Conference on Principles and Practice of Constraint Programming (1998): 417-431.
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use rand::prelude::SliceRandom;
+
+use crate::assignment::{Assignment, Domain};
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::iterated_local_search::{AcceptanceCriterion, DefaultAcceptanceCriterion};
+use crate::local_search::{History, Score, ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::repair::RepairOperator;
+use crate::termination::{Termination, TerminationState};
+
+/// DestroyOperator ruins a part of a solution, e.g. by unassigning or randomizing some variables, so that a
+/// RepairOperator can rebuild it differently.
+pub trait DestroyOperator {
+    type _R: rand::Rng;
+    type _Solution: Solution;
+
+    fn destroy(&mut self, solution: &Self::_Solution, rng: &mut Self::_R) -> Self::_Solution;
+
+    /// Name the operator, e.g. in SolverStats::operator_weights. Defaults to the type name.
+    fn get_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// RandomDestroy assigns random domain values to a random fraction of the decision variables, at least one.
+pub struct RandomDestroy<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    domain: _D,
+    fraction: f64,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _D> RandomDestroy<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    pub fn new(domain: _D, fraction: f64) -> Self {
+        assert!(
+            fraction > 0.0 && fraction <= 1.0,
+            "fraction must be greater than 0 and at most 1"
+        );
+        Self {
+            domain,
+            fraction,
+            phantom_r: PhantomData,
+        }
+    }
+}
+
+impl<_R, _D> DestroyOperator for RandomDestroy<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    type _R = _R;
+    type _Solution = _D::_Solution;
+
+    fn destroy(&mut self, solution: &Self::_Solution, rng: &mut _R) -> Self::_Solution {
+        let variable_count = solution.get_variable_count();
+        let destroy_count = ((variable_count as f64 * self.fraction).ceil() as usize).min(variable_count);
+        let mut destroyed = solution.clone();
+        for variable in rand::seq::index::sample(rng, variable_count, destroy_count) {
+            if let Some(value) = self.domain.get_values(&destroyed, variable).choose(rng) {
+                destroyed.set_value(variable, value.clone());
+            }
+        }
+        destroyed
+    }
+}
+
+/// LargeNeighborhoodSearch runs destroy and repair rounds from an initial solution. Every repaired solution is added to
+/// the History, and the acceptance criterion chooses between it and the current solution.
+pub struct LargeNeighborhoodSearch<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _DO,
+    _RO,
+    _AC = DefaultAcceptanceCriterion<_R, _Solution, _Score>,
+> where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _DO: DestroyOperator<_R = _R, _Solution = _Solution>,
+    _RO: RepairOperator<_R = _R, _Solution = _Solution>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    solution_score_calculator: _SSC,
+    destroy_operator: _DO,
+    repair_operator: _RO,
+    acceptance_criterion: _AC,
+    history: History<_R, _Solution, _Score>,
+    rng: _R,
+    current: ScoredSolution<_Solution, _Score>,
+    iteration: u64,
+    rounds_without_improvement: u64,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _DO, _RO, _AC>
+    LargeNeighborhoodSearch<_R, _Solution, _Score, _SSC, _DO, _RO, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _DO: DestroyOperator<_R = _R, _Solution = _Solution>,
+    _RO: RepairOperator<_R = _R, _Solution = _Solution>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    pub fn new(
+        initial_solution: _Solution,
+        solution_score_calculator: _SSC,
+        destroy_operator: _DO,
+        repair_operator: _RO,
+        acceptance_criterion: _AC,
+        mut history: History<_R, _Solution, _Score>,
+        rng: _R,
+    ) -> Self {
+        let current = solution_score_calculator.get_scored_solution(initial_solution);
+        history.local_search_chose_solution(current.clone());
+        let best_solution_handle = BestSolutionHandle::new();
+        best_solution_handle.offer(&current);
+        Self {
+            solution_score_calculator,
+            destroy_operator,
+            repair_operator,
+            acceptance_criterion,
+            history,
+            rng,
+            current,
+            iteration: 0,
+            rounds_without_improvement: 0,
+            best_solution_handle,
+        }
+    }
+
+    pub fn get_iteration(&self) -> u64 {
+        self.iteration
+    }
+
+    pub fn get_current_solution(&self) -> &ScoredSolution<_Solution, _Score> {
+        &self.current
+    }
+
+    pub fn get_best_solution(&self) -> ScoredSolution<_Solution, _Score> {
+        self.history.get_best().unwrap()
+    }
+
+    /// Get a handle that always holds the best solution found so far, see BestSolutionHandle.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    pub fn execute_round(&mut self) {
+        self.iteration += 1;
+        let _span =
+            tracing::debug_span!("large_neighborhood_search_round", iteration = self.iteration).entered();
+        let destroyed = self
+            .destroy_operator
+            .destroy(&self.current.solution, &mut self.rng);
+        let repaired = self
+            .solution_score_calculator
+            .get_scored_solution(self.repair_operator.repair(destroyed, &mut self.rng));
+        tracing::trace!(current_score = ?self.current.score, repaired_score = ?repaired.score, "repaired");
+        match self.history.get_best() {
+            Some(best) if repaired.score >= best.score => self.rounds_without_improvement += 1,
+            _ => self.rounds_without_improvement = 0,
+        }
+        self.history.local_search_chose_solution(repaired.clone());
+        self.best_solution_handle.offer(&repaired);
+        self.current =
+            self.acceptance_criterion
+                .choose(&self.current, &repaired, &self.history, &mut self.rng);
+    }
+
+    /// Execute rounds until termination says to stop, see the termination module, or until a best possible solution
+    /// is found, and return the best solution found. At least one round is always executed.
+    pub fn execute<_T>(&mut self, termination: &_T) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let started = Instant::now();
+        let first_iteration = self.iteration;
+        loop {
+            self.execute_round();
+            let best = self.get_best_solution();
+            let state = TerminationState {
+                iteration: self.iteration - first_iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&best.score),
+                iterations_without_improvement: self.rounds_without_improvement,
+            };
+            if best.score.is_best() || termination.is_terminated(&state) {
+                return best;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::fixtures::Bits;
+    use crate::large_neighborhood_search::{DestroyOperator, RandomDestroy};
+    use crate::modeling::ListDomain;
+
+    #[test]
+    fn random_destroy_reassigns_the_fraction_of_variables() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut destroy = RandomDestroy::new(ListDomain::uniform(20, vec![1]), 0.25);
+        let destroyed = destroy.destroy(&Bits(vec![0; 20]), &mut rng);
+        assert_eq!(5, destroyed.0.iter().filter(|bit| **bit == 1).count());
+    }
+}
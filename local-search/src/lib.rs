@@ -5,8 +5,63 @@ extern crate derivative;
 extern crate approx;
 
 mod ackley;
+pub mod adaptive_large_neighborhood_search;
+pub mod analysis;
+pub mod archive;
+pub mod assignment;
+pub mod bandit;
+pub mod bench;
+pub mod best_solution_handle;
+pub mod bloom_filter;
+pub mod branch_and_bound;
+pub mod cache;
+#[cfg(feature = "serde")]
+pub mod checkpoint;
+pub mod compare;
+pub mod config;
+pub mod constraints;
+pub mod construction;
+pub mod convergence;
+pub mod diversity;
+pub mod error;
+pub mod event_log;
+#[cfg(test)]
+mod fixtures;
+pub mod incremental;
 pub mod iterated_local_search;
+pub mod large_neighborhood_search;
 pub mod local_search;
+pub mod memetic;
+pub mod modeling;
+pub mod moves;
+pub mod multistart;
+pub mod neighborhood;
+pub mod noise;
+pub mod objectives;
+pub mod observer;
+pub mod parallel;
+pub mod pareto;
+pub mod path_relinking;
+pub mod prelude;
+pub mod progress;
+pub mod propagation;
+pub mod record_to_record;
+pub mod repair;
+pub mod restart;
+pub mod rng;
+pub mod runner;
+pub mod score;
+pub mod simulated_annealing;
+pub mod solver;
+pub mod stats;
+pub mod step_size;
+pub mod strategic_oscillation;
+pub mod tabu;
+pub mod termination;
+pub mod trace;
+pub mod tuning;
+pub mod two_phase;
+pub mod variable_neighborhood_search;
 
 // use std::{fmt::Debug, marker::PhantomData};
 
@@ -1,9 +1,38 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use rand::prelude::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::ser::SerializeStruct;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::archive::BestSolutionArchive;
+use crate::bloom_filter::BloomFilter;
+use crate::config::LocalSearchConfig;
+use crate::constraints::{ConstraintId, ConstraintLevel, ConstraintViolation};
+use crate::diversity::SolutionDistance;
+use crate::error::SolverError;
+use crate::incremental::{IncrementalScoreCalculator, NeighborScoreCalculator};
+use crate::moves::NeighborMove;
+use crate::observer::Observer;
+use crate::pareto::ParetoArchive;
+use crate::score::HardSoftScoring;
+use crate::stats::SolverStats;
+use crate::tabu::MoveTabu;
+use crate::termination::{
+    CancellationToken, MaxIterations, MinImprovement, Or, ScoreThreshold, Stagnation, Termination,
+    TerminationState,
+};
 
 /// local_search contains methods that represent a solution and proposing moves in the neighborhood of a solution.
 /// Use methods in this module you can discover local minima. This is the LocalSearch part of [1] section 2pages 2 and
@@ -12,12 +41,16 @@ use rand::prelude::SliceRandom;
 /// [1] Lourenço, Helena Ramalhinho, Olivier C. Martin and Thomas Stützle. "Iterated Local Search: Framework and
 /// Applications." (2010).
 
-/// Solution is a plain old data object.
+/// Solution is a plain old data object. Implement it with `#[derive(Solution)]`, or by hand on top of the derives
+/// of its supertraits.
 pub trait Solution:
     Clone + Send + PartialEq + Eq + PartialOrd + Ord + std::hash::Hash + std::fmt::Debug
 {
 }
 
+/// Derive Solution with `#[derive(Solution)]`, see local_search_derive::Solution.
+pub use local_search_derive::Solution;
+
 /// Score for a solution. Could just be e.g. u64, f64, num::Num. Could be more complicated like a tuple
 /// (hard score, soft score).
 pub trait Score: Clone + Send + PartialEq + Eq + PartialOrd + Ord + std::fmt::Debug {
@@ -26,7 +59,9 @@ pub trait Score: Clone + Send + PartialEq + Eq + PartialOrd + Ord + std::fmt::De
     fn is_best(&self) -> bool;
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, Derivative)]
+#[derivative(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScoredSolution<_Solution, _Score>
 where
     _Solution: Solution,
@@ -34,6 +69,14 @@ where
 {
     pub score: _Score,
     pub solution: _Solution,
+
+    /// How often every violated constraint is violated by solution, for score calculators that count violations, e.g.
+    /// ConstraintScoreCalculator::set_count_violations, so that UIs can show which constraints are still violated.
+    /// Calculators that don't count violations leave it None. Violations follow from solution, so they are ignored when
+    /// comparing and hashing scored solutions.
+    #[derivative(PartialEq = "ignore", PartialOrd = "ignore", Ord = "ignore", Hash = "ignore")]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub constraint_violations: Option<Vec<(ConstraintId, u64)>>,
 }
 
 impl<_Solution, _Score> ScoredSolution<_Solution, _Score>
@@ -42,7 +85,16 @@ where
     _Score: Score,
 {
     pub fn new(solution: _Solution, score: _Score) -> Self {
-        Self { solution, score }
+        Self {
+            solution,
+            score,
+            constraint_violations: None,
+        }
+    }
+
+    pub fn with_constraint_violations(mut self, constraint_violations: Vec<(ConstraintId, u64)>) -> Self {
+        self.constraint_violations = Some(constraint_violations);
+        self
     }
 }
 
@@ -63,6 +115,25 @@ pub trait SolutionScoreCalculator {
     /// should be.
     fn get_scored_solution(&self, solution: Self::_Solution)
         -> ScoredSolution<Self::_Solution, Self::_Score>;
+
+    /// verify checks that solution is feasible, e.g. before an application uses a returned solution, and lists the
+    /// hard violations if it isn't. By default the hard score is reported as one violation named "hard score";
+    /// calculators that know their constraints, like ConstraintScoreCalculator, list them one by one.
+    fn verify(&self, solution: &Self::_Solution) -> Result<(), Vec<ConstraintViolation>>
+    where
+        Self::_Score: HardSoftScoring,
+    {
+        let hard_score = self.get_scored_solution(solution.clone()).score.get_hard_score();
+        if hard_score == 0.0 {
+            return Ok(());
+        }
+        Err(vec![ConstraintViolation {
+            name: "hard score".to_string(),
+            level: ConstraintLevel::Hard,
+            score: hard_score,
+            matches: vec![],
+        }])
+    }
 }
 
 pub trait InitialSolutionGenerator {
@@ -81,40 +152,441 @@ pub trait MoveProposer {
     type Solution: Solution;
 
     /// Iterate over the neighborhood of solutions need a start solution randomly. Must be a finite-sized iterator that
-    /// is computationally feasbile to fully consume. However, local search will typically not exhaust this iterator.
+    /// is computationally feasbile to fully consume. However, local search will typically not exhaust this iterator,
+    /// and takes at most LocalSearch::set_max_neighborhood_size solutions from it.
     fn iter_local_moves(
         &self,
         start: &Self::Solution,
         rng: &mut Self::R,
     ) -> Box<dyn Iterator<Item = Self::Solution>>;
+
+    /// Like iter_local_moves, but yield every neighbor with a cheap estimate of how much it changes the score of
+    /// start, lower is better, e.g. a delta computed from only the few constraints that a swap touches. LocalSearch
+    /// uses the estimates to pick the neighbors worth scoring, see LocalSearch::set_estimated_candidates. Estimates
+    /// don't have to be exact, only to rank neighbors roughly like their scores would. Returns None by default, for
+    /// move proposers that can't estimate, and then LocalSearch uses iter_local_moves.
+    fn iter_estimated_local_moves(
+        &self,
+        _start: &Self::Solution,
+        _rng: &mut Self::R,
+    ) -> Option<Box<dyn Iterator<Item = (Self::Solution, f64)>>> {
+        None
+    }
+}
+
+/// TabuStorage decides how History remembers the recently seen solutions that are tabu.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TabuStorage {
+    /// Keep every recently seen solution. A seen solution that local search then chooses shares its memory with the
+    /// best solutions, see History.
+    #[default]
+    Solutions,
+
+    /// Keep a 64-bit hash of every recently seen solution instead of a copy, which takes a fraction of the memory for
+    /// large solutions. A solution that was not seen is tabu if its hash collides with that of a seen one, which is
+    /// rare enough to not matter for the search.
+    Hashes,
+
+    /// Keep the seen solutions in two Bloom filters of all_solutions_capacity solutions each, see the bloom_filter
+    /// module, so that memory stays bounded however long the search runs. Once the newer filter is full the older one
+    /// is dropped, so a solution is forgotten after between one and two times all_solutions_capacity other solutions.
+    /// Solutions that were not seen are tabu with about false_positive_rate probability, and
+    /// all_solution_iteration_expiry and all_solution_time_expiry are ignored.
+    BloomFilter { false_positive_rate: f64 },
+}
+
+/// SeenKeys is a first in, first out set of keys of seen solutions with the iteration and the time they were added in.
+/// The time is a SystemTime rather than an Instant so that it survives serialization.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct SeenKeys<_Key>
+where
+    _Key: Eq + std::hash::Hash + Clone,
+{
+    keys: VecDeque<(_Key, u64, SystemTime)>,
+    lookup: HashSet<_Key>,
+}
+
+impl<_Key> SeenKeys<_Key>
+where
+    _Key: Eq + std::hash::Hash + Clone,
+{
+    fn new(capacity: usize) -> Self {
+        Self {
+            keys: VecDeque::with_capacity(capacity),
+            lookup: Default::default(),
+        }
+    }
+
+    fn push_front(&mut self, key: _Key, iteration: u64) {
+        self.lookup.insert(key.clone());
+        self.keys.push_front((key, iteration, SystemTime::now()));
+    }
+
+    fn pop_back(&mut self) {
+        if let Some((key, _iteration, _seen_at)) = self.keys.pop_back() {
+            self.lookup.remove(&key);
+        }
+    }
+
+    fn get_back_iteration(&self) -> Option<u64> {
+        self.keys.back().map(|(_key, iteration, _seen_at)| *iteration)
+    }
+
+    fn get_back_seen_at(&self) -> Option<SystemTime> {
+        self.keys.back().map(|(_key, _iteration, seen_at)| *seen_at)
+    }
+
+    fn clear(&mut self) {
+        self.keys.clear();
+        self.lookup.clear();
+    }
+}
+
+/// SeenSolution is a recently seen solution of TabuStorage::Solutions, which is compared and hashed by its solution
+/// alone so that SeenKeys can look it up by solution.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+struct SeenSolution<_Solution, _Score>(Arc<ScoredSolution<_Solution, _Score>>)
+where
+    _Solution: Solution,
+    _Score: Score;
+
+impl<_Solution, _Score> Clone for SeenSolution<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<_Solution, _Score> PartialEq for SeenSolution<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.solution == other.0.solution
+    }
+}
+
+impl<_Solution, _Score> Eq for SeenSolution<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+}
+
+impl<_Solution, _Score> Hash for SeenSolution<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.solution.hash(state)
+    }
+}
+
+impl<_Solution, _Score> Borrow<_Solution> for SeenSolution<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    fn borrow(&self) -> &_Solution {
+        &self.0.solution
+    }
+}
+
+/// SeenBloomFilters are the generations of TabuStorage::BloomFilter.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct SeenBloomFilters {
+    newer: BloomFilter,
+    older: BloomFilter,
+    newer_count: usize,
+    capacity: usize,
+}
+
+impl SeenBloomFilters {
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            newer: BloomFilter::new(capacity, false_positive_rate),
+            older: BloomFilter::new(capacity, false_positive_rate),
+            newer_count: 0,
+            capacity,
+        }
+    }
+
+    fn contains<_Solution: Solution>(&self, solution: &_Solution) -> bool {
+        self.newer.contains(solution) || self.older.contains(solution)
+    }
+
+    fn insert<_Solution: Solution>(&mut self, solution: &_Solution) {
+        if self.newer_count == self.capacity {
+            std::mem::swap(&mut self.newer, &mut self.older);
+            self.newer.clear();
+            self.newer_count = 0;
+        }
+        self.newer.insert(solution);
+        self.newer_count += 1;
+    }
+
+    fn clear(&mut self) {
+        self.newer.clear();
+        self.older.clear();
+        self.newer_count = 0;
+    }
+}
+
+/// SeenSolutions stores the recently seen solutions of History as TabuStorage says.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum SeenSolutions<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    Solutions(SeenKeys<SeenSolution<_Solution, _Score>>),
+    Hashes(SeenKeys<u64>),
+    BloomFilters(SeenBloomFilters),
 }
 
-#[derive(Derivative)]
-#[derivative(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct ScoredSolutionAndIterationAdded<_Solution, _Score>
+impl<_Solution, _Score> SeenSolutions<_Solution, _Score>
 where
     _Solution: Solution,
     _Score: Score,
 {
-    scored_solution: ScoredSolution<_Solution, _Score>,
-    iteration: u64,
+    fn new(tabu_storage: &TabuStorage, capacity: usize) -> Self {
+        match tabu_storage {
+            TabuStorage::Solutions => SeenSolutions::Solutions(SeenKeys::new(capacity)),
+            TabuStorage::Hashes => SeenSolutions::Hashes(SeenKeys::new(capacity)),
+            TabuStorage::BloomFilter { false_positive_rate } => {
+                SeenSolutions::BloomFilters(SeenBloomFilters::new(capacity, *false_positive_rate))
+            }
+        }
+    }
+
+    fn contains(&self, solution: &_Solution) -> bool {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.lookup.contains(solution),
+            SeenSolutions::Hashes(seen) => seen.lookup.contains(&get_hash(solution)),
+            SeenSolutions::BloomFilters(seen) => seen.contains(solution),
+        }
+    }
+
+    fn push_front(&mut self, solution: ScoredSolution<_Solution, _Score>, iteration: u64) {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.push_front(SeenSolution(Arc::new(solution)), iteration),
+            SeenSolutions::Hashes(seen) => seen.push_front(get_hash(&solution.solution), iteration),
+            SeenSolutions::BloomFilters(seen) => seen.insert(&solution.solution),
+        }
+    }
+
+    /// Get the stored copy of solution, if it is stored and has the same score, so that the best solutions can share
+    /// it instead of keeping a copy of their own.
+    fn get_shared(
+        &self,
+        solution: &ScoredSolution<_Solution, _Score>,
+    ) -> Option<Arc<ScoredSolution<_Solution, _Score>>> {
+        match self {
+            // The chosen solution is usually the one seen last, which saves hashing it.
+            SeenSolutions::Solutions(seen) => seen
+                .keys
+                .front()
+                .map(|(seen_solution, _iteration, _seen_at)| seen_solution)
+                .filter(|seen_solution| seen_solution.0.solution == solution.solution)
+                .or_else(|| seen.lookup.get(&solution.solution))
+                .filter(|seen_solution| {
+                    seen_solution.0.score == solution.score
+                        && seen_solution.0.constraint_violations == solution.constraint_violations
+                })
+                .map(|seen_solution| Arc::clone(&seen_solution.0)),
+            SeenSolutions::Hashes(_) | SeenSolutions::BloomFilters(_) => None,
+        }
+    }
+
+    fn pop_back(&mut self) {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.pop_back(),
+            SeenSolutions::Hashes(seen) => seen.pop_back(),
+            // Bloom filters can't forget single solutions, they forget a generation at a time.
+            SeenSolutions::BloomFilters(_) => {}
+        }
+    }
+
+    fn get_back_iteration(&self) -> Option<u64> {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.get_back_iteration(),
+            SeenSolutions::Hashes(seen) => seen.get_back_iteration(),
+            SeenSolutions::BloomFilters(_) => None,
+        }
+    }
+
+    fn get_back_seen_at(&self) -> Option<SystemTime> {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.get_back_seen_at(),
+            SeenSolutions::Hashes(seen) => seen.get_back_seen_at(),
+            SeenSolutions::BloomFilters(_) => None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.keys.len(),
+            SeenSolutions::Hashes(seen) => seen.keys.len(),
+            SeenSolutions::BloomFilters(seen) => seen.newer_count,
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            SeenSolutions::Solutions(seen) => seen.clear(),
+            SeenSolutions::Hashes(seen) => seen.clear(),
+            SeenSolutions::BloomFilters(seen) => seen.clear(),
+        }
+    }
+}
+
+/// Sample size items uniformly from items with Algorithm R, see [1], or all items if there are at most size.
+///
+/// [1] Vitter, Jeffrey S. "Random sampling with a reservoir." ACM Transactions on Mathematical Software 11.1 (1985):
+/// 37-57.
+fn sample_reservoir<_T, _R: rand::Rng>(
+    items: impl Iterator<Item = _T>,
+    size: usize,
+    rng: &mut _R,
+) -> Vec<_T> {
+    let mut reservoir = Vec::with_capacity(size);
+    for (index, item) in items.enumerate() {
+        if index < size {
+            reservoir.push(item);
+        } else {
+            let replace = rng.gen_range(0..=index);
+            if replace < size {
+                reservoir[replace] = item;
+            }
+        }
+    }
+    reservoir
+}
+
+/// Select the count items with the lowest estimates, lowest first, keeping only count items in memory while iterating
+/// items. Items with equal estimates keep the order in which items yields them.
+fn select_lowest_estimates<_T>(items: impl Iterator<Item = (_T, f64)>, count: usize) -> Vec<_T> {
+    let mut lowest = BinaryHeap::with_capacity(count + 1);
+    for (index, (item, estimate)) in items.enumerate() {
+        lowest.push(Estimated {
+            estimate,
+            index,
+            item,
+        });
+        if lowest.len() > count {
+            lowest.pop();
+        }
+    }
+    lowest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|estimated| estimated.item)
+        .collect()
+}
+
+/// Estimated orders an item by its estimate, and then by its index, so that a max-heap of them pops the item with
+/// the highest estimate that was yielded last.
+struct Estimated<_T> {
+    estimate: f64,
+    index: usize,
+    item: _T,
+}
+
+impl<_T> PartialEq for Estimated<_T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<_T> Eq for Estimated<_T> {}
+
+impl<_T> PartialOrd for Estimated<_T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<_T> Ord for Estimated<_T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.estimate
+            .total_cmp(&other.estimate)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+/// Count an improvement to score as progress, resetting no_improvement_for, unless min_improvement says it is too
+/// small compared to plateau_score, the score when progress was last counted.
+fn count_improvement<_Score: Score>(
+    min_improvement: Option<&MinImprovement<_Score>>,
+    score: &_Score,
+    plateau_score: &mut _Score,
+    no_improvement_for: &mut u64,
+) {
+    match min_improvement.is_none_or(|min_improvement| min_improvement.is_significant(score, plateau_score)) {
+        true => {
+            *no_improvement_for = 0;
+            *plateau_score = score.clone();
+        }
+        false => *no_improvement_for += 1,
+    }
+}
+
+/// Get a hash of solution that is the same in every run, unlike the hashes of a HashSet.
+pub(crate) fn get_hash<_Solution: Solution>(solution: &_Solution) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    solution.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// History keeps track of the all solutions that LocalSearch finds. You can then ask History for the best solutions
-/// it's seen so far, the tabu set, etc.
+/// it's seen so far, the tabu set, etc. With the serde feature History can be serialized, except for its solution
+/// distance, see set_solution_distance, its Pareto archive, see set_pareto_archive, whose front IteratedLocalSearch
+/// serializes instead, its best solution archive, see set_best_solution_archive, and the scores behind its score
+/// statistics, see set_score_statistics.
+///
+/// Every solution that History keeps is stored once, in an Arc, and the recently seen solutions, their lookup by
+/// solution and the best solutions are indexes into these. A best solution that was also recently seen shares the
+/// memory of the seen one. Serialization writes shared solutions once per index and deserialization doesn't restore
+/// the sharing.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "_Solution: Serialize, _Score: Serialize",
+        deserialize = "_Solution: Deserialize<'de>, _Score: Deserialize<'de>"
+    ))
+)]
 pub struct History<_R, _Solution, _Score>
 where
     _R: rand::Rng,
     _Solution: Solution,
     _Score: Score,
 {
-    best_solutions: BTreeSet<ScoredSolution<_Solution, _Score>>,
+    best_solutions: BTreeSet<Arc<ScoredSolution<_Solution, _Score>>>,
     best_solutions_capacity: usize,
-    all_solutions: VecDeque<ScoredSolutionAndIterationAdded<_Solution, _Score>>,
+    all_solutions: SeenSolutions<_Solution, _Score>,
     all_solutions_capacity: usize,
-    all_solutions_lookup: HashSet<_Solution>,
     all_solution_iteration_expiry: u64,
+    all_solution_time_expiry: Option<Duration>,
     pub iteration_count: u64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    solution_distance: Option<Box<dyn SolutionDistance<_Solution = _Solution> + Send>>,
+    min_distance: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pareto_archive: Option<ParetoArchive<_Solution, _Score>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    best_solution_archive: Option<BestSolutionArchive<_Solution, _Score>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    score_counts: Option<BTreeMap<_Score, u64>>,
+    distinct_solution_count: u64,
+    tabu_check_count: u64,
+    tabu_hit_count: u64,
     phantom_r: PhantomData<_R>,
 }
 
@@ -143,59 +615,248 @@ where
         History {
             best_solutions: Default::default(),
             best_solutions_capacity,
-            all_solutions: VecDeque::with_capacity(all_solutions_capacity),
+            all_solutions: SeenSolutions::new(&TabuStorage::default(), all_solutions_capacity),
             all_solutions_capacity,
-            all_solutions_lookup: Default::default(),
             all_solution_iteration_expiry,
+            all_solution_time_expiry: None,
             iteration_count: 0,
+            solution_distance: None,
+            min_distance: 0.0,
+            pareto_archive: None,
+            best_solution_archive: None,
+            score_counts: None,
+            distinct_solution_count: 0,
+            tabu_check_count: 0,
+            tabu_hit_count: 0,
             phantom_r: PhantomData,
         }
     }
 
+    /// Also forget recently seen solutions once they were seen longer than all_solution_time_expiry ago, so that they
+    /// stop being tabu, e.g. when some score calculations take much longer than others and iterations are a poor
+    /// measure of age. None, the default, only expires solutions by all_solution_iteration_expiry.
+    pub fn set_all_solution_time_expiry(&mut self, all_solution_time_expiry: Option<Duration>) {
+        self.all_solution_time_expiry = all_solution_time_expiry;
+    }
+
+    /// Like set_all_solution_time_expiry, to set the expiry when creating the history.
+    pub fn with_all_solution_time_expiry(mut self, all_solution_time_expiry: Duration) -> Self {
+        self.set_all_solution_time_expiry(Some(all_solution_time_expiry));
+        self
+    }
+
+    /// Treat best solutions that are less than min_distance apart according to solution_distance as duplicates, and
+    /// only keep the better one, see the diversity module. Without a solution distance only equal solutions are
+    /// duplicates.
+    pub fn set_solution_distance(
+        &mut self,
+        solution_distance: Box<dyn SolutionDistance<_Solution = _Solution> + Send>,
+        min_distance: f64,
+    ) {
+        assert!(min_distance > 0.0, "min_distance must be positive");
+        self.solution_distance = Some(solution_distance);
+        self.min_distance = min_distance;
+    }
+
+    /// Also keep the Pareto front of the solutions that local search chooses in pareto_archive, see the pareto module.
+    pub fn set_pareto_archive(&mut self, pareto_archive: ParetoArchive<_Solution, _Score>) {
+        self.pareto_archive = Some(pareto_archive);
+    }
+
+    /// Like set_pareto_archive, to attach the archive when creating the history.
+    pub fn with_pareto_archive(mut self, pareto_archive: ParetoArchive<_Solution, _Score>) -> Self {
+        self.set_pareto_archive(pareto_archive);
+        self
+    }
+
+    /// Append every solution that local search chooses and that is better than every best solution so far to
+    /// best_solution_archive, see the archive module. If writing to the archive fails the history logs a warning and
+    /// stops archiving.
+    pub fn set_best_solution_archive(
+        &mut self,
+        best_solution_archive: Option<BestSolutionArchive<_Solution, _Score>>,
+    ) {
+        self.best_solution_archive = best_solution_archive;
+    }
+
+    /// Like set_best_solution_archive, to attach the archive when creating the history.
+    pub fn with_best_solution_archive(
+        mut self,
+        best_solution_archive: BestSolutionArchive<_Solution, _Score>,
+    ) -> Self {
+        self.set_best_solution_archive(Some(best_solution_archive));
+        self
+    }
+
+    /// Count the scores of the solutions seen from now on, for get_best_score_seen, get_score_at_percentile and the
+    /// other score statistics. Every distinct score is kept in memory with its count, which is about one entry per
+    /// seen solution for float scores, so statistics are off by default. Turning them off forgets the counts.
+    pub fn set_score_statistics(&mut self, is_enabled: bool) {
+        self.score_counts = match is_enabled {
+            true => self.score_counts.take().or_else(|| Some(BTreeMap::new())),
+            false => None,
+        };
+    }
+
+    /// Like set_score_statistics, to turn score statistics on when creating the history.
+    pub fn with_score_statistics(mut self) -> Self {
+        self.set_score_statistics(true);
+        self
+    }
+
+    /// The Pareto front so far, best score first, or None without a Pareto archive.
+    pub fn get_pareto_front(&self) -> Option<Vec<ScoredSolution<_Solution, _Score>>> {
+        self.pareto_archive.as_ref().map(ParetoArchive::get_front)
+    }
+
+    pub fn get_best_solutions_capacity(&self) -> usize {
+        self.best_solutions_capacity
+    }
+
+    /// Store recently seen solutions as tabu_storage says. Forgets the solutions that were seen so far.
+    pub fn set_tabu_storage(&mut self, tabu_storage: TabuStorage) {
+        self.all_solutions = SeenSolutions::new(&tabu_storage, self.all_solutions_capacity);
+    }
+
+    /// Like set_tabu_storage, to choose the storage when creating the history, e.g.
+    /// `History::new(16, 100_000, 1_000).with_tabu_storage(TabuStorage::BloomFilter { false_positive_rate: 0.01 })`.
+    pub fn with_tabu_storage(mut self, tabu_storage: TabuStorage) -> Self {
+        self.set_tabu_storage(tabu_storage);
+        self
+    }
+
+    /// Replace this history with a deserialized one, but keep the solution distance, Pareto archive, best solution
+    /// archive and seen scores, which aren't serialized.
+    #[cfg(feature = "serde")]
+    pub(crate) fn replace_with(&mut self, mut history: Self) {
+        history.solution_distance = self.solution_distance.take();
+        history.min_distance = self.min_distance;
+        history.pareto_archive = self.pareto_archive.take();
+        history.best_solution_archive = self.best_solution_archive.take();
+        history.score_counts = self.score_counts.take();
+        *self = history;
+    }
+
+    /// Replace the solutions in the Pareto archive, if there is one, with front, e.g. the front of a checkpoint.
+    #[cfg(feature = "serde")]
+    pub(crate) fn replace_pareto_front(&mut self, front: Vec<ScoredSolution<_Solution, _Score>>) {
+        if let Some(pareto_archive) = self.pareto_archive.as_mut() {
+            pareto_archive.clear();
+            for solution in front {
+                pareto_archive.insert(solution);
+            }
+        }
+    }
+
     pub fn seen_solution(&mut self, solution: ScoredSolution<_Solution, _Score>) {
         self.iteration_count += 1;
+        if let Some(score_counts) = self.score_counts.as_mut() {
+            *score_counts.entry(solution.score.clone()).or_insert(0) += 1;
+        }
         self._pop_solution_for_age();
-        if self.all_solutions_lookup.contains(&solution.solution) {
+        if self.all_solutions.contains(&solution.solution) {
             return;
         }
+        self.distinct_solution_count += 1;
         self._add_solution(solution);
     }
 
     fn _add_solution(&mut self, solution: ScoredSolution<_Solution, _Score>) {
         self._pop_solution_for_size();
-        self.all_solutions.push_front(ScoredSolutionAndIterationAdded {
-            scored_solution: solution.clone(),
-            iteration: self.iteration_count,
-        });
-        self.all_solutions_lookup.insert(solution.solution.clone());
+        self.all_solutions.push_front(solution, self.iteration_count);
     }
 
     fn _pop_solution_for_size(&mut self) {
         while self.all_solutions.len() > self.all_solutions_capacity {
-            if let Some(solution) = self.all_solutions.pop_back() {
-                self.all_solutions_lookup
-                    .remove(&solution.scored_solution.solution);
-            }
+            self.all_solutions.pop_back();
         }
     }
 
     fn _pop_solution_for_age(&mut self) {
-        loop {
-            if let Some(solution) = self.all_solutions.back() {
-                let inner_solution = &solution.scored_solution.solution;
-                if solution.iteration + self.all_solution_iteration_expiry >= self.iteration_count {
-                    self.all_solutions_lookup.remove(inner_solution);
-                    self.all_solutions.pop_back();
-                    continue;
-                }
-                break;
+        while let Some(iteration) = self.all_solutions.get_back_iteration() {
+            if iteration.saturating_add(self.all_solution_iteration_expiry) < self.iteration_count {
+                self.all_solutions.pop_back();
+                continue;
             }
             break;
         }
+        if let Some(all_solution_time_expiry) = self.all_solution_time_expiry {
+            while let Some(seen_at) = self.all_solutions.get_back_seen_at() {
+                // A clock that went backwards leaves the solution tabu rather than expiring it early.
+                match seen_at.elapsed() {
+                    Ok(age) if age >= all_solution_time_expiry => self.all_solutions.pop_back(),
+                    _ => break,
+                }
+            }
+        }
     }
 
     pub fn is_solution_tabu(&self, solution: &_Solution) -> bool {
-        self.all_solutions_lookup.contains(solution)
+        self.all_solutions.contains(solution)
+    }
+
+    /// Like is_solution_tabu, but counts towards get_tabu_hit_rate. LocalSearch checks the neighbors it proposes with
+    /// it.
+    pub fn check_solution_tabu(&mut self, solution: &_Solution) -> bool {
+        let is_tabu = self.is_solution_tabu(solution);
+        self.tabu_check_count += 1;
+        if is_tabu {
+            self.tabu_hit_count += 1;
+        }
+        is_tabu
+    }
+
+    /// The best score of the solutions seen so far, see seen_solution, or None if none were seen or score statistics
+    /// are off, see set_score_statistics. Unlike get_best this includes solutions that local search passed through
+    /// without choosing.
+    pub fn get_best_score_seen(&self) -> Option<_Score> {
+        self.score_counts.as_ref()?.keys().next().cloned()
+    }
+
+    /// The worst score of the solutions seen so far, or None if none were seen or score statistics are off.
+    pub fn get_worst_score_seen(&self) -> Option<_Score> {
+        self.score_counts.as_ref()?.keys().next_back().cloned()
+    }
+
+    /// The median score of the solutions seen so far, or None if none were seen or score statistics are off.
+    pub fn get_median_score_seen(&self) -> Option<_Score> {
+        self.get_score_at_percentile(50.0)
+    }
+
+    /// The nearest-rank percentile of the scores of the solutions seen so far, where 0 is the best and 100 the worst
+    /// score, or None if none were seen or score statistics are off. Every seen solution counts, so a solution seen
+    /// twice weighs twice. Seen scores are not serialized.
+    pub fn get_score_at_percentile(&self, percentile: f64) -> Option<_Score> {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "percentile must be between 0 and 100"
+        );
+        let score_counts = self.score_counts.as_ref()?;
+        let seen: u64 = score_counts.values().sum();
+        let rank = ((percentile / 100.0 * seen as f64).ceil() as u64).max(1);
+        let mut cumulative = 0;
+        for (score, count) in score_counts.iter() {
+            cumulative += count;
+            if cumulative >= rank {
+                return Some(score.clone());
+            }
+        }
+        None
+    }
+
+    /// How many seen solutions were not tabu when they were seen, i.e. distinct from the recently seen solutions
+    /// that the tabu storage remembers.
+    pub fn get_distinct_solution_count(&self) -> u64 {
+        self.distinct_solution_count
+    }
+
+    /// The fraction of tabu checks, see check_solution_tabu, that found a tabu solution, or 0 without checks. A high
+    /// rate means that local search keeps proposing solutions it has just seen.
+    pub fn get_tabu_hit_rate(&self) -> f64 {
+        match self.tabu_check_count {
+            0 => 0.0,
+            tabu_check_count => self.tabu_hit_count as f64 / tabu_check_count as f64,
+        }
     }
 
     pub fn is_best_solution(&self, solution: ScoredSolution<_Solution, _Score>) -> bool {
@@ -203,27 +864,70 @@ where
     }
 
     pub fn local_search_chose_solution(&mut self, solution: ScoredSolution<_Solution, _Score>) {
+        if let Some(best_solution_archive) = self.best_solution_archive.as_mut() {
+            let is_new_best = self
+                .best_solutions
+                .first()
+                .is_none_or(|best| solution.score < best.score);
+            if is_new_best {
+                if let Err(err) = best_solution_archive.archive(&solution) {
+                    tracing::warn!(%err, "failed to archive best solution, disabling the archive");
+                    self.best_solution_archive = None;
+                }
+            }
+        }
+        if let Some(pareto_archive) = self.pareto_archive.as_mut() {
+            pareto_archive.insert(solution.clone());
+        }
+        if let Some(solution_distance) = self.solution_distance.as_ref() {
+            let near: Vec<Arc<ScoredSolution<_Solution, _Score>>> = self
+                .best_solutions
+                .iter()
+                .filter(|best| {
+                    solution_distance.get_distance(&best.solution, &solution.solution) < self.min_distance
+                })
+                .cloned()
+                .collect();
+            if near.iter().any(|near| **near <= solution) {
+                return;
+            }
+            for near in near {
+                self.best_solutions.remove(&near);
+            }
+        }
         if self.best_solutions.len() < self.best_solutions_capacity {
-            self.best_solutions.insert(solution.clone());
+            let solution = self.share_solution(solution);
+            self.best_solutions.insert(solution);
             return;
         }
 
         // TODO better heuristic for creating a diverse best solution set even if the candidate solution has a worse
         // score.
-        let worst_solution = self.best_solutions.iter().next_back().unwrap().clone();
+        let worst_solution = Arc::clone(self.best_solutions.iter().next_back().unwrap());
         if solution.score <= worst_solution.score {
             self.best_solutions.remove(&worst_solution);
+            let solution = self.share_solution(solution);
             self.best_solutions.insert(solution);
         }
     }
 
+    /// Wrap solution in an Arc for the best solutions, sharing the one of the recently seen solutions if there is one.
+    fn share_solution(
+        &self,
+        solution: ScoredSolution<_Solution, _Score>,
+    ) -> Arc<ScoredSolution<_Solution, _Score>> {
+        self.all_solutions
+            .get_shared(&solution)
+            .unwrap_or_else(|| Arc::new(solution))
+    }
+
     pub fn get_random_best_solution(&self, rng: &mut _R) -> Option<ScoredSolution<_Solution, _Score>> {
         if self.best_solutions.is_empty() {
             return None;
         }
-        let best_solutions_vec: Vec<ScoredSolution<_Solution, _Score>> =
-            self.best_solutions.iter().cloned().collect();
-        let random_best_solution = best_solutions_vec.choose(rng).unwrap().clone();
+        let best_solutions_vec: Vec<&ScoredSolution<_Solution, _Score>> =
+            self.best_solutions.iter().map(Arc::as_ref).collect();
+        let random_best_solution = (*best_solutions_vec.choose(rng).unwrap()).clone();
         Some(random_best_solution)
     }
 
@@ -231,7 +935,12 @@ where
         if self.best_solutions.is_empty() {
             return None;
         }
-        let result = self.best_solutions.iter().take(number_to_get).cloned().collect();
+        let result = self
+            .best_solutions
+            .iter()
+            .take(number_to_get)
+            .map(|best| ScoredSolution::clone(best))
+            .collect();
         Some(result)
     }
 
@@ -239,16 +948,76 @@ where
         if self.best_solutions.is_empty() {
             return None;
         }
-        Some(self.best_solutions.iter().next().unwrap().clone())
+        Some(ScoredSolution::clone(self.best_solutions.iter().next().unwrap()))
+    }
+
+    /// Get the constraint violations of the best solution, see ScoredSolution::constraint_violations, or None if
+    /// there is no best solution yet or its score calculator doesn't count violations.
+    pub fn get_best_constraint_violations(&self) -> Option<&[(ConstraintId, u64)]> {
+        self.best_solutions
+            .iter()
+            .next()?
+            .constraint_violations
+            .as_deref()
     }
 
     pub fn clear(&mut self) {
         self.all_solutions.clear();
-        self.all_solutions_lookup.clear();
         self.best_solutions.clear();
     }
 }
 
+/// StepAcceptance decides whether LocalSearch moves from the current solution to the best solution in the sampled
+/// neighborhood.
+///
+/// [3] Burke, Edmund K. and Yuri Bykov. "The Late Acceptance Hill-Climbing Heuristic." European Journal of
+/// Operational Research 258 (2017): 70-78.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StepAcceptance {
+    /// Always move to the best neighbor, even if it is worse than the current solution.
+    BestNeighbor,
+
+    /// Late Acceptance Hill Climbing, see [3]. Move to the best neighbor if it is no worse than the current solution,
+    /// or no worse than the current solution was length iterations ago.
+    LateAcceptance { length: usize },
+}
+
+/// NeighborhoodExploration decides how many neighbors LocalSearch scores per step before it picks the best of them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NeighborhoodExploration {
+    /// Stop at the first neighbor that is strictly better than the current solution, after at most window_size
+    /// neighbors. If none is better, use the best of the window_size neighbors. Neighbors are proposed and scored one
+    /// at a time, so the neighbors after the first improving one are never scored.
+    FirstImprovement,
+
+    /// Score window_size neighbors and use the best one.
+    BestImprovement,
+
+    /// Score size neighbors instead of window_size and use the best one.
+    BestOfWindow { size: usize },
+
+    /// Score size neighbors that are sampled uniformly from every neighbor that MoveProposer::iter_local_moves yields,
+    /// with reservoir sampling, and use the best one. Unlike BestOfWindow the sample does not favor the neighbors that
+    /// the move proposer happens to yield first, at the cost of iterating (but not scoring) the whole neighborhood, or
+    /// its first max_neighborhood_size neighbors, see LocalSearch::set_max_neighborhood_size.
+    ReservoirSample { size: usize },
+
+    /// Score every neighbor that MoveProposer::iter_local_moves yields, up to max_neighborhood_size of them, see
+    /// LocalSearch::set_max_neighborhood_size, and use the best one, and stop once none of them improves on the current
+    /// solution, so that the result is a local optimum of the whole neighborhood. Only use with small neighborhoods.
+    Exhaustive,
+}
+
+/// AdaptiveWindowSize lets the window size of LocalSearch follow the search instead of staying fixed: it doubles after
+/// every step that does not improve on the best solution, to look harder for a way off a plateau, and halves after
+/// every step that does, to move on quickly while improvements are easy to find. It always stays between min and max,
+/// and starts every execute at the window size of LocalSearch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdaptiveWindowSize {
+    pub min: usize,
+    pub max: usize,
+}
+
 /// LocalSearch lets you find local minima for an optimization problem.
 pub struct LocalSearch<R, _Solution, _Score, SSC, MP>
 where
@@ -262,6 +1031,21 @@ where
     solution_score_calculator: SSC,
     max_iterations: u64,
     window_size: usize,
+    adaptive_window_size: Option<AdaptiveWindowSize>,
+    neighborhood_exploration: NeighborhoodExploration,
+    step_acceptance: StepAcceptance,
+    random_walk_probability: f64,
+    move_tabu: Option<Box<dyn MoveTabu<_Solution = _Solution> + Send>>,
+    incremental_score_calculator:
+        Option<Box<dyn NeighborScoreCalculator<_Solution = _Solution, _Score = _Score> + Send>>,
+    deadline: Option<Instant>,
+    score_threshold: Option<ScoreThreshold<_Score>>,
+    cancellation_token: Option<CancellationToken>,
+    min_improvement: Option<MinImprovement<_Score>>,
+    estimated_candidates: Option<usize>,
+    max_neighborhood_size: usize,
+    observers: Vec<Box<dyn Observer<_Solution = _Solution, _Score = _Score> + Send>>,
+    stats: SolverStats<_Score>,
     history: History<R, _Solution, _Score>,
     rng: R,
 }
@@ -289,6 +1073,20 @@ where
             solution_score_calculator,
             max_iterations,
             window_size,
+            adaptive_window_size: None,
+            neighborhood_exploration: NeighborhoodExploration::BestImprovement,
+            step_acceptance: StepAcceptance::BestNeighbor,
+            random_walk_probability: 0.0,
+            move_tabu: None,
+            incremental_score_calculator: None,
+            deadline: None,
+            score_threshold: None,
+            cancellation_token: None,
+            min_improvement: None,
+            estimated_candidates: None,
+            max_neighborhood_size: 100_000,
+            observers: vec![],
+            stats: SolverStats::default(),
             history: History::new(
                 best_solutions_capacity,
                 all_solutions_capacity,
@@ -298,48 +1096,866 @@ where
         }
     }
 
-    pub fn execute(
-        &mut self,
-        start: _Solution,
-        allow_no_improvement_for: u64,
-    ) -> ScoredSolution<_Solution, _Score> {
-        let mut current_solution = self.solution_score_calculator.get_scored_solution(start);
-        let mut best_solution = current_solution.clone();
-        let mut no_improvement_for = 0;
-        for _current_iteration in 0..self.max_iterations {
-            self.history.seen_solution(current_solution.clone());
-            if current_solution.score.is_best() {
-                println!("local search found best possible solution and is terminating");
-                return current_solution;
-            }
-            let mut neighborhood: Vec<ScoredSolution<_Solution, _Score>> = self
-                .move_proposer
-                .iter_local_moves(&current_solution.solution, &mut self.rng)
-                .into_iter()
-                .filter(|solution| !self.history.is_solution_tabu(solution))
-                .map(|solution| self.solution_score_calculator.get_scored_solution(solution))
-                .take(self.window_size)
-                .collect();
-            neighborhood.sort();
-            // println!("ls neighborhood size {}, best score {:?}", neighborhood.len(), neighborhood.first());
-            if let Some(neighborhood_best) = neighborhood.first() {
-                if neighborhood_best.score < current_solution.score {
-                    best_solution = neighborhood_best.clone();
-                    no_improvement_for = 0;
-                } else {
-                    no_improvement_for += 1;
-                    if no_improvement_for >= allow_no_improvement_for {
-                        break;
-                    }
-                }
-                current_solution = neighborhood_best.clone();
-            } else {
-                break;
+    /// Create from the settings of config, see the config module. Shorthand for
+    /// LocalSearchBuilder::new(...).config(config).try_build().
+    pub fn from_config(
+        move_proposer: MP,
+        solution_score_calculator: SSC,
+        rng: R,
+        config: &LocalSearchConfig,
+    ) -> Result<Self, SolverError> {
+        LocalSearchBuilder::new(move_proposer, solution_score_calculator, rng)
+            .config(config)
+            .try_build()
+    }
+
+    /// By default LocalSearch uses NeighborhoodExploration::BestImprovement.
+    pub fn set_neighborhood_exploration(&mut self, neighborhood_exploration: NeighborhoodExploration) {
+        if let NeighborhoodExploration::BestOfWindow { size }
+        | NeighborhoodExploration::ReservoirSample { size } = neighborhood_exploration
+        {
+            assert!(size > 0, "window size must be positive");
+        }
+        self.neighborhood_exploration = neighborhood_exploration;
+    }
+
+    /// Adapt the window size of NeighborhoodExploration::FirstImprovement and BestImprovement to the progress of the
+    /// search, see AdaptiveWindowSize. None, the default, keeps it fixed.
+    pub fn set_adaptive_window_size(&mut self, adaptive_window_size: Option<AdaptiveWindowSize>) {
+        if let Some(AdaptiveWindowSize { min, max }) = adaptive_window_size {
+            assert!(min > 0, "min window size must be positive");
+            assert!(min <= max, "min window size must be at most max window size");
+        }
+        self.adaptive_window_size = adaptive_window_size;
+    }
+
+    /// By default LocalSearch uses StepAcceptance::BestNeighbor.
+    pub fn set_step_acceptance(&mut self, step_acceptance: StepAcceptance) {
+        if let StepAcceptance::LateAcceptance { length } = step_acceptance {
+            assert!(length > 0, "late acceptance length must be positive");
+        }
+        self.step_acceptance = step_acceptance;
+    }
+
+    /// Make a random walk step with probability random_walk_probability: move to a uniformly random neighbor of the
+    /// window regardless of its score and of the StepAcceptance. This is the noise of min-conflicts style searches, and
+    /// helps to leave plateaus without a perturbation. Defaults to 0.
+    pub fn set_random_walk_probability(&mut self, random_walk_probability: f64) {
+        assert!(
+            (0.0..=1.0).contains(&random_walk_probability),
+            "random_walk_probability must be between 0 and 1"
+        );
+        self.random_walk_probability = random_walk_probability;
+    }
+
+    /// Store the recently seen solutions that are tabu as tabu_storage says. Defaults to TabuStorage::Solutions.
+    pub fn set_tabu_storage(&mut self, tabu_storage: TabuStorage) {
+        self.history.set_tabu_storage(tabu_storage);
+    }
+
+    /// Expire recently seen solutions by age too, see History::set_all_solution_time_expiry.
+    pub fn set_all_solution_time_expiry(&mut self, all_solution_time_expiry: Option<Duration>) {
+        self.history
+            .set_all_solution_time_expiry(all_solution_time_expiry);
+    }
+
+    /// Forbid moves by their attributes, see the tabu module, instead of forbidding every solution that was seen
+    /// recently. Tabu moves are still made if they lead to a new best solution.
+    pub fn set_move_tabu(&mut self, move_tabu: Box<dyn MoveTabu<_Solution = _Solution> + Send>) {
+        self.move_tabu = Some(move_tabu);
+    }
+
+    /// Score neighbors with incremental_score_calculator instead of rescoring them in full with the
+    /// SolutionScoreCalculator, see the incremental module. Neighbors that are not a single move away from the current
+    /// solution, see NeighborMove, are still scored in full, and so is the start solution.
+    pub fn set_incremental_score_calculator<_ISC>(&mut self, incremental_score_calculator: _ISC)
+    where
+        _ISC: IncrementalScoreCalculator<_Solution = _Solution, _Score = _Score> + Send + 'static,
+        _ISC::_Move: NeighborMove,
+    {
+        self.incremental_score_calculator = Some(Box::new(incremental_score_calculator));
+    }
+
+    /// Make execute stop and return the best solution found so far once time_limit has passed from now.
+    pub fn set_time_limit(&mut self, time_limit: Duration) {
+        self.set_deadline(Some(Instant::now() + time_limit));
+    }
+
+    /// Like set_time_limit, but with an absolute point in time. None removes the limit.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    /// Stop as soon as the current solution reaches score_threshold, in addition to stopping at best possible
+    /// solutions.
+    pub fn set_score_threshold(&mut self, score_threshold: Option<ScoreThreshold<_Score>>) {
+        self.score_threshold = score_threshold;
+    }
+
+    /// Make execute stop and return the best solution found so far once cancellation_token is cancelled.
+    pub fn set_cancellation_token(&mut self, cancellation_token: Option<CancellationToken>) {
+        self.cancellation_token = cancellation_token;
+    }
+
+    /// Only count improvements of the best solution that min_improvement says are significant, see MinImprovement, so
+    /// that execute stops on a plateau of tiny improvements. Defaults to counting every improvement.
+    pub fn set_min_improvement(&mut self, min_improvement: Option<MinImprovement<_Score>>) {
+        self.min_improvement = min_improvement;
+    }
+
+    /// If the move proposer estimates its moves, see MoveProposer::iter_estimated_local_moves, only score the
+    /// estimated_candidates neighbors with the lowest estimates every step, best estimate first. This iterates, but
+    /// doesn't score or keep, the whole neighborhood, so it pays off when scoring costs much more than estimating.
+    /// Defaults to scoring neighbors in the order that the move proposer yields them.
+    pub fn set_estimated_candidates(&mut self, estimated_candidates: Option<usize>) {
+        assert!(
+            estimated_candidates != Some(0),
+            "estimated_candidates must be positive"
+        );
+        self.estimated_candidates = estimated_candidates;
+    }
+
+    /// Take at most max_neighborhood_size neighbors from the move proposer every step, tabu or not. Only the
+    /// explorations that iterate the whole neighborhood, NeighborhoodExploration::ReservoirSample and Exhaustive, and
+    /// estimated candidates get that far, and the cap is what makes them stop on a move proposer that yields neighbors
+    /// forever. Defaults to 100,000.
+    pub fn set_max_neighborhood_size(&mut self, max_neighborhood_size: usize) {
+        assert!(
+            max_neighborhood_size > 0,
+            "max_neighborhood_size must be positive"
+        );
+        self.max_neighborhood_size = max_neighborhood_size;
+    }
+
+    /// Call observer on every step of every execute from now on, see the observer module.
+    pub fn add_observer(
+        &mut self,
+        observer: Box<dyn Observer<_Solution = _Solution, _Score = _Score> + Send>,
+    ) {
+        self.observers.push(observer);
+    }
+
+    /// Get the move counts of every execute so far.
+    pub fn get_stats(&self) -> &SolverStats<_Score> {
+        &self.stats
+    }
+
+    /// Search from start for at most max_iterations steps, stopping early once allow_no_improvement_for steps in a
+    /// row did not improve the score. The search always takes a first step, so 0 behaves like 1: it keeps stepping
+    /// while the score improves and stops at the first step that doesn't.
+    pub fn execute(
+        &mut self,
+        start: _Solution,
+        allow_no_improvement_for: u64,
+    ) -> ScoredSolution<_Solution, _Score> {
+        let termination = Or(
+            MaxIterations(self.max_iterations),
+            Stagnation(allow_no_improvement_for.max(1)),
+        );
+        self.execute_until(start, &termination)
+    }
+
+    /// Search from start until termination says to stop, see the termination module. Also stops at the deadline,
+    /// if any, when cancelled, and as soon as a best possible solution is found.
+    pub fn execute_until<_T>(
+        &mut self,
+        start: _Solution,
+        termination: &_T,
+    ) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let _span = tracing::trace_span!("local_search").entered();
+        let started = Instant::now();
+        let mut current_solution = self.solution_score_calculator.get_scored_solution(start);
+        let mut best_solution = current_solution.clone();
+        let mut no_improvement_for = 0;
+        // The best score when an improvement was last counted, see set_min_improvement.
+        let mut plateau_score = best_solution.score.clone();
+        let mut adapted_window_size = match self.adaptive_window_size.as_ref() {
+            Some(adaptive_window_size) => self
+                .window_size
+                .clamp(adaptive_window_size.min, adaptive_window_size.max),
+            None => self.window_size,
+        };
+        // Circular buffer of the scores of the last length current solutions, only used by late acceptance.
+        let mut late_scores: Vec<_Score> = match self.step_acceptance {
+            StepAcceptance::BestNeighbor => vec![],
+            StepAcceptance::LateAcceptance { length } => vec![current_solution.score.clone(); length],
+        };
+        for current_iteration in 0.. {
+            let state = TerminationState {
+                iteration: current_iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&best_solution.score),
+                iterations_without_improvement: no_improvement_for,
+            };
+            if termination.is_terminated(&state) {
+                break;
+            }
+            if self.move_tabu.is_none() {
+                self.history.seen_solution(current_solution.clone());
+            }
+            if current_solution.score.is_best() {
+                tracing::debug!(
+                    iteration = current_iteration,
+                    "found best possible solution, terminating"
+                );
+                return current_solution;
+            }
+            if self
+                .score_threshold
+                .as_ref()
+                .is_some_and(|score_threshold| score_threshold.is_reached(&current_solution.score))
+            {
+                tracing::debug!(
+                    iteration = current_iteration,
+                    "reached the score threshold, terminating"
+                );
+                return std::cmp::min(current_solution, best_solution);
+            }
+            if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                tracing::debug!(iteration = current_iteration, "ran out of time, terminating");
+                break;
+            }
+            if self
+                .cancellation_token
+                .as_ref()
+                .is_some_and(|cancellation_token| cancellation_token.is_cancelled())
+            {
+                tracing::debug!(iteration = current_iteration, "cancelled, terminating");
+                break;
+            }
+            let (window_size, first_improvement) = match self.neighborhood_exploration {
+                NeighborhoodExploration::FirstImprovement => (adapted_window_size, true),
+                NeighborhoodExploration::BestImprovement => (adapted_window_size, false),
+                NeighborhoodExploration::BestOfWindow { size }
+                | NeighborhoodExploration::ReservoirSample { size } => (size, false),
+                NeighborhoodExploration::Exhaustive => (usize::MAX, false),
+            };
+            let is_random_walk =
+                self.random_walk_probability > 0.0 && self.rng.gen_bool(self.random_walk_probability);
+            let max_neighborhood_size = self.max_neighborhood_size;
+            let estimated_moves = self.estimated_candidates.and_then(|estimated_candidates| {
+                self.move_proposer
+                    .iter_estimated_local_moves(&current_solution.solution, &mut self.rng)
+                    .map(|estimated_moves| {
+                        (estimated_candidates, estimated_moves.take(max_neighborhood_size))
+                    })
+            });
+            let mut is_tabu =
+                |solution: &_Solution| self.move_tabu.is_none() && self.history.check_solution_tabu(solution);
+            let moves: Box<dyn Iterator<Item = _Solution>> = match estimated_moves {
+                Some((estimated_candidates, estimated_moves)) => {
+                    // Only the estimated_candidates lowest estimates are scored, so only those are kept, and with
+                    // FirstImprovement scoring stops at the first of them that improves.
+                    let estimated_moves = estimated_moves.filter(|(solution, _)| !is_tabu(solution));
+                    Box::new(select_lowest_estimates(estimated_moves, estimated_candidates).into_iter())
+                }
+                None => Box::new(
+                    self.move_proposer
+                        .iter_local_moves(&current_solution.solution, &mut self.rng)
+                        .take(max_neighborhood_size)
+                        .filter(move |solution| !is_tabu(solution)),
+                ),
+            };
+            let moves: Box<dyn Iterator<Item = _Solution>> = match self.neighborhood_exploration {
+                NeighborhoodExploration::ReservoirSample { size } => {
+                    Box::new(sample_reservoir(moves, size, &mut self.rng).into_iter())
+                }
+                _ => Box::new(moves),
+            };
+            let neighborhood = moves
+                .map(|solution| {
+                    let score =
+                        self.incremental_score_calculator
+                            .as_ref()
+                            .and_then(|incremental_score_calculator| {
+                                incremental_score_calculator.get_neighbor_score(
+                                    &current_solution.solution,
+                                    &current_solution.score,
+                                    &solution,
+                                )
+                            });
+                    match score {
+                        Some(score) => ScoredSolution::new(solution, score),
+                        None => self.solution_score_calculator.get_scored_solution(solution),
+                    }
+                })
+                .filter(|neighbor| match self.move_tabu.as_ref() {
+                    Some(move_tabu) => {
+                        neighbor.score < best_solution.score
+                            || !move_tabu.is_move_tabu(&current_solution.solution, &neighbor.solution)
+                    }
+                    None => true,
+                })
+                .take(window_size);
+            let mut proposed_moves = 0;
+            let mut neighborhood_best: Option<ScoredSolution<_Solution, _Score>> = None;
+            for neighbor in neighborhood {
+                proposed_moves += 1;
+                if is_random_walk {
+                    // Reservoir sampling, so that every neighbor of the window is equally likely to be the step.
+                    if self.rng.gen_range(0..proposed_moves) == 0 {
+                        neighborhood_best = Some(neighbor);
+                    }
+                    continue;
+                }
+                let improves = neighbor.score < current_solution.score;
+                if neighborhood_best.as_ref().is_none_or(|best| neighbor < *best) {
+                    neighborhood_best = Some(neighbor);
+                }
+                if first_improvement && improves {
+                    break;
+                }
+            }
+            self.stats.proposed_moves += proposed_moves;
+            let neighborhood_best = match neighborhood_best {
+                Some(neighborhood_best) => neighborhood_best,
+                None => break,
+            };
+            if !is_random_walk
+                && self.neighborhood_exploration == NeighborhoodExploration::Exhaustive
+                && neighborhood_best.score >= current_solution.score
+            {
+                tracing::debug!(
+                    iteration = current_iteration,
+                    "reached a local optimum, terminating"
+                );
+                break;
+            }
+            let new_best = (neighborhood_best.score < best_solution.score).then(|| neighborhood_best.clone());
+            match self.step_acceptance {
+                _ if is_random_walk => {
+                    if neighborhood_best.score < best_solution.score {
+                        best_solution = neighborhood_best.clone();
+                        count_improvement(
+                            self.min_improvement.as_ref(),
+                            &best_solution.score,
+                            &mut plateau_score,
+                            &mut no_improvement_for,
+                        );
+                    } else {
+                        no_improvement_for += 1;
+                    }
+                    self.record_move(&current_solution, &neighborhood_best);
+                    current_solution = neighborhood_best;
+                }
+                StepAcceptance::BestNeighbor => {
+                    if neighborhood_best.score < current_solution.score {
+                        best_solution = neighborhood_best.clone();
+                        count_improvement(
+                            self.min_improvement.as_ref(),
+                            &best_solution.score,
+                            &mut plateau_score,
+                            &mut no_improvement_for,
+                        );
+                    } else {
+                        no_improvement_for += 1;
+                    }
+                    self.record_move(&current_solution, &neighborhood_best);
+                    current_solution = neighborhood_best;
+                }
+                StepAcceptance::LateAcceptance { length } => {
+                    if neighborhood_best.score < best_solution.score {
+                        best_solution = neighborhood_best.clone();
+                        count_improvement(
+                            self.min_improvement.as_ref(),
+                            &best_solution.score,
+                            &mut plateau_score,
+                            &mut no_improvement_for,
+                        );
+                    } else {
+                        no_improvement_for += 1;
+                    }
+                    let late_index = (current_iteration % length as u64) as usize;
+                    if neighborhood_best.score <= late_scores[late_index]
+                        || neighborhood_best.score <= current_solution.score
+                    {
+                        self.record_move(&current_solution, &neighborhood_best);
+                        current_solution = neighborhood_best;
+                    }
+                    late_scores[late_index] = current_solution.score.clone();
+                }
+            }
+            if let Some(adaptive_window_size) = self.adaptive_window_size.as_ref() {
+                adapted_window_size = match no_improvement_for {
+                    0 => (adapted_window_size / 2).max(adaptive_window_size.min),
+                    _ => adapted_window_size
+                        .saturating_mul(2)
+                        .min(adaptive_window_size.max),
+                };
+            }
+            for observer in self.observers.iter_mut() {
+                if let Some(new_best) = new_best.as_ref() {
+                    observer.on_new_best(current_iteration, new_best);
+                }
+                observer.on_iteration(current_iteration, &current_solution);
             }
         }
-        // println!("ls best solution: {:?}", best_solution);
+        tracing::trace!(best_score = ?best_solution.score, "finished");
         best_solution
     }
+
+    fn record_move(
+        &mut self,
+        from: &ScoredSolution<_Solution, _Score>,
+        to: &ScoredSolution<_Solution, _Score>,
+    ) {
+        self.stats.accepted_moves += 1;
+        if to.score < from.score {
+            self.stats.improving_moves += 1;
+        }
+        if let Some(move_tabu) = self.move_tabu.as_mut() {
+            move_tabu.record_move(&from.solution, &to.solution);
+        }
+    }
+}
+
+/// LocalSearchState is what LocalSearch serializes to with the serde feature, see LocalSearch::restore.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+pub(crate) struct LocalSearchState<R, _Solution, _Score>
+where
+    R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    history: History<R, _Solution, _Score>,
+    stats: SolverStats<_Score>,
+    rng: R,
+}
+
+/// With the serde feature LocalSearch serializes the state that changes while it executes, i.e. its history,
+/// statistics and random number generator. Its settings, move proposer and score calculator are not serialized, so
+/// that a checkpoint is restored into a LocalSearch that was created with the same settings, see restore.
+#[cfg(feature = "serde")]
+impl<R, _Solution, _Score, SSC, MP> Serialize for LocalSearch<R, _Solution, _Score, SSC, MP>
+where
+    R: rand::Rng + Serialize,
+    _Solution: Solution + Serialize,
+    _Score: Score + Serialize,
+    SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    MP: MoveProposer<R = R, Solution = _Solution>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LocalSearch", 3)?;
+        state.serialize_field("history", &self.history)?;
+        state.serialize_field("stats", &self.stats)?;
+        state.serialize_field("rng", &self.rng)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<R, _Solution, _Score, SSC, MP> LocalSearch<R, _Solution, _Score, SSC, MP>
+where
+    R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    MP: MoveProposer<R = R, Solution = _Solution>,
+{
+    /// Replace the state of this LocalSearch with one it was serialized to, so that it continues exactly where the
+    /// serialized one stopped. The solution distance of the history is kept.
+    pub fn restore<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+        R: Deserialize<'de>,
+        _Solution: Deserialize<'de>,
+        _Score: Deserialize<'de>,
+    {
+        self.set_state(LocalSearchState::deserialize(deserializer)?);
+        Ok(())
+    }
+
+    pub(crate) fn set_state(&mut self, state: LocalSearchState<R, _Solution, _Score>) {
+        self.history.replace_with(state.history);
+        self.stats = state.stats;
+        self.rng = state.rng;
+    }
+}
+
+/// LocalSearchBuilder creates a LocalSearch with named settings instead of LocalSearch::new's positional arguments.
+/// Settings that aren't set keep their defaults, and build checks that they make sense.
+pub struct LocalSearchBuilder<R, _Solution, _Score, SSC, MP>
+where
+    R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    MP: MoveProposer<R = R, Solution = _Solution>,
+{
+    move_proposer: MP,
+    solution_score_calculator: SSC,
+    rng: R,
+    max_iterations: u64,
+    window_size: usize,
+    adaptive_window_size: Option<AdaptiveWindowSize>,
+    best_solutions_capacity: usize,
+    all_solutions_capacity: usize,
+    all_solution_iteration_expiry: u64,
+    neighborhood_exploration: NeighborhoodExploration,
+    step_acceptance: StepAcceptance,
+    random_walk_probability: f64,
+    tabu_storage: TabuStorage,
+    all_solution_time_expiry: Option<Duration>,
+    min_improvement: Option<MinImprovement<_Score>>,
+    estimated_candidates: Option<usize>,
+    max_neighborhood_size: usize,
+}
+
+impl<R, _Solution, _Score, SSC, MP> LocalSearchBuilder<R, _Solution, _Score, SSC, MP>
+where
+    R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    MP: MoveProposer<R = R, Solution = _Solution>,
+{
+    pub fn new(move_proposer: MP, solution_score_calculator: SSC, rng: R) -> Self {
+        Self {
+            move_proposer,
+            solution_score_calculator,
+            rng,
+            max_iterations: 10_000,
+            window_size: 100,
+            adaptive_window_size: None,
+            best_solutions_capacity: 16,
+            all_solutions_capacity: 10_000,
+            all_solution_iteration_expiry: 100_000,
+            neighborhood_exploration: NeighborhoodExploration::BestImprovement,
+            step_acceptance: StepAcceptance::BestNeighbor,
+            random_walk_probability: 0.0,
+            tabu_storage: TabuStorage::default(),
+            all_solution_time_expiry: None,
+            min_improvement: None,
+            estimated_candidates: None,
+            max_neighborhood_size: 100_000,
+        }
+    }
+
+    /// Maximum number of steps per LocalSearch::execute. Defaults to 10,000.
+    pub fn max_iterations(mut self, max_iterations: u64) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Number of neighbors sampled per step. Defaults to 100.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Let the window size grow and shrink between min and max, see AdaptiveWindowSize. Defaults to a fixed window
+    /// size.
+    pub fn adaptive_window_size(mut self, min: usize, max: usize) -> Self {
+        self.adaptive_window_size = Some(AdaptiveWindowSize { min, max });
+        self
+    }
+
+    /// Defaults to 16.
+    pub fn best_solutions_capacity(mut self, best_solutions_capacity: usize) -> Self {
+        self.best_solutions_capacity = best_solutions_capacity;
+        self
+    }
+
+    /// Number of recently seen solutions that are tabu. Defaults to 10,000.
+    pub fn all_solutions_capacity(mut self, all_solutions_capacity: usize) -> Self {
+        self.all_solutions_capacity = all_solutions_capacity;
+        self
+    }
+
+    /// Forget a recently seen solution once more than all_solution_iteration_expiry solutions were seen after it.
+    /// Defaults to 100,000.
+    pub fn all_solution_iteration_expiry(mut self, all_solution_iteration_expiry: u64) -> Self {
+        self.all_solution_iteration_expiry = all_solution_iteration_expiry;
+        self
+    }
+
+    /// Also expire recently seen solutions by age, see History::set_all_solution_time_expiry. Defaults to only
+    /// expiring them by iterations.
+    pub fn all_solution_time_expiry(mut self, all_solution_time_expiry: Duration) -> Self {
+        self.all_solution_time_expiry = Some(all_solution_time_expiry);
+        self
+    }
+
+    /// Only count significant improvements, see LocalSearch::set_min_improvement. Defaults to counting every
+    /// improvement.
+    pub fn min_improvement(mut self, min_improvement: MinImprovement<_Score>) -> Self {
+        self.min_improvement = Some(min_improvement);
+        self
+    }
+
+    /// Only score the estimated_candidates neighbors with the lowest estimates, see
+    /// LocalSearch::set_estimated_candidates. Defaults to not using estimates.
+    pub fn estimated_candidates(mut self, estimated_candidates: usize) -> Self {
+        self.estimated_candidates = Some(estimated_candidates);
+        self
+    }
+
+    /// Maximum number of neighbors taken from the move proposer per step, see LocalSearch::set_max_neighborhood_size.
+    /// Defaults to 100,000.
+    pub fn max_neighborhood_size(mut self, max_neighborhood_size: usize) -> Self {
+        self.max_neighborhood_size = max_neighborhood_size;
+        self
+    }
+
+    /// Defaults to NeighborhoodExploration::BestImprovement.
+    pub fn neighborhood_exploration(mut self, neighborhood_exploration: NeighborhoodExploration) -> Self {
+        self.neighborhood_exploration = neighborhood_exploration;
+        self
+    }
+
+    /// How recently seen solutions are stored, see TabuStorage. Defaults to TabuStorage::Solutions.
+    pub fn tabu_storage(mut self, tabu_storage: TabuStorage) -> Self {
+        self.tabu_storage = tabu_storage;
+        self
+    }
+
+    /// Defaults to StepAcceptance::BestNeighbor.
+    pub fn step_acceptance(mut self, step_acceptance: StepAcceptance) -> Self {
+        self.step_acceptance = step_acceptance;
+        self
+    }
+
+    /// Probability of a random walk step, see LocalSearch::set_random_walk_probability. Defaults to 0.
+    pub fn random_walk_probability(mut self, random_walk_probability: f64) -> Self {
+        self.random_walk_probability = random_walk_probability;
+        self
+    }
+
+    /// Apply every setting of config, see the config module.
+    pub fn config(mut self, config: &LocalSearchConfig) -> Self {
+        self.max_iterations = config.max_iterations;
+        self.window_size = config.window_size;
+        self.best_solutions_capacity = config.best_solutions_capacity;
+        self.all_solutions_capacity = config.all_solutions_capacity;
+        self.all_solution_iteration_expiry = config.all_solution_iteration_expiry;
+        self.random_walk_probability = config.random_walk_probability;
+        self.estimated_candidates = config.estimated_candidates;
+        self
+    }
+
+    /// Build the local search. Panics if a setting is out of range, see try_build.
+    pub fn build(self) -> LocalSearch<R, _Solution, _Score, SSC, MP> {
+        self.try_build().unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like build, but return SolverError::InvalidConfiguration instead of panicking if a setting is out of range.
+    /// Every setting is checked here, so a local search that was built doesn't panic later because of its settings.
+    pub fn try_build(self) -> Result<LocalSearch<R, _Solution, _Score, SSC, MP>, SolverError> {
+        if self.max_iterations == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "max_iterations must be positive".to_string(),
+            ));
+        }
+        if self.window_size == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "window_size must be positive".to_string(),
+            ));
+        }
+        if self.best_solutions_capacity == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "best_solutions_capacity must be positive".to_string(),
+            ));
+        }
+        if self.estimated_candidates == Some(0) {
+            return Err(SolverError::InvalidConfiguration(
+                "estimated_candidates must be positive".to_string(),
+            ));
+        }
+        if self.max_neighborhood_size == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "max_neighborhood_size must be positive".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.random_walk_probability) {
+            return Err(SolverError::InvalidConfiguration(
+                "random_walk_probability must be between 0 and 1".to_string(),
+            ));
+        }
+        if let NeighborhoodExploration::BestOfWindow { size: 0 }
+        | NeighborhoodExploration::ReservoirSample { size: 0 } = self.neighborhood_exploration
+        {
+            return Err(SolverError::InvalidConfiguration(
+                "window size must be positive".to_string(),
+            ));
+        }
+        if let Some(AdaptiveWindowSize { min, max }) = self.adaptive_window_size {
+            if min == 0 {
+                return Err(SolverError::InvalidConfiguration(
+                    "min window size must be positive".to_string(),
+                ));
+            }
+            if min > max {
+                return Err(SolverError::InvalidConfiguration(
+                    "min window size must be at most max window size".to_string(),
+                ));
+            }
+        }
+        if let StepAcceptance::LateAcceptance { length: 0 } = self.step_acceptance {
+            return Err(SolverError::InvalidConfiguration(
+                "late acceptance length must be positive".to_string(),
+            ));
+        }
+        if let TabuStorage::BloomFilter { false_positive_rate } = self.tabu_storage {
+            if !(false_positive_rate > 0.0 && false_positive_rate < 1.0) {
+                return Err(SolverError::InvalidConfiguration(
+                    "false_positive_rate must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+        let mut local_search = LocalSearch::new(
+            self.move_proposer,
+            self.solution_score_calculator,
+            self.max_iterations,
+            self.window_size,
+            self.best_solutions_capacity,
+            self.all_solutions_capacity,
+            self.all_solution_iteration_expiry,
+            self.rng,
+        );
+        local_search.set_neighborhood_exploration(self.neighborhood_exploration);
+        local_search.set_adaptive_window_size(self.adaptive_window_size);
+        local_search.set_step_acceptance(self.step_acceptance);
+        local_search.set_random_walk_probability(self.random_walk_probability);
+        local_search.set_tabu_storage(self.tabu_storage);
+        local_search.set_all_solution_time_expiry(self.all_solution_time_expiry);
+        local_search.set_min_improvement(self.min_improvement);
+        local_search.set_estimated_candidates(self.estimated_candidates);
+        local_search.set_max_neighborhood_size(self.max_neighborhood_size);
+        Ok(local_search)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::local_search::{
+        sample_reservoir, select_lowest_estimates, LocalSearchBuilder, MoveProposer, ScoredSolution,
+        Solution, SolutionScoreCalculator,
+    };
+    use crate::score::IntegerHardSoft;
+    use crate::termination::MaxIterations;
+
+    #[test]
+    fn sample_reservoir_is_uniform() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        assert_eq!(vec![0, 1], sample_reservoir(0..2, 3, &mut rng));
+        let mut counts = [0; 10];
+        for _ in 0..10_000 {
+            for item in sample_reservoir(0..10, 2, &mut rng) {
+                counts[item] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|count| (1_800..2_200).contains(count)),
+            "{:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn select_lowest_estimates_keeps_order_of_ties() {
+        let items = vec![("a", 3.0), ("b", 1.0), ("c", 2.0), ("d", 1.0), ("e", 0.5)];
+        assert_eq!(
+            vec!["e", "b", "d"],
+            select_lowest_estimates(items.clone().into_iter(), 3)
+        );
+        assert_eq!(
+            vec!["e", "b", "d", "c", "a"],
+            select_lowest_estimates(items.into_iter(), 10)
+        );
+    }
+
+    #[test]
+    fn scored_solutions_ignore_constraint_violations() {
+        let hash = |scored_solution: &ScoredSolution<RingPosition, IntegerHardSoft>| {
+            let mut hasher = DefaultHasher::new();
+            scored_solution.hash(&mut hasher);
+            hasher.finish()
+        };
+        let score = IntegerHardSoft {
+            hard_score: 1,
+            soft_score: 0,
+        };
+        let counted = ScoredSolution::new(RingPosition(0), score)
+            .with_constraint_violations(vec![("no ones".to_string(), 1)]);
+        let uncounted = ScoredSolution::new(RingPosition(0), score);
+        assert_eq!(counted, uncounted);
+        assert_eq!(Ordering::Equal, counted.cmp(&uncounted));
+        assert_eq!(hash(&counted), hash(&uncounted));
+    }
+
+    const RING_SIZE: usize = 4;
+
+    /// A position on a ring of RING_SIZE positions.
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct RingPosition(usize);
+    impl Solution for RingPosition {}
+
+    /// Moves one position clockwise or counterclockwise.
+    struct RingMoveProposer;
+    impl MoveProposer for RingMoveProposer {
+        type R = ChaCha20Rng;
+        type Solution = RingPosition;
+
+        fn iter_local_moves(
+            &self,
+            start: &RingPosition,
+            _rng: &mut ChaCha20Rng,
+        ) -> Box<dyn Iterator<Item = RingPosition>> {
+            let neighbors = [(start.0 + 1) % RING_SIZE, (start.0 + RING_SIZE - 1) % RING_SIZE];
+            Box::new(neighbors.into_iter().map(RingPosition))
+        }
+    }
+
+    /// Scores every position the same, so that only the tabu recently seen solutions steer the search.
+    struct FlatScoreCalculator;
+    impl SolutionScoreCalculator for FlatScoreCalculator {
+        type _Solution = RingPosition;
+        type _Score = IntegerHardSoft;
+
+        fn get_scored_solution(
+            &self,
+            solution: RingPosition,
+        ) -> ScoredSolution<RingPosition, IntegerHardSoft> {
+            ScoredSolution::new(solution, IntegerHardSoft::new(0, 1))
+        }
+    }
+
+    /// Walk the ring for up to 20 steps and count the steps.
+    fn get_steps_around_ring(all_solution_iteration_expiry: u64) -> u64 {
+        let mut local_search = LocalSearchBuilder::new(
+            RingMoveProposer,
+            FlatScoreCalculator,
+            ChaCha20Rng::seed_from_u64(42),
+        )
+        .all_solution_iteration_expiry(all_solution_iteration_expiry)
+        .build();
+        local_search.execute_until(RingPosition(0), &MaxIterations(20));
+        local_search.get_stats().accepted_moves
+    }
+
+    #[test]
+    fn recently_seen_solutions_stay_tabu_for_the_iteration_expiry() {
+        // Positions stay tabu until more than the expiry solutions were seen after them, so with the default expiry
+        // the search walks around the ring once and stops, since both neighbors of the last position are tabu.
+        assert_eq!(RING_SIZE as u64 - 1, get_steps_around_ring(100_000));
+        // After one lap the next position was seen RING_SIZE - 1 solutions ago, so an expiry of RING_SIZE - 2 is
+        // the largest that lets the search walk on, and the previous position is still tabu.
+        assert_eq!(RING_SIZE as u64 - 1, get_steps_around_ring(RING_SIZE as u64 - 1));
+        assert_eq!(20, get_steps_around_ring(RING_SIZE as u64 - 2));
+        // An expiry of 0 forgets a position once any other is seen.
+        assert_eq!(20, get_steps_around_ring(0));
+    }
 }
 
 /// In order to test local search methods, we take a handful of benchmark functions from [2] and make sure that
@@ -354,11 +1970,18 @@ mod ackley_tests {
             AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleyScore, AckleySolution,
             AckleySolutionScoreCalculator,
         },
-        local_search::{InitialSolutionGenerator, LocalSearch, SolutionScoreCalculator},
+        local_search::{
+            AdaptiveWindowSize, History, InitialSolutionGenerator, LocalSearch, LocalSearchBuilder,
+            MoveProposer, NeighborhoodExploration, ScoredSolution, SolutionScoreCalculator, StepAcceptance,
+            TabuStorage,
+        },
     };
     use approx::assert_abs_diff_eq;
     use ordered_float::OrderedFloat;
     use rand::SeedableRng;
+    use std::time::Duration;
+
+    use crate::termination::MinImprovement;
 
     #[test]
     fn ackley_local_minima_found() {
@@ -468,4 +2091,532 @@ mod ackley_tests {
             "expected end solution to be same as start solution"
         );
     }
+
+    #[test]
+    fn ackley_late_acceptance_local_minima_found() {
+        let dimensions = 2;
+        let seed = 42;
+        let allow_no_improvement_for = 100;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+        let solution_score_calculator = AckleySolutionScoreCalculator::default();
+        let start_score = solution_score_calculator.get_scored_solution(start.clone()).score;
+
+        let mut local_search = crate::ackley::new_ackley_local_search(dimensions, seed);
+        local_search.set_step_acceptance(StepAcceptance::LateAcceptance { length: 50 });
+        let end = local_search.execute(start.clone(), allow_no_improvement_for);
+
+        println!(
+            "start_score: {:?}, end_score: {:?}, start: {:?}, end: {:?}",
+            start_score, end.score, start, end
+        );
+        assert!(
+            end.score < start_score,
+            "expected end_score to be better than start_score"
+        );
+    }
+
+    #[test]
+    fn ackley_first_improvement_scores_fewer_neighbors() {
+        let dimensions = 2;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+        let execute = |neighborhood_exploration| {
+            let mut local_search = crate::ackley::new_ackley_local_search(dimensions, seed);
+            local_search.set_neighborhood_exploration(neighborhood_exploration);
+            let end = local_search.execute_until(start.clone(), &crate::termination::MaxIterations(20));
+            (end, local_search.get_stats().clone())
+        };
+
+        let (first_end, first_stats) = execute(NeighborhoodExploration::FirstImprovement);
+        let (best_end, best_stats) = execute(NeighborhoodExploration::BestImprovement);
+        let (_, window_stats) = execute(NeighborhoodExploration::BestOfWindow { size: 10 });
+        let (reservoir_end, reservoir_stats) = execute(NeighborhoodExploration::ReservoirSample { size: 10 });
+        println!(
+            "first improvement: {:?} {:?}, best improvement: {:?} {:?}",
+            first_end.score, first_stats, best_end.score, best_stats
+        );
+        assert!(first_end.score < start_score);
+        assert!(best_end.score < start_score);
+        assert!(first_stats.proposed_moves < best_stats.proposed_moves);
+        assert!(window_stats.proposed_moves <= 20 * 10);
+        assert!(reservoir_end.score < start_score);
+        assert!(reservoir_stats.proposed_moves <= 20 * 10);
+    }
+
+    #[test]
+    fn ackley_adaptive_window_size_stays_within_bounds() {
+        let dimensions = 10;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+        let mut local_search = crate::ackley::new_ackley_local_search(dimensions, seed);
+        local_search.set_adaptive_window_size(Some(AdaptiveWindowSize { min: 2, max: 8 }));
+        let end = local_search.execute_until(start, &crate::termination::MaxIterations(20));
+        let stats = local_search.get_stats();
+        assert!(end.score < start_score);
+        assert!(stats.proposed_moves <= 20 * 8, "{:?}", stats);
+        assert!(stats.proposed_moves >= 20 * 2, "{:?}", stats);
+    }
+
+    #[test]
+    #[should_panic(expected = "min window size must be at most max window size")]
+    fn builder_rejects_inverted_adaptive_window_size() {
+        LocalSearchBuilder::new(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .adaptive_window_size(8, 2)
+        .build();
+    }
+
+    #[test]
+    fn ackley_exhaustive_exploration_only_improves() {
+        let dimensions = 10;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+
+        let mut local_search = crate::ackley::new_ackley_local_search(dimensions, seed);
+        local_search.set_neighborhood_exploration(NeighborhoodExploration::Exhaustive);
+        let end = local_search.execute(start, u64::MAX);
+        let stats = local_search.get_stats();
+        println!(
+            "start_score: {:?}, end: {:?}, stats: {:?}",
+            start_score, end, stats
+        );
+        assert!(end.score < start_score);
+        assert!(stats.accepted_moves > 0);
+        assert_eq!(stats.accepted_moves, stats.improving_moves);
+    }
+
+    #[test]
+    fn ackley_random_walk_accepts_worse_neighbors() {
+        let dimensions = 2;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+
+        let mut local_search = crate::ackley::new_ackley_local_search(dimensions, seed);
+        local_search.set_neighborhood_exploration(NeighborhoodExploration::Exhaustive);
+        local_search.set_random_walk_probability(1.0);
+        local_search.execute_until(start, &crate::termination::MaxIterations(100));
+        let stats = local_search.get_stats();
+        println!("stats: {:?}", stats);
+        assert_eq!(100, stats.accepted_moves);
+        assert!(stats.improving_moves < stats.accepted_moves);
+    }
+
+    #[test]
+    fn ackley_hashed_tabu_storage_matches_solutions() {
+        let dimensions = 2;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+
+        let mut history =
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 100, 1_000);
+        history.set_tabu_storage(TabuStorage::Hashes);
+        let scored = AckleySolutionScoreCalculator::default().get_scored_solution(start.clone());
+        assert!(!history.is_solution_tabu(&start));
+        history.seen_solution(scored);
+        assert!(history.is_solution_tabu(&start));
+
+        let mut solutions = crate::ackley::new_ackley_local_search(dimensions, seed);
+        let mut hashes = crate::ackley::new_ackley_local_search(dimensions, seed);
+        hashes.set_tabu_storage(TabuStorage::Hashes);
+        assert_eq!(solutions.execute(start.clone(), 5), hashes.execute(start, 5));
+    }
+
+    #[test]
+    fn bloom_filter_tabu_storage_forgets_old_generations() {
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = AckleySolutionScoreCalculator::default();
+        let solutions: Vec<AckleySolution> = (0..30)
+            .map(|_| {
+                AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut initial_solution_rng)
+            })
+            .collect();
+        let mut history =
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 10, 1_000)
+                .with_tabu_storage(TabuStorage::BloomFilter {
+                    false_positive_rate: 1e-6,
+                });
+        for solution in &solutions[..20] {
+            history.seen_solution(calculator.get_scored_solution(solution.clone()));
+        }
+        assert!(solutions[..20]
+            .iter()
+            .all(|solution| history.is_solution_tabu(solution)));
+        assert!(!solutions[20..]
+            .iter()
+            .any(|solution| history.is_solution_tabu(solution)));
+
+        history.seen_solution(calculator.get_scored_solution(solutions[20].clone()));
+        assert!(!solutions[..10]
+            .iter()
+            .any(|solution| history.is_solution_tabu(solution)));
+        assert!(solutions[10..=20]
+            .iter()
+            .all(|solution| history.is_solution_tabu(solution)));
+    }
+
+    #[test]
+    fn ackley_min_improvement_stops_on_plateau() {
+        let new_local_search = |min_improvement: Option<MinImprovement<AckleyScore>>| {
+            let mut local_search = LocalSearchBuilder::new(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            )
+            .window_size(10)
+            .build();
+            local_search.set_min_improvement(min_improvement);
+            local_search
+        };
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut rng);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+
+        let mut every_improvement = new_local_search(None);
+        every_improvement.execute(start.clone(), 10);
+        assert!(every_improvement.get_stats().accepted_moves > 10);
+
+        let mut significant_improvement = new_local_search(Some(MinImprovement::epsilon(f64::MAX)));
+        let best = significant_improvement.execute(start, 10);
+        assert_eq!(10, significant_improvement.get_stats().accepted_moves);
+        assert!(best.score < start_score);
+    }
+
+    #[test]
+    fn ackley_execute_without_allowed_stagnation_still_descends() {
+        let new_local_search = || {
+            LocalSearchBuilder::new(
+                AckleyMoveProposer::new(2, 1e-3, 0.5),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            )
+            .window_size(10)
+            .build()
+        };
+        // Halfway between two local minima, so that the first steps improve.
+        let start = AckleySolution::new(vec![OrderedFloat(2.5); 2]);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+
+        // Like 1, 0 takes steps while they improve the score instead of returning start.
+        let mut no_stagnation = new_local_search();
+        let best = no_stagnation.execute(start.clone(), 0);
+        assert!(best.score < start_score);
+        assert!(no_stagnation.get_stats().accepted_moves > 1);
+        assert_eq!(best, new_local_search().execute(start, 1));
+    }
+
+    #[test]
+    fn iteration_expiry_forgets_solutions_seen_too_many_iterations_ago() {
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = AckleySolutionScoreCalculator::default();
+        let solutions: Vec<AckleySolution> = (0..4)
+            .map(|_| {
+                AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut initial_solution_rng)
+            })
+            .collect();
+        let mut history = History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 10, 2);
+        for solution in &solutions[..3] {
+            history.seen_solution(calculator.get_scored_solution(solution.clone()));
+        }
+        assert!(solutions[..3]
+            .iter()
+            .all(|solution| history.is_solution_tabu(solution)));
+
+        history.seen_solution(calculator.get_scored_solution(solutions[3].clone()));
+        assert!(!history.is_solution_tabu(&solutions[0]));
+        assert!(solutions[1..]
+            .iter()
+            .all(|solution| history.is_solution_tabu(solution)));
+    }
+
+    #[test]
+    fn time_expiry_forgets_solutions_seen_too_long_ago() {
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = AckleySolutionScoreCalculator::default();
+        let solutions: Vec<AckleySolution> = (0..2)
+            .map(|_| {
+                AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut initial_solution_rng)
+            })
+            .collect();
+        let new_history = |all_solution_time_expiry: Duration| {
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 10, 1_000)
+                .with_all_solution_time_expiry(all_solution_time_expiry)
+        };
+
+        let mut history = new_history(Duration::from_secs(3_600));
+        for solution in &solutions {
+            history.seen_solution(calculator.get_scored_solution(solution.clone()));
+        }
+        assert!(solutions
+            .iter()
+            .all(|solution| history.is_solution_tabu(solution)));
+
+        let mut history = new_history(Duration::ZERO);
+        for solution in &solutions {
+            history.seen_solution(calculator.get_scored_solution(solution.clone()));
+        }
+        assert!(!history.is_solution_tabu(&solutions[0]));
+        assert!(history.is_solution_tabu(&solutions[1]));
+    }
+
+    #[test]
+    fn history_score_statistics() {
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = AckleySolutionScoreCalculator::default();
+        let solutions: Vec<ScoredSolution<AckleySolution, AckleyScore>> = (0..4)
+            .map(|_| {
+                calculator.get_scored_solution(
+                    AckleyInitialSolutionGenerator::new(2)
+                        .generate_initial_solution(&mut initial_solution_rng),
+                )
+            })
+            .collect();
+        let mut history =
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 100, 1_000)
+                .with_score_statistics();
+        assert_eq!(None, history.get_median_score_seen());
+        assert_eq!(0.0, history.get_tabu_hit_rate());
+        for solution in solutions.iter().chain(solutions[..1].iter()) {
+            history.seen_solution(solution.clone());
+        }
+        // The first solution was seen twice, so it weighs twice.
+        let mut scores: Vec<AckleyScore> = solutions
+            .iter()
+            .chain(solutions[..1].iter())
+            .map(|solution| solution.score.clone())
+            .collect();
+        scores.sort();
+        assert_eq!(Some(scores[0].clone()), history.get_best_score_seen());
+        assert_eq!(Some(scores[4].clone()), history.get_worst_score_seen());
+        assert_eq!(Some(scores[2].clone()), history.get_median_score_seen());
+        assert_eq!(Some(scores[4].clone()), history.get_score_at_percentile(100.0));
+        assert_eq!(4, history.get_distinct_solution_count());
+
+        assert!(history.check_solution_tabu(&solutions[0].solution));
+        assert!(history.check_solution_tabu(&solutions[1].solution));
+        assert!(!history.check_solution_tabu(&AckleySolution::new(vec![OrderedFloat(0.0); 2])));
+        assert!(!history.check_solution_tabu(&AckleySolution::new(vec![OrderedFloat(1.0); 2])));
+        assert_eq!(0.5, history.get_tabu_hit_rate());
+
+        history.set_score_statistics(false);
+        assert_eq!(None, history.get_best_score_seen());
+        history.seen_solution(solutions[1].clone());
+        assert_eq!(None, history.get_median_score_seen());
+    }
+
+    #[test]
+    fn history_score_statistics_are_off_by_default() {
+        let calculator = AckleySolutionScoreCalculator::default();
+        let mut history =
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(16, 100, 1_000);
+        history
+            .seen_solution(calculator.get_scored_solution(AckleySolution::new(vec![OrderedFloat(1.0); 2])));
+        assert_eq!(None, history.get_best_score_seen());
+        assert_eq!(None, history.get_score_at_percentile(100.0));
+        assert_eq!(1, history.get_distinct_solution_count());
+    }
+
+    #[test]
+    fn best_solutions_share_seen_solutions() {
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let calculator = AckleySolutionScoreCalculator::default();
+        let solutions: Vec<ScoredSolution<AckleySolution, AckleyScore>> = (0..3)
+            .map(|_| {
+                calculator.get_scored_solution(
+                    AckleyInitialSolutionGenerator::new(2)
+                        .generate_initial_solution(&mut initial_solution_rng),
+                )
+            })
+            .collect();
+        let mut history =
+            History::<rand_chacha::ChaCha20Rng, AckleySolution, AckleyScore>::new(2, 100, 1_000);
+        for solution in &solutions[..2] {
+            history.seen_solution(solution.clone());
+        }
+        for solution in &solutions[..2] {
+            history.local_search_chose_solution(solution.clone());
+        }
+        // The third solution was never seen, so the best solutions keep it on their own.
+        history.local_search_chose_solution(solutions[2].clone());
+
+        let mut best: Vec<ScoredSolution<AckleySolution, AckleyScore>> = solutions.clone();
+        best.sort();
+        assert_eq!(Some(best[..2].to_vec()), history.get_best_multiple(3));
+        // A seen solution is shared by the recently seen solutions, their lookup and the best solutions.
+        for best in &history.best_solutions {
+            let expected_count = match best.solution == solutions[2].solution {
+                true => 1,
+                false => 3,
+            };
+            assert_eq!(expected_count, std::sync::Arc::strong_count(best));
+        }
+        assert!(solutions[..2]
+            .iter()
+            .all(|solution| history.is_solution_tabu(&solution.solution)));
+    }
+
+    #[test]
+    fn ackley_builder_matches_constructor() {
+        let dimensions = 2;
+        let seed = 42;
+        let mut initial_solution_rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+        let start = AckleyInitialSolutionGenerator::new(dimensions)
+            .generate_initial_solution(&mut initial_solution_rng);
+
+        let mut constructed = crate::ackley::new_ackley_local_search(dimensions, seed);
+        let mut built = LocalSearchBuilder::new(
+            AckleyMoveProposer::new(dimensions, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        )
+        .max_iterations(100_000)
+        .window_size(500)
+        .all_solution_iteration_expiry(10_000)
+        .build();
+        assert_eq!(constructed.execute(start.clone(), 5), built.execute(start, 5));
+    }
+
+    /// Estimates the score of Ackley neighbors by their distance from the global minimum at the origin.
+    struct EstimatingAckleyMoveProposer(AckleyMoveProposer);
+    impl MoveProposer for EstimatingAckleyMoveProposer {
+        type R = rand_chacha::ChaCha20Rng;
+        type Solution = AckleySolution;
+
+        fn iter_local_moves(
+            &self,
+            start: &AckleySolution,
+            rng: &mut Self::R,
+        ) -> Box<dyn Iterator<Item = AckleySolution>> {
+            self.0.iter_local_moves(start, rng)
+        }
+
+        fn iter_estimated_local_moves(
+            &self,
+            start: &AckleySolution,
+            rng: &mut Self::R,
+        ) -> Option<Box<dyn Iterator<Item = (AckleySolution, f64)>>> {
+            let start_distance: f64 = start.get_x().iter().map(|x| x.0 * x.0).sum();
+            Some(Box::new(self.0.iter_local_moves(start, rng).map(
+                move |neighbor| {
+                    let distance: f64 = neighbor.get_x().iter().map(|x| x.0 * x.0).sum();
+                    (neighbor, distance - start_distance)
+                },
+            )))
+        }
+    }
+
+    #[test]
+    fn ackley_estimated_candidates_limit_scoring() {
+        let dimensions = 10;
+        let mut local_search = LocalSearchBuilder::new(
+            EstimatingAckleyMoveProposer(AckleyMoveProposer::new(dimensions, 1e-3, 0.5)),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(1_000)
+        .estimated_candidates(3)
+        .build();
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = AckleySolutionScoreCalculator::default().get_scored_solution(
+            AckleyInitialSolutionGenerator::new(dimensions).generate_initial_solution(&mut rng),
+        );
+        let best = local_search.execute(start.solution.clone(), 50);
+        assert!(best.score < start.score);
+        let stats = local_search.get_stats();
+        assert!(stats.accepted_moves > 0);
+        assert!(stats.proposed_moves <= 3 * (stats.accepted_moves + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "estimated_candidates must be positive")]
+    fn estimated_candidates_must_be_positive() {
+        let mut local_search = LocalSearchBuilder::new(
+            EstimatingAckleyMoveProposer(AckleyMoveProposer::new(2, 1e-3, 0.5)),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .build();
+        local_search.set_estimated_candidates(Some(0));
+    }
+
+    /// Yields the Ackley neighbors over and over again, without end.
+    struct RepeatingAckleyMoveProposer(AckleyMoveProposer);
+    impl MoveProposer for RepeatingAckleyMoveProposer {
+        type R = rand_chacha::ChaCha20Rng;
+        type Solution = AckleySolution;
+
+        fn iter_local_moves(
+            &self,
+            start: &AckleySolution,
+            rng: &mut Self::R,
+        ) -> Box<dyn Iterator<Item = AckleySolution>> {
+            let moves: Vec<AckleySolution> = self.0.iter_local_moves(start, rng).collect();
+            Box::new(moves.into_iter().cycle())
+        }
+    }
+
+    #[test]
+    fn ackley_whole_neighborhood_explorations_stop_on_endless_move_proposers() {
+        let dimensions = 2;
+        for neighborhood_exploration in [
+            NeighborhoodExploration::ReservoirSample { size: 3 },
+            NeighborhoodExploration::Exhaustive,
+        ] {
+            let mut local_search = LocalSearchBuilder::new(
+                RepeatingAckleyMoveProposer(AckleyMoveProposer::new(dimensions, 1e-3, 0.5)),
+                AckleySolutionScoreCalculator::default(),
+                rand_chacha::ChaCha20Rng::seed_from_u64(42),
+            )
+            .max_iterations(100)
+            .max_neighborhood_size(1_000)
+            .neighborhood_exploration(neighborhood_exploration)
+            .build();
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+            let start = AckleySolutionScoreCalculator::default().get_scored_solution(
+                AckleyInitialSolutionGenerator::new(dimensions).generate_initial_solution(&mut rng),
+            );
+            let best = local_search.execute(start.solution.clone(), 10);
+            assert!(best.score <= start.score);
+            assert!(local_search.get_stats().proposed_moves <= 1_000 * 100);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "window_size must be positive")]
+    fn builder_rejects_empty_window() {
+        LocalSearchBuilder::new(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .window_size(0)
+        .build();
+    }
 }
@@ -0,0 +1,264 @@
+//! memetic is a population-based alternative to IteratedLocalSearch. MemeticSearch keeps a population of local minima
+//! and creates every generation by recombining pairs of them and improving the children with local search, so that
+//! good parts of different solutions can be combined instead of only perturbing one solution at a time, see [1].
+//!
+//! [1] Moscato, Pablo. "On Evolution, Search, Optimization, Genetic Algorithms and Martial Arts: Towards Memetic
+//! Algorithms." Caltech Concurrent Computation Program, C3P Report 826 (1989).
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use crate::assignment::Assignment;
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::local_search::{
+    InitialSolutionGenerator, LocalSearch, MoveProposer, Score, ScoredSolution, Solution,
+    SolutionScoreCalculator,
+};
+use crate::termination::{Termination, TerminationState};
+
+/// Recombinator creates a child from two parent solutions (crossover). Domains with structure that a generic
+/// operator would break, e.g. permutations, implement their own.
+pub trait Recombinator {
+    type _R: rand::Rng;
+    type _Solution: Solution;
+
+    fn recombine(
+        &self,
+        first: &Self::_Solution,
+        second: &Self::_Solution,
+        rng: &mut Self::_R,
+    ) -> Self::_Solution;
+}
+
+/// UniformCrossover takes the value of every decision variable from either parent with equal probability.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct UniformCrossover<_R, _Solution>
+where
+    _R: rand::Rng,
+    _Solution: Assignment,
+{
+    phantom_r: PhantomData<_R>,
+    phantom_solution: PhantomData<_Solution>,
+}
+
+impl<_R, _Solution> Recombinator for UniformCrossover<_R, _Solution>
+where
+    _R: rand::Rng,
+    _Solution: Assignment,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+
+    fn recombine(&self, first: &_Solution, second: &_Solution, rng: &mut _R) -> _Solution {
+        let mut child = first.clone();
+        for variable in 0..child.get_variable_count() {
+            if rng.gen_bool(0.5) {
+                child.set_value(variable, second.get_value(variable));
+            }
+        }
+        child
+    }
+}
+
+/// MemeticSearch keeps population_size distinct local minima, sorted best first. Every generation creates
+/// population_size children, each from two parents chosen by binary tournament, improves them with local search, and
+/// keeps the best population_size distinct solutions of parents and children.
+pub struct MemeticSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _RC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _RC: Recombinator<_R = _R, _Solution = _Solution>,
+{
+    initial_solution_generator: _ISG,
+    local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+    recombinator: _RC,
+    population: Vec<ScoredSolution<_Solution, _Score>>,
+    population_size: usize,
+    max_allow_no_improvement_for: u64,
+    generation: u64,
+    generations_without_improvement: u64,
+    rng: _R,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _RC> MemeticSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _RC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _RC: Recombinator<_R = _R, _Solution = _Solution>,
+{
+    pub fn new(
+        initial_solution_generator: _ISG,
+        local_search: LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+        recombinator: _RC,
+        population_size: usize,
+        max_allow_no_improvement_for: u64,
+        rng: _R,
+    ) -> Self {
+        assert!(population_size >= 2, "population_size must be at least 2");
+        Self {
+            initial_solution_generator,
+            local_search,
+            recombinator,
+            population: vec![],
+            population_size,
+            max_allow_no_improvement_for,
+            generation: 0,
+            generations_without_improvement: 0,
+            rng,
+            best_solution_handle: BestSolutionHandle::new(),
+        }
+    }
+
+    /// Get the current population, sorted best first. Empty until the first generation.
+    pub fn get_population(&self) -> &[ScoredSolution<_Solution, _Score>] {
+        &self.population
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get_best_solution(&self) -> ScoredSolution<_Solution, _Score> {
+        self.population.first().unwrap().clone()
+    }
+
+    /// Get a handle that always holds the best solution found so far, see BestSolutionHandle.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    /// The first generation creates the population from local searches of initial solutions, every later generation
+    /// recombines it.
+    pub fn execute_generation(&mut self) {
+        self.generation += 1;
+        let _span = tracing::debug_span!("memetic_generation", generation = self.generation).entered();
+        let previous_best = self.population.first().map(|best| best.score.clone());
+        let mut candidates = std::mem::take(&mut self.population);
+        let initializing = candidates.is_empty();
+        for _ in 0..self.population_size {
+            let start = match initializing {
+                true => self
+                    .initial_solution_generator
+                    .generate_initial_solution(&mut self.rng),
+                false => {
+                    let first = self.select_parent(&candidates);
+                    let second = self.select_parent(&candidates);
+                    self.recombinator.recombine(first, second, &mut self.rng)
+                }
+            };
+            let child = self
+                .local_search
+                .execute(start, self.max_allow_no_improvement_for);
+            candidates.push(child);
+        }
+        candidates.sort();
+        candidates.dedup_by(|a, b| a.solution == b.solution);
+        candidates.truncate(self.population_size);
+        self.population = candidates;
+
+        let best = self.population.first().unwrap();
+        tracing::debug!(best_score = ?best.score, "generation finished");
+        self.best_solution_handle.offer(best);
+        match previous_best {
+            Some(previous_best) if best.score >= previous_best => self.generations_without_improvement += 1,
+            _ => self.generations_without_improvement = 0,
+        }
+    }
+
+    /// Binary tournament: the better of two random members of population.
+    fn select_parent<'a>(&mut self, population: &'a [ScoredSolution<_Solution, _Score>]) -> &'a _Solution {
+        let first = &population[self.rng.gen_range(0..population.len())];
+        let second = &population[self.rng.gen_range(0..population.len())];
+        &first.min(second).solution
+    }
+
+    /// Execute generations until termination says to stop, see the termination module, or until a best possible
+    /// solution is found, and return the best solution. Iterations are generations, and at least one generation is
+    /// always executed so that there is a best solution to return.
+    pub fn execute<_T>(&mut self, termination: &_T) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let started = Instant::now();
+        let first_generation = self.generation;
+        loop {
+            self.execute_generation();
+            let best = self.get_best_solution();
+            let state = TerminationState {
+                iteration: self.generation - first_generation,
+                elapsed: started.elapsed(),
+                best_score: Some(&best.score),
+                iterations_without_improvement: self.generations_without_improvement,
+            };
+            if best.score.is_best() || termination.is_terminated(&state) {
+                return best;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::fixtures::Bits;
+    use crate::memetic::{Recombinator, UniformCrossover};
+
+    #[test]
+    fn uniform_crossover_takes_every_value_from_a_parent() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let crossover = UniformCrossover::default();
+        let first = Bits(vec![0; 64]);
+        let second = Bits(vec![1; 64]);
+        let child = crossover.recombine(&first, &second, &mut rng);
+        let from_second = child.0.iter().filter(|value| **value == 1).count();
+        assert!(
+            from_second > 16 && from_second < 48,
+            "took {} of 64 values from the second parent",
+            from_second
+        );
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::ackley::{new_ackley_local_search, AckleyCrossover, AckleyInitialSolutionGenerator};
+    use crate::memetic::MemeticSearch;
+    use crate::termination::MaxIterations;
+
+    #[test]
+    fn population_improves_and_stays_distinct() {
+        let mut memetic_search = MemeticSearch::new(
+            AckleyInitialSolutionGenerator::new(5),
+            new_ackley_local_search(5, 42),
+            AckleyCrossover::default(),
+            8,
+            5,
+            ChaCha20Rng::seed_from_u64(42),
+        );
+        memetic_search.execute_generation();
+        let first_best = memetic_search.get_best_solution();
+
+        let best = memetic_search.execute(&MaxIterations(5));
+        println!("best: {:?}", best.score);
+        assert!(best.score <= first_best.score);
+        assert_eq!(Some(best), memetic_search.get_best_solution_handle().get());
+
+        let population = memetic_search.get_population();
+        assert!(population.len() <= 8);
+        assert!(population.windows(2).all(|window| window[0] < window[1]));
+    }
+}
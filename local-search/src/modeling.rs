@@ -0,0 +1,230 @@
+//! modeling is an optional layer on top of Assignment and Domain. Problems that describe themselves as decision
+//! variables with explicit domains get move proposers for free, instead of hand-writing a MoveProposer per problem
+//! domain. See the modeling_tests of examples/nqueens, which solve n-queens with a ListDomain and a
+//! ConflictMoveProposer.
+use std::marker::PhantomData;
+
+use rand::prelude::SliceRandom;
+
+use crate::analysis::{get_worst_variables, ScoreContributionCalculator};
+use crate::assignment::{Assignment, Domain};
+use crate::error::{check_weights, SolverError};
+use crate::local_search::{InitialSolutionGenerator, MoveProposer};
+
+/// ListDomain stores the values of every decision variable explicitly, independent of the solution. Unlike a Domain
+/// computed from the solution, a ListDomain can be narrowed before search starts.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct ListDomain<_Solution>
+where
+    _Solution: Assignment,
+{
+    values: Vec<Vec<_Solution::Value>>,
+}
+
+impl<_Solution> ListDomain<_Solution>
+where
+    _Solution: Assignment,
+{
+    /// Create a domain from the values of each variable, indexed by variable.
+    pub fn new(values: Vec<Vec<_Solution::Value>>) -> Self {
+        Self { values }
+    }
+
+    /// Create a domain where all variable_count variables can take any of values.
+    pub fn uniform(variable_count: usize, values: Vec<_Solution::Value>) -> Self {
+        Self::new(vec![values; variable_count])
+    }
+
+    pub fn get_variable_count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get_variable_values(&self, variable: usize) -> &[_Solution::Value] {
+        &self.values[variable]
+    }
+
+    /// Remove value from the domain of variable. Returns whether the value was in the domain.
+    pub fn remove_value(&mut self, variable: usize, value: &_Solution::Value) -> bool {
+        self.retain_values(variable, |existing| existing != value) != 0
+    }
+
+    /// Keep only the values of variable for which keep returns true. Returns the number of values removed.
+    pub fn retain_values<F>(&mut self, variable: usize, keep: F) -> usize
+    where
+        F: FnMut(&_Solution::Value) -> bool,
+    {
+        let before = self.values[variable].len();
+        self.values[variable].retain(keep);
+        before - self.values[variable].len()
+    }
+}
+
+impl<_Solution> Domain for ListDomain<_Solution>
+where
+    _Solution: Assignment,
+{
+    type _Solution = _Solution;
+
+    fn get_values(&self, _solution: &Self::_Solution, variable: usize) -> Vec<_Solution::Value> {
+        self.values[variable].clone()
+    }
+}
+
+/// DomainInitialSolutionGenerator assigns every variable of a template solution a random value from its domain. Paired
+/// with a ListDomain that was narrowed by propagation, initial solutions already respect the pruned constraints.
+/// Variables with an empty domain keep the template's value.
+pub struct DomainInitialSolutionGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    template: _D::_Solution,
+    domain: _D,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _D> DomainInitialSolutionGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    pub fn new(template: _D::_Solution, domain: _D) -> Self {
+        Self {
+            template,
+            domain,
+            phantom_r: PhantomData,
+        }
+    }
+}
+
+impl<_R, _D> InitialSolutionGenerator for DomainInitialSolutionGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    type R = _R;
+    type Solution = _D::_Solution;
+
+    fn generate_initial_solution(&self, rng: &mut Self::R) -> Self::Solution {
+        let mut solution = self.template.clone();
+        for variable in 0..solution.get_variable_count() {
+            if let Some(value) = self.domain.get_values(&self.template, variable).choose(rng) {
+                solution.set_value(variable, value.clone());
+            }
+        }
+        solution
+    }
+}
+
+/// ConflictStrategy decides which variable ConflictMoveProposer changes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConflictStrategy {
+    /// Pick a random variable that is involved in a violation.
+    MinConflict,
+
+    /// Pick one of the variables with the largest score contribution, breaking ties randomly.
+    MaxMinConflict,
+}
+
+/// ConflictMoveProposer changes a single variable, chosen by a weighted random ConflictStrategy, to every other value
+/// of its domain. Moves are ordered by the score contribution of the changed variable, so the values with the fewest
+/// conflicts come first, and ties are broken randomly. If no variable is involved in a violation there are no moves.
+pub struct ConflictMoveProposer<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    solution_score_calculator: _SSC,
+    domain: _D,
+    strategy: Vec<(ConflictStrategy, u64)>,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _SSC, _D> ConflictMoveProposer<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    /// Fails unless at least one strategy has a positive weight.
+    pub fn new(
+        solution_score_calculator: _SSC,
+        domain: _D,
+        strategy: Vec<(ConflictStrategy, u64)>,
+    ) -> Result<Self, SolverError> {
+        check_weights("strategy", &strategy)?;
+        Ok(Self {
+            solution_score_calculator,
+            domain,
+            strategy,
+            phantom_r: PhantomData,
+        })
+    }
+
+    fn choose_variable(&self, start: &_SSC::_Solution, rng: &mut _R) -> Option<usize> {
+        let contributions = self.solution_score_calculator.get_score_contributions(start);
+        let worst_variables = get_worst_variables(&contributions, contributions.len());
+        let worst = worst_variables.first()?;
+        let candidates: Vec<usize> = match self
+            .strategy
+            .choose_weighted(rng, |s| s.1)
+            .expect("strategy weights are checked in new")
+            .0
+        {
+            ConflictStrategy::MinConflict => worst_variables.clone(),
+            ConflictStrategy::MaxMinConflict => worst_variables
+                .iter()
+                .copied()
+                .filter(|variable| contributions[*variable] == contributions[*worst])
+                .collect(),
+        };
+        candidates.choose(rng).copied()
+    }
+}
+
+impl<_R, _SSC, _D> MoveProposer for ConflictMoveProposer<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment + 'static,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    type R = _R;
+    type Solution = _SSC::_Solution;
+
+    fn iter_local_moves(
+        &self,
+        start: &Self::Solution,
+        rng: &mut Self::R,
+    ) -> Box<dyn Iterator<Item = Self::Solution>> {
+        let variable = match self.choose_variable(start, rng) {
+            Some(variable) => variable,
+            None => return Box::new(std::iter::empty()),
+        };
+        let current_value = start.get_value(variable);
+        let mut values = self.domain.get_values(start, variable);
+        values.retain(|value| *value != current_value);
+        values.shuffle(rng);
+        let mut moves: Vec<(_SSC::_Score, Self::Solution)> = values
+            .into_iter()
+            .map(|value| {
+                let mut candidate = start.clone();
+                candidate.set_value(variable, value);
+                let contribution =
+                    self.solution_score_calculator.get_score_contributions(&candidate)[variable].clone();
+                (contribution, candidate)
+            })
+            .collect();
+        // Stable sort, so the shuffle above breaks ties.
+        moves.sort_by(|left, right| left.0.cmp(&right.0));
+        let moves: Vec<Self::Solution> = moves
+            .into_iter()
+            .map(|(_contribution, candidate)| candidate)
+            .collect();
+        Box::new(moves.into_iter())
+    }
+}
@@ -0,0 +1,381 @@
+//! moves describes neighbors as changes to a solution instead of as whole solutions. A MoveProposer clones the whole
+//! solution for every neighbor, which dominates the cost of a step for large solutions. A Move is applied to the current
+//! solution in place, scored, and undone again, so MoveLocalSearch only clones when it moves to a new solution.
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use rand::prelude::SliceRandom;
+
+use crate::assignment::{Assignment, Domain};
+use crate::constraints::ConstraintId;
+use crate::incremental::{IncrementalScoreCalculator, MoveScoreCalculator};
+use crate::local_search::{Score, ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::stats::SolverStats;
+use crate::termination::{Termination, TerminationState};
+
+/// Move changes a solution in place. undo must exactly revert apply when called right after it on the same solution.
+pub trait Move: Clone + std::fmt::Debug {
+    type _Solution: Solution;
+
+    fn apply(&self, solution: &mut Self::_Solution);
+    fn undo(&self, solution: &mut Self::_Solution);
+}
+
+/// NeighborMove is a Move that can be recovered from a solution and one of its neighbors, so that LocalSearch, whose
+/// MoveProposer yields whole neighbors, can score them with an IncrementalScoreCalculator.
+pub trait NeighborMove: Move + Sized {
+    /// Get the move that turns solution into neighbor, or None if no single move does.
+    fn from_neighbor(solution: &Self::_Solution, neighbor: &Self::_Solution) -> Option<Self>;
+}
+
+/// MoveGenerator iterates randomly over the moves from a solution, like MoveProposer iterates over the neighborhood of
+/// a solution. The same rules apply: the iterator must be finite, and typically won't be exhausted.
+pub trait MoveGenerator {
+    type _R: rand::Rng;
+    type _Move: Move;
+
+    fn iter_moves(
+        &self,
+        solution: &<Self::_Move as Move>::_Solution,
+        rng: &mut Self::_R,
+    ) -> Box<dyn Iterator<Item = Self::_Move>>;
+}
+
+/// ChangeValueMove assigns a new value to one decision variable of an Assignment, and remembers the old value to undo.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub struct ChangeValueMove<_Solution>
+where
+    _Solution: Assignment,
+{
+    pub variable: usize,
+    pub from: _Solution::Value,
+    pub to: _Solution::Value,
+}
+
+impl<_Solution> Move for ChangeValueMove<_Solution>
+where
+    _Solution: Assignment,
+{
+    type _Solution = _Solution;
+
+    fn apply(&self, solution: &mut _Solution) {
+        solution.set_value(self.variable, self.to.clone());
+    }
+
+    fn undo(&self, solution: &mut _Solution) {
+        solution.set_value(self.variable, self.from.clone());
+    }
+}
+
+/// The neighbor must differ from the solution in exactly one decision variable.
+impl<_Solution> NeighborMove for ChangeValueMove<_Solution>
+where
+    _Solution: Assignment,
+{
+    fn from_neighbor(solution: &_Solution, neighbor: &_Solution) -> Option<Self> {
+        if solution.get_variable_count() != neighbor.get_variable_count() {
+            return None;
+        }
+        let mut changed = (0..solution.get_variable_count())
+            .filter(|variable| solution.get_value(*variable) != neighbor.get_value(*variable));
+        let variable = changed.next()?;
+        if changed.next().is_some() {
+            return None;
+        }
+        Some(ChangeValueMove {
+            variable,
+            from: solution.get_value(variable),
+            to: neighbor.get_value(variable),
+        })
+    }
+}
+
+/// ChangeValueMoveGenerator picks up to variables random decision variables and generates a ChangeValueMove to every
+/// other value of their domain.
+pub struct ChangeValueMoveGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    domain: _D,
+    variables: usize,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _D> ChangeValueMoveGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+{
+    pub fn new(domain: _D, variables: usize) -> Self {
+        assert!(variables > 0, "variables must be positive");
+        Self {
+            domain,
+            variables,
+            phantom_r: PhantomData,
+        }
+    }
+}
+
+impl<_R, _D> MoveGenerator for ChangeValueMoveGenerator<_R, _D>
+where
+    _R: rand::Rng,
+    _D: Domain,
+    _D::_Solution: 'static,
+{
+    type _R = _R;
+    type _Move = ChangeValueMove<_D::_Solution>;
+
+    fn iter_moves(&self, solution: &_D::_Solution, rng: &mut _R) -> Box<dyn Iterator<Item = Self::_Move>> {
+        let variable_count = solution.get_variable_count();
+        let mut moves = vec![];
+        for variable in rand::seq::index::sample(rng, variable_count, self.variables.min(variable_count)) {
+            let from = solution.get_value(variable);
+            let mut values = self.domain.get_values(solution, variable);
+            values.shuffle(rng);
+            moves.extend(
+                values
+                    .into_iter()
+                    .filter(|to| *to != from)
+                    .map(|to| ChangeValueMove {
+                        variable,
+                        from: from.clone(),
+                        to,
+                    }),
+            );
+        }
+        Box::new(moves.into_iter())
+    }
+}
+
+/// A move with its score and, if scored by the SolutionScoreCalculator, its constraint violations.
+type ScoredMove<_Move, _Score> = (_Move, _Score, Option<Vec<(ConstraintId, u64)>>);
+
+type BoxedMoveScoreCalculator<_Solution, _Score, _Move> =
+    Box<dyn MoveScoreCalculator<_Solution = _Solution, _Score = _Score, _Move = _Move> + Send>;
+
+/// MoveLocalSearch is LocalSearch with StepAcceptance::BestNeighbor over moves instead of solutions. Every step scores
+/// at most window_size moves from the current solution and makes the best one, even if it is worse.
+pub struct MoveLocalSearch<_R, _Solution, _Score, _SSC, _MG>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MG: MoveGenerator<_R = _R>,
+    _MG::_Move: Move<_Solution = _Solution>,
+{
+    move_generator: _MG,
+    solution_score_calculator: _SSC,
+    incremental_score_calculator: Option<BoxedMoveScoreCalculator<_Solution, _Score, _MG::_Move>>,
+    window_size: usize,
+    stats: SolverStats<_Score>,
+    rng: _R,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MG> MoveLocalSearch<_R, _Solution, _Score, _SSC, _MG>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MG: MoveGenerator<_R = _R>,
+    _MG::_Move: Move<_Solution = _Solution>,
+{
+    pub fn new(move_generator: _MG, solution_score_calculator: _SSC, window_size: usize, rng: _R) -> Self {
+        assert!(window_size > 0, "window_size must be positive");
+        Self {
+            move_generator,
+            solution_score_calculator,
+            incremental_score_calculator: None,
+            window_size,
+            stats: SolverStats::default(),
+            rng,
+        }
+    }
+
+    /// Score moves with incremental_score_calculator instead of applying them and rescoring the whole solution with the
+    /// SolutionScoreCalculator. Only the start solution is still scored in full, so only the start solution keeps the
+    /// constraint violations of the SolutionScoreCalculator.
+    pub fn set_incremental_score_calculator<_ISC>(&mut self, incremental_score_calculator: _ISC)
+    where
+        _ISC: IncrementalScoreCalculator<_Solution = _Solution, _Score = _Score, _Move = _MG::_Move>
+            + Send
+            + 'static,
+    {
+        self.incremental_score_calculator = Some(Box::new(incremental_score_calculator));
+    }
+
+    /// Get the move counts of every execute_until so far.
+    pub fn get_stats(&self) -> &SolverStats<_Score> {
+        &self.stats
+    }
+
+    /// Search from start until termination says to stop, see the termination module, until there are no moves, or as
+    /// soon as a best possible solution is found, and return the best solution found.
+    pub fn execute_until<_T>(
+        &mut self,
+        start: _Solution,
+        termination: &_T,
+    ) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let _span = tracing::trace_span!("move_local_search").entered();
+        let started = Instant::now();
+        let mut current = self.solution_score_calculator.get_scored_solution(start);
+        let mut best_solution = current.clone();
+        let mut no_improvement_for = 0;
+        for iteration in 0.. {
+            let state = TerminationState {
+                iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&best_solution.score),
+                iterations_without_improvement: no_improvement_for,
+            };
+            if current.score.is_best() || termination.is_terminated(&state) {
+                break;
+            }
+            let moves = self.move_generator.iter_moves(&current.solution, &mut self.rng);
+            let mut solution = current.solution;
+            let mut best_move: Option<ScoredMove<_MG::_Move, _Score>> = None;
+            for candidate in moves.take(self.window_size) {
+                let (score, constraint_violations) = match self.incremental_score_calculator.as_ref() {
+                    Some(incremental_score_calculator) => (
+                        incremental_score_calculator.get_move_score(&solution, &current.score, &candidate),
+                        None,
+                    ),
+                    None => {
+                        // Scoring takes the solution by value, so it is handed over and taken back instead of cloned.
+                        candidate.apply(&mut solution);
+                        let mut scored = self.solution_score_calculator.get_scored_solution(solution);
+                        candidate.undo(&mut scored.solution);
+                        solution = scored.solution;
+                        (scored.score, scored.constraint_violations)
+                    }
+                };
+                self.stats.proposed_moves += 1;
+                if best_move
+                    .as_ref()
+                    .is_none_or(|(_, best_score, _)| score < *best_score)
+                {
+                    best_move = Some((candidate, score, constraint_violations));
+                }
+            }
+            let (best_move, score, constraint_violations) = match best_move {
+                Some(best_move) => best_move,
+                None => {
+                    current.solution = solution;
+                    break;
+                }
+            };
+            best_move.apply(&mut solution);
+            self.stats.accepted_moves += 1;
+            if score < current.score {
+                self.stats.improving_moves += 1;
+            }
+            current = ScoredSolution {
+                solution,
+                score,
+                constraint_violations,
+            };
+            if current.score < best_solution.score {
+                best_solution = current.clone();
+                no_improvement_for = 0;
+            } else {
+                no_improvement_for += 1;
+            }
+        }
+        tracing::trace!(best_score = ?best_solution.score, "finished");
+        std::cmp::min(current, best_solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::fixtures::Bits;
+    use crate::local_search::{Score, ScoredSolution, SolutionScoreCalculator};
+    use crate::modeling::ListDomain;
+    use crate::moves::{
+        ChangeValueMove, ChangeValueMoveGenerator, Move, MoveGenerator, MoveLocalSearch, NeighborMove,
+    };
+    use crate::termination::MaxIterations;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Zeros(u64);
+    impl Score for Zeros {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    /// Best with all bits set.
+    struct CountZeros;
+    impl SolutionScoreCalculator for CountZeros {
+        type _Solution = Bits;
+        type _Score = Zeros;
+
+        fn get_scored_solution(&self, solution: Bits) -> ScoredSolution<Bits, Zeros> {
+            let zeros = solution.0.iter().filter(|bit| **bit == 0).count() as u64;
+            ScoredSolution::new(solution, Zeros(zeros))
+        }
+    }
+
+    #[test]
+    fn undo_reverts_apply() {
+        let mut bits = Bits(vec![0, 1, 0]);
+        let change = ChangeValueMove {
+            variable: 2,
+            from: 0,
+            to: 1,
+        };
+        change.apply(&mut bits);
+        assert_eq!(Bits(vec![0, 1, 1]), bits);
+        change.undo(&mut bits);
+        assert_eq!(Bits(vec![0, 1, 0]), bits);
+    }
+
+    #[test]
+    fn change_value_moves_from_neighbors() {
+        let solution = Bits(vec![0, 1, 0]);
+        assert_eq!(
+            Some(ChangeValueMove {
+                variable: 1,
+                from: 1,
+                to: 0,
+            }),
+            ChangeValueMove::from_neighbor(&solution, &Bits(vec![0, 0, 0]))
+        );
+        assert_eq!(None, ChangeValueMove::from_neighbor(&solution, &solution));
+        assert_eq!(
+            None,
+            ChangeValueMove::from_neighbor(&solution, &Bits(vec![1, 0, 0]))
+        );
+    }
+
+    #[test]
+    fn change_value_moves_skip_the_current_value() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let generator = ChangeValueMoveGenerator::new(ListDomain::uniform(4, vec![0, 1, 2]), 2);
+        let moves: Vec<ChangeValueMove<Bits>> =
+            generator.iter_moves(&Bits(vec![0, 1, 2, 0]), &mut rng).collect();
+        assert_eq!(4, moves.len());
+        assert!(moves.iter().all(|change| change.from != change.to));
+    }
+
+    #[test]
+    fn move_local_search_finds_the_best_solution() {
+        let mut local_search = MoveLocalSearch::new(
+            ChangeValueMoveGenerator::new(ListDomain::uniform(16, vec![0, 1]), 16),
+            CountZeros,
+            16,
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        );
+        let result = local_search.execute_until(Bits(vec![0; 16]), &MaxIterations(100));
+        assert_eq!(Zeros(0), result.score);
+        assert_eq!(16, local_search.get_stats().improving_moves);
+    }
+}
@@ -0,0 +1,198 @@
+//! multistart runs several complete, independent local searches from fresh initial solutions and keeps the best of
+//! them. Unlike IteratedLocalSearch nothing carries over from one search to the next, which makes it a baseline for
+//! experiments, e.g. to tell how much perturbation and acceptance add, and a simpler default for tiny problems, where
+//! a handful of local searches already find the best solution.
+use std::thread;
+
+use rand_chacha::ChaCha20Rng;
+
+use crate::error::SolverError;
+use crate::local_search::{
+    InitialSolutionGenerator, LocalSearch, MoveProposer, Score, ScoredSolution, Solution,
+    SolutionScoreCalculator,
+};
+use crate::rng::RngStreams;
+
+type Runs<_R, _Solution, _Score, _SSC, _MP> = Vec<(LocalSearch<_R, _Solution, _Score, _SSC, _MP>, _Solution)>;
+
+/// BestOfRestarts executes every local search from its start solution, until it has not improved for
+/// allow_no_improvement_for steps, see LocalSearch::execute, and returns the best solution of all of them. Ties go to
+/// the earliest run.
+pub struct BestOfRestarts<_R, _Solution, _Score, _SSC, _MP>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+{
+    runs: Runs<_R, _Solution, _Score, _SSC, _MP>,
+    allow_no_improvement_for: u64,
+    run_bests: Vec<ScoredSolution<_Solution, _Score>>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP> BestOfRestarts<_R, _Solution, _Score, _SSC, _MP>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+{
+    /// Create from runs, a local search and the solution it starts from each, or fail if there are no runs.
+    pub fn new(
+        runs: Runs<_R, _Solution, _Score, _SSC, _MP>,
+        allow_no_improvement_for: u64,
+    ) -> Result<Self, SolverError> {
+        if runs.is_empty() {
+            return Err(SolverError::InvalidConfiguration(
+                "runs must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            runs,
+            allow_no_improvement_for,
+            run_bests: vec![],
+        })
+    }
+
+    /// Create restarts runs. Every run gets its own random number generator from rng_streams, which first generates
+    /// its start solution with initial_solution_generator and is then passed to create_local_search together with the
+    /// index of the run, so the runs are independent, but reproducible from the master seed. Fails if restarts is 0.
+    pub fn from_factory<_ISG, F>(
+        restarts: usize,
+        allow_no_improvement_for: u64,
+        rng_streams: &RngStreams,
+        initial_solution_generator: &_ISG,
+        mut create_local_search: F,
+    ) -> Result<Self, SolverError>
+    where
+        _ISG: InitialSolutionGenerator<R = ChaCha20Rng, Solution = _Solution>,
+        F: FnMut(usize, ChaCha20Rng) -> LocalSearch<_R, _Solution, _Score, _SSC, _MP>,
+    {
+        let runs = (0..restarts)
+            .map(|index| {
+                let mut rng = rng_streams.get_stream(index);
+                let start = initial_solution_generator.generate_initial_solution(&mut rng);
+                (create_local_search(index, rng), start)
+            })
+            .collect();
+        Self::new(runs, allow_no_improvement_for)
+    }
+
+    /// Execute the runs one after the other and return the best solution of all of them.
+    pub fn execute(&mut self) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        let allow_no_improvement_for = self.allow_no_improvement_for;
+        self.run_bests = self
+            .runs
+            .iter_mut()
+            .map(|(local_search, start)| local_search.execute(start.clone(), allow_no_improvement_for))
+            .collect();
+        self.get_best()
+    }
+
+    /// Like execute, but execute every run on its own thread. The result is the same as that of execute.
+    pub fn execute_parallel(&mut self) -> Result<ScoredSolution<_Solution, _Score>, SolverError>
+    where
+        LocalSearch<_R, _Solution, _Score, _SSC, _MP>: Send,
+        ScoredSolution<_Solution, _Score>: Send,
+    {
+        let allow_no_improvement_for = self.allow_no_improvement_for;
+        self.run_bests = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .runs
+                .iter_mut()
+                .map(|(local_search, start)| {
+                    let start = start.clone();
+                    scope.spawn(move || local_search.execute(start, allow_no_improvement_for))
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        self.get_best()
+    }
+
+    /// Get the best solution of every run of the last execute, in run order, e.g. to see how much the runs vary.
+    pub fn get_run_bests(&self) -> &[ScoredSolution<_Solution, _Score>] {
+        &self.run_bests
+    }
+
+    /// Get the best solution of the last execute, or SolverError::NoSolution before the first execute.
+    fn get_best(&self) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        self.run_bests
+            .iter()
+            .min_by(|left, right| left.score.cmp(&right.score))
+            .cloned()
+            .ok_or(SolverError::NoSolution)
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyLocalSearch, AckleyMoveProposer, AckleySolutionScoreCalculator,
+    };
+    use crate::error::SolverError;
+    use crate::local_search::LocalSearchBuilder;
+    use crate::multistart::BestOfRestarts;
+    use crate::rng::RngStreams;
+
+    fn new_local_search(_index: usize, rng: ChaCha20Rng) -> AckleyLocalSearch {
+        LocalSearchBuilder::new(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rng,
+        )
+        .max_iterations(1_000)
+        .window_size(10)
+        .build()
+    }
+
+    #[test]
+    fn ackley_best_of_restarts() {
+        let new_best_of_restarts = || {
+            BestOfRestarts::from_factory(
+                4,
+                20,
+                &RngStreams::new(42),
+                &AckleyInitialSolutionGenerator::new(2),
+                new_local_search,
+            )
+            .unwrap()
+        };
+        let mut sequential = new_best_of_restarts();
+        let best = sequential.execute().unwrap();
+        assert_eq!(4, sequential.get_run_bests().len());
+        assert_eq!(
+            Some(&best.score),
+            sequential
+                .get_run_bests()
+                .iter()
+                .map(|run_best| &run_best.score)
+                .min()
+        );
+
+        let mut parallel = new_best_of_restarts();
+        assert_eq!(best, parallel.execute_parallel().unwrap());
+        assert_eq!(sequential.get_run_bests(), parallel.get_run_bests());
+    }
+
+    #[test]
+    fn runs_must_not_be_empty() {
+        let best_of_restarts = BestOfRestarts::from_factory(
+            0,
+            20,
+            &RngStreams::new(42),
+            &AckleyInitialSolutionGenerator::new(2),
+            new_local_search,
+        );
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "runs must not be empty".to_string()
+            )),
+            best_of_restarts.err()
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! neighborhood combines MoveProposers into one, so that a problem domain can offer several kinds of moves, e.g.
+//! changing one day of a schedule and swapping two days, without writing a new iterator for every combination.
+use std::sync::Arc;
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::SeedableRng;
+
+use crate::local_search::MoveProposer;
+
+type Moves<_Solution> = Box<dyn Iterator<Item = _Solution>>;
+
+/// Box<dyn MoveProposer> is a MoveProposer, so that move proposers of different types can be combined with Weighted.
+impl<_MP> MoveProposer for Box<_MP>
+where
+    _MP: MoveProposer + ?Sized,
+{
+    type R = _MP::R;
+    type Solution = _MP::Solution;
+
+    fn iter_local_moves(&self, start: &Self::Solution, rng: &mut Self::R) -> Moves<Self::Solution> {
+        (**self).iter_local_moves(start, rng)
+    }
+}
+
+/// Union alternates between the moves of both move proposers until both are exhausted, so that neither neighborhood
+/// is starved when local search only looks at the first few moves.
+pub struct Union<_MP1, _MP2>(pub _MP1, pub _MP2);
+
+impl<_MP1, _MP2> MoveProposer for Union<_MP1, _MP2>
+where
+    _MP1: MoveProposer,
+    _MP1::Solution: 'static,
+    _MP2: MoveProposer<R = _MP1::R, Solution = _MP1::Solution>,
+{
+    type R = _MP1::R;
+    type Solution = _MP1::Solution;
+
+    fn iter_local_moves(&self, start: &Self::Solution, rng: &mut Self::R) -> Moves<Self::Solution> {
+        let mut first = self.0.iter_local_moves(start, rng).fuse();
+        let mut second = self.1.iter_local_moves(start, rng).fuse();
+        let mut take_first = true;
+        Box::new(std::iter::from_fn(move || {
+            take_first = !take_first;
+            match take_first {
+                false => first.next().or_else(|| second.next()),
+                true => second.next().or_else(|| first.next()),
+            }
+        }))
+    }
+}
+
+/// Chain proposes all moves of the first move proposer before any of the second, e.g. cheap moves before expensive
+/// ones.
+pub struct Chain<_MP1, _MP2>(pub _MP1, pub _MP2);
+
+impl<_MP1, _MP2> MoveProposer for Chain<_MP1, _MP2>
+where
+    _MP1: MoveProposer,
+    _MP1::Solution: 'static,
+    _MP2: MoveProposer<R = _MP1::R, Solution = _MP1::Solution>,
+{
+    type R = _MP1::R;
+    type Solution = _MP1::Solution;
+
+    fn iter_local_moves(&self, start: &Self::Solution, rng: &mut Self::R) -> Moves<Self::Solution> {
+        let first = self.0.iter_local_moves(start, rng);
+        let second = self.1.iter_local_moves(start, rng);
+        Box::new(first.chain(second))
+    }
+}
+
+/// Weighted draws every move from one of its move proposers, chosen at random in proportion to its weight, until all
+/// are exhausted. Combine move proposers of different types by boxing them as Box<dyn MoveProposer>.
+pub struct Weighted<_MP>(pub Vec<(_MP, u64)>);
+
+impl<_MP> MoveProposer for Weighted<_MP>
+where
+    _MP: MoveProposer,
+    _MP::R: SeedableRng + 'static,
+    _MP::Solution: 'static,
+{
+    type R = _MP::R;
+    type Solution = _MP::Solution;
+
+    fn iter_local_moves(&self, start: &Self::Solution, rng: &mut Self::R) -> Moves<Self::Solution> {
+        assert!(
+            self.0.iter().any(|(_, weight)| *weight > 0),
+            "at least one weight must be positive"
+        );
+        let mut neighborhoods: Vec<(Moves<Self::Solution>, u64)> = self
+            .0
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .map(|(move_proposer, weight)| (move_proposer.iter_local_moves(start, rng), *weight))
+            .collect();
+        let mut rng = Self::R::from_rng(&mut *rng).unwrap();
+        Box::new(std::iter::from_fn(move || loop {
+            if neighborhoods.is_empty() {
+                return None;
+            }
+            let index = WeightedIndex::new(neighborhoods.iter().map(|(_, weight)| *weight))
+                .unwrap()
+                .sample(&mut rng);
+            match neighborhoods[index].0.next() {
+                Some(neighbor) => return Some(neighbor),
+                None => drop(neighborhoods.swap_remove(index)),
+            }
+        }))
+    }
+}
+
+/// Filter only proposes the moves of a move proposer that the predicate accepts, see MoveProposerExt::filter.
+pub struct Filter<_MP, F>
+where
+    _MP: MoveProposer,
+    F: Fn(&_MP::Solution) -> bool,
+{
+    move_proposer: _MP,
+    predicate: Arc<F>,
+}
+
+impl<_MP, F> MoveProposer for Filter<_MP, F>
+where
+    _MP: MoveProposer,
+    _MP::Solution: 'static,
+    F: Fn(&_MP::Solution) -> bool + Send + Sync + 'static,
+{
+    type R = _MP::R;
+    type Solution = _MP::Solution;
+
+    fn iter_local_moves(&self, start: &Self::Solution, rng: &mut Self::R) -> Moves<Self::Solution> {
+        let predicate = self.predicate.clone();
+        Box::new(
+            self.move_proposer
+                .iter_local_moves(start, rng)
+                .filter(move |neighbor| predicate(neighbor)),
+        )
+    }
+}
+
+/// MoveProposerExt adds adapters to every MoveProposer.
+pub trait MoveProposerExt: MoveProposer + Sized {
+    /// Only propose neighbors that predicate accepts, e.g. schedules that leave pinned days alone. Rejected neighbors
+    /// are never scored, and do not count towards the window size of local search.
+    fn filter<F>(self, predicate: F) -> Filter<Self, F>
+    where
+        F: Fn(&Self::Solution) -> bool + Send + Sync + 'static,
+    {
+        Filter {
+            move_proposer: self,
+            predicate: Arc::new(predicate),
+        }
+    }
+}
+
+impl<_MP> MoveProposerExt for _MP where _MP: MoveProposer {}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::local_search::{MoveProposer, Solution};
+    use crate::neighborhood::{Chain, MoveProposerExt, Union, Weighted};
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Number(i64);
+    impl Solution for Number {}
+
+    /// Proposes start + offset for offset in offsets.
+    struct Offsets(Vec<i64>);
+    impl MoveProposer for Offsets {
+        type R = ChaCha20Rng;
+        type Solution = Number;
+
+        fn iter_local_moves(
+            &self,
+            start: &Number,
+            _rng: &mut ChaCha20Rng,
+        ) -> Box<dyn Iterator<Item = Number>> {
+            let start = start.0;
+            Box::new(
+                self.0
+                    .clone()
+                    .into_iter()
+                    .map(move |offset| Number(start + offset)),
+            )
+        }
+    }
+
+    fn get_moves<_MP: MoveProposer<R = ChaCha20Rng, Solution = Number>>(move_proposer: &_MP) -> Vec<i64> {
+        move_proposer
+            .iter_local_moves(&Number(100), &mut ChaCha20Rng::seed_from_u64(42))
+            .map(|number| number.0)
+            .collect()
+    }
+
+    #[test]
+    fn union_alternates_and_chain_concatenates() {
+        let union = Union(Offsets(vec![1, 2, 3]), Offsets(vec![-1]));
+        assert_eq!(vec![101, 99, 102, 103], get_moves(&union));
+        let chain = Chain(Offsets(vec![1, 2]), Offsets(vec![-1]));
+        assert_eq!(vec![101, 102, 99], get_moves(&chain));
+    }
+
+    #[test]
+    fn weighted_draws_every_move_in_proportion() {
+        let weighted = Weighted(vec![
+            (Offsets((1..=1_000).collect()), 9),
+            (Offsets((-1_000..=-1).collect()), 1),
+            (Offsets(vec![0]), 0),
+        ]);
+        let moves = get_moves(&weighted);
+        assert_eq!(2_000, moves.len());
+        let up_in_first_hundred = moves[..100].iter().filter(|number| **number > 100).count();
+        assert!(up_in_first_hundred > 80, "{}", up_in_first_hundred);
+        assert!(!moves.contains(&100));
+    }
+
+    #[test]
+    fn weighted_combines_boxed_move_proposers() {
+        let weighted: Weighted<Box<dyn MoveProposer<R = ChaCha20Rng, Solution = Number>>> = Weighted(vec![
+            (Box::new(Offsets(vec![1])), 1),
+            (Box::new(Chain(Offsets(vec![2]), Offsets(vec![3]))), 1),
+        ]);
+        let mut moves = get_moves(&weighted);
+        moves.sort();
+        assert_eq!(vec![101, 102, 103], moves);
+    }
+
+    #[test]
+    fn filter_drops_rejected_moves() {
+        let filtered =
+            Union(Offsets(vec![1, 2, 3]), Offsets(vec![-1, -2])).filter(|number| number.0 % 2 == 1);
+        assert_eq!(vec![101, 99, 103], get_moves(&filtered));
+    }
+}
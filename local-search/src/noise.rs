@@ -0,0 +1,143 @@
+//! noise wraps a SolutionScoreCalculator so that the scores it returns are randomly perturbed. Noisy scores let the
+//! search accept slightly worse solutions now and then, which helps it walk off plateaus where many neighbors score the
+//! same, e.g. in the Traveling Salesperson Problem (TSP). See "noising methods" in [1].
+//!
+//! [1] Charon, Irène, and Olivier Hudry. "The noising method: a new method for combinatorial optimization."
+//! Operations Research Letters 14.3 (1993): 133-137.
+use std::sync::Mutex;
+
+use rand_distr::Distribution;
+
+use crate::constraints::ConstraintViolation;
+use crate::local_search::{ScoredSolution, SolutionScoreCalculator};
+use crate::score::{FromHardSoftScore, HardSoftScoring};
+
+/// NoisyScoreCalculator adds a sample of noise to the soft score of every solution that solution_score_calculator
+/// scores. Hard scores are left alone, so that feasibility is never misjudged. Only use it for the search itself: the
+/// best solution it reports carries a noisy score too, so rescore it with the wrapped calculator before showing it.
+pub struct NoisyScoreCalculator<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+    _D: Distribution<f64>,
+{
+    solution_score_calculator: _SSC,
+    noise: _D,
+    rng: Mutex<_R>,
+}
+
+impl<_R, _SSC, _D> NoisyScoreCalculator<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+    _D: Distribution<f64>,
+{
+    /// Add samples of noise, e.g. rand_distr::Normal::new(0.0, 0.5), drawn with rng.
+    pub fn new(solution_score_calculator: _SSC, noise: _D, rng: _R) -> Self {
+        Self {
+            solution_score_calculator,
+            noise,
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn get_solution_score_calculator(&self) -> &_SSC {
+        &self.solution_score_calculator
+    }
+
+    pub fn into_inner(self) -> _SSC {
+        self.solution_score_calculator
+    }
+}
+
+impl<_R, _SSC, _D> SolutionScoreCalculator for NoisyScoreCalculator<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+    _D: Distribution<f64>,
+{
+    type _Solution = _SSC::_Solution;
+    type _Score = _SSC::_Score;
+
+    fn get_scored_solution(
+        &self,
+        solution: Self::_Solution,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let scored_solution = self.solution_score_calculator.get_scored_solution(solution);
+        let noise = self.noise.sample(&mut *self.rng.lock().unwrap());
+        let score = Self::_Score::from_hard_soft_score(
+            scored_solution.score.get_hard_score(),
+            scored_solution.score.get_soft_score() + noise,
+        );
+        ScoredSolution {
+            score,
+            ..scored_solution
+        }
+    }
+
+    /// Verifies with the wrapped calculator, without noise, which never touches the hard score anyway.
+    fn verify(&self, solution: &Self::_Solution) -> Result<(), Vec<ConstraintViolation>>
+    where
+        Self::_Score: HardSoftScoring,
+    {
+        self.solution_score_calculator.verify(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::fixtures::{assert_verify_lists_no_ones, new_no_ones_score_calculator};
+    use crate::noise::NoisyScoreCalculator;
+
+    #[test]
+    fn verify_lists_the_violations_of_the_wrapped_calculator() {
+        let noisy_score_calculator = NoisyScoreCalculator::new(
+            new_no_ones_score_calculator(),
+            rand_distr::Uniform::new(-0.5, 0.5),
+            ChaCha20Rng::seed_from_u64(42),
+        );
+        assert_verify_lists_no_ones(&noisy_score_calculator);
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use ordered_float::OrderedFloat;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::ackley::{AckleySolution, AckleySolutionScoreCalculator};
+    use crate::local_search::SolutionScoreCalculator;
+    use crate::noise::NoisyScoreCalculator;
+    use crate::score::HardSoftScoring;
+
+    #[test]
+    fn perturbs_soft_score_within_noise() {
+        let noisy_score_calculator = NoisyScoreCalculator::new(
+            AckleySolutionScoreCalculator::default(),
+            rand_distr::Uniform::new(-0.5, 0.5),
+            ChaCha20Rng::seed_from_u64(42),
+        );
+        let solution = AckleySolution::new(vec![OrderedFloat(1.0), OrderedFloat(2.0)]);
+        let exact = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(solution.clone())
+            .score
+            .get_soft_score();
+        let noisy: Vec<f64> = (0..100)
+            .map(|_| {
+                noisy_score_calculator
+                    .get_scored_solution(solution.clone())
+                    .score
+                    .get_soft_score()
+            })
+            .collect();
+        assert!(noisy.iter().all(|score| (score - exact).abs() < 0.5));
+        assert!(noisy.iter().any(|score| *score != noisy[0]));
+    }
+}
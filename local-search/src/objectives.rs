@@ -0,0 +1,186 @@
+//! objectives combines soft objectives that are measured on wildly different scales, e.g. a count of days and a count
+//! of weekends, into one number. Added up as they are, the objective with the largest range silently dominates the
+//! others whatever their weights say. WeightedSum instead rescales every objective to [0, 1] by the smallest and
+//! largest value it has seen so far, and only then applies the weights.
+//!
+//! The ranges grow while the search runs, so the same solution can get a different value later on. That is usually
+//! harmless, because the ranges settle quickly, but compare the raw objectives, see WeightedSum::get_objective_values,
+//! when reporting results.
+use std::sync::Mutex;
+
+use crate::local_search::Solution;
+
+type Evaluate<_Solution> = Box<dyn Fn(&_Solution) -> f64 + Send + Sync>;
+
+/// Range is the smallest and largest value seen of an objective.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// WeightedSum is the weighted sum of normalized objectives, each of which is a cost, so lower is better. Use it as a
+/// soft constraint of ConstraintScoreCalculator, next to the hard constraints:
+/// `constraint("objectives", ConstraintLevel::Soft, move |solution| weighted_sum.evaluate(solution))`.
+pub struct WeightedSum<_Solution>
+where
+    _Solution: Solution,
+{
+    names: Vec<String>,
+    weights: Vec<f64>,
+    objectives: Vec<Evaluate<_Solution>>,
+    ranges: Mutex<Vec<Option<Range>>>,
+}
+
+impl<_Solution> Default for WeightedSum<_Solution>
+where
+    _Solution: Solution,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<_Solution> WeightedSum<_Solution>
+where
+    _Solution: Solution,
+{
+    pub fn new() -> Self {
+        Self {
+            names: vec![],
+            weights: vec![],
+            objectives: vec![],
+            ranges: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn add_objective<_F>(&mut self, name: impl Into<String>, weight: f64, evaluate: _F)
+    where
+        _F: Fn(&_Solution) -> f64 + Send + Sync + 'static,
+    {
+        assert!(weight >= 0.0, "weight must not be negative");
+        self.names.push(name.into());
+        self.weights.push(weight);
+        self.objectives.push(Box::new(evaluate));
+        self.ranges.get_mut().unwrap().push(None);
+    }
+
+    /// Like add_objective, to add objectives when creating the weighted sum.
+    pub fn with_objective<_F>(mut self, name: impl Into<String>, weight: f64, evaluate: _F) -> Self
+    where
+        _F: Fn(&_Solution) -> f64 + Send + Sync + 'static,
+    {
+        self.add_objective(name, weight, evaluate);
+        self
+    }
+
+    /// Get the raw values of the objectives of solution by name, in the order they were added.
+    pub fn get_objective_values(&self, solution: &_Solution) -> Vec<(String, f64)> {
+        self.names
+            .iter()
+            .cloned()
+            .zip(self.objectives.iter().map(|evaluate| evaluate(solution)))
+            .collect()
+    }
+
+    /// Get the ranges seen so far, in the order the objectives were added, None for objectives not evaluated yet.
+    pub fn get_ranges(&self) -> Vec<Option<Range>> {
+        self.ranges.lock().unwrap().clone()
+    }
+
+    /// Widen the ranges by the objectives of solution, then get the weighted sum of its normalized objectives. An
+    /// objective that has only ever had one value counts as 0.
+    pub fn evaluate(&self, solution: &_Solution) -> f64 {
+        let mut ranges = self.ranges.lock().unwrap();
+        self.objectives
+            .iter()
+            .zip(self.weights.iter())
+            .zip(ranges.iter_mut())
+            .map(|((evaluate, weight), range)| {
+                let value = evaluate(solution);
+                let range = range.get_or_insert(Range {
+                    min: value,
+                    max: value,
+                });
+                range.min = range.min.min(value);
+                range.max = range.max.max(value);
+                match range.max > range.min {
+                    true => weight * (value - range.min) / (range.max - range.min),
+                    false => 0.0,
+                }
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constraints::{constraint, ConstraintLevel, ConstraintScoreCalculator};
+    use crate::local_search::Solution;
+    use crate::objectives::{Range, WeightedSum};
+    use crate::score::{FloatHardSoft, HardSoftScoring};
+
+    /// How many days and how many weekends an employee works.
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Workload {
+        days: u64,
+        weekends: u64,
+    }
+    impl Solution for Workload {}
+
+    fn get_weighted_sum() -> WeightedSum<Workload> {
+        WeightedSum::new()
+            .with_objective("days", 1.0, |workload: &Workload| workload.days as f64)
+            .with_objective("weekends", 1.0, |workload: &Workload| workload.weekends as f64)
+    }
+
+    fn workload(days: u64, weekends: u64) -> Workload {
+        Workload { days, weekends }
+    }
+
+    #[test]
+    fn objectives_on_different_scales_weigh_the_same() {
+        let weighted_sum = get_weighted_sum();
+        assert_eq!(0.0, weighted_sum.evaluate(&workload(0, 0)));
+        assert_eq!(2.0, weighted_sum.evaluate(&workload(1_000, 10)));
+        assert_eq!(1.0, weighted_sum.evaluate(&workload(500, 5)));
+        assert_eq!(
+            weighted_sum.evaluate(&workload(1_000, 0)),
+            weighted_sum.evaluate(&workload(0, 10))
+        );
+        assert_eq!(
+            vec![
+                Some(Range {
+                    min: 0.0,
+                    max: 1_000.0
+                }),
+                Some(Range { min: 0.0, max: 10.0 })
+            ],
+            weighted_sum.get_ranges()
+        );
+        assert_eq!(
+            vec![("days".to_string(), 500.0), ("weekends".to_string(), 5.0)],
+            weighted_sum.get_objective_values(&workload(500, 5))
+        );
+    }
+
+    #[test]
+    fn soft_constraint_of_constraint_score_calculator() {
+        let weighted_sum = get_weighted_sum();
+        let mut constraints = ConstraintScoreCalculator::<Workload, FloatHardSoft>::new();
+        constraints.add_constraint(constraint(
+            "at most 200 days",
+            ConstraintLevel::Hard,
+            |workload: &Workload| (workload.days as f64 - 200.0).max(0.0),
+        ));
+        constraints.add_constraint(constraint(
+            "objectives",
+            ConstraintLevel::Soft,
+            move |workload: &Workload| weighted_sum.evaluate(workload),
+        ));
+        constraints.get_score(&workload(0, 0));
+        let score = constraints.get_score(&workload(300, 4));
+        assert_eq!(100.0, score.get_hard_score());
+        assert_eq!(2.0, score.get_soft_score());
+    }
+}
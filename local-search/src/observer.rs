@@ -0,0 +1,148 @@
+//! observer lets applications watch a solver while it runs, e.g. to plot live convergence curves, without patching
+//! the solver loops. LocalSearch and IteratedLocalSearch call every Observer added with their add_observer.
+use std::sync::{Arc, Mutex};
+
+use crate::local_search::{Score, ScoredSolution, Solution};
+
+/// Observer is called by the solver it was added to. All methods do nothing by default, so implementations only
+/// override the events they care about. For LocalSearch an iteration is a step to a neighbor and the iteration
+/// count restarts with every execute, for IteratedLocalSearch it is a round.
+pub trait Observer {
+    type _Solution: Solution;
+    type _Score: Score;
+
+    /// Called by IteratedLocalSearch before its first round with the solution it starts from.
+    fn on_start(&mut self, _current: &ScoredSolution<Self::_Solution, Self::_Score>) {}
+
+    /// Called at the end of every iteration with the solution the solver continues from.
+    fn on_iteration(&mut self, _iteration: u64, _current: &ScoredSolution<Self::_Solution, Self::_Score>) {}
+
+    /// Called whenever the solver finds a solution that is better than every solution before it.
+    fn on_new_best(&mut self, _iteration: u64, _best: &ScoredSolution<Self::_Solution, Self::_Score>) {}
+
+    /// Called by IteratedLocalSearch after perturbing current into the starting point of the next local search.
+    fn on_perturbation(
+        &mut self,
+        _iteration: u64,
+        _current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        _perturbed: &Self::_Solution,
+    ) {
+    }
+
+    /// Called by IteratedLocalSearch after it replaced the current solution with a new initial solution.
+    fn on_restart(&mut self, _iteration: u64, _restarted: &ScoredSolution<Self::_Solution, Self::_Score>) {}
+}
+
+/// Share one observer between solvers, e.g. between an IteratedLocalSearch and its LocalSearch, by adding clones of
+/// an Arc<Mutex<_>> to each.
+impl<_O> Observer for Arc<Mutex<_O>>
+where
+    _O: Observer,
+{
+    type _Solution = _O::_Solution;
+    type _Score = _O::_Score;
+
+    fn on_start(&mut self, current: &ScoredSolution<Self::_Solution, Self::_Score>) {
+        self.lock().unwrap().on_start(current);
+    }
+
+    fn on_iteration(&mut self, iteration: u64, current: &ScoredSolution<Self::_Solution, Self::_Score>) {
+        self.lock().unwrap().on_iteration(iteration, current);
+    }
+
+    fn on_new_best(&mut self, iteration: u64, best: &ScoredSolution<Self::_Solution, Self::_Score>) {
+        self.lock().unwrap().on_new_best(iteration, best);
+    }
+
+    fn on_perturbation(
+        &mut self,
+        iteration: u64,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        perturbed: &Self::_Solution,
+    ) {
+        self.lock()
+            .unwrap()
+            .on_perturbation(iteration, current, perturbed);
+    }
+
+    fn on_restart(&mut self, iteration: u64, restarted: &ScoredSolution<Self::_Solution, Self::_Score>) {
+        self.lock().unwrap().on_restart(iteration, restarted);
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use std::sync::{Arc, Mutex};
+
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        new_ackley_local_search, AckleyInitialSolutionGenerator, AckleyPerturbation, AckleyScore,
+        AckleySolution, AckleySolutionScoreCalculator,
+    };
+    use crate::iterated_local_search::IteratedLocalSearchBuilder;
+    use crate::local_search::ScoredSolution;
+    use crate::observer::Observer;
+
+    #[derive(Default)]
+    struct Recorder {
+        iterations: Vec<u64>,
+        best_scores: Vec<AckleyScore>,
+        perturbations: u64,
+    }
+
+    impl Observer for Recorder {
+        type _Solution = AckleySolution;
+        type _Score = AckleyScore;
+
+        fn on_iteration(&mut self, iteration: u64, _current: &ScoredSolution<AckleySolution, AckleyScore>) {
+            self.iterations.push(iteration);
+        }
+
+        fn on_new_best(&mut self, _iteration: u64, best: &ScoredSolution<AckleySolution, AckleyScore>) {
+            self.best_scores.push(best.score.clone());
+        }
+
+        fn on_perturbation(
+            &mut self,
+            _iteration: u64,
+            _current: &ScoredSolution<AckleySolution, AckleyScore>,
+            _perturbed: &AckleySolution,
+        ) {
+            self.perturbations += 1;
+        }
+    }
+
+    #[test]
+    fn observers_see_rounds_steps_and_new_bests() {
+        let rounds = Arc::new(Mutex::new(Recorder::default()));
+        let steps = Arc::new(Mutex::new(Recorder::default()));
+        let mut local_search = new_ackley_local_search(2, 0);
+        local_search.add_observer(Box::new(steps.clone()));
+        let mut iterated_local_search = IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(0),
+        )
+        .max_iterations(10)
+        .build();
+        iterated_local_search.add_observer(Box::new(rounds.clone()));
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+
+        let rounds = rounds.lock().unwrap();
+        let executed = rounds.iterations.len() as u64;
+        assert!(executed > 0);
+        assert_eq!((1..=executed).collect::<Vec<u64>>(), rounds.iterations);
+        assert_eq!(executed, rounds.perturbations);
+        assert!(rounds.best_scores.windows(2).all(|window| window[1] < window[0]));
+        assert_eq!(
+            Some(&iterated_local_search.get_best_solution().score),
+            rounds.best_scores.last()
+        );
+        assert!(steps.lock().unwrap().iterations.len() as u64 >= executed);
+    }
+}
@@ -0,0 +1,355 @@
+//! parallel runs several independent IteratedLocalSearch instances side by side, one per thread, so that a solve can
+//! use every core instead of one. The instances periodically exchange their best solutions, so that a good region
+//! found by one of them is intensified by all of them.
+use std::num::NonZeroU64;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::error::SolverError;
+use crate::iterated_local_search::{
+    AcceptanceCriterion, DefaultAcceptanceCriterion, IteratedLocalSearch, Perturbation,
+};
+use crate::local_search::{
+    InitialSolutionGenerator, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
+};
+use crate::rng::RngStreams;
+use crate::termination::{Termination, TerminationState};
+
+type Solvers<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> =
+    Vec<IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>>;
+
+/// ParallelIteratedLocalSearch executes the rounds of every solver on its own thread. Every exchange_every rounds a
+/// solver publishes its best solution to a shared BestSolutionHandle, and imports the best solution of all solvers
+/// into its History if that is better than its own, see IteratedLocalSearch::import_solution.
+pub struct ParallelIteratedLocalSearch<
+    _R,
+    _Solution,
+    _Score,
+    _SSC,
+    _MP,
+    _ISG,
+    _P,
+    _AC = DefaultAcceptanceCriterion<_R, _Solution, _Score>,
+> where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    solvers: Solvers<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    exchange_every: NonZeroU64,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    ParallelIteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    /// Run solvers side by side, or fail if there are no solvers.
+    pub fn new(
+        solvers: Solvers<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+        exchange_every: NonZeroU64,
+    ) -> Result<Self, SolverError> {
+        if solvers.is_empty() {
+            return Err(SolverError::InvalidConfiguration(
+                "solvers must not be empty".to_string(),
+            ));
+        }
+        Ok(Self {
+            solvers,
+            exchange_every,
+            best_solution_handle: BestSolutionHandle::new(),
+        })
+    }
+
+    /// Create threads solvers by calling create_solver with the index of the solver and its own random number
+    /// generator from rng_streams, see RngStreams::get_rng, so the solvers explore independently, but reproducibly
+    /// from the master seed. Fails if threads is 0.
+    pub fn from_factory<F>(
+        threads: usize,
+        exchange_every: NonZeroU64,
+        rng_streams: &RngStreams,
+        mut create_solver: F,
+    ) -> Result<Self, SolverError>
+    where
+        _R: rand::SeedableRng<Seed = [u8; 32]>,
+        F: FnMut(usize, _R) -> IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    {
+        let solvers = (0..threads)
+            .map(|index| create_solver(index, rng_streams.get_rng(index)))
+            .collect();
+        Self::new(solvers, exchange_every)
+    }
+
+    pub fn get_solvers(&self) -> &Solvers<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> {
+        &self.solvers
+    }
+
+    /// Get a handle that always holds the best solution that any solver has published so far.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    /// Execute rounds on every solver until termination says to stop, evaluated per solver, or until the solver is
+    /// finished, and return the best solution of all solvers. Every solver also stops once any of them found a best
+    /// possible solution. At least one round is always executed per solver.
+    ///
+    /// Every solver gets one thread for the whole execution. The threads run exchange_every rounds at a time and then
+    /// wait for each other at a barrier to exchange, so that which solutions are exchanged does not depend on thread
+    /// scheduling. With solvers that are deterministic for their seed, e.g. from from_factory, and a termination that
+    /// does not depend on time, the result only depends on the seeds. Returns the first error of any solver, after all
+    /// of them finished their rounds.
+    pub fn execute<_T>(&mut self, termination: &_T) -> Result<ScoredSolution<_Solution, _Score>, SolverError>
+    where
+        _T: Termination<_Score> + Sync,
+        IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>: Send,
+    {
+        let exchange = Exchange {
+            barrier: Barrier::new(self.solvers.len()),
+            bests: self.solvers.iter().map(|_| Mutex::new(None)).collect(),
+            stopped: self.solvers.iter().map(|_| AtomicBool::new(false)).collect(),
+            failed: AtomicBool::new(false),
+            done: AtomicBool::new(false),
+            best_solution_handle: &self.best_solution_handle,
+        };
+        let exchange_every = self.exchange_every.get();
+        let started = Instant::now();
+        let results: Vec<Result<(), SolverError>> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .solvers
+                .iter_mut()
+                .enumerate()
+                .map(|(index, solver)| {
+                    let exchange = &exchange;
+                    scope.spawn(move || {
+                        let first_iteration = solver.get_iteration_info().iteration;
+                        let mut outcome = Ok(Ok(()));
+                        let mut stopped = false;
+                        loop {
+                            if !stopped {
+                                // A panicking solver still has to take part in the exchanges, or the others would
+                                // wait for it at the barrier forever. Its panic is resumed once all of them stopped.
+                                match panic::catch_unwind(AssertUnwindSafe(|| {
+                                    execute_rounds(
+                                        solver,
+                                        exchange_every,
+                                        termination,
+                                        first_iteration,
+                                        started,
+                                    )
+                                })) {
+                                    Ok(Ok(is_stopped)) => stopped = is_stopped,
+                                    failure => {
+                                        exchange.failed.store(true, Ordering::SeqCst);
+                                        outcome = failure.map(|result| result.map(|_| ()));
+                                        stopped = true;
+                                    }
+                                }
+                            }
+                            if !exchange.exchange(index, solver, stopped) {
+                                break;
+                            }
+                        }
+                        outcome.unwrap_or_else(|payload| panic::resume_unwind(payload))
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        results.into_iter().collect::<Result<Vec<()>, _>>()?;
+        self.best_solution_handle.get().ok_or(SolverError::NoSolution)
+    }
+}
+
+/// Execute up to exchange_every rounds on solver, and return whether solver stopped because termination says so or
+/// because it is finished.
+fn execute_rounds<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC, _T>(
+    solver: &mut IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    exchange_every: u64,
+    termination: &_T,
+    first_iteration: u64,
+    started: Instant,
+) -> Result<bool, SolverError>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    _T: Termination<_Score>,
+{
+    for _ in 0..exchange_every {
+        solver.execute_round()?;
+        let best = solver.get_best_solution();
+        let state = TerminationState {
+            iteration: solver.get_iteration_info().iteration - first_iteration,
+            elapsed: started.elapsed(),
+            best_score: Some(&best.score),
+            iterations_without_improvement: solver.get_rounds_without_improvement(),
+        };
+        if solver.is_finished() || termination.is_terminated(&state) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Exchange is the state the threads of ParallelIteratedLocalSearch::execute share to exchange their best solutions.
+struct Exchange<'a, _Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    barrier: Barrier,
+    bests: Vec<Mutex<Option<ScoredSolution<_Solution, _Score>>>>,
+    stopped: Vec<AtomicBool>,
+    failed: AtomicBool,
+    done: AtomicBool,
+    best_solution_handle: &'a BestSolutionHandle<_Solution, _Score>,
+}
+
+impl<'a, _Solution, _Score> Exchange<'a, _Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    /// Wait for every solver to reach the exchange, then publish the best solution of every solver, in solver order,
+    /// and import the best of them into solver if it has not found as good a solution. Returns whether solver should
+    /// continue, i.e. some solver has not stopped yet, none failed and none found a best possible solution.
+    fn exchange<_R, _SSC, _MP, _ISG, _P, _AC>(
+        &self,
+        index: usize,
+        solver: &mut IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+        stopped: bool,
+    ) -> bool
+    where
+        _R: rand::Rng,
+        _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+        _MP: MoveProposer<R = _R, Solution = _Solution>,
+        _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+        _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+        _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    {
+        *self.bests[index].lock().unwrap() = solver.try_get_best_solution().ok();
+        self.stopped[index].store(stopped, Ordering::SeqCst);
+        if self.barrier.wait().is_leader() {
+            let failed = self.failed.load(Ordering::SeqCst);
+            if !failed {
+                for best in self.bests.iter() {
+                    if let Some(best) = &*best.lock().unwrap() {
+                        self.best_solution_handle.offer(best);
+                    }
+                }
+            }
+            let done = failed
+                || self.stopped.iter().all(|stopped| stopped.load(Ordering::SeqCst))
+                || self
+                    .best_solution_handle
+                    .get_score()
+                    .is_some_and(|score| score.is_best());
+            self.done.store(done, Ordering::SeqCst);
+        }
+        self.barrier.wait();
+        if self.failed.load(Ordering::SeqCst) {
+            return false;
+        }
+        let global_best = self.best_solution_handle.get().unwrap();
+        if global_best < solver.get_best_solution() {
+            tracing::debug!(score = ?global_best.score, "importing best solution of another solver");
+            solver.import_solution(global_best);
+        }
+        !self.done.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use std::num::NonZeroU64;
+
+    use rand::Rng;
+
+    use crate::ackley::{new_ackley_iterated_local_search, AckleyIteratedLocalSearch};
+    use crate::error::SolverError;
+    use crate::parallel::ParallelIteratedLocalSearch;
+    use crate::rng::{DefaultRng, RngStreams};
+    use crate::termination::MaxIterations;
+
+    #[test]
+    fn returns_best_solution_of_all_solvers() {
+        let mut parallel = ParallelIteratedLocalSearch::from_factory(
+            4,
+            NonZeroU64::new(2).unwrap(),
+            &RngStreams::new(42),
+            |_, mut rng: DefaultRng| new_ackley_iterated_local_search(5, rng.gen(), 10),
+        )
+        .unwrap();
+        let best = parallel.execute(&MaxIterations(6)).unwrap();
+        println!("best: {:?}", best.score);
+
+        assert_eq!(Some(best.clone()), parallel.get_best_solution_handle().get());
+        let solver_scores: Vec<_> = parallel
+            .get_solvers()
+            .iter()
+            .map(|solver| solver.get_best_solution().score)
+            .collect();
+        assert_eq!(Some(&best.score), solver_scores.iter().min());
+    }
+
+    #[test]
+    fn same_seed_gives_same_result() {
+        let solve = || {
+            let mut parallel = ParallelIteratedLocalSearch::from_factory(
+                4,
+                NonZeroU64::new(3).unwrap(),
+                &RngStreams::new(7),
+                |_, mut rng: DefaultRng| new_ackley_iterated_local_search(5, rng.gen(), 100),
+            )
+            .unwrap();
+            let best = parallel.execute(&MaxIterations(10)).unwrap();
+            let solver_bests: Vec<_> = parallel
+                .get_solvers()
+                .iter()
+                .map(|solver| solver.get_best_solution())
+                .collect();
+            (best, solver_bests)
+        };
+        assert_eq!(solve(), solve());
+    }
+
+    #[test]
+    fn solvers_must_not_be_empty() {
+        let parallel = ParallelIteratedLocalSearch::from_factory(
+            0,
+            NonZeroU64::new(2).unwrap(),
+            &RngStreams::new(42),
+            |_, mut rng: DefaultRng| -> AckleyIteratedLocalSearch {
+                new_ackley_iterated_local_search(2, rng.gen(), 10)
+            },
+        );
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "solvers must not be empty".to_string()
+            )),
+            parallel.err()
+        );
+    }
+}
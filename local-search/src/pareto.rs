@@ -0,0 +1,170 @@
+//! pareto keeps the trade-offs of a multi-objective search instead of collapsing the objectives into one score, e.g.
+//! cost against fairness. A solution dominates another if it is at least as good in every objective and better in at
+//! least one, and the Pareto front is the set of solutions that no other solution dominates. Attach a ParetoArchive to
+//! a History with History::set_pareto_archive, and read the front at the end of the run with
+//! History::get_pareto_front.
+use crate::local_search::{Score, ScoredSolution, Solution};
+
+/// Does first dominate second. Objectives are costs, so lower is better.
+pub fn dominates(first: &[f64], second: &[f64]) -> bool {
+    first.iter().zip(second).all(|(first, second)| first <= second)
+        && first.iter().zip(second).any(|(first, second)| first < second)
+}
+
+/// ParetoArchive stores mutually non-dominated solutions, with objectives from get_objectives. When the front grows
+/// past capacity, the solution that is closest to another one in objective space is dropped, so that the archive
+/// keeps a spread of trade-offs rather than many near copies of one.
+#[derive(Clone, Debug)]
+pub struct ParetoArchive<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    get_objectives: fn(&_Score) -> Vec<f64>,
+    capacity: usize,
+    front: Vec<(Vec<f64>, ScoredSolution<_Solution, _Score>)>,
+}
+
+impl<_Solution, _Score> ParetoArchive<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    pub fn new(get_objectives: fn(&_Score) -> Vec<f64>, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            get_objectives,
+            capacity,
+            front: vec![],
+        }
+    }
+
+    /// Add solution unless a solution in the archive dominates it or has the same objectives, and drop the solutions
+    /// it dominates. Returns whether solution was added.
+    pub fn insert(&mut self, solution: ScoredSolution<_Solution, _Score>) -> bool {
+        let objectives = (self.get_objectives)(&solution.score);
+        if self
+            .front
+            .iter()
+            .any(|(other, _)| *other == objectives || dominates(other, &objectives))
+        {
+            return false;
+        }
+        self.front.retain(|(other, _)| !dominates(&objectives, other));
+        self.front.push((objectives, solution));
+        if self.front.len() > self.capacity {
+            let most_crowded = (0..self.front.len())
+                .min_by(|first, second| {
+                    self.get_nearest_distance(*first)
+                        .total_cmp(&self.get_nearest_distance(*second))
+                })
+                .unwrap();
+            drop(self.front.swap_remove(most_crowded));
+        }
+        true
+    }
+
+    /// The non-dominated solutions, best score first.
+    pub fn get_front(&self) -> Vec<ScoredSolution<_Solution, _Score>> {
+        let mut front: Vec<ScoredSolution<_Solution, _Score>> =
+            self.front.iter().map(|(_, solution)| solution.clone()).collect();
+        front.sort();
+        front
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty()
+    }
+
+    /// Remove every solution, keeping the objectives and the capacity.
+    pub fn clear(&mut self) {
+        self.front.clear();
+    }
+
+    fn get_nearest_distance(&self, index: usize) -> f64 {
+        let objectives = &self.front[index].0;
+        self.front
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .map(|(_, (other, _))| {
+                objectives
+                    .iter()
+                    .zip(other)
+                    .map(|(first, second)| (first - second).powi(2))
+                    .sum::<f64>()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::local_search::{ScoredSolution, Solution};
+    use crate::pareto::{dominates, ParetoArchive};
+    use crate::score::{HardSoftScoring, IntegerHardSoft};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Plan(i64);
+    impl Solution for Plan {}
+
+    fn get_objectives(score: &IntegerHardSoft) -> Vec<f64> {
+        vec![score.get_hard_score(), score.get_soft_score()]
+    }
+
+    /// Scores a plan by its cost and unfairness, both as costs.
+    fn insert(archive: &mut ParetoArchive<Plan, IntegerHardSoft>, cost: i64, unfairness: i64) -> bool {
+        archive.insert(ScoredSolution::new(
+            Plan(cost * 100 + unfairness),
+            IntegerHardSoft::new(cost, unfairness),
+        ))
+    }
+
+    #[test]
+    fn dominance() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 3.0]));
+        assert!(!dominates(&[1.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[1.0, 3.0], &[2.0, 2.0]));
+    }
+
+    #[test]
+    fn keeps_only_non_dominated_solutions() {
+        let mut archive = ParetoArchive::new(get_objectives, 10);
+        assert!(insert(&mut archive, 5, 5));
+        assert!(insert(&mut archive, 1, 9));
+        assert!(insert(&mut archive, 9, 1));
+        assert!(!insert(&mut archive, 6, 6));
+        assert!(!insert(&mut archive, 5, 5));
+        assert!(insert(&mut archive, 4, 4));
+        let front: Vec<IntegerHardSoft> = archive
+            .get_front()
+            .into_iter()
+            .map(|solution| solution.score)
+            .collect();
+        assert_eq!(
+            vec![
+                IntegerHardSoft::new(1, 9),
+                IntegerHardSoft::new(4, 4),
+                IntegerHardSoft::new(9, 1)
+            ],
+            front
+        );
+    }
+
+    #[test]
+    fn drops_the_most_crowded_solution_over_capacity() {
+        let mut archive = ParetoArchive::new(get_objectives, 3);
+        insert(&mut archive, 0, 10);
+        insert(&mut archive, 10, 0);
+        insert(&mut archive, 5, 5);
+        insert(&mut archive, 6, 4);
+        assert_eq!(3, archive.len());
+        let front = archive.get_front();
+        assert_eq!(IntegerHardSoft::new(0, 10), front[0].score);
+        assert_eq!(IntegerHardSoft::new(10, 0), front[2].score);
+    }
+}
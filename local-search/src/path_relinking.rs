@@ -0,0 +1,188 @@
+//! path_relinking intensifies the search between elite solutions. Starting from one elite solution it applies the
+//! differences to another elite solution one at a time, always the one that leads to the best score, and returns the
+//! best solution strictly in between, see [1]. Good solutions often share structure, so the region between two of
+//! them is worth searching more closely than a random restart would.
+//!
+//! [1] Glover, Fred, Manuel Laguna and Rafael Martí. "Fundamentals of Scatter Search and Path Relinking." Control and
+//! Cybernetics 29.3 (2000): 653-684.
+use rand::Rng;
+
+use crate::assignment::Assignment;
+use crate::iterated_local_search::Perturbation;
+use crate::local_search::{History, ScoredSolution, Solution, SolutionScoreCalculator};
+
+/// Relinkable solutions can list how they differ from another solution, and apply those differences one at a time.
+/// Every Assignment is Relinkable, with one difference per decision variable whose value differs.
+pub trait Relinkable: Solution {
+    type Difference: Clone + std::fmt::Debug;
+
+    /// Get the differences that turn self into other once all of them are applied, in any order.
+    fn differences_to(&self, other: &Self) -> Vec<Self::Difference>;
+    fn apply_difference(&mut self, difference: &Self::Difference);
+}
+
+impl<_Solution> Relinkable for _Solution
+where
+    _Solution: Assignment,
+{
+    type Difference = (usize, _Solution::Value);
+
+    fn differences_to(&self, other: &Self) -> Vec<Self::Difference> {
+        (0..self.get_variable_count())
+            .filter(|variable| self.get_value(*variable) != other.get_value(*variable))
+            .map(|variable| (variable, other.get_value(variable)))
+            .collect()
+    }
+
+    fn apply_difference(&mut self, (variable, value): &Self::Difference) {
+        self.set_value(*variable, value.clone());
+    }
+}
+
+/// Walk greedily from initiating to guiding and return the best intermediate solution, or None if the two are less
+/// than two differences apart so that there is nothing in between. A path with d differences costs about d * d / 2
+/// score calculations.
+pub fn relink<_SSC>(
+    solution_score_calculator: &_SSC,
+    initiating: &_SSC::_Solution,
+    guiding: &_SSC::_Solution,
+) -> Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>>
+where
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Solution: Relinkable,
+{
+    let mut differences = initiating.differences_to(guiding);
+    let mut current = initiating.clone();
+    let mut best: Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>> = None;
+    // The last difference would reach guiding itself, which isn't an intermediate solution.
+    while differences.len() > 1 {
+        let (index, step) = differences
+            .iter()
+            .enumerate()
+            .map(|(index, difference)| {
+                let mut candidate = current.clone();
+                candidate.apply_difference(difference);
+                (index, solution_score_calculator.get_scored_solution(candidate))
+            })
+            .min_by(|(_, first), (_, second)| first.cmp(second))
+            .unwrap();
+        differences.swap_remove(index);
+        current = step.solution.clone();
+        if best.as_ref().is_none_or(|best| step < *best) {
+            best = Some(step);
+        }
+    }
+    best
+}
+
+/// PathRelinkingPerturbation relinks the current solution with a random elite solution from the History with
+/// probability relink_probability, and starts the next local search from the best intermediate solution. Otherwise,
+/// or if there is nothing to relink, it defers to the inner perturbation.
+pub struct PathRelinkingPerturbation<_P>
+where
+    _P: Perturbation,
+    _P::_Solution: Relinkable,
+{
+    inner: _P,
+    solution_score_calculator: _P::_SSC,
+    relink_probability: f64,
+}
+
+impl<_P> PathRelinkingPerturbation<_P>
+where
+    _P: Perturbation,
+    _P::_Solution: Relinkable,
+{
+    pub fn new(inner: _P, solution_score_calculator: _P::_SSC, relink_probability: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&relink_probability),
+            "relink_probability must be between 0 and 1"
+        );
+        Self {
+            inner,
+            solution_score_calculator,
+            relink_probability,
+        }
+    }
+}
+
+impl<_P> Perturbation for PathRelinkingPerturbation<_P>
+where
+    _P: Perturbation,
+    _P::_Solution: Relinkable,
+{
+    type _R = _P::_R;
+    type _Solution = _P::_Solution;
+    type _Score = _P::_Score;
+    type _SSC = _P::_SSC;
+
+    fn propose_new_starting_solution(
+        &mut self,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        history: &History<Self::_R, Self::_Solution, Self::_Score>,
+        rng: &mut Self::_R,
+    ) -> Self::_Solution {
+        if rng.gen_bool(self.relink_probability) {
+            if let Some(elite) = history.get_random_best_solution(rng) {
+                if let Some(intermediate) = relink(
+                    &self.solution_score_calculator,
+                    &current.solution,
+                    &elite.solution,
+                ) {
+                    tracing::trace!(score = ?intermediate.score, "relinked with an elite solution");
+                    return intermediate.solution;
+                }
+            }
+        }
+        self.inner.propose_new_starting_solution(current, history, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Bits;
+    use crate::local_search::{Score, ScoredSolution, SolutionScoreCalculator};
+    use crate::path_relinking::{relink, Relinkable};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Distance(u64);
+    impl Score for Distance {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    /// Best with exactly half of the bits set, so both all zeros and all ones are bad.
+    struct HalfSet;
+    impl SolutionScoreCalculator for HalfSet {
+        type _Solution = Bits;
+        type _Score = Distance;
+
+        fn get_scored_solution(&self, solution: Bits) -> ScoredSolution<Bits, Distance> {
+            let ones = solution.0.iter().filter(|bit| **bit == 1).count();
+            let score = Distance(ones.abs_diff(solution.0.len() / 2) as u64);
+            ScoredSolution::new(solution, score)
+        }
+    }
+
+    #[test]
+    fn differences_of_assignments_turn_one_into_the_other() {
+        let mut from = Bits(vec![0, 1, 0, 1]);
+        let to = Bits(vec![1, 1, 0, 0]);
+        let differences = from.differences_to(&to);
+        assert_eq!(vec![(0, 1), (3, 0)], differences);
+        for difference in &differences {
+            from.apply_difference(difference);
+        }
+        assert_eq!(to, from);
+    }
+
+    #[test]
+    fn relink_finds_the_best_solution_in_between() {
+        let best = relink(&HalfSet, &Bits(vec![0; 4]), &Bits(vec![1; 4])).unwrap();
+        assert_eq!(Distance(0), best.score);
+        assert_eq!(2, best.solution.0.iter().filter(|bit| **bit == 1).count());
+
+        assert_eq!(None, relink(&HalfSet, &Bits(vec![0, 0]), &Bits(vec![0, 1])));
+    }
+}
@@ -0,0 +1,13 @@
+//! prelude re-exports the traits and types that almost every problem domain needs, so that a single
+//! `use local_search::prelude::*;` replaces a handful of imports from different modules.
+pub use crate::iterated_local_search::{
+    AcceptanceCriterion, AcceptanceFn, DefaultAcceptanceCriterion, HomebaseStrategy, IteratedLocalSearch,
+    IteratedLocalSearchBuilder, Perturbation, PerturbationStrength,
+};
+pub use crate::local_search::{
+    History, InitialSolutionGenerator, LocalSearch, LocalSearchBuilder, MoveProposer, Score, ScoredSolution,
+    Solution, SolutionScoreCalculator,
+};
+pub use crate::score::{FloatHardSoft, HardSoftScoring, IntegerHardSoft};
+pub use crate::solver::Solver;
+pub use crate::termination::{MaxIterations, Termination};
@@ -0,0 +1,20 @@
+//! progress publishes a ProgressEvent per IteratedLocalSearch round on a channel, so that a UI thread can render
+//! progress without blocking or polling the solver, see IteratedLocalSearch::set_progress_sender.
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::local_search::Score;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProgressEvent<_Score>
+where
+    _Score: Score,
+{
+    pub iteration: u64,
+    pub current_score: _Score,
+    pub best_score: _Score,
+
+    /// Time since the first round started.
+    pub elapsed: Duration,
+}
@@ -0,0 +1,193 @@
+//! propagation prunes values that cannot be part of any feasible solution from a ListDomain before search starts, by
+//! enforcing node consistency for unary constraints and arc consistency (AC-3) for binary constraints. Search then
+//! never wastes moves on e.g. scheduling an employee on their holiday.
+use std::collections::VecDeque;
+
+use crate::assignment::Assignment;
+use crate::modeling::ListDomain;
+
+type UnaryPredicate<_Value> = Box<dyn Fn(&_Value) -> bool + Send + Sync>;
+type BinaryPredicate<_Value> = Box<dyn Fn(&_Value, &_Value) -> bool + Send + Sync>;
+
+struct UnaryConstraint<_Solution>
+where
+    _Solution: Assignment,
+{
+    variable: usize,
+    is_allowed: UnaryPredicate<_Solution::Value>,
+}
+
+struct BinaryConstraint<_Solution>
+where
+    _Solution: Assignment,
+{
+    left: usize,
+    right: usize,
+    is_allowed: BinaryPredicate<_Solution::Value>,
+}
+
+/// EmptyDomain means that propagation removed every value of a variable, so the problem has no feasible solution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmptyDomain {
+    pub variable: usize,
+}
+
+/// ConstraintNetwork holds the hard constraints of a problem that only involve one or two variables. Constraints on
+/// more variables are left to the score calculator.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct ConstraintNetwork<_Solution>
+where
+    _Solution: Assignment,
+{
+    unary: Vec<UnaryConstraint<_Solution>>,
+    binary: Vec<BinaryConstraint<_Solution>>,
+}
+
+impl<_Solution> ConstraintNetwork<_Solution>
+where
+    _Solution: Assignment,
+{
+    pub fn new() -> Self {
+        Self {
+            unary: vec![],
+            binary: vec![],
+        }
+    }
+
+    /// Require the value of variable to satisfy is_allowed.
+    pub fn add_unary<F>(&mut self, variable: usize, is_allowed: F)
+    where
+        F: Fn(&_Solution::Value) -> bool + Send + Sync + 'static,
+    {
+        self.unary.push(UnaryConstraint {
+            variable,
+            is_allowed: Box::new(is_allowed),
+        });
+    }
+
+    /// Require the values of left and right to satisfy is_allowed, called as is_allowed(left_value, right_value).
+    pub fn add_binary<F>(&mut self, left: usize, right: usize, is_allowed: F)
+    where
+        F: Fn(&_Solution::Value, &_Solution::Value) -> bool + Send + Sync + 'static,
+    {
+        self.binary.push(BinaryConstraint {
+            left,
+            right,
+            is_allowed: Box::new(is_allowed),
+        });
+    }
+
+    /// Prune domain until it is node and arc consistent. Returns the number of values removed, or EmptyDomain as soon
+    /// as a variable has no values left.
+    pub fn propagate(&self, domain: &mut ListDomain<_Solution>) -> Result<usize, EmptyDomain> {
+        let mut removed = 0;
+        for constraint in &self.unary {
+            removed += domain.retain_values(constraint.variable, |value| (constraint.is_allowed)(value));
+            if domain.get_variable_values(constraint.variable).is_empty() {
+                return Err(EmptyDomain {
+                    variable: constraint.variable,
+                });
+            }
+        }
+
+        // An arc is a constraint index and whether it is revised from its left variable's point of view.
+        let mut queue: VecDeque<(usize, bool)> = (0..self.binary.len())
+            .flat_map(|index| [(index, true), (index, false)])
+            .collect();
+        while let Some((index, from_left)) = queue.pop_front() {
+            let constraint = &self.binary[index];
+            let (variable, other) = if from_left {
+                (constraint.left, constraint.right)
+            } else {
+                (constraint.right, constraint.left)
+            };
+            let other_values = domain.get_variable_values(other).to_vec();
+            let revised = domain.retain_values(variable, |value| {
+                other_values.iter().any(|other_value| {
+                    if from_left {
+                        (constraint.is_allowed)(value, other_value)
+                    } else {
+                        (constraint.is_allowed)(other_value, value)
+                    }
+                })
+            });
+            if revised == 0 {
+                continue;
+            }
+            removed += revised;
+            if domain.get_variable_values(variable).is_empty() {
+                return Err(EmptyDomain { variable });
+            }
+            // Values of the neighbours of variable may have lost their only support, so revise them again.
+            for (neighbour_index, neighbour) in self.binary.iter().enumerate() {
+                if neighbour_index == index {
+                    continue;
+                }
+                if neighbour.right == variable {
+                    queue.push_back((neighbour_index, true));
+                }
+                if neighbour.left == variable {
+                    queue.push_back((neighbour_index, false));
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assignment::Assignment;
+    use crate::local_search::Solution;
+    use crate::modeling::ListDomain;
+    use crate::propagation::{ConstraintNetwork, EmptyDomain};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Colors(Vec<u8>);
+    impl Solution for Colors {}
+    impl Assignment for Colors {
+        type Value = u8;
+
+        fn get_variable_count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn get_value(&self, variable: usize) -> u8 {
+            self.0[variable]
+        }
+
+        fn set_value(&mut self, variable: usize, value: u8) {
+            self.0[variable] = value;
+        }
+    }
+
+    #[test]
+    fn propagation_prunes_along_a_path() {
+        // 0 - 1 - 2 must all differ, and 0 is pinned to color 0. With two colors the whole path is forced.
+        let mut network = ConstraintNetwork::<Colors>::new();
+        network.add_unary(0, |color| *color == 0);
+        network.add_binary(0, 1, |left, right| left != right);
+        network.add_binary(1, 2, |left, right| left != right);
+        let mut domain = ListDomain::uniform(3, vec![0, 1]);
+
+        assert_eq!(Ok(3), network.propagate(&mut domain));
+        assert_eq!(&[0], domain.get_variable_values(0));
+        assert_eq!(&[1], domain.get_variable_values(1));
+        assert_eq!(&[0], domain.get_variable_values(2));
+    }
+
+    #[test]
+    fn propagation_detects_infeasibility() {
+        // A triangle can't be colored with two colors, but arc consistency alone only notices once a value is pinned.
+        let mut network = ConstraintNetwork::<Colors>::new();
+        network.add_binary(0, 1, |left, right| left != right);
+        network.add_binary(1, 2, |left, right| left != right);
+        network.add_binary(2, 0, |left, right| left != right);
+        let mut domain = ListDomain::uniform(3, vec![0, 1]);
+        assert_eq!(Ok(0), network.propagate(&mut domain));
+
+        network.add_unary(0, |color| *color == 0);
+        assert!(matches!(network.propagate(&mut domain), Err(EmptyDomain { .. })));
+    }
+}
@@ -0,0 +1,151 @@
+//! record_to_record implements Record-to-Record Travel acceptance: a new local minima is accepted as long as it is not
+//! much worse than the best solution found so far, the record. Unlike simulated annealing there is no temperature to
+//! tune, only how far from the record the search may travel.
+use std::marker::PhantomData;
+
+use crate::iterated_local_search::AcceptanceCriterion;
+use crate::local_search::{History, ScoredSolution, Solution};
+use crate::score::HardSoftScoring;
+
+/// Deviation is how much worse than the record a new local minima may be, in units of HardSoftScoring::get_energy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Deviation {
+    /// Accept energies up to the energy of the record plus this amount.
+    Absolute(f64),
+
+    /// Accept energies up to the energy of the record plus this fraction of it, e.g. 0.05 for 5% worse than the record.
+    Relative(f64),
+}
+
+/// RecordToRecordTravelAcceptanceCriterion makes IteratedLocalSearch move to the new local minima if its energy is at
+/// most the energy of the best solution in the history plus the deviation, and stay at the existing local minima
+/// otherwise.
+pub struct RecordToRecordTravelAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+{
+    deviation: Deviation,
+    phantom_r: PhantomData<_R>,
+    phantom_solution: PhantomData<_Solution>,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_R, _Solution, _Score> RecordToRecordTravelAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+{
+    pub fn new(deviation: Deviation) -> Self {
+        Self {
+            deviation,
+            phantom_r: PhantomData,
+            phantom_solution: PhantomData,
+            phantom_score: PhantomData,
+        }
+    }
+
+    fn get_threshold(&self, record: &_Score) -> f64 {
+        let energy = record.get_energy();
+        match self.deviation {
+            Deviation::Absolute(deviation) => energy + deviation,
+            Deviation::Relative(fraction) => energy + fraction * energy.abs(),
+        }
+    }
+}
+
+impl<_R, _Solution, _Score> AcceptanceCriterion
+    for RecordToRecordTravelAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<_Solution, _Score>,
+        new_local_minima: &ScoredSolution<_Solution, _Score>,
+        history: &History<_R, _Solution, _Score>,
+        _rng: &mut _R,
+    ) -> ScoredSolution<_Solution, _Score> {
+        if new_local_minima.score <= existing_local_minima.score {
+            return new_local_minima.clone();
+        }
+        let record = match history.get_best() {
+            Some(record) => record.score,
+            None => existing_local_minima.score.clone(),
+        };
+        match new_local_minima.score.get_energy() <= self.get_threshold(&record) {
+            true => new_local_minima.clone(),
+            false => existing_local_minima.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::iterated_local_search::AcceptanceCriterion;
+    use crate::local_search::{History, Score, ScoredSolution, Solution};
+    use crate::record_to_record::{Deviation, RecordToRecordTravelAcceptanceCriterion};
+    use crate::score::HardSoftScoring;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Name(&'static str);
+    impl Solution for Name {}
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(u64);
+    impl Score for Cost {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+    impl HardSoftScoring for Cost {
+        fn get_hard_score(&self) -> f64 {
+            0.0
+        }
+
+        fn get_soft_score(&self) -> f64 {
+            self.0 as f64
+        }
+    }
+
+    fn choose(deviation: Deviation, existing: u64, new: u64) -> u64 {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut history = History::<rand_chacha::ChaCha20Rng, Name, Cost>::default();
+        history.local_search_chose_solution(ScoredSolution::new(Name("record"), Cost(100)));
+        let mut acceptance_criterion = RecordToRecordTravelAcceptanceCriterion::new(deviation);
+        acceptance_criterion
+            .choose(
+                &ScoredSolution::new(Name("existing"), Cost(existing)),
+                &ScoredSolution::new(Name("new"), Cost(new)),
+                &history,
+                &mut rng,
+            )
+            .score
+            .0
+    }
+
+    #[test]
+    fn absolute_deviation() {
+        assert_eq!(105, choose(Deviation::Absolute(10.0), 100, 105));
+        assert_eq!(110, choose(Deviation::Absolute(10.0), 100, 110));
+        assert_eq!(100, choose(Deviation::Absolute(10.0), 100, 111));
+        assert_eq!(111, choose(Deviation::Absolute(10.0), 120, 111));
+    }
+
+    #[test]
+    fn relative_deviation() {
+        assert_eq!(120, choose(Deviation::Relative(0.2), 100, 120));
+        assert_eq!(100, choose(Deviation::Relative(0.2), 100, 121));
+        assert_eq!(100, choose(Deviation::Relative(0.0), 100, 101));
+    }
+}
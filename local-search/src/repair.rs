@@ -0,0 +1,177 @@
+//! repair contains operators that move a solution back towards feasibility, e.g. after a perturbation or after the
+//! problem facts changed. Repaired solutions re-enter local search closer to feasibility, instead of relying on the
+//! inner search to fix gross violations one move at a time.
+use std::marker::PhantomData;
+
+use rand::prelude::SliceRandom;
+
+use crate::analysis::{get_worst_variables, ScoreContributionCalculator};
+use crate::assignment::{Assignment, Domain};
+use crate::local_search::{ScoredSolution, Solution};
+
+/// RepairOperator takes a solution, typically an infeasible one, and returns a solution that is hopefully closer to
+/// feasibility. Repairs do not have to succeed, but they should not make the solution worse.
+pub trait RepairOperator {
+    type _R: rand::Rng;
+    type _Solution: Solution;
+
+    fn repair(&mut self, solution: Self::_Solution, rng: &mut Self::_R) -> Self::_Solution;
+
+    /// Name the operator, e.g. in SolverStats::operator_weights. Defaults to the type name.
+    fn get_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// GreedyReassignmentRepair repeatedly takes the variable with the worst score contribution and reassigns it the value
+/// from its domain that gives the best score. If the worst variable cannot be improved it tries the next worst one,
+/// and it stops once no violating variable can be improved or after max_reassignments reassignments. Ties between
+/// equally good values are broken randomly.
+pub struct GreedyReassignmentRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    solution_score_calculator: _SSC,
+    domain: _D,
+    max_reassignments: usize,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _SSC, _D> GreedyReassignmentRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    pub fn new(solution_score_calculator: _SSC, domain: _D, max_reassignments: usize) -> Self {
+        Self {
+            solution_score_calculator,
+            domain,
+            max_reassignments,
+            phantom_r: PhantomData,
+        }
+    }
+
+    fn get_best_reassignment(
+        &self,
+        current: &ScoredSolution<_SSC::_Solution, _SSC::_Score>,
+        variable: usize,
+        rng: &mut _R,
+    ) -> Option<ScoredSolution<_SSC::_Solution, _SSC::_Score>> {
+        let current_value = current.solution.get_value(variable);
+        let mut values = self.domain.get_values(&current.solution, variable);
+        values.shuffle(rng);
+        values
+            .into_iter()
+            .filter(|value| *value != current_value)
+            .map(|value| {
+                let mut candidate = current.solution.clone();
+                candidate.set_value(variable, value);
+                self.solution_score_calculator.get_scored_solution(candidate)
+            })
+            .min_by(|left, right| left.score.cmp(&right.score))
+            .filter(|candidate| candidate.score < current.score)
+    }
+}
+
+impl<_R, _SSC, _D> RepairOperator for GreedyReassignmentRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    type _R = _R;
+    type _Solution = _SSC::_Solution;
+
+    fn repair(&mut self, solution: Self::_Solution, rng: &mut Self::_R) -> Self::_Solution {
+        let mut current = self.solution_score_calculator.get_scored_solution(solution);
+        for _ in 0..self.max_reassignments {
+            let contributions = self
+                .solution_score_calculator
+                .get_score_contributions(&current.solution);
+            let violating_variables = get_worst_variables(&contributions, contributions.len());
+            let improved = violating_variables
+                .into_iter()
+                .find_map(|variable| self.get_best_reassignment(&current, variable, rng));
+            match improved {
+                Some(improved) => current = improved,
+                None => break,
+            }
+        }
+        current.solution
+    }
+}
+
+/// RandomFeasibilityRepair assigns random domain values to randomly chosen violating variables, keeping every
+/// assignment that does not make the score worse. It is much cheaper per step than GreedyReassignmentRepair and,
+/// because it accepts sideways steps, it can walk across plateaus that the greedy repair gets stuck on.
+pub struct RandomFeasibilityRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    solution_score_calculator: _SSC,
+    domain: _D,
+    max_attempts: usize,
+    phantom_r: PhantomData<_R>,
+}
+
+impl<_R, _SSC, _D> RandomFeasibilityRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    pub fn new(solution_score_calculator: _SSC, domain: _D, max_attempts: usize) -> Self {
+        Self {
+            solution_score_calculator,
+            domain,
+            max_attempts,
+            phantom_r: PhantomData,
+        }
+    }
+}
+
+impl<_R, _SSC, _D> RepairOperator for RandomFeasibilityRepair<_R, _SSC, _D>
+where
+    _R: rand::Rng,
+    _SSC: ScoreContributionCalculator,
+    _SSC::_Solution: Assignment,
+    _D: Domain<_Solution = _SSC::_Solution>,
+{
+    type _R = _R;
+    type _Solution = _SSC::_Solution;
+
+    fn repair(&mut self, solution: Self::_Solution, rng: &mut Self::_R) -> Self::_Solution {
+        let mut current = self.solution_score_calculator.get_scored_solution(solution);
+        for _ in 0..self.max_attempts {
+            let contributions = self
+                .solution_score_calculator
+                .get_score_contributions(&current.solution);
+            let violating_variables = get_worst_variables(&contributions, contributions.len());
+            let variable = match violating_variables.choose(rng) {
+                None => break,
+                Some(variable) => *variable,
+            };
+            let value = match self.domain.get_values(&current.solution, variable).choose(rng) {
+                None => continue,
+                Some(value) => value.clone(),
+            };
+            let mut candidate = current.solution.clone();
+            candidate.set_value(variable, value);
+            let candidate = self.solution_score_calculator.get_scored_solution(candidate);
+            if candidate.score <= current.score {
+                current = candidate;
+            }
+        }
+        current.solution
+    }
+}
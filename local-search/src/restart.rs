@@ -0,0 +1,119 @@
+//! restart decides when IteratedLocalSearch abandons its current solution and restarts from a new initial solution.
+//! Perturbation only moves a little away from the current local minimum, so a restart is the way out of a region of the
+//! search space that perturbation keeps returning to. The best solutions found so far stay in the history.
+
+/// RestartState is what IteratedLocalSearch knows about its progress when it asks a RestartPolicy whether to restart,
+/// at the start of every round.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RestartState {
+    /// The round that is about to run, starting at 1.
+    pub iteration: u64,
+
+    /// How many rounds ran since the last restart, or since the start if there was none.
+    pub rounds_since_restart: u64,
+
+    /// How many rounds in a row have not improved on the best solution.
+    pub rounds_without_improvement: u64,
+}
+
+pub trait RestartPolicy {
+    fn should_restart(&mut self, state: &RestartState) -> bool;
+}
+
+/// Never restart.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NeverRestart;
+
+impl RestartPolicy for NeverRestart {
+    fn should_restart(&mut self, _state: &RestartState) -> bool {
+        false
+    }
+}
+
+/// Restart every this many rounds, or never with 0. IteratedLocalSearch restarts every 50 rounds by default.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixedIntervalRestart(pub u64);
+
+impl RestartPolicy for FixedIntervalRestart {
+    fn should_restart(&mut self, state: &RestartState) -> bool {
+        self.0 > 0 && state.iteration % self.0 == 0
+    }
+}
+
+/// Restart once this many rounds in a row have not improved on the best solution, and at most once every this many
+/// rounds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StagnationRestart(pub u64);
+
+impl RestartPolicy for StagnationRestart {
+    fn should_restart(&mut self, state: &RestartState) -> bool {
+        state.rounds_without_improvement >= self.0 && state.rounds_since_restart >= self.0
+    }
+}
+
+/// Restart after initial_interval rounds, and then after intervals that grow by factor every restart, so that later
+/// restarts leave more time to improve on a promising start.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeometricRestart {
+    interval: f64,
+    factor: f64,
+}
+
+impl GeometricRestart {
+    pub fn new(initial_interval: u64, factor: f64) -> Self {
+        assert!(initial_interval > 0, "initial_interval must be positive");
+        assert!(factor >= 1.0, "factor must be at least 1");
+        Self {
+            interval: initial_interval as f64,
+            factor,
+        }
+    }
+}
+
+impl RestartPolicy for GeometricRestart {
+    fn should_restart(&mut self, state: &RestartState) -> bool {
+        if (state.rounds_since_restart as f64) < self.interval.round() {
+            return false;
+        }
+        self.interval *= self.factor;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::restart::{
+        FixedIntervalRestart, GeometricRestart, NeverRestart, RestartPolicy, RestartState, StagnationRestart,
+    };
+
+    /// Get the rounds at which restart_policy restarts in the first rounds rounds, where the best solution never
+    /// improves.
+    fn get_restarts(restart_policy: &mut impl RestartPolicy, rounds: u64) -> Vec<u64> {
+        let mut restarts = vec![];
+        let mut rounds_since_restart = 0;
+        for iteration in 1..=rounds {
+            let state = RestartState {
+                iteration,
+                rounds_since_restart,
+                rounds_without_improvement: iteration - 1,
+            };
+            if restart_policy.should_restart(&state) {
+                restarts.push(iteration);
+                rounds_since_restart = 0;
+            }
+            rounds_since_restart += 1;
+        }
+        restarts
+    }
+
+    #[test]
+    fn policies() {
+        assert!(get_restarts(&mut NeverRestart, 100).is_empty());
+        assert_eq!(vec![10, 20, 30], get_restarts(&mut FixedIntervalRestart(10), 30));
+        assert_eq!(vec![6, 11, 16], get_restarts(&mut StagnationRestart(5), 18));
+        assert_eq!(
+            vec![3, 7, 15, 31],
+            get_restarts(&mut GeometricRestart::new(2, 2.0), 40)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! rng turns user-facing seeds into random number generators, and derives the random number generators of the workers
+//! of a parallel solve from one master seed, so that the solve gives the same answer for the same seed no matter how its
+//! threads are scheduled.
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+type Blake2b256 = Blake2b<U32>;
+
+/// DefaultRng is the random number generator the examples and helpers use unless told otherwise. ChaCha20 has
+/// independent streams, see RngStreams, and a portable, well studied output. Solvers that are bound by random number
+/// generation, e.g. local search with large window sizes, can use a smaller and faster generator like rand_pcg::Pcg64
+/// instead, which like every SeedableRng with a 32 byte seed can be made with Seed::get_rng.
+pub type DefaultRng = ChaCha20Rng;
+
+/// Seed is the 32 bytes that seed a random number generator, made from whatever the user provides: a string, e.g. from
+/// the command line, is hashed with Blake2b-256, a u64 is expanded like SeedableRng::seed_from_u64 does, and raw bytes
+/// are used as they are.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Seed([u8; 32]);
+
+impl Seed {
+    pub fn get_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    /// Get a random number generator seeded with this seed. Equal seeds give equal generators.
+    pub fn get_rng<_R: SeedableRng<Seed = [u8; 32]>>(&self) -> _R {
+        _R::from_seed(self.0)
+    }
+
+    /// Get the per-worker streams of this seed, see RngStreams.
+    pub fn get_rng_streams(&self) -> RngStreams {
+        RngStreams::from_seed(self.0)
+    }
+}
+
+impl From<&str> for Seed {
+    fn from(seed: &str) -> Self {
+        let mut hasher = Blake2b256::new();
+        hasher.update(seed.as_bytes());
+        Self(hasher.finalize().into())
+    }
+}
+
+impl From<u64> for Seed {
+    fn from(seed: u64) -> Self {
+        Self(ChaCha20Rng::seed_from_u64(seed).get_seed())
+    }
+}
+
+impl From<[u8; 32]> for Seed {
+    fn from(seed: [u8; 32]) -> Self {
+        Self(seed)
+    }
+}
+
+/// RngStreams splits one master seed into independent ChaCha20 streams, one per worker. Every stream uses the master
+/// seed as key and the worker index as stream number, so streams never overlap and a worker's stream does not depend
+/// on how many other workers there are.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RngStreams {
+    seed: [u8; 32],
+}
+
+impl RngStreams {
+    pub fn new(seed: u64) -> Self {
+        Seed::from(seed).get_rng_streams()
+    }
+
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self { seed }
+    }
+
+    /// Get the generator of worker.
+    pub fn get_stream(&self, worker: usize) -> ChaCha20Rng {
+        let mut rng = ChaCha20Rng::from_seed(self.seed);
+        rng.set_stream(worker as u64);
+        rng
+    }
+
+    /// Get a random number generator of any type with a 32 byte seed for worker, seeded from the stream of worker, so
+    /// that it is as independent of the generators of the other workers as the streams are.
+    pub fn get_rng<_R: SeedableRng<Seed = [u8; 32]>>(&self, worker: usize) -> _R {
+        _R::from_seed(self.get_stream(worker).gen())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use rand_chacha::{ChaCha20Rng, ChaCha8Rng};
+
+    use crate::rng::{RngStreams, Seed};
+
+    #[test]
+    fn seed_from_str_u64_and_bytes() {
+        let seed = Seed::from("42");
+        assert_eq!(seed, Seed::from("42"));
+        assert_ne!(seed, Seed::from("43"));
+        assert_eq!(seed, Seed::from(seed.get_bytes()));
+        assert_eq!(Seed::from(42), Seed::from(42));
+        assert_ne!(Seed::from(42), seed);
+
+        let mut first: ChaCha20Rng = seed.get_rng();
+        let mut second: ChaCha20Rng = Seed::from("42").get_rng();
+        assert_eq!(first.gen::<u64>(), second.gen::<u64>());
+        assert_eq!(Seed::from(42).get_rng_streams(), RngStreams::new(42));
+    }
+
+    #[test]
+    fn streams_are_reproducible_and_independent() {
+        let rng_streams = RngStreams::new(42);
+        let first: Vec<u64> = (0..4)
+            .map(|worker| rng_streams.get_stream(worker).gen())
+            .collect();
+        let second: Vec<u64> = (0..4)
+            .map(|worker| RngStreams::new(42).get_stream(worker).gen())
+            .collect();
+        assert_eq!(first, second);
+        let mut distinct = first.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(4, distinct.len());
+        assert_ne!(first[0], RngStreams::new(43).get_stream(0).gen::<u64>());
+    }
+
+    #[test]
+    fn get_rng_seeds_any_generator_from_the_stream() {
+        let rng_streams = RngStreams::new(42);
+        let mut first: ChaCha8Rng = rng_streams.get_rng(0);
+        let mut second: ChaCha8Rng = RngStreams::new(42).get_rng(0);
+        assert_eq!(first.gen::<u64>(), second.gen::<u64>());
+        assert_ne!(
+            rng_streams.get_rng::<ChaCha8Rng>(0).gen::<u64>(),
+            rng_streams.get_rng::<ChaCha8Rng>(1).gen::<u64>()
+        );
+    }
+}
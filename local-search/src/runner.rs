@@ -0,0 +1,260 @@
+//! runner lets applications run an IteratedLocalSearch on a dedicated thread and control it from the outside, so that
+//! embedding a solver doesn't require writing thread management code. The best solution and statistics are published
+//! after every round, so an application thread can read them while the solver keeps working.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+
+use crate::best_solution_handle::BestSolutionHandle;
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, IterationInfo, Perturbation};
+use crate::local_search::{
+    InitialSolutionGenerator, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
+};
+use crate::stats::SolverStats;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolverState {
+    Running,
+    Paused,
+    Finished,
+}
+
+/// SolverStatus is a snapshot of a running solver, see SolverHandle::poll_status.
+#[derive(Clone, Debug)]
+pub struct SolverStatus<_Score>
+where
+    _Score: Score,
+{
+    pub state: SolverState,
+    pub iteration_info: IterationInfo<_Score>,
+    pub best_score: Option<_Score>,
+}
+
+struct RunnerControl<_Score>
+where
+    _Score: Score,
+{
+    state: Mutex<SolverState>,
+    resumed: Condvar,
+    stop_requested: AtomicBool,
+    iteration_info: Mutex<IterationInfo<_Score>>,
+}
+
+impl<_Score> RunnerControl<_Score>
+where
+    _Score: Score,
+{
+    /// Blocks while the solver is paused. Returns whether the solver should keep executing rounds.
+    fn wait_while_paused(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        while *state == SolverState::Paused && !self.stop_requested.load(Ordering::SeqCst) {
+            state = self.resumed.wait(state).unwrap();
+        }
+        !self.stop_requested.load(Ordering::SeqCst)
+    }
+}
+
+pub struct SolverRunner;
+
+impl SolverRunner {
+    /// Move the solver onto a new thread and start executing rounds until it is finished or stopped. At least one round
+    /// is always executed so that there is a best solution to return.
+    pub fn spawn<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>(
+        mut iterated_local_search: IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+    ) -> SolverHandle<_Solution, _Score>
+    where
+        _R: rand::Rng + Send + 'static,
+        _Score: Score + Sync + 'static,
+        _Solution: Solution + Sync + 'static,
+        _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score> + Send + 'static,
+        _MP: MoveProposer<R = _R, Solution = _Solution> + Send + 'static,
+        _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution> + Send + 'static,
+        _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC> + Send + 'static,
+        _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score> + Send + 'static,
+    {
+        let control = Arc::new(RunnerControl {
+            state: Mutex::new(SolverState::Running),
+            resumed: Condvar::new(),
+            stop_requested: AtomicBool::new(false),
+            iteration_info: Mutex::new(iterated_local_search.get_iteration_info()),
+        });
+        let best_solution_handle = iterated_local_search.get_best_solution_handle();
+        let stats = Arc::new(Mutex::new(iterated_local_search.get_stats()));
+        let thread_control = control.clone();
+        let thread_stats = stats.clone();
+        let join_handle = std::thread::spawn(move || {
+            loop {
+                if let Err(error) = iterated_local_search.execute_round() {
+                    *thread_control.state.lock().unwrap() = SolverState::Finished;
+                    return Err(error);
+                }
+                *thread_control.iteration_info.lock().unwrap() = iterated_local_search.get_iteration_info();
+                *thread_stats.lock().unwrap() = iterated_local_search.get_stats();
+                if iterated_local_search.is_finished() || !thread_control.wait_while_paused() {
+                    break;
+                }
+            }
+            *thread_control.state.lock().unwrap() = SolverState::Finished;
+            iterated_local_search.try_get_best_solution()
+        });
+        SolverHandle {
+            control,
+            best_solution_handle,
+            stats,
+            join_handle,
+        }
+    }
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+    IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng + Send + 'static,
+    _Score: Score + Sync + 'static,
+    _Solution: Solution + Sync + 'static,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score> + Send + 'static,
+    _MP: MoveProposer<R = _R, Solution = _Solution> + Send + 'static,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution> + Send + 'static,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC> + Send + 'static,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score> + Send + 'static,
+{
+    /// Move this solver onto a new thread and start executing rounds, see SolverRunner::spawn.
+    pub fn spawn(self) -> SolverHandle<_Solution, _Score> {
+        SolverRunner::spawn(self)
+    }
+}
+
+/// SolverHandle controls a solver that SolverRunner::spawn moved onto its own thread.
+pub struct SolverHandle<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    control: Arc<RunnerControl<_Score>>,
+    best_solution_handle: BestSolutionHandle<_Solution, _Score>,
+    stats: Arc<Mutex<SolverStats<_Score>>>,
+    join_handle: JoinHandle<Result<ScoredSolution<_Solution, _Score>, SolverError>>,
+}
+
+impl<_Solution, _Score> SolverHandle<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    /// Get the current state, progress and best score without blocking on the solver round in flight.
+    pub fn poll_status(&self) -> SolverStatus<_Score> {
+        SolverStatus {
+            state: *self.control.state.lock().unwrap(),
+            iteration_info: self.control.iteration_info.lock().unwrap().clone(),
+            best_score: self.best_solution_handle.get_score(),
+        }
+    }
+
+    /// Get a copy of the best solution found so far, or None if the solver has not finished a round yet.
+    pub fn best_so_far(&self) -> Option<ScoredSolution<_Solution, _Score>> {
+        self.best_solution_handle.get()
+    }
+
+    /// Get the statistics as of the last round that completed, see IteratedLocalSearch::get_stats.
+    pub fn stats(&self) -> SolverStats<_Score> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Get a handle to the best solution found so far, which stays valid after this SolverHandle is consumed.
+    pub fn get_best_solution_handle(&self) -> BestSolutionHandle<_Solution, _Score> {
+        self.best_solution_handle.clone()
+    }
+
+    /// Pause the solver once the round in flight completes. Has no effect on a finished solver.
+    pub fn pause(&self) {
+        let mut state = self.control.state.lock().unwrap();
+        if *state == SolverState::Running {
+            *state = SolverState::Paused;
+        }
+    }
+
+    pub fn resume(&self) {
+        let mut state = self.control.state.lock().unwrap();
+        if *state == SolverState::Paused {
+            *state = SolverState::Running;
+            self.control.resumed.notify_all();
+        }
+    }
+
+    /// Ask the solver to stop after the round in flight, also if it is paused, without waiting for it, see join.
+    pub fn stop(&self) {
+        self.control.stop_requested.store(true, Ordering::SeqCst);
+        let _state = self.control.state.lock().unwrap();
+        self.control.resumed.notify_all();
+    }
+
+    /// Wait for the solver to finish or stop, and return the best solution, or the error that stopped the solver.
+    pub fn join(self) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        self.join_handle.join().unwrap()
+    }
+
+    /// Ask the solver to stop after the round in flight, wait for the thread to exit, and return the best solution.
+    pub fn stop_and_join(self) -> Result<ScoredSolution<_Solution, _Score>, SolverError> {
+        self.stop();
+        self.join()
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use crate::ackley::new_slow_ackley_iterated_local_search;
+    use crate::runner::{SolverRunner, SolverState};
+    use crate::termination::CancellationToken;
+
+    #[test]
+    fn pause_resume_and_stop() {
+        let iterated_local_search = new_slow_ackley_iterated_local_search(42, u64::MAX);
+        let handle = SolverRunner::spawn(iterated_local_search);
+
+        while handle.poll_status().iteration_info.iteration < 5 {
+            std::thread::yield_now();
+        }
+        handle.pause();
+        let paused_at = handle.poll_status().iteration_info.iteration;
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let status = handle.poll_status();
+        assert_eq!(SolverState::Paused, status.state);
+        assert!(
+            status.iteration_info.iteration <= paused_at + 1,
+            "expected at most the round in flight to complete while paused"
+        );
+
+        handle.resume();
+        while handle.poll_status().iteration_info.iteration < paused_at + 5 {
+            std::thread::yield_now();
+        }
+        let best_score = handle.poll_status().best_score;
+        let best = handle.stop_and_join().unwrap();
+        assert!(best_score.unwrap() >= best.score);
+    }
+
+    #[test]
+    fn spawn_executes_a_round_of_a_finished_solver() {
+        let mut iterated_local_search = new_slow_ackley_iterated_local_search(42, u64::MAX);
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+        iterated_local_search.set_cancellation_token(Some(cancellation_token));
+        assert!(iterated_local_search.is_finished());
+        let handle = SolverRunner::spawn(iterated_local_search);
+        assert!(handle.join().is_ok());
+    }
+
+    #[test]
+    fn spawn_publishes_best_so_far_and_stats() {
+        let handle = new_slow_ackley_iterated_local_search(42, u64::MAX).spawn();
+        while handle.poll_status().iteration_info.iteration < 3 {
+            std::thread::yield_now();
+        }
+        let best_so_far = handle.best_so_far().unwrap();
+        assert!(handle.stats().perturbations > 0);
+        handle.stop();
+        let best = handle.join().unwrap();
+        assert!(best.score <= best_so_far.score);
+    }
+}
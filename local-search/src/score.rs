@@ -0,0 +1,416 @@
+//! score holds optional extensions to Score that generic tooling, like the convergence recorder, can rely on, and
+//! ready-made scores: HardSoftScore, BendableScore, and Lexico to layer existing scores.
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+
+use crate::local_search::Score;
+
+/// HARD_SCORE_WEIGHT is how much more one unit of hard score weighs than one unit of soft score in get_energy.
+pub const HARD_SCORE_WEIGHT: f64 = 1e6;
+
+/// HardSoftScoring is implemented by Scores that are made of a hard part (constraints that must hold for a solution to
+/// be usable) and a soft part (preferences). Both are costs, so lower is better and zero means no violations. Scores
+/// without a notion of hard constraints report a hard score of zero.
+pub trait HardSoftScoring: Score {
+    fn get_hard_score(&self) -> f64;
+    fn get_soft_score(&self) -> f64;
+
+    /// Collapse the score into a single number, e.g. for simulated annealing, where the hard score dominates as long
+    /// as soft scores stay below HARD_SCORE_WEIGHT.
+    fn get_energy(&self) -> f64 {
+        self.get_hard_score() * HARD_SCORE_WEIGHT + self.get_soft_score()
+    }
+}
+
+/// FromHardSoftScore is a HardSoftScoring that can be built from its hard and soft parts, so that generic tooling, like
+/// ConstraintScoreCalculator, can create scores.
+pub trait FromHardSoftScore: HardSoftScoring {
+    fn from_hard_soft_score(hard_score: f64, soft_score: f64) -> Self;
+}
+
+/// HardSoftValue is a number that HardSoftScore can be made of, e.g. i64 or OrderedFloat<f64>.
+pub trait HardSoftValue:
+    num_traits::Num
+    + num_traits::ToPrimitive
+    + num_traits::NumCast
+    + Copy
+    + Ord
+    + std::hash::Hash
+    + std::fmt::Debug
+    + std::fmt::Display
+    + Send
+{
+}
+
+impl<_T> HardSoftValue for _T where
+    _T: num_traits::Num
+        + num_traits::ToPrimitive
+        + num_traits::NumCast
+        + Copy
+        + Ord
+        + std::hash::Hash
+        + std::fmt::Debug
+        + std::fmt::Display
+        + Send
+{
+}
+
+/// HardSoftScore is a ready-made HardSoftScoring score, so that problem domains don't have to write their own (hard,
+/// soft) score. Scores compare by hard score first, then by soft score. Use FloatHardSoft or IntegerHardSoft.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct HardSoftScore<_T> {
+    pub hard_score: _T,
+    pub soft_score: _T,
+}
+
+pub type FloatHardSoft = HardSoftScore<OrderedFloat<f64>>;
+pub type IntegerHardSoft = HardSoftScore<i64>;
+
+impl<_T> HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    pub fn new(hard_score: _T, soft_score: _T) -> Self {
+        Self {
+            hard_score,
+            soft_score,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(_T::zero(), _T::zero())
+    }
+
+    /// Is no hard constraint violated, i.e. can the solution be used at all.
+    pub fn is_feasible(&self) -> bool {
+        self.hard_score.is_zero()
+    }
+}
+
+impl<_T> Score for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn is_best(&self) -> bool {
+        self.hard_score.is_zero() && self.soft_score.is_zero()
+    }
+}
+
+impl<_T> HardSoftScoring for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn get_hard_score(&self) -> f64 {
+        self.hard_score.to_f64().unwrap()
+    }
+
+    fn get_soft_score(&self) -> f64 {
+        self.soft_score.to_f64().unwrap()
+    }
+}
+
+/// Integer scores are rounded towards zero.
+impl<_T> FromHardSoftScore for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn from_hard_soft_score(hard_score: f64, soft_score: f64) -> Self {
+        Self::new(
+            _T::from(hard_score).expect("hard_score must fit the score type"),
+            _T::from(soft_score).expect("soft_score must fit the score type"),
+        )
+    }
+}
+
+impl<_T> Add for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.hard_score + other.hard_score,
+            self.soft_score + other.soft_score,
+        )
+    }
+}
+
+impl<_T> Sub for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.hard_score - other.hard_score,
+            self.soft_score - other.soft_score,
+        )
+    }
+}
+
+impl<_T> AddAssign for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<_T> SubAssign for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<_T> std::iter::Sum for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn sum<_I: Iterator<Item = Self>>(scores: _I) -> Self {
+        scores.fold(Self::zero(), |total, score| total + score)
+    }
+}
+
+/// Display like 2hard/15soft.
+impl<_T> std::fmt::Display for HardSoftScore<_T>
+where
+    _T: HardSoftValue,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}hard/{}soft", self.hard_score, self.soft_score)
+    }
+}
+
+/// BendableScore is a score with HARD hard levels and SOFT soft levels, e.g. to keep medium priority constraints apart
+/// from both hard constraints and soft preferences. Levels compare lexicographically: all hard levels first, in order,
+/// then all soft levels. Like HardSoftScore, every level is a cost, so lower is better.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct BendableScore<const HARD: usize, const SOFT: usize> {
+    hard_scores: [i64; HARD],
+    soft_scores: [i64; SOFT],
+}
+
+impl<const HARD: usize, const SOFT: usize> BendableScore<HARD, SOFT> {
+    pub fn new(hard_scores: [i64; HARD], soft_scores: [i64; SOFT]) -> Self {
+        Self {
+            hard_scores,
+            soft_scores,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new([0; HARD], [0; SOFT])
+    }
+
+    pub fn get_hard_scores(&self) -> &[i64; HARD] {
+        &self.hard_scores
+    }
+
+    pub fn get_soft_scores(&self) -> &[i64; SOFT] {
+        &self.soft_scores
+    }
+
+    /// Is no hard level violated.
+    pub fn is_feasible(&self) -> bool {
+        self.hard_scores.iter().all(|score| *score == 0)
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> Default for BendableScore<HARD, SOFT> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> Score for BendableScore<HARD, SOFT> {
+    fn is_best(&self) -> bool {
+        self.is_feasible() && self.soft_scores.iter().all(|score| *score == 0)
+    }
+}
+
+/// The hard and soft scores are the sums of the hard and soft levels, which loses the order between levels of the same
+/// kind.
+impl<const HARD: usize, const SOFT: usize> HardSoftScoring for BendableScore<HARD, SOFT> {
+    fn get_hard_score(&self) -> f64 {
+        self.hard_scores.iter().sum::<i64>() as f64
+    }
+
+    fn get_soft_score(&self) -> f64 {
+        self.soft_scores.iter().sum::<i64>() as f64
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> Add for BendableScore<HARD, SOFT> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> Sub for BendableScore<HARD, SOFT> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> AddAssign for BendableScore<HARD, SOFT> {
+    fn add_assign(&mut self, other: Self) {
+        for (score, other_score) in self.hard_scores.iter_mut().zip(other.hard_scores) {
+            *score += other_score;
+        }
+        for (score, other_score) in self.soft_scores.iter_mut().zip(other.soft_scores) {
+            *score += other_score;
+        }
+    }
+}
+
+impl<const HARD: usize, const SOFT: usize> SubAssign for BendableScore<HARD, SOFT> {
+    fn sub_assign(&mut self, other: Self) {
+        for (score, other_score) in self.hard_scores.iter_mut().zip(other.hard_scores) {
+            *score -= other_score;
+        }
+        for (score, other_score) in self.soft_scores.iter_mut().zip(other.soft_scores) {
+            *score -= other_score;
+        }
+    }
+}
+
+/// Display like [1/0]hard/[2/15/3]soft.
+impl<const HARD: usize, const SOFT: usize> std::fmt::Display for BendableScore<HARD, SOFT> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |scores: &[i64]| {
+            scores
+                .iter()
+                .map(|score| score.to_string())
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        write!(
+            f,
+            "[{}]hard/[{}]soft",
+            join(&self.hard_scores),
+            join(&self.soft_scores)
+        )
+    }
+}
+
+/// Lexico layers existing scores, e.g. Lexico<(Feasibility, Fairness, Preference)>, and compares them
+/// lexicographically: the first score decides, and every later score only breaks ties of the ones before it. A Lexico
+/// is best if all of its scores are. Lexico is implemented for tuples of 2 to 6 scores.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Lexico<_T>(pub _T);
+
+macro_rules! impl_lexico_score {
+    ($($_S:ident $index:tt),+) => {
+        impl<$($_S),+> Score for Lexico<($($_S,)+)>
+        where
+            $($_S: Score,)+
+        {
+            fn is_best(&self) -> bool {
+                $(self.0.$index.is_best())&&+
+            }
+        }
+    };
+}
+
+impl_lexico_score!(_S0 0, _S1 1);
+impl_lexico_score!(_S0 0, _S1 1, _S2 2);
+impl_lexico_score!(_S0 0, _S1 1, _S2 2, _S3 3);
+impl_lexico_score!(_S0 0, _S1 1, _S2 2, _S3 3, _S4 4);
+impl_lexico_score!(_S0 0, _S1 1, _S2 2, _S3 3, _S4 4, _S5 5);
+
+#[cfg(test)]
+mod tests {
+    use ordered_float::OrderedFloat;
+
+    use crate::local_search::Score;
+    use crate::score::{
+        BendableScore, FloatHardSoft, FromHardSoftScore, HardSoftScoring, IntegerHardSoft, Lexico,
+    };
+
+    #[test]
+    fn hard_score_dominates_soft_score() {
+        assert!(IntegerHardSoft::new(0, 100) < IntegerHardSoft::new(1, 0));
+        assert!(IntegerHardSoft::new(1, 2) < IntegerHardSoft::new(1, 3));
+        assert!(IntegerHardSoft::new(0, 100).is_feasible());
+        assert!(!IntegerHardSoft::new(1, 0).is_feasible());
+        assert!(IntegerHardSoft::zero().is_best());
+    }
+
+    #[test]
+    fn arithmetic_and_display() {
+        let mut score = IntegerHardSoft::new(1, 2) + IntegerHardSoft::new(3, 4);
+        assert_eq!(IntegerHardSoft::new(4, 6), score);
+        score -= IntegerHardSoft::new(4, 1);
+        assert_eq!(IntegerHardSoft::new(0, 5), score);
+        assert_eq!(
+            IntegerHardSoft::new(2, 7),
+            vec![IntegerHardSoft::new(1, 3), IntegerHardSoft::new(1, 4)]
+                .into_iter()
+                .sum()
+        );
+        assert_eq!("0hard/5soft", score.to_string());
+        assert_eq!(
+            "1hard/2.5soft",
+            FloatHardSoft::new(OrderedFloat(1.0), OrderedFloat(2.5)).to_string()
+        );
+    }
+
+    #[test]
+    fn converts_from_and_to_hard_and_soft_parts() {
+        let score = FloatHardSoft::from_hard_soft_score(1.0, 2.5);
+        assert_eq!(1.0, score.get_hard_score());
+        assert_eq!(2.5, score.get_soft_score());
+        assert_eq!(
+            IntegerHardSoft::new(1, 2),
+            IntegerHardSoft::from_hard_soft_score(1.0, 2.5)
+        );
+    }
+
+    #[test]
+    fn bendable_levels_compare_lexicographically() {
+        type Score21 = BendableScore<2, 1>;
+        assert!(Score21::new([0, 5], [100]) < Score21::new([1, 0], [0]));
+        assert!(Score21::new([0, 1], [100]) < Score21::new([0, 2], [0]));
+        assert!(Score21::new([0, 1], [1]) < Score21::new([0, 1], [2]));
+        assert!(Score21::new([0, 0], [3]).is_feasible());
+        assert!(!Score21::new([0, 1], [0]).is_feasible());
+        assert!(Score21::zero().is_best());
+
+        let score = Score21::new([1, 2], [3]) + Score21::new([0, 1], [1]) - Score21::new([1, 0], [0]);
+        assert_eq!(Score21::new([0, 3], [4]), score);
+        assert_eq!(3.0, score.get_hard_score());
+        assert_eq!("[0/3]hard/[4]soft", score.to_string());
+    }
+
+    #[test]
+    fn lexico_layers_scores() {
+        type Layered = Lexico<(IntegerHardSoft, BendableScore<0, 1>, IntegerHardSoft)>;
+        let layered = |hard, fairness, preference| {
+            Lexico((
+                IntegerHardSoft::new(hard, 0),
+                BendableScore::new([], [fairness]),
+                IntegerHardSoft::new(0, preference),
+            ))
+        };
+        let feasible: Layered = layered(0, 9, 9);
+        assert!(feasible < layered(1, 0, 0));
+        assert!(layered(0, 1, 9) < layered(0, 2, 0));
+        assert!(layered(0, 1, 1) < layered(0, 1, 2));
+        assert!(!feasible.is_best());
+        assert!(layered(0, 0, 0).is_best());
+    }
+}
@@ -0,0 +1,378 @@
+//! simulated_annealing implements Metropolis acceptance: a worse solution is accepted with probability
+//! exp(-delta / temperature), and the temperature follows a TemperatureSchedule. High temperatures explore, low
+//! temperatures exploit.
+use std::marker::PhantomData;
+
+use crate::iterated_local_search::AcceptanceCriterion;
+use crate::local_search::{History, Score, ScoredSolution, Solution};
+use crate::score::HardSoftScoring;
+
+/// TemperatureSchedule decides how the temperature changes after every acceptance decision. Temperatures never drop
+/// below the minimum temperature of SimulatedAnnealing.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemperatureSchedule {
+    /// Multiply the temperature by alpha, which should be slightly less than 1.
+    Geometric { initial_temperature: f64, alpha: f64 },
+
+    /// Subtract step from the temperature.
+    Linear { initial_temperature: f64, step: f64 },
+
+    /// Every window worsening proposals, compare the fraction of them that was accepted to target_acceptance_rate.
+    /// Heat up by a factor of (1 + adjustment) if too few were accepted, and cool down by the same factor otherwise.
+    Adaptive {
+        initial_temperature: f64,
+        target_acceptance_rate: f64,
+        adjustment: f64,
+        window: u64,
+    },
+}
+
+impl TemperatureSchedule {
+    fn get_initial_temperature(&self) -> f64 {
+        match self {
+            TemperatureSchedule::Geometric {
+                initial_temperature, ..
+            }
+            | TemperatureSchedule::Linear {
+                initial_temperature, ..
+            }
+            | TemperatureSchedule::Adaptive {
+                initial_temperature, ..
+            } => *initial_temperature,
+        }
+    }
+}
+
+/// Reheat sets the temperature back to temperature once after acceptance decisions have not improved on the best
+/// energy seen so far, so that the search does not freeze in one basin long before its iteration budget is spent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reheat {
+    pub after: u64,
+    pub temperature: f64,
+}
+
+/// SimulatedAnnealing keeps the temperature and makes Metropolis acceptance decisions. Scores are turned into energies
+/// by get_energy, e.g. HardSoftScoring::get_energy, because the acceptance probability needs score differences.
+pub struct SimulatedAnnealing<_Score>
+where
+    _Score: Score,
+{
+    schedule: TemperatureSchedule,
+    minimum_temperature: f64,
+    temperature: f64,
+    get_energy: fn(&_Score) -> f64,
+    worsening_proposals: u64,
+    worsening_accepted: u64,
+    reheat: Option<Reheat>,
+    best_energy: f64,
+    non_improving_decisions: u64,
+    reheats: u64,
+}
+
+impl<_Score> SimulatedAnnealing<_Score>
+where
+    _Score: Score,
+{
+    pub fn new(
+        schedule: TemperatureSchedule,
+        minimum_temperature: f64,
+        get_energy: fn(&_Score) -> f64,
+    ) -> Self {
+        let temperature = schedule.get_initial_temperature().max(minimum_temperature);
+        Self {
+            schedule,
+            minimum_temperature,
+            temperature,
+            get_energy,
+            worsening_proposals: 0,
+            worsening_accepted: 0,
+            reheat: None,
+            best_energy: f64::INFINITY,
+            non_improving_decisions: 0,
+            reheats: 0,
+        }
+    }
+
+    /// Reheat when the search stagnates, see Reheat. By default the temperature only follows the schedule.
+    pub fn with_reheat(mut self, reheat: Reheat) -> Self {
+        assert!(reheat.after > 0, "after must be positive");
+        self.reheat = Some(reheat);
+        self
+    }
+
+    pub fn get_temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    /// How often the temperature was reheated so far.
+    pub fn get_reheats(&self) -> u64 {
+        self.reheats
+    }
+
+    /// Decide whether to move from existing to new, then update the temperature. Solutions that are at least as good
+    /// are always accepted.
+    pub fn accept<_R: rand::Rng>(&mut self, existing: &_Score, new: &_Score, rng: &mut _R) -> bool {
+        let new_energy = (self.get_energy)(new);
+        let delta = new_energy - (self.get_energy)(existing);
+        let accepted = if delta <= 0.0 {
+            true
+        } else {
+            self.worsening_proposals += 1;
+            let accepted = self.temperature > 0.0 && rng.gen::<f64>() < (-delta / self.temperature).exp();
+            if accepted {
+                self.worsening_accepted += 1;
+            }
+            accepted
+        };
+        self.update_temperature();
+        self.update_reheat(new_energy);
+        accepted
+    }
+
+    fn update_reheat(&mut self, new_energy: f64) {
+        let reheat = match self.reheat.as_ref() {
+            Some(reheat) => reheat,
+            None => return,
+        };
+        if new_energy < self.best_energy {
+            self.best_energy = new_energy;
+            self.non_improving_decisions = 0;
+            return;
+        }
+        self.non_improving_decisions += 1;
+        if self.non_improving_decisions >= reheat.after {
+            self.temperature = reheat.temperature.max(self.minimum_temperature);
+            self.non_improving_decisions = 0;
+            self.reheats += 1;
+        }
+    }
+
+    fn update_temperature(&mut self) {
+        let temperature = match self.schedule {
+            TemperatureSchedule::Geometric { alpha, .. } => self.temperature * alpha,
+            TemperatureSchedule::Linear { step, .. } => self.temperature - step,
+            TemperatureSchedule::Adaptive {
+                target_acceptance_rate,
+                adjustment,
+                window,
+                ..
+            } => {
+                if self.worsening_proposals < window {
+                    return;
+                }
+                let acceptance_rate = self.worsening_accepted as f64 / self.worsening_proposals as f64;
+                self.worsening_proposals = 0;
+                self.worsening_accepted = 0;
+                if acceptance_rate < target_acceptance_rate {
+                    self.temperature * (1.0 + adjustment)
+                } else {
+                    self.temperature / (1.0 + adjustment)
+                }
+            }
+        };
+        self.temperature = temperature.max(self.minimum_temperature);
+    }
+}
+
+/// SimulatedAnnealingAcceptanceCriterion makes IteratedLocalSearch use Metropolis acceptance between the existing and
+/// new local minima, with energies from HardSoftScoring::get_energy, instead of the weighted random pick.
+pub struct SimulatedAnnealingAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    simulated_annealing: SimulatedAnnealing<_Score>,
+    phantom_r: PhantomData<_R>,
+    phantom_solution: PhantomData<_Solution>,
+}
+
+impl<_R, _Solution, _Score> SimulatedAnnealingAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+{
+    pub fn new(schedule: TemperatureSchedule, minimum_temperature: f64) -> Self {
+        Self {
+            simulated_annealing: SimulatedAnnealing::new(
+                schedule,
+                minimum_temperature,
+                <_Score as HardSoftScoring>::get_energy,
+            ),
+            phantom_r: PhantomData,
+            phantom_solution: PhantomData,
+        }
+    }
+
+    /// See SimulatedAnnealing::with_reheat.
+    pub fn with_reheat(mut self, reheat: Reheat) -> Self {
+        self.simulated_annealing = self.simulated_annealing.with_reheat(reheat);
+        self
+    }
+
+    pub fn get_temperature(&self) -> f64 {
+        self.simulated_annealing.get_temperature()
+    }
+
+    pub fn get_reheats(&self) -> u64 {
+        self.simulated_annealing.get_reheats()
+    }
+}
+
+impl<_R, _Solution, _Score> AcceptanceCriterion
+    for SimulatedAnnealingAcceptanceCriterion<_R, _Solution, _Score>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+{
+    type _R = _R;
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<_Solution, _Score>,
+        new_local_minima: &ScoredSolution<_Solution, _Score>,
+        _history: &History<_R, _Solution, _Score>,
+        rng: &mut _R,
+    ) -> ScoredSolution<_Solution, _Score> {
+        match self
+            .simulated_annealing
+            .accept(&existing_local_minima.score, &new_local_minima.score, rng)
+        {
+            true => new_local_minima.clone(),
+            false => existing_local_minima.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::local_search::Score;
+    use crate::simulated_annealing::{Reheat, SimulatedAnnealing, TemperatureSchedule};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(u64);
+    impl Score for Cost {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    fn get_energy(cost: &Cost) -> f64 {
+        cost.0 as f64
+    }
+
+    #[test]
+    fn geometric_and_linear_cooling_stop_at_minimum_temperature() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut geometric = SimulatedAnnealing::new(
+            TemperatureSchedule::Geometric {
+                initial_temperature: 10.0,
+                alpha: 0.5,
+            },
+            1.0,
+            get_energy,
+        );
+        geometric.accept(&Cost(1), &Cost(1), &mut rng);
+        assert_eq!(5.0, geometric.get_temperature());
+        for _ in 0..10 {
+            geometric.accept(&Cost(1), &Cost(1), &mut rng);
+        }
+        assert_eq!(1.0, geometric.get_temperature());
+
+        let mut linear = SimulatedAnnealing::new(
+            TemperatureSchedule::Linear {
+                initial_temperature: 3.0,
+                step: 2.0,
+            },
+            0.0,
+            get_energy,
+        );
+        linear.accept(&Cost(1), &Cost(1), &mut rng);
+        assert_eq!(1.0, linear.get_temperature());
+        linear.accept(&Cost(1), &Cost(1), &mut rng);
+        assert_eq!(0.0, linear.get_temperature());
+        assert!(
+            !linear.accept(&Cost(1), &Cost(2), &mut rng),
+            "expected no uphill moves at zero temperature"
+        );
+    }
+
+    #[test]
+    fn improvements_are_always_accepted_and_uphill_moves_get_rarer_when_cold() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut count_accepted = |temperature: f64| {
+            let mut annealing = SimulatedAnnealing::new(
+                TemperatureSchedule::Linear {
+                    initial_temperature: temperature,
+                    step: 0.0,
+                },
+                0.0,
+                get_energy,
+            );
+            assert!(annealing.accept(&Cost(5), &Cost(3), &mut rng));
+            (0..1_000)
+                .filter(|_| annealing.accept(&Cost(3), &Cost(5), &mut rng))
+                .count()
+        };
+        let hot = count_accepted(100.0);
+        let cold = count_accepted(0.5);
+        assert!(hot > 900, "hot accepted {}", hot);
+        assert!(cold < 100, "cold accepted {}", cold);
+    }
+
+    #[test]
+    fn adaptive_schedule_heats_up_when_too_few_moves_are_accepted() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut annealing = SimulatedAnnealing::new(
+            TemperatureSchedule::Adaptive {
+                initial_temperature: 0.1,
+                target_acceptance_rate: 0.5,
+                adjustment: 1.0,
+                window: 10,
+            },
+            0.0,
+            get_energy,
+        );
+        for _ in 0..10 {
+            annealing.accept(&Cost(0), &Cost(100), &mut rng);
+        }
+        assert_eq!(0.2, annealing.get_temperature());
+    }
+
+    #[test]
+    fn reheat_after_stagnation() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let mut annealing = SimulatedAnnealing::new(
+            TemperatureSchedule::Geometric {
+                initial_temperature: 10.0,
+                alpha: 0.5,
+            },
+            0.0,
+            get_energy,
+        )
+        .with_reheat(Reheat {
+            after: 3,
+            temperature: 8.0,
+        });
+        annealing.accept(&Cost(5), &Cost(5), &mut rng);
+        annealing.accept(&Cost(5), &Cost(5), &mut rng);
+        annealing.accept(&Cost(5), &Cost(5), &mut rng);
+        assert_eq!(1.25, annealing.get_temperature());
+        annealing.accept(&Cost(5), &Cost(5), &mut rng);
+        assert_eq!(8.0, annealing.get_temperature());
+        assert_eq!(1, annealing.get_reheats());
+
+        annealing.accept(&Cost(5), &Cost(5), &mut rng);
+        annealing.accept(&Cost(5), &Cost(4), &mut rng);
+        annealing.accept(&Cost(4), &Cost(4), &mut rng);
+        annealing.accept(&Cost(4), &Cost(4), &mut rng);
+        assert_eq!(0.5, annealing.get_temperature());
+        assert_eq!(1, annealing.get_reheats());
+    }
+}
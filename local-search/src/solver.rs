@@ -0,0 +1,89 @@
+//! solver erases the type parameters of a solver, so that solvers with different move proposers, perturbations or
+//! acceptance criteria can be stored together, e.g. in a Vec<Box<dyn Solver<_Solution = ..., _Score = ...>>>, or be
+//! returned from a function without spelling out all of their types.
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
+use crate::local_search::{
+    InitialSolutionGenerator, MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator,
+};
+
+/// Solver is a solver that is stepped one round at a time. It is object safe, see the module documentation.
+pub trait Solver {
+    type _Solution: Solution;
+    type _Score: Score;
+
+    /// Execute one round, see IteratedLocalSearch::execute_round.
+    fn execute_round(&mut self) -> Result<(), SolverError>;
+
+    /// Is the solver finished, see IteratedLocalSearch::is_finished.
+    fn is_finished(&self) -> bool;
+
+    fn get_best_solution(&self) -> ScoredSolution<Self::_Solution, Self::_Score>;
+
+    /// Execute rounds until the solver is finished and return the best solution found. At least one round is always
+    /// executed.
+    fn solve(&mut self) -> Result<ScoredSolution<Self::_Solution, Self::_Score>, SolverError> {
+        loop {
+            self.execute_round()?;
+            if self.is_finished() {
+                return Ok(self.get_best_solution());
+            }
+        }
+    }
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC> Solver
+    for IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: Score,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn execute_round(&mut self) -> Result<(), SolverError> {
+        IteratedLocalSearch::execute_round(self)
+    }
+
+    fn is_finished(&self) -> bool {
+        IteratedLocalSearch::is_finished(self)
+    }
+
+    fn get_best_solution(&self) -> ScoredSolution<_Solution, _Score> {
+        IteratedLocalSearch::get_best_solution(self)
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use crate::ackley::{
+        new_ackley_iterated_local_search, new_ackley_iterated_local_search_with_acceptance_criterion,
+        AckleyScore, AckleySolution,
+    };
+    use crate::record_to_record::{Deviation, RecordToRecordTravelAcceptanceCriterion};
+    use crate::solver::Solver;
+
+    #[test]
+    fn solvers_of_different_types_in_one_vec() {
+        let mut solvers: Vec<Box<dyn Solver<_Solution = AckleySolution, _Score = AckleyScore> + Send>> = vec![
+            Box::new(new_ackley_iterated_local_search(2, 42, 3)),
+            Box::new(new_ackley_iterated_local_search_with_acceptance_criterion(
+                2,
+                42,
+                3,
+                RecordToRecordTravelAcceptanceCriterion::new(Deviation::Relative(0.1)),
+            )),
+        ];
+        for solver in solvers.iter_mut() {
+            let best = solver.solve().unwrap();
+            assert!(solver.is_finished());
+            assert_eq!(best, solver.get_best_solution());
+        }
+    }
+}
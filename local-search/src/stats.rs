@@ -0,0 +1,78 @@
+//! stats counts what a solver did, e.g. how many proposed moves were accepted, which helps to tune move and
+//! perturbation weights. See LocalSearch::get_stats, IteratedLocalSearch::execute_with_stats and
+//! AdaptiveLargeNeighborhoodSearch::get_stats.
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::local_search::Score;
+
+/// BestScoreSample is one point of the best score timeline, taken whenever a solver found a new best solution.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct BestScoreSample<_Score>
+where
+    _Score: Score,
+{
+    pub iteration: u64,
+    pub elapsed: Duration,
+    pub best_score: _Score,
+}
+
+/// OperatorWeight is the current selection weight of an operator and how often it was selected so far.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+pub struct OperatorWeight {
+    pub name: String,
+    pub weight: f64,
+    pub uses: u64,
+}
+
+#[derive(Clone, Debug, Derivative, PartialEq, Serialize)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[derivative(Default(bound = ""))]
+pub struct SolverStats<_Score>
+where
+    _Score: Score,
+{
+    /// Neighbors that local search scored.
+    pub proposed_moves: u64,
+
+    /// Steps that local search made to a neighbor.
+    pub accepted_moves: u64,
+
+    /// Accepted moves that improved on the solution they started from.
+    pub improving_moves: u64,
+
+    pub perturbations: u64,
+    pub restarts: u64,
+
+    /// Iterations here are IteratedLocalSearch rounds.
+    pub best_score_timeline: Vec<BestScoreSample<_Score>>,
+
+    /// Weights of adaptive operators, see AdaptiveLargeNeighborhoodSearch. Empty for other solvers.
+    pub operator_weights: Vec<OperatorWeight>,
+}
+
+impl<_Score> SolverStats<_Score>
+where
+    _Score: Score,
+{
+    /// Fraction of proposed moves that were accepted, or 0 if nothing was proposed.
+    pub fn get_acceptance_rate(&self) -> f64 {
+        match self.proposed_moves {
+            0 => 0.0,
+            proposed_moves => self.accepted_moves as f64 / proposed_moves as f64,
+        }
+    }
+
+    /// Fraction of accepted moves that improved, or 0 if nothing was accepted.
+    pub fn get_improvement_rate(&self) -> f64 {
+        match self.accepted_moves {
+            0 => 0.0,
+            accepted_moves => self.improving_moves as f64 / accepted_moves as f64,
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! step_size adapts the step size of moves on continuous problems, e.g. Ackley, to the search instead of sampling it
+//! from a fixed range. OneFifthRule follows Rechenberg's 1/5th success rule, see [1]: if more than a fifth of the
+//! recent steps improved the solution the steps are too timid and the step size grows, if fewer than a fifth did they
+//! overshoot and it shrinks.
+//!
+//! A move proposer reads the step size with OneFifthRule::get_step_size, and the observer of get_observer, added to the
+//! LocalSearch, records whether every step found a new best solution. Clones of a OneFifthRule share their state.
+//!
+//! [1] Rechenberg, Ingo. "Evolutionsstrategie: Optimierung technischer Systeme nach Prinzipien der biologischen
+//! Evolution." Frommann-Holzboog (1973).
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::local_search::{Score, ScoredSolution, Solution};
+use crate::observer::Observer;
+
+#[derive(Debug)]
+struct OneFifthRuleState {
+    step_size: f64,
+    successes: u64,
+    steps: u64,
+}
+
+/// OneFifthRule multiplies the step size by factor after every period steps of which more than a fifth were
+/// successes, and divides it by factor if fewer than a fifth were. The step size stays between min and max.
+#[derive(Clone, Debug)]
+pub struct OneFifthRule {
+    state: Arc<Mutex<OneFifthRuleState>>,
+    min: f64,
+    max: f64,
+    period: u64,
+    factor: f64,
+}
+
+impl OneFifthRule {
+    /// Start at initial_step_size, with a period of 10 steps and a factor of 1.22, see [1] of the module documentation.
+    pub fn new(initial_step_size: f64, min: f64, max: f64) -> Self {
+        assert!(min > 0.0, "min must be positive");
+        assert!(min <= max, "min must not be greater than max");
+        Self {
+            state: Arc::new(Mutex::new(OneFifthRuleState {
+                step_size: initial_step_size.clamp(min, max),
+                successes: 0,
+                steps: 0,
+            })),
+            min,
+            max,
+            period: 10,
+            factor: 1.22,
+        }
+    }
+
+    /// Number of steps between adaptations. Defaults to 10.
+    pub fn with_period(mut self, period: u64) -> Self {
+        assert!(period > 0, "period must be positive");
+        self.period = period;
+        self
+    }
+
+    /// How much the step size grows or shrinks per adaptation. Defaults to 1.22.
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        assert!(factor > 1.0, "factor must be greater than 1");
+        self.factor = factor;
+        self
+    }
+
+    pub fn get_step_size(&self) -> f64 {
+        self.state.lock().unwrap().step_size
+    }
+
+    /// Record whether a step improved the solution, and adapt the step size at the end of every period.
+    pub fn record(&self, is_success: bool) {
+        let mut state = self.state.lock().unwrap();
+        state.steps += 1;
+        if is_success {
+            state.successes += 1;
+        }
+        if state.steps < self.period {
+            return;
+        }
+        let success_rate = state.successes as f64 / state.steps as f64;
+        if success_rate > 0.2 {
+            state.step_size *= self.factor;
+        } else if success_rate < 0.2 {
+            state.step_size /= self.factor;
+        }
+        state.step_size = state.step_size.clamp(self.min, self.max);
+        state.successes = 0;
+        state.steps = 0;
+    }
+
+    /// Get an observer that records a success for every step of a LocalSearch that finds a new best solution, and a
+    /// failure for every other step. Steps to worse neighbors, e.g. with StepAcceptance::BestNeighbor, are failures,
+    /// so that walking back and forth on a plateau doesn't count as progress.
+    pub fn get_observer<_Solution, _Score>(&self) -> OneFifthRuleObserver<_Solution, _Score>
+    where
+        _Solution: Solution,
+        _Score: Score,
+    {
+        OneFifthRuleObserver {
+            one_fifth_rule: self.clone(),
+            is_new_best: false,
+            phantom_solution: PhantomData,
+            phantom_score: PhantomData,
+        }
+    }
+}
+
+/// OneFifthRuleObserver feeds the steps of a LocalSearch to a OneFifthRule, see OneFifthRule::get_observer.
+pub struct OneFifthRuleObserver<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    one_fifth_rule: OneFifthRule,
+    is_new_best: bool,
+    phantom_solution: PhantomData<_Solution>,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_Solution, _Score> Observer for OneFifthRuleObserver<_Solution, _Score>
+where
+    _Solution: Solution,
+    _Score: Score,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn on_iteration(&mut self, _iteration: u64, _current: &ScoredSolution<_Solution, _Score>) {
+        self.one_fifth_rule.record(self.is_new_best);
+        self.is_new_best = false;
+    }
+
+    fn on_new_best(&mut self, _iteration: u64, _best: &ScoredSolution<_Solution, _Score>) {
+        self.is_new_best = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::step_size::OneFifthRule;
+
+    #[test]
+    fn adapts_to_the_success_rate() {
+        let rule = OneFifthRule::new(1.0, 0.1, 2.0).with_period(5).with_factor(2.0);
+        let shared = rule.clone();
+        for is_success in [true, true, false, false, false] {
+            rule.record(is_success);
+        }
+        assert_eq!(2.0, shared.get_step_size());
+        for is_success in [true, false, false, false, false] {
+            rule.record(is_success);
+        }
+        assert_eq!(2.0, shared.get_step_size());
+        for _ in 0..10 {
+            rule.record(false);
+        }
+        assert_eq!(0.5, shared.get_step_size());
+        for _ in 0..15 {
+            rule.record(false);
+        }
+        assert_eq!(0.1, shared.get_step_size());
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleyScore, AckleySolution,
+        AckleySolutionScoreCalculator,
+    };
+    use crate::local_search::{InitialSolutionGenerator, LocalSearchBuilder, SolutionScoreCalculator};
+    use crate::step_size::OneFifthRule;
+
+    #[test]
+    fn ackley_step_size_shrinks_in_a_local_minimum() {
+        let rule = OneFifthRule::new(0.5, 1e-6, 1.0);
+        let mut local_search = LocalSearchBuilder::new(
+            AckleyMoveProposer::new(2, 1e-3, 0.5).with_one_fifth_rule(rule.clone()),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(42),
+        )
+        .max_iterations(1_000)
+        .window_size(4)
+        // Forget seen solutions right away, so that the search stays in the local minimum instead of running out of
+        // neighbors that are not tabu.
+        .all_solution_iteration_expiry(0)
+        .build();
+        local_search.add_observer(Box::new(rule.get_observer::<AckleySolution, AckleyScore>()));
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut rng));
+        let best = local_search.execute(start.solution.clone(), 50);
+        assert!(best.score < start.score);
+        assert!(rule.get_step_size() < 0.5);
+    }
+}
@@ -0,0 +1,247 @@
+//! strategic_oscillation searches on both sides of the feasibility boundary, see [1]. Instead of first removing all
+//! hard constraint violations and then only optimizing the soft score among feasible solutions, which gets stuck when
+//! feasible solutions are few and far apart, e.g. on tightly constrained rosters, hard violations are relaxed into a
+//! penalty on the soft score. The penalty grows while the search is infeasible and shrinks while it is feasible, so
+//! that the search keeps crossing the boundary and reaches feasible regions it could not have walked to directly.
+//!
+//! [1] Glover, Fred and Jin-Kao Hao. "The case for strategic oscillation." Annals of Operations Research 183.1
+//! (2011): 163-173.
+use std::time::Instant;
+
+use crate::local_search::{MoveProposer, ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+use crate::termination::{Termination, TerminationState};
+
+/// PenaltyOscillation is how the penalty multiplier of hard violations moves. It starts at initial, and after every
+/// step is multiplied by factor if the current solution is infeasible and divided by factor if it is feasible, staying
+/// between min and max.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PenaltyOscillation {
+    pub initial: f64,
+    pub min: f64,
+    pub max: f64,
+    pub factor: f64,
+}
+
+impl Default for PenaltyOscillation {
+    fn default() -> Self {
+        Self {
+            initial: 1.0,
+            min: 1e-3,
+            max: 1e3,
+            factor: 1.1,
+        }
+    }
+}
+
+/// StrategicOscillation moves to the neighbor with the lowest relaxed energy, soft score + penalty * hard score, among
+/// at most window_size neighbors of the current solution, even if it is worse, and keeps the best solution by its real
+/// score. The hard score must be non-negative, e.g. a count of violations.
+pub struct StrategicOscillation<_R, _Solution, _Score, _SSC, _MP>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+{
+    solution_score_calculator: _SSC,
+    move_proposer: _MP,
+    window_size: usize,
+    oscillation: PenaltyOscillation,
+    rng: _R,
+    penalty: f64,
+    boundary_crossings: u64,
+}
+
+impl<_R, _Solution, _Score, _SSC, _MP> StrategicOscillation<_R, _Solution, _Score, _SSC, _MP>
+where
+    _R: rand::Rng,
+    _Solution: Solution,
+    _Score: HardSoftScoring,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+{
+    pub fn new(
+        solution_score_calculator: _SSC,
+        move_proposer: _MP,
+        window_size: usize,
+        oscillation: PenaltyOscillation,
+        rng: _R,
+    ) -> Self {
+        assert!(window_size > 0, "window_size must be positive");
+        assert!(oscillation.min > 0.0, "min penalty must be positive");
+        assert!(
+            oscillation.min <= oscillation.initial && oscillation.initial <= oscillation.max,
+            "initial penalty must be between min and max"
+        );
+        assert!(oscillation.factor > 1.0, "factor must be greater than 1");
+        Self {
+            solution_score_calculator,
+            move_proposer,
+            window_size,
+            penalty: oscillation.initial,
+            oscillation,
+            rng,
+            boundary_crossings: 0,
+        }
+    }
+
+    /// The current penalty multiplier of hard violations.
+    pub fn get_penalty(&self) -> f64 {
+        self.penalty
+    }
+
+    /// How often the current solution went from feasible to infeasible or back so far.
+    pub fn get_boundary_crossings(&self) -> u64 {
+        self.boundary_crossings
+    }
+
+    /// Oscillate from start until termination says to stop, see the termination module, until a best possible
+    /// solution is found, or until a solution has no neighbors, and return the best solution. Iterations are steps.
+    pub fn execute<_T>(&mut self, start: _Solution, termination: &_T) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let _span = tracing::trace_span!("strategic_oscillation").entered();
+        let started = Instant::now();
+        let mut current = self.solution_score_calculator.get_scored_solution(start);
+        let mut best = current.clone();
+        let mut no_improvement_for = 0;
+        for iteration in 0.. {
+            let state = TerminationState {
+                iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&best.score),
+                iterations_without_improvement: no_improvement_for,
+            };
+            if best.score.is_best() || termination.is_terminated(&state) {
+                break;
+            }
+            let penalty = self.penalty;
+            let neighborhood_best = self
+                .move_proposer
+                .iter_local_moves(&current.solution, &mut self.rng)
+                .take(self.window_size)
+                .map(|solution| self.solution_score_calculator.get_scored_solution(solution))
+                .min_by(|first, second| {
+                    get_relaxed_energy(&first.score, penalty)
+                        .total_cmp(&get_relaxed_energy(&second.score, penalty))
+                });
+            let neighborhood_best = match neighborhood_best {
+                Some(neighborhood_best) => neighborhood_best,
+                None => break,
+            };
+            let was_feasible = current.score.get_hard_score() == 0.0;
+            current = neighborhood_best;
+            let is_feasible = current.score.get_hard_score() == 0.0;
+            if was_feasible != is_feasible {
+                self.boundary_crossings += 1;
+            }
+            self.penalty = match is_feasible {
+                true => self.penalty / self.oscillation.factor,
+                false => self.penalty * self.oscillation.factor,
+            }
+            .clamp(self.oscillation.min, self.oscillation.max);
+            if current.score < best.score {
+                tracing::trace!(score = ?current.score, penalty = self.penalty, "improved");
+                best = current.clone();
+                no_improvement_for = 0;
+            } else {
+                no_improvement_for += 1;
+            }
+        }
+        best
+    }
+}
+
+fn get_relaxed_energy<_Score: HardSoftScoring>(score: &_Score, penalty: f64) -> f64 {
+    score.get_soft_score() + penalty * score.get_hard_score()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::local_search::{MoveProposer, ScoredSolution, Solution, SolutionScoreCalculator};
+    use crate::score::IntegerHardSoft;
+    use crate::strategic_oscillation::{PenaltyOscillation, StrategicOscillation};
+    use crate::termination::MaxIterations;
+
+    const WEIGHTS: [i64; 12] = [23, 31, 29, 44, 53, 38, 63, 85, 89, 82, 41, 37];
+    const VALUES: [i64; 12] = [92, 57, 49, 68, 60, 43, 67, 84, 87, 72, 45, 40];
+    const CAPACITY: i64 = 165;
+
+    /// Which items are in a knapsack.
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Knapsack(Vec<bool>);
+    impl Solution for Knapsack {}
+
+    /// Hard score is the weight over capacity, soft score is the value left behind.
+    struct KnapsackScoreCalculator;
+    impl SolutionScoreCalculator for KnapsackScoreCalculator {
+        type _Solution = Knapsack;
+        type _Score = IntegerHardSoft;
+
+        fn get_scored_solution(&self, solution: Knapsack) -> ScoredSolution<Knapsack, IntegerHardSoft> {
+            let packed = || {
+                WEIGHTS
+                    .iter()
+                    .zip(VALUES.iter())
+                    .zip(solution.0.iter())
+                    .filter(|(_, packed)| **packed)
+            };
+            let weight: i64 = packed().map(|((weight, _), _)| weight).sum();
+            let value: i64 = packed().map(|((_, value), _)| value).sum();
+            ScoredSolution::new(
+                solution,
+                IntegerHardSoft::new((weight - CAPACITY).max(0), VALUES.iter().sum::<i64>() - value),
+            )
+        }
+    }
+
+    /// Adds or removes one item.
+    struct FlipMoveProposer;
+    impl MoveProposer for FlipMoveProposer {
+        type R = ChaCha20Rng;
+        type Solution = Knapsack;
+
+        fn iter_local_moves(
+            &self,
+            start: &Knapsack,
+            _rng: &mut ChaCha20Rng,
+        ) -> Box<dyn Iterator<Item = Knapsack>> {
+            let start = start.clone();
+            Box::new((0..start.0.len()).map(move |item| {
+                let mut flipped = start.clone();
+                flipped.0[item] = !flipped.0[item];
+                flipped
+            }))
+        }
+    }
+
+    #[test]
+    fn crosses_the_feasibility_boundary_and_returns_a_feasible_best() {
+        let mut strategic_oscillation = StrategicOscillation::new(
+            KnapsackScoreCalculator,
+            FlipMoveProposer,
+            WEIGHTS.len(),
+            PenaltyOscillation::default(),
+            ChaCha20Rng::seed_from_u64(42),
+        );
+        let best = strategic_oscillation.execute(Knapsack(vec![false; WEIGHTS.len()]), &MaxIterations(500));
+        let optimal = (0..1_u32 << WEIGHTS.len())
+            .map(|bits| {
+                KnapsackScoreCalculator.get_scored_solution(Knapsack(
+                    (0..WEIGHTS.len()).map(|item| bits >> item & 1 == 1).collect(),
+                ))
+            })
+            .min()
+            .unwrap();
+        println!("best: {:?}, optimal: {:?}", best.score, optimal.score);
+        assert!(best.score.is_feasible());
+        assert!(strategic_oscillation.get_boundary_crossings() >= 2);
+        assert_eq!(optimal.score, best.score);
+    }
+}
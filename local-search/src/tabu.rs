@@ -0,0 +1,164 @@
+//! tabu forbids moves by their attributes, e.g. "queen in column 3 moved", rather than by the exact solutions they
+//! lead to. Remembering a few attributes per move is much cheaper than keeping every visited solution, and it also
+//! stops the search from walking back into a neighbourhood of a recent solution, not only onto the solution itself.
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::assignment::Assignment;
+use crate::local_search::Solution;
+
+/// MoveAttributes describes a move, the step from one solution to a neighbouring one, by a few attributes. Two moves
+/// that share an attribute are considered similar.
+pub trait MoveAttributes {
+    type _Solution: Solution;
+    type Attribute: Clone + Eq + Hash + std::fmt::Debug + Send;
+
+    fn get_move_attributes(&self, from: &Self::_Solution, to: &Self::_Solution) -> Vec<Self::Attribute>;
+}
+
+/// MoveTabu decides which moves LocalSearch must not make, see LocalSearch::set_move_tabu.
+pub trait MoveTabu {
+    type _Solution: Solution;
+
+    fn is_move_tabu(&self, from: &Self::_Solution, to: &Self::_Solution) -> bool;
+
+    /// Called whenever LocalSearch moves from one solution to another.
+    fn record_move(&mut self, from: &Self::_Solution, to: &Self::_Solution);
+}
+
+/// AttributeTabuList makes a move tabu if it shares an attribute with one of the moves made in the last tenure
+/// iterations.
+pub struct AttributeTabuList<_MA>
+where
+    _MA: MoveAttributes,
+{
+    move_attributes: _MA,
+    tenure: u64,
+    iteration: u64,
+    expiries: HashMap<_MA::Attribute, u64>,
+}
+
+impl<_MA> AttributeTabuList<_MA>
+where
+    _MA: MoveAttributes,
+{
+    pub fn new(move_attributes: _MA, tenure: u64) -> Self {
+        Self {
+            move_attributes,
+            tenure,
+            iteration: 0,
+            expiries: HashMap::new(),
+        }
+    }
+
+    pub fn is_attribute_tabu(&self, attribute: &_MA::Attribute) -> bool {
+        self.expiries
+            .get(attribute)
+            .is_some_and(|expiry| *expiry > self.iteration)
+    }
+
+    pub fn get_tabu_attribute_count(&self) -> usize {
+        self.expiries.len()
+    }
+}
+
+impl<_MA> MoveTabu for AttributeTabuList<_MA>
+where
+    _MA: MoveAttributes,
+{
+    type _Solution = _MA::_Solution;
+
+    fn is_move_tabu(&self, from: &Self::_Solution, to: &Self::_Solution) -> bool {
+        self.move_attributes
+            .get_move_attributes(from, to)
+            .iter()
+            .any(|attribute| self.is_attribute_tabu(attribute))
+    }
+
+    fn record_move(&mut self, from: &Self::_Solution, to: &Self::_Solution) {
+        self.iteration += 1;
+        let iteration = self.iteration;
+        self.expiries.retain(|_attribute, expiry| *expiry > iteration);
+        let expiry = iteration + self.tenure;
+        for attribute in self.move_attributes.get_move_attributes(from, to) {
+            self.expiries.insert(attribute, expiry);
+        }
+    }
+}
+
+/// ChangedVariables describes a move between two Assignment solutions by the decision variables whose values differ,
+/// so that with an AttributeTabuList a variable that was just changed stays put for tenure iterations.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct ChangedVariables<_Solution>
+where
+    _Solution: Assignment,
+{
+    phantom_solution: PhantomData<_Solution>,
+}
+
+impl<_Solution> MoveAttributes for ChangedVariables<_Solution>
+where
+    _Solution: Assignment,
+{
+    type _Solution = _Solution;
+    type Attribute = usize;
+
+    fn get_move_attributes(&self, from: &_Solution, to: &_Solution) -> Vec<usize> {
+        (0..from.get_variable_count())
+            .filter(|variable| from.get_value(*variable) != to.get_value(*variable))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assignment::Assignment;
+    use crate::local_search::Solution;
+    use crate::tabu::{AttributeTabuList, ChangedVariables, MoveTabu};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Rows(Vec<u8>);
+    impl Solution for Rows {}
+    impl Assignment for Rows {
+        type Value = u8;
+
+        fn get_variable_count(&self) -> usize {
+            self.0.len()
+        }
+
+        fn get_value(&self, variable: usize) -> u8 {
+            self.0[variable]
+        }
+
+        fn set_value(&mut self, variable: usize, value: u8) {
+            self.0[variable] = value;
+        }
+    }
+
+    #[test]
+    fn changed_variables_stay_tabu_for_tenure_moves() {
+        let mut tabu_list = AttributeTabuList::new(ChangedVariables::default(), 2);
+        let start = Rows(vec![0, 0, 0]);
+        let first = Rows(vec![1, 0, 0]);
+        let second = Rows(vec![1, 1, 0]);
+        let third = Rows(vec![1, 1, 1]);
+
+        tabu_list.record_move(&start, &first);
+        assert!(tabu_list.is_move_tabu(&first, &Rows(vec![2, 0, 0])));
+        assert!(!tabu_list.is_move_tabu(&first, &second));
+
+        tabu_list.record_move(&first, &second);
+        assert!(tabu_list.is_move_tabu(&second, &Rows(vec![2, 1, 0])));
+        assert!(tabu_list.is_move_tabu(&second, &Rows(vec![1, 2, 0])));
+
+        tabu_list.record_move(&second, &third);
+        assert!(
+            !tabu_list.is_move_tabu(&third, &Rows(vec![2, 1, 1])),
+            "expected column 0 to be allowed again after 2 moves"
+        );
+        assert!(tabu_list.is_move_tabu(&third, &Rows(vec![1, 2, 1])));
+        assert_eq!(2, tabu_list.get_tabu_attribute_count());
+    }
+}
@@ -0,0 +1,343 @@
+//! termination decides when a solver stops. Conditions like MaxIterations or TimeBudget are small values that combine
+//! with And and Or, e.g. `Or(MaxIterations(1_000), TimeBudget(Duration::from_secs(30)))`, and are handed to
+//! LocalSearch::execute_until or IteratedLocalSearch::execute.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::local_search::Score;
+use crate::score::HardSoftScoring;
+
+/// TerminationState is what a solver knows about its progress when it asks a Termination whether to stop. For
+/// LocalSearch an iteration is a step to a neighbor, for IteratedLocalSearch it is a round.
+pub struct TerminationState<'a, _Score>
+where
+    _Score: Score,
+{
+    pub iteration: u64,
+    pub elapsed: Duration,
+    pub best_score: Option<&'a _Score>,
+    pub iterations_without_improvement: u64,
+}
+
+pub trait Termination<_Score>
+where
+    _Score: Score,
+{
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool;
+}
+
+/// Terminate after this many iterations.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaxIterations(pub u64);
+
+impl<_Score: Score> Termination<_Score> for MaxIterations {
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        state.iteration >= self.0
+    }
+}
+
+/// Terminate once this much time has passed since the solver started.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimeBudget(pub Duration);
+
+impl<_Score: Score> Termination<_Score> for TimeBudget {
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        state.elapsed >= self.0
+    }
+}
+
+/// Terminate once the best score is at least as good as this one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScoreReached<_Score: Score>(pub _Score);
+
+impl<_Score: Score> Termination<_Score> for ScoreReached<_Score> {
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        state.best_score.is_some_and(|best_score| *best_score <= self.0)
+    }
+}
+
+/// ScoreThreshold is a user-defined "good enough" score. Both solvers stop as soon as a solution reaches it, see
+/// LocalSearch::set_score_threshold and IteratedLocalSearch::set_score_threshold, and it also works as a Termination
+/// on the best score.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct ScoreThreshold<_Score>
+where
+    _Score: Score,
+{
+    is_reached: Arc<dyn Fn(&_Score) -> bool + Send + Sync>,
+}
+
+impl<_Score> ScoreThreshold<_Score>
+where
+    _Score: Score,
+{
+    /// The threshold is reached by every score for which is_reached returns true.
+    pub fn new<F>(is_reached: F) -> Self
+    where
+        F: Fn(&_Score) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            is_reached: Arc::new(is_reached),
+        }
+    }
+
+    /// The threshold is reached by every score that is at least as good as threshold.
+    pub fn at_most(threshold: _Score) -> Self
+    where
+        _Score: Sync + 'static,
+    {
+        Self::new(move |score| *score <= threshold)
+    }
+
+    pub fn is_reached(&self, score: &_Score) -> bool {
+        (self.is_reached)(score)
+    }
+}
+
+impl<_Score> ScoreThreshold<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// The threshold is reached by every feasible score, i.e. one with a hard score of zero, whatever the soft score.
+    pub fn feasible() -> Self {
+        Self::new(|score: &_Score| score.get_hard_score() <= 0.0)
+    }
+}
+
+impl<_Score: Score> Termination<_Score> for ScoreThreshold<_Score> {
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        state
+            .best_score
+            .is_some_and(|best_score| self.is_reached(best_score))
+    }
+}
+
+/// Terminate once this many iterations in a row did not improve the score.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stagnation(pub u64);
+
+impl<_Score: Score> Termination<_Score> for Stagnation {
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        state.iterations_without_improvement >= self.0
+    }
+}
+
+type IsSignificant<_Score> = Arc<dyn Fn(&_Score, &_Score) -> bool + Send + Sync>;
+
+/// MinImprovement says which improvements of the best score are big enough to count as progress. With float scores
+/// tiny oscillations keep improving the best score by a rounding error, so that Stagnation never triggers; with a
+/// MinImprovement on the solver they count as iterations without improvement instead, so that a plateau stops the
+/// search, or for the local search of IteratedLocalSearch, moves on to the next perturbation. Improvements are
+/// measured against the best score when progress was last counted, so that many small improvements still add up.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct MinImprovement<_Score>
+where
+    _Score: Score,
+{
+    is_significant: IsSignificant<_Score>,
+}
+
+impl<_Score> MinImprovement<_Score>
+where
+    _Score: Score,
+{
+    /// is_significant gets a better score and the score it improves on, and returns whether the improvement counts.
+    pub fn new<F>(is_significant: F) -> Self
+    where
+        F: Fn(&_Score, &_Score) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            is_significant: Arc::new(is_significant),
+        }
+    }
+
+    pub fn is_significant(&self, score: &_Score, previous_score: &_Score) -> bool {
+        (self.is_significant)(score, previous_score)
+    }
+}
+
+impl<_Score> MinImprovement<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// Improvements count if they lower the energy, see HardSoftScoring::get_energy, by at least epsilon.
+    pub fn epsilon(epsilon: f64) -> Self {
+        assert!(epsilon >= 0.0, "epsilon must not be negative");
+        Self::new(move |score: &_Score, previous_score: &_Score| {
+            previous_score.get_energy() - score.get_energy() >= epsilon
+        })
+    }
+}
+
+/// CancellationToken lets another thread, e.g. a GUI or a web request handler, abort a running solve. Clones share the
+/// same flag. Both solvers check it every iteration once it is set with set_cancellation_token, and as a Termination
+/// it terminates once cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl<_Score: Score> Termination<_Score> for CancellationToken {
+    fn is_terminated(&self, _state: &TerminationState<_Score>) -> bool {
+        self.is_cancelled()
+    }
+}
+
+/// Terminate once both conditions would.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct And<_A, _B>(pub _A, pub _B);
+
+impl<_Score, _A, _B> Termination<_Score> for And<_A, _B>
+where
+    _Score: Score,
+    _A: Termination<_Score>,
+    _B: Termination<_Score>,
+{
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        self.0.is_terminated(state) && self.1.is_terminated(state)
+    }
+}
+
+/// Terminate as soon as either condition would.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Or<_A, _B>(pub _A, pub _B);
+
+impl<_Score, _A, _B> Termination<_Score> for Or<_A, _B>
+where
+    _Score: Score,
+    _A: Termination<_Score>,
+    _B: Termination<_Score>,
+{
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        self.0.is_terminated(state) || self.1.is_terminated(state)
+    }
+}
+
+impl<_Score, _T> Termination<_Score> for Box<_T>
+where
+    _Score: Score,
+    _T: Termination<_Score> + ?Sized,
+{
+    fn is_terminated(&self, state: &TerminationState<_Score>) -> bool {
+        (**self).is_terminated(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::local_search::Score;
+    use crate::score::HardSoftScoring;
+    use crate::termination::{
+        And, CancellationToken, MaxIterations, MinImprovement, Or, ScoreReached, ScoreThreshold, Stagnation,
+        Termination, TerminationState, TimeBudget,
+    };
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct Cost(u64);
+    impl Score for Cost {
+        fn is_best(&self) -> bool {
+            self.0 == 0
+        }
+    }
+
+    fn state(iteration: u64, best_score: Option<&Cost>) -> TerminationState<'_, Cost> {
+        TerminationState {
+            iteration,
+            elapsed: Duration::from_secs(iteration),
+            best_score,
+            iterations_without_improvement: iteration / 2,
+        }
+    }
+
+    #[test]
+    fn conditions() {
+        assert!(!MaxIterations(10).is_terminated(&state(9, None)));
+        assert!(MaxIterations(10).is_terminated(&state(10, None)));
+        assert!(!TimeBudget(Duration::from_secs(5)).is_terminated(&state(4, None)));
+        assert!(TimeBudget(Duration::from_secs(5)).is_terminated(&state(5, None)));
+        assert!(!ScoreReached(Cost(3)).is_terminated(&state(1, None)));
+        assert!(!ScoreReached(Cost(3)).is_terminated(&state(1, Some(&Cost(4)))));
+        assert!(ScoreReached(Cost(3)).is_terminated(&state(1, Some(&Cost(3)))));
+        assert!(!Stagnation(3).is_terminated(&state(5, None)));
+        assert!(Stagnation(3).is_terminated(&state(6, None)));
+
+        let cancellation_token = CancellationToken::new();
+        assert!(!Termination::<Cost>::is_terminated(
+            &cancellation_token,
+            &state(1, None)
+        ));
+        cancellation_token.clone().cancel();
+        assert!(Termination::<Cost>::is_terminated(
+            &cancellation_token,
+            &state(1, None)
+        ));
+    }
+
+    #[test]
+    fn combinators() {
+        let either = Or(MaxIterations(10), ScoreReached(Cost(0)));
+        assert!(!either.is_terminated(&state(5, Some(&Cost(1)))));
+        assert!(either.is_terminated(&state(5, Some(&Cost(0)))));
+        assert!(either.is_terminated(&state(10, Some(&Cost(1)))));
+
+        let both = And(MaxIterations(10), ScoreReached(Cost(0)));
+        assert!(!both.is_terminated(&state(5, Some(&Cost(0)))));
+        assert!(!both.is_terminated(&state(10, Some(&Cost(1)))));
+        assert!(both.is_terminated(&state(10, Some(&Cost(0)))));
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+    struct HardSoft(u64, u64);
+    impl Score for HardSoft {
+        fn is_best(&self) -> bool {
+            self.0 == 0 && self.1 == 0
+        }
+    }
+    impl HardSoftScoring for HardSoft {
+        fn get_hard_score(&self) -> f64 {
+            self.0 as f64
+        }
+
+        fn get_soft_score(&self) -> f64 {
+            self.1 as f64
+        }
+    }
+
+    #[test]
+    fn min_improvement_epsilon() {
+        let min_improvement = MinImprovement::epsilon(2.0);
+        assert!(min_improvement.is_significant(&HardSoft(0, 3), &HardSoft(0, 5)));
+        assert!(!min_improvement.is_significant(&HardSoft(0, 4), &HardSoft(0, 5)));
+        assert!(min_improvement.is_significant(&HardSoft(0, 5), &HardSoft(1, 5)));
+    }
+
+    #[test]
+    fn score_thresholds() {
+        let at_most = ScoreThreshold::at_most(Cost(3));
+        assert!(!at_most.is_reached(&Cost(4)));
+        assert!(at_most.is_reached(&Cost(3)));
+        assert!(!at_most.is_terminated(&state(1, None)));
+        assert!(at_most.is_terminated(&state(1, Some(&Cost(2)))));
+
+        let feasible = ScoreThreshold::feasible();
+        assert!(!feasible.is_reached(&HardSoft(1, 0)));
+        assert!(feasible.is_reached(&HardSoft(0, 100)));
+    }
+}
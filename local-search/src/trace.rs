@@ -0,0 +1,773 @@
+//! trace records what IteratedLocalSearch does every round, i.e. which local minimum it proposed, how much that
+//! changed the score, and whether the acceptance criterion accepted it, into a newline-delimited JSON file that can be
+//! searched with standard tools and loaded back to replay a run step by step. To keep the file compact only the first
+//! event holds a whole solution: every later one holds the decision variables it changed, see Assignment. A recorder
+//! that is also added as an Observer records restarts, perturbations, local search steps and new bests as well, which
+//! is what the event_log module builds on. This is opt-in because serializing every round is expensive.
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::assignment::{apply_changes, get_changes, Assignment, Changes};
+use crate::error::SolverError;
+use crate::local_search::{Score, ScoredSolution, Solution};
+use crate::observer::Observer;
+use crate::score::HardSoftScoring;
+
+/// ScoreDelta is how much the hard and soft scores of a proposal differ from those of the current solution. Negative
+/// values are improvements.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreDelta {
+    pub hard: f64,
+    pub soft: f64,
+}
+
+impl ScoreDelta {
+    pub fn new<_Score: HardSoftScoring>(from: &_Score, to: &_Score) -> Self {
+        Self {
+            hard: to.get_hard_score() - from.get_hard_score(),
+            soft: to.get_soft_score() - from.get_soft_score(),
+        }
+    }
+}
+
+/// TraceEvent is one line of a trace. iteration is always the IteratedLocalSearch round the event happened in, or 0
+/// before the first round. Every event after Start changes one of the solutions that TraceReplay keeps track of, see
+/// TraceReplay::step.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "event",
+    rename_all = "snake_case",
+    bound(
+        serialize = "_Solution: Serialize, _Solution::Value: Serialize, _Score: Serialize",
+        deserialize = "_Solution: Deserialize<'de>, _Solution::Value: Deserialize<'de>, _Score: Deserialize<'de>"
+    )
+)]
+pub enum TraceEvent<_Solution, _Score>
+where
+    _Solution: Assignment,
+{
+    /// The current solution before the first recorded event.
+    Start {
+        iteration: u64,
+        solution: _Solution,
+        score: _Score,
+    },
+
+    /// The local minimum that local search proposed, as the variables it changed in the current solution and its
+    /// score, how much it changed the score of the current solution of IteratedLocalSearch, and whether the
+    /// acceptance criterion moved to it.
+    Round {
+        iteration: u64,
+        changes: Changes<_Solution>,
+        score: _Score,
+        score_delta: ScoreDelta,
+        accepted: bool,
+    },
+
+    /// The current solution was replaced by a new initial solution, which differs from it by changes.
+    Restart {
+        iteration: u64,
+        changes: Changes<_Solution>,
+        score: _Score,
+    },
+
+    /// The current solution was perturbed by changes into the starting point of the local search.
+    Perturbation {
+        iteration: u64,
+        changes: Changes<_Solution>,
+    },
+
+    /// The local search moved to a neighbor by changes. step counts from 0 within every local search.
+    Step {
+        iteration: u64,
+        step: u64,
+        changes: Changes<_Solution>,
+        score: _Score,
+    },
+
+    /// The local search found a solution that is better than every solution before it. changes are relative to the
+    /// solution the local search is at, or to the current solution before the first perturbation.
+    NewBest {
+        iteration: u64,
+        changes: Changes<_Solution>,
+        score: _Score,
+    },
+
+    /// The acceptance criterion chose the current solution for the next round, which differs from the last current
+    /// solution by changes.
+    Accepted {
+        iteration: u64,
+        changes: Changes<_Solution>,
+        score: _Score,
+    },
+}
+
+impl<_Solution, _Score> TraceEvent<_Solution, _Score>
+where
+    _Solution: Assignment,
+{
+    pub fn get_iteration(&self) -> u64 {
+        match self {
+            TraceEvent::Start { iteration, .. }
+            | TraceEvent::Round { iteration, .. }
+            | TraceEvent::Restart { iteration, .. }
+            | TraceEvent::Perturbation { iteration, .. }
+            | TraceEvent::Step { iteration, .. }
+            | TraceEvent::NewBest { iteration, .. }
+            | TraceEvent::Accepted { iteration, .. } => *iteration,
+        }
+    }
+}
+
+const MISSING_START: &str = "the trace must begin with a start event";
+
+/// Positions are the solutions that the events of a trace change: the current solution of IteratedLocalSearch, and
+/// the solution that its local search is at. JsonLinesTraceRecorder diffs against them and TraceReplay applies to
+/// them, with the same apply, so that both always agree.
+struct Positions<_Solution> {
+    current: Option<_Solution>,
+    local_search_position: Option<_Solution>,
+}
+
+impl<_Solution> Default for Positions<_Solution> {
+    fn default() -> Self {
+        Self {
+            current: None,
+            local_search_position: None,
+        }
+    }
+}
+
+impl<_Solution: Assignment> Positions<_Solution> {
+    /// The solution that the changes of Step and NewBest events are relative to.
+    fn get_local_search_base(&self) -> Option<&_Solution> {
+        self.local_search_position.as_ref().or(self.current.as_ref())
+    }
+
+    /// Apply the changes of event and return the solution it describes, e.g. the proposal of a Round. Panics if
+    /// there was no Start event before a later one.
+    fn apply<_Score>(&mut self, event: &TraceEvent<_Solution, _Score>) -> _Solution {
+        match event {
+            TraceEvent::Start { solution, .. } => {
+                self.current = Some(solution.clone());
+                self.local_search_position = None;
+                solution.clone()
+            }
+            TraceEvent::Round {
+                changes, accepted, ..
+            } => {
+                let proposed = apply_changes(self.current.as_ref().expect(MISSING_START), changes);
+                if *accepted {
+                    self.current = Some(proposed.clone());
+                }
+                proposed
+            }
+            TraceEvent::Restart { changes, .. } | TraceEvent::Accepted { changes, .. } => {
+                let current = apply_changes(self.current.as_ref().expect(MISSING_START), changes);
+                self.current = Some(current.clone());
+                current
+            }
+            TraceEvent::Perturbation { changes, .. } => {
+                let perturbed = apply_changes(self.current.as_ref().expect(MISSING_START), changes);
+                self.local_search_position = Some(perturbed.clone());
+                perturbed
+            }
+            TraceEvent::Step { changes, .. } => {
+                let step = apply_changes(self.get_local_search_base().expect(MISSING_START), changes);
+                self.local_search_position = Some(step.clone());
+                step
+            }
+            TraceEvent::NewBest { changes, .. } => {
+                apply_changes(self.get_local_search_base().expect(MISSING_START), changes)
+            }
+        }
+    }
+}
+
+/// TraceSink is called by IteratedLocalSearch once per round with the current solution at the start of the round,
+/// the local minimum that local search proposed, and whether the acceptance criterion moved to it. Implementations
+/// decide which rounds to keep and where to put them.
+pub trait TraceSink {
+    type _Solution: Solution;
+    type _Score: Score;
+
+    fn record(
+        &mut self,
+        iteration: u64,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        proposed: &ScoredSolution<Self::_Solution, Self::_Score>,
+        accepted: bool,
+    ) -> std::io::Result<()>;
+}
+
+/// Share one trace sink, e.g. between the trace sink and the observers of an IteratedLocalSearch, by setting clones
+/// of an Arc<Mutex<_>>.
+impl<_T> TraceSink for Arc<Mutex<_T>>
+where
+    _T: TraceSink,
+{
+    type _Solution = _T::_Solution;
+    type _Score = _T::_Score;
+
+    fn record(
+        &mut self,
+        iteration: u64,
+        current: &ScoredSolution<Self::_Solution, Self::_Score>,
+        proposed: &ScoredSolution<Self::_Solution, Self::_Score>,
+        accepted: bool,
+    ) -> std::io::Result<()> {
+        self.lock()
+            .unwrap()
+            .record(iteration, current, proposed, accepted)
+    }
+}
+
+/// JsonLinesTraceRecorder writes one JSON object per line for the events of every sample_every-th round. Lines are
+/// buffered by the writer and only flushed by finish, or when the recorder is dropped. As a TraceSink it records
+/// every Round. Added as an Observer to the same IteratedLocalSearch, before the first round, it also records the
+/// Start, every Restart, Perturbation, NewBest and Accepted, and added to its LocalSearch with observe_steps every
+/// Step. Share it between all of them as an Arc<Mutex<JsonLinesTraceRecorder>>, see the event_log module.
+pub struct JsonLinesTraceRecorder<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    writer: _W,
+    sample_every: u64,
+    iteration: u64,
+    positions: Positions<_Solution>,
+    error: Option<std::io::Error>,
+    phantom_score: PhantomData<_Score>,
+}
+
+impl<_W, _Solution, _Score> JsonLinesTraceRecorder<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    /// Record every sample_every-th round to writer, or fail if sample_every is 0. A sample_every of 1 records every
+    /// round, see every_round.
+    pub fn new(writer: _W, sample_every: u64) -> Result<Self, SolverError> {
+        if sample_every == 0 {
+            return Err(SolverError::InvalidConfiguration(
+                "sample_every must be positive".to_string(),
+            ));
+        }
+        Ok(Self::with_sample_every(writer, sample_every))
+    }
+
+    /// Record every round to writer.
+    pub fn every_round(writer: _W) -> Self {
+        Self::with_sample_every(writer, 1)
+    }
+
+    fn with_sample_every(writer: _W, sample_every: u64) -> Self {
+        Self {
+            writer,
+            sample_every,
+            iteration: 0,
+            positions: Positions::default(),
+            error: None,
+            phantom_score: PhantomData,
+        }
+    }
+
+    /// Get an observer for a LocalSearch that records its steps into recorder.
+    pub fn observe_steps(recorder: Arc<Mutex<Self>>) -> StepObserver<_W, _Solution, _Score> {
+        StepObserver(recorder)
+    }
+
+    pub fn get_ref(&self) -> &_W {
+        &self.writer
+    }
+
+    /// Flush the lines written so far, so that errors surface here instead of being ignored when the recorder is
+    /// dropped. Also returns the first error that happened while observing, as observers can't return one.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()
+    }
+
+    fn is_sampled(&self) -> bool {
+        self.iteration % self.sample_every == 0
+    }
+
+    fn write_event(&mut self, event: TraceEvent<_Solution, _Score>) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &event)?;
+        self.writer.write_all(b"\n")?;
+        self.positions.apply(&event);
+        Ok(())
+    }
+
+    /// Get the current solution to diff against. If the recorder missed the start, e.g. because it was added after
+    /// the first round, record solution as the start instead, so that the trace can still be replayed from there.
+    fn get_current(&mut self, solution: &ScoredSolution<_Solution, _Score>) -> std::io::Result<&_Solution> {
+        if self.positions.current.is_none() {
+            self.write_event(TraceEvent::Start {
+                iteration: self.iteration,
+                solution: solution.solution.clone(),
+                score: solution.score.clone(),
+            })?;
+        }
+        Ok(self.positions.current.as_ref().unwrap())
+    }
+
+    /// Like get_current, but get the solution that Step and NewBest events are relative to.
+    fn get_local_search_base(
+        &mut self,
+        solution: &ScoredSolution<_Solution, _Score>,
+    ) -> std::io::Result<&_Solution> {
+        self.get_current(solution)?;
+        Ok(self.positions.get_local_search_base().unwrap())
+    }
+
+    /// Keep the first error of an observer method for finish.
+    fn observed(&mut self, result: std::io::Result<()>) {
+        if let Err(err) = result {
+            tracing::warn!(%err, "failed to record trace event");
+            self.error.get_or_insert(err);
+        }
+    }
+}
+
+impl<_Solution, _Score> JsonLinesTraceRecorder<BufWriter<File>, _Solution, _Score>
+where
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    /// Create (or truncate) the trace file at path.
+    pub fn create<P: AsRef<Path>>(path: P, sample_every: u64) -> Result<Self, SolverError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|error| {
+            SolverError::InvalidConfiguration(format!("failed to create {}: {}", path.display(), error))
+        })?;
+        Self::new(BufWriter::new(file), sample_every)
+    }
+}
+
+impl<_W, _Solution, _Score> TraceSink for JsonLinesTraceRecorder<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn record(
+        &mut self,
+        iteration: u64,
+        current: &ScoredSolution<_Solution, _Score>,
+        proposed: &ScoredSolution<_Solution, _Score>,
+        accepted: bool,
+    ) -> std::io::Result<()> {
+        self.iteration = iteration;
+        if !self.is_sampled() {
+            return Ok(());
+        }
+        let changes = get_changes(self.get_current(current)?, &proposed.solution);
+        self.write_event(TraceEvent::Round {
+            iteration,
+            changes,
+            score: proposed.score.clone(),
+            score_delta: ScoreDelta::new(&current.score, &proposed.score),
+            accepted,
+        })
+    }
+}
+
+impl<_W, _Solution, _Score> Observer for JsonLinesTraceRecorder<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn on_start(&mut self, current: &ScoredSolution<_Solution, _Score>) {
+        let result = self.write_event(TraceEvent::Start {
+            iteration: self.iteration,
+            solution: current.solution.clone(),
+            score: current.score.clone(),
+        });
+        self.observed(result);
+    }
+
+    fn on_iteration(&mut self, iteration: u64, current: &ScoredSolution<_Solution, _Score>) {
+        self.iteration = iteration;
+        if !self.is_sampled() {
+            return;
+        }
+        let result = self
+            .get_current(current)
+            .map(|from| get_changes(from, &current.solution))
+            .and_then(|changes| {
+                self.write_event(TraceEvent::Accepted {
+                    iteration,
+                    changes,
+                    score: current.score.clone(),
+                })
+            });
+        self.observed(result);
+    }
+
+    fn on_new_best(&mut self, iteration: u64, best: &ScoredSolution<_Solution, _Score>) {
+        self.iteration = iteration;
+        if !self.is_sampled() {
+            return;
+        }
+        let result = self
+            .get_local_search_base(best)
+            .map(|from| get_changes(from, &best.solution))
+            .and_then(|changes| {
+                self.write_event(TraceEvent::NewBest {
+                    iteration,
+                    changes,
+                    score: best.score.clone(),
+                })
+            });
+        self.observed(result);
+    }
+
+    fn on_perturbation(
+        &mut self,
+        iteration: u64,
+        current: &ScoredSolution<_Solution, _Score>,
+        perturbed: &_Solution,
+    ) {
+        self.iteration = iteration;
+        if !self.is_sampled() {
+            return;
+        }
+        let result = self
+            .get_current(current)
+            .map(|from| get_changes(from, perturbed))
+            .and_then(|changes| self.write_event(TraceEvent::Perturbation { iteration, changes }));
+        self.observed(result);
+    }
+
+    fn on_restart(&mut self, iteration: u64, restarted: &ScoredSolution<_Solution, _Score>) {
+        self.iteration = iteration;
+        if !self.is_sampled() {
+            return;
+        }
+        let result = self
+            .get_current(restarted)
+            .map(|from| get_changes(from, &restarted.solution))
+            .and_then(|changes| {
+                self.write_event(TraceEvent::Restart {
+                    iteration,
+                    changes,
+                    score: restarted.score.clone(),
+                })
+            });
+        self.observed(result);
+    }
+}
+
+impl<_W, _Solution, _Score> Drop for JsonLinesTraceRecorder<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    fn drop(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            tracing::warn!(%err, "failed to flush trace");
+        }
+    }
+}
+
+/// StepObserver records the steps of a LocalSearch into a JsonLinesTraceRecorder, see
+/// JsonLinesTraceRecorder::observe_steps. Steps are recorded in the round of the last perturbation.
+pub struct StepObserver<_W, _Solution, _Score>(Arc<Mutex<JsonLinesTraceRecorder<_W, _Solution, _Score>>>)
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize;
+
+impl<_W, _Solution, _Score> Observer for StepObserver<_W, _Solution, _Score>
+where
+    _W: Write,
+    _Solution: Assignment + Serialize,
+    _Solution::Value: Serialize,
+    _Score: HardSoftScoring + Serialize,
+{
+    type _Solution = _Solution;
+    type _Score = _Score;
+
+    fn on_iteration(&mut self, step: u64, current: &ScoredSolution<_Solution, _Score>) {
+        let mut recorder = self.0.lock().unwrap();
+        if !recorder.is_sampled() {
+            return;
+        }
+        let iteration = recorder.iteration;
+        let result = recorder
+            .get_local_search_base(current)
+            .map(|from| get_changes(from, &current.solution))
+            .and_then(|changes| {
+                recorder.write_event(TraceEvent::Step {
+                    iteration,
+                    step,
+                    changes,
+                    score: current.score.clone(),
+                })
+            });
+        recorder.observed(result);
+    }
+}
+
+/// TraceReplay loads a trace written by JsonLinesTraceRecorder and steps through it one event at a time, rebuilding
+/// the current solution, the proposal of the last round, the solution the local search is at, and the best solution,
+/// as the solver had them after that event, by applying the changes of every event to the solution of the Start
+/// event.
+pub struct TraceReplay<_Solution, _Score>
+where
+    _Solution: Assignment,
+    _Score: Score,
+{
+    events: Vec<TraceEvent<_Solution, _Score>>,
+    position: usize,
+    positions: Positions<_Solution>,
+    current: Option<ScoredSolution<_Solution, _Score>>,
+    proposed: Option<ScoredSolution<_Solution, _Score>>,
+    best: Option<ScoredSolution<_Solution, _Score>>,
+}
+
+impl<_Solution, _Score> TraceReplay<_Solution, _Score>
+where
+    _Solution: Assignment + DeserializeOwned,
+    _Solution::Value: DeserializeOwned,
+    _Score: Score + DeserializeOwned,
+{
+    /// Load the events of a trace, or fail if it doesn't begin with a Start event.
+    pub fn load<R: BufRead>(reader: R) -> std::io::Result<Self> {
+        let mut events = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(serde_json::from_str(&line)?);
+        }
+        if !matches!(events.first(), None | Some(TraceEvent::Start { .. })) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                MISSING_START,
+            ));
+        }
+        Ok(Self {
+            events,
+            position: 0,
+            positions: Positions::default(),
+            current: None,
+            proposed: None,
+            best: None,
+        })
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Self::load(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<_Solution, _Score> TraceReplay<_Solution, _Score>
+where
+    _Solution: Assignment,
+    _Score: Score,
+{
+    pub fn get_events(&self) -> &[TraceEvent<_Solution, _Score>] {
+        &self.events
+    }
+
+    /// Apply the next event and return it, or None once the trace is exhausted.
+    pub fn step(&mut self) -> Option<&TraceEvent<_Solution, _Score>> {
+        let event = self.events.get(self.position)?;
+        self.position += 1;
+        let solution = self.positions.apply(event);
+        match event {
+            TraceEvent::Start { score, .. }
+            | TraceEvent::Restart { score, .. }
+            | TraceEvent::Accepted { score, .. } => {
+                self.current = Some(ScoredSolution::new(solution, score.clone()));
+            }
+            TraceEvent::Round { score, accepted, .. } => {
+                let proposed = ScoredSolution::new(solution, score.clone());
+                if *accepted {
+                    self.current = Some(proposed.clone());
+                }
+                self.proposed = Some(proposed);
+            }
+            TraceEvent::NewBest { score, .. } => {
+                self.best = Some(ScoredSolution::new(solution, score.clone()));
+            }
+            TraceEvent::Perturbation { .. } | TraceEvent::Step { .. } => {}
+        }
+        Some(event)
+    }
+
+    /// Step forward until an event matches the predicate, e.g. the first rejected improvement. Returns None, with the
+    /// replay exhausted, if no later event matches.
+    pub fn step_until<F>(&mut self, predicate: F) -> Option<&TraceEvent<_Solution, _Score>>
+    where
+        F: Fn(&TraceEvent<_Solution, _Score>) -> bool,
+    {
+        loop {
+            let position = self.position;
+            let event = self.step()?;
+            if predicate(event) {
+                return self.events.get(position);
+            }
+        }
+    }
+
+    /// Step through all remaining events and return the final current solution.
+    pub fn finish(&mut self) -> Option<&ScoredSolution<_Solution, _Score>> {
+        while self.step().is_some() {}
+        self.get_current_solution()
+    }
+
+    /// The Start solution, or the solution that the last Restart, accepted Round or Accepted event moved to, as of the
+    /// replay position.
+    pub fn get_current_solution(&self) -> Option<&ScoredSolution<_Solution, _Score>> {
+        self.current.as_ref()
+    }
+
+    /// The proposal of the last round stepped through, whether it was accepted or not.
+    pub fn get_proposed_solution(&self) -> Option<&ScoredSolution<_Solution, _Score>> {
+        self.proposed.as_ref()
+    }
+
+    /// The perturbed solution or local search step the solver was at, as of the replay position.
+    pub fn get_local_search_position(&self) -> Option<&_Solution> {
+        self.positions.local_search_position.as_ref()
+    }
+
+    pub fn get_best_solution(&self) -> Option<&ScoredSolution<_Solution, _Score>> {
+        self.best.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use ordered_float::OrderedFloat;
+
+    use crate::ackley::{
+        new_ackley_iterated_local_search, AckleyScore, AckleySolution, AckleySolutionScoreCalculator,
+    };
+    use crate::error::SolverError;
+    use crate::local_search::SolutionScoreCalculator;
+    use crate::trace::{JsonLinesTraceRecorder, TraceEvent, TraceReplay, TraceSink};
+
+    #[test]
+    fn recorder_only_keeps_sampled_rounds() {
+        let solution_score_calculator = AckleySolutionScoreCalculator::default();
+        let start = solution_score_calculator
+            .get_scored_solution(AckleySolution::new(vec![OrderedFloat(0.0), OrderedFloat(0.0)]));
+        let mut recorder = JsonLinesTraceRecorder::new(vec![], 3).unwrap();
+        for iteration in 1..=9 {
+            let proposed = AckleySolution::new(vec![OrderedFloat(iteration as f64), OrderedFloat(0.0)]);
+            let proposed = solution_score_calculator.get_scored_solution(proposed);
+            recorder
+                .record(iteration, &start, &proposed, iteration == 6)
+                .unwrap();
+        }
+
+        let mut replay =
+            TraceReplay::<AckleySolution, AckleyScore>::load(recorder.get_ref().as_slice()).unwrap();
+        let iterations: Vec<u64> = replay
+            .get_events()
+            .iter()
+            .map(TraceEvent::get_iteration)
+            .collect();
+        assert_eq!(vec![3, 3, 6, 9], iterations);
+        // Every round changes a single variable of the current solution instead of holding the whole proposal.
+        assert!(replay.get_events().iter().all(|event| match event {
+            TraceEvent::Round { changes, .. } => changes.len() == 1,
+            _ => matches!(event, TraceEvent::Start { .. }),
+        }));
+
+        assert!(replay.get_current_solution().is_none());
+        replay.step().unwrap();
+        assert_eq!(Some(&start), replay.get_current_solution());
+        let accepted = replay
+            .step_until(|event| matches!(event, TraceEvent::Round { accepted: true, .. }))
+            .cloned()
+            .unwrap();
+        assert_eq!(6, accepted.get_iteration());
+        let expected = AckleySolution::new(vec![OrderedFloat(6.0), OrderedFloat(0.0)]);
+        assert_eq!(
+            Some(&expected),
+            replay.get_current_solution().map(|current| &current.solution)
+        );
+        assert!(matches!(accepted, TraceEvent::Round { score_delta, .. } if score_delta.soft > 0.0));
+        assert_eq!(9, replay.step().unwrap().get_iteration());
+        let expected = AckleySolution::new(vec![OrderedFloat(9.0), OrderedFloat(0.0)]);
+        assert_eq!(
+            Some(&expected),
+            replay.get_proposed_solution().map(|proposed| &proposed.solution)
+        );
+        assert!(replay.step().is_none());
+    }
+
+    #[test]
+    fn recorder_needs_a_positive_sample_every() {
+        let recorder = JsonLinesTraceRecorder::<Vec<u8>, AckleySolution, AckleyScore>::new(vec![], 0);
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "sample_every must be positive".to_string()
+            )),
+            recorder.err()
+        );
+    }
+
+    #[test]
+    fn iterated_local_search_writes_trace_file() {
+        let path = std::env::temp_dir().join(format!("local-search-trace-{}.jsonl", std::process::id()));
+        let mut iterated_local_search = new_ackley_iterated_local_search(2, 42, 20);
+        iterated_local_search.set_trace_sink(Box::new(JsonLinesTraceRecorder::create(&path, 1).unwrap()));
+        while !iterated_local_search.is_finished() {
+            iterated_local_search.execute_round().unwrap();
+        }
+        let best = iterated_local_search.get_best_solution();
+        // Dropping the solver drops the recorder, which flushes the file.
+        drop(iterated_local_search);
+
+        let mut replay = TraceReplay::<AckleySolution, AckleyScore>::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(1, replay.get_events().first().unwrap().get_iteration());
+        let mut proposals = vec![];
+        while let Some(event) = replay.step() {
+            if let TraceEvent::Round { .. } = event {
+                proposals.push(replay.get_proposed_solution().unwrap().clone());
+            }
+        }
+        let best_proposal = proposals
+            .into_iter()
+            .min_by_key(|proposal| proposal.score.clone())
+            .unwrap();
+        assert_eq!(best.score, best_proposal.score);
+        assert_eq!(
+            AckleySolutionScoreCalculator::default().get_scored_solution(best_proposal.solution.clone()),
+            best_proposal
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! tuning searches for good parameter settings of a solver, e.g. its window size, perturbation weights or
+//! no-improvement limit, instead of tuning them by hand for every new problem. random_search samples configurations
+//! from a ParameterSpace, runs every configuration over the same seeds with run_experiment, see the bench module, and
+//! reports them all, best first. Use seeds and a termination that are representative of the real runs, because the
+//! best configuration for short runs is often not the best for long ones.
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::bench::{run_experiment, ExperimentReport};
+use crate::error::SolverError;
+use crate::iterated_local_search::{AcceptanceCriterion, IteratedLocalSearch, Perturbation};
+use crate::local_search::{InitialSolutionGenerator, MoveProposer, Solution, SolutionScoreCalculator};
+use crate::score::HardSoftScoring;
+use crate::termination::Termination;
+
+/// ParameterRange is the values that a parameter is sampled from, both bounds inclusive.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParameterRange {
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    Float {
+        min: f64,
+        max: f64,
+    },
+
+    /// Floats sampled uniformly on a log scale, for parameters that span orders of magnitude, e.g. a temperature.
+    /// min must be positive.
+    LogFloat {
+        min: f64,
+        max: f64,
+    },
+}
+
+impl ParameterRange {
+    fn sample<_R: rand::Rng>(&self, rng: &mut _R) -> ParameterValue {
+        match *self {
+            ParameterRange::Integer { min, max } => ParameterValue::Integer(rng.gen_range(min..=max)),
+            ParameterRange::Float { min, max } => ParameterValue::Float(rng.gen_range(min..=max)),
+            ParameterRange::LogFloat { min, max } => {
+                ParameterValue::Float(rng.gen_range(min.ln()..=max.ln()).exp())
+            }
+        }
+    }
+}
+
+/// ParameterValue is the value of one parameter in a Configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ParameterValue {
+    Integer(i64),
+    Float(f64),
+}
+
+/// ParameterSpace is the named parameters to tune and their ranges.
+#[derive(Clone, Debug, Default)]
+pub struct ParameterSpace {
+    parameters: Vec<(String, ParameterRange)>,
+}
+
+impl ParameterSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_parameter(&mut self, name: impl Into<String>, range: ParameterRange) {
+        let name = name.into();
+        match range {
+            ParameterRange::Integer { min, max } => assert!(min <= max, "min must not be greater than max"),
+            ParameterRange::Float { min, max } => assert!(min <= max, "min must not be greater than max"),
+            ParameterRange::LogFloat { min, max } => {
+                assert!(min > 0.0, "min must be positive");
+                assert!(min <= max, "min must not be greater than max");
+            }
+        }
+        assert!(
+            self.parameters.iter().all(|(other, _range)| *other != name),
+            "parameter names must be unique, {} is already used",
+            name
+        );
+        self.parameters.push((name, range));
+    }
+
+    /// Like add_parameter, to add parameters when creating the space.
+    pub fn with_parameter(mut self, name: impl Into<String>, range: ParameterRange) -> Self {
+        self.add_parameter(name, range);
+        self
+    }
+
+    /// Sample every parameter independently and uniformly from its range.
+    pub fn sample<_R: rand::Rng>(&self, rng: &mut _R) -> Configuration {
+        Configuration {
+            values: self
+                .parameters
+                .iter()
+                .map(|(name, range)| (name.clone(), range.sample(rng)))
+                .collect(),
+        }
+    }
+}
+
+/// Configuration is one value for every parameter of a ParameterSpace.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Configuration {
+    pub values: BTreeMap<String, ParameterValue>,
+}
+
+impl Configuration {
+    /// Get the value of an integer parameter. Panics if there is no such parameter.
+    pub fn get_integer(&self, name: &str) -> i64 {
+        match self.values.get(name) {
+            Some(ParameterValue::Integer(value)) => *value,
+            _ => panic!("no integer parameter named {}", name),
+        }
+    }
+
+    /// Get the value of a float parameter. Panics if there is no such parameter.
+    pub fn get_float(&self, name: &str) -> f64 {
+        match self.values.get(name) {
+            Some(ParameterValue::Float(value)) => *value,
+            _ => panic!("no float parameter named {}", name),
+        }
+    }
+}
+
+/// Trial is how one configuration did over the seeds of the tuning.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Trial<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub configuration: Configuration,
+    pub report: ExperimentReport<_Score>,
+}
+
+/// TuningReport is every trial of a tuning, best first: by the mean energy of their best solutions, then by the
+/// median, and then by the mean elapsed time.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TuningReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    pub trials: Vec<Trial<_Score>>,
+}
+
+impl<_Score> TuningReport<_Score>
+where
+    _Score: HardSoftScoring,
+{
+    /// Get the best trial, or None if there are no trials.
+    pub fn get_best_trial(&self) -> Option<&Trial<_Score>> {
+        self.trials.first()
+    }
+}
+
+/// Sample trial_count configurations from space with sample_rng, run the solver that create_solver creates for every
+/// configuration and seed until termination says to stop, and report how every configuration did. Fails if
+/// trial_count is 0, and stops at the first run that fails.
+pub fn random_search<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC, _T, _SR, F>(
+    space: &ParameterSpace,
+    trial_count: usize,
+    seeds: &[u64],
+    termination: &_T,
+    sample_rng: &mut _SR,
+    mut create_solver: F,
+) -> Result<TuningReport<_Score>, SolverError>
+where
+    _R: rand::Rng,
+    _Score: HardSoftScoring,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+    _MP: MoveProposer<R = _R, Solution = _Solution>,
+    _ISG: InitialSolutionGenerator<R = _R, Solution = _Solution>,
+    _P: Perturbation<_R = _R, _Solution = _Solution, _Score = _Score, _SSC = _SSC>,
+    _AC: AcceptanceCriterion<_R = _R, _Solution = _Solution, _Score = _Score>,
+    _T: Termination<_Score>,
+    _SR: rand::Rng,
+    F: FnMut(&Configuration, u64) -> IteratedLocalSearch<_R, _Solution, _Score, _SSC, _MP, _ISG, _P, _AC>,
+{
+    if trial_count == 0 {
+        return Err(SolverError::InvalidConfiguration(
+            "trial_count must be positive".to_string(),
+        ));
+    }
+    let mut trials = (0..trial_count)
+        .map(|trial| {
+            let configuration = space.sample(sample_rng);
+            let _span = tracing::info_span!("tuning_trial", trial).entered();
+            let report = run_experiment(seeds, termination, None, |seed| {
+                create_solver(&configuration, seed)
+            })?;
+            Ok(Trial {
+                configuration,
+                report,
+            })
+        })
+        .collect::<Result<Vec<_>, SolverError>>()?;
+    trials.sort_by(|a, b| {
+        a.report
+            .final_energy
+            .mean
+            .total_cmp(&b.report.final_energy.mean)
+            .then(
+                a.report
+                    .final_energy
+                    .median
+                    .total_cmp(&b.report.final_energy.median),
+            )
+            .then(
+                a.report
+                    .elapsed_seconds
+                    .mean
+                    .total_cmp(&b.report.elapsed_seconds.mean),
+            )
+    });
+    Ok(TuningReport { trials })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::tuning::{ParameterRange, ParameterSpace, ParameterValue};
+
+    #[test]
+    fn samples_within_ranges() {
+        let space = ParameterSpace::new()
+            .with_parameter("window_size", ParameterRange::Integer { min: 10, max: 20 })
+            .with_parameter("weight", ParameterRange::Float { min: 0.0, max: 1.0 })
+            .with_parameter(
+                "temperature",
+                ParameterRange::LogFloat {
+                    min: 0.01,
+                    max: 100.0,
+                },
+            );
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        for _ in 0..100 {
+            let configuration = space.sample(&mut rng);
+            assert_eq!(3, configuration.values.len());
+            assert!((10..=20).contains(&configuration.get_integer("window_size")));
+            assert!((0.0..=1.0).contains(&configuration.get_float("weight")));
+            assert!((0.01..=100.0).contains(&configuration.get_float("temperature")));
+        }
+        assert_eq!(
+            Some(&ParameterValue::Integer(10)),
+            ParameterSpace::new()
+                .with_parameter("fixed", ParameterRange::Integer { min: 10, max: 10 })
+                .sample(&mut rng)
+                .values
+                .get("fixed")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "parameter names must be unique")]
+    fn rejects_duplicate_names() {
+        ParameterSpace::new()
+            .with_parameter("window_size", ParameterRange::Integer { min: 10, max: 20 })
+            .with_parameter("window_size", ParameterRange::Integer { min: 1, max: 2 });
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{
+        AckleyInitialSolutionGenerator, AckleyIteratedLocalSearch, AckleyMoveProposer, AckleyPerturbation,
+        AckleySolutionScoreCalculator,
+    };
+    use crate::error::SolverError;
+    use crate::iterated_local_search::IteratedLocalSearchBuilder;
+    use crate::local_search::LocalSearchBuilder;
+    use crate::termination::MaxIterations;
+    use crate::tuning::{random_search, Configuration, ParameterRange, ParameterSpace};
+
+    fn new_solver(configuration: &Configuration, seed: u64) -> AckleyIteratedLocalSearch {
+        let local_search = LocalSearchBuilder::new(
+            AckleyMoveProposer::new(2, 1e-3, 0.5),
+            AckleySolutionScoreCalculator::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        )
+        .max_iterations(1_000)
+        .window_size(configuration.get_integer("window_size") as usize)
+        .build();
+        IteratedLocalSearchBuilder::new(
+            AckleyInitialSolutionGenerator::new(2),
+            AckleySolutionScoreCalculator::default(),
+            local_search,
+            AckleyPerturbation::default(),
+            rand_chacha::ChaCha20Rng::seed_from_u64(seed),
+        )
+        .max_iterations(u64::MAX)
+        .max_allow_no_improvement_for(configuration.get_integer("max_allow_no_improvement_for") as u64)
+        .build()
+    }
+
+    fn new_space() -> ParameterSpace {
+        ParameterSpace::new()
+            .with_parameter("window_size", ParameterRange::Integer { min: 1, max: 200 })
+            .with_parameter(
+                "max_allow_no_improvement_for",
+                ParameterRange::Integer { min: 1, max: 10 },
+            )
+    }
+
+    #[test]
+    fn ackley_random_search() {
+        let space = new_space();
+        let mut sample_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let report =
+            random_search(&space, 4, &[0, 1], &MaxIterations(3), &mut sample_rng, new_solver).unwrap();
+        assert_eq!(4, report.trials.len());
+        assert!(report
+            .trials
+            .windows(2)
+            .all(|pair| pair[0].report.final_energy.mean <= pair[1].report.final_energy.mean));
+        assert_eq!(Some(&report.trials[0]), report.get_best_trial());
+        assert!(report
+            .trials
+            .iter()
+            .all(|trial| trial.report.runs.iter().map(|run| run.seed).eq([0, 1])));
+    }
+
+    #[test]
+    fn random_search_needs_trials() {
+        let mut sample_rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let report = random_search(
+            &new_space(),
+            0,
+            &[0],
+            &MaxIterations(3),
+            &mut sample_rng,
+            new_solver,
+        );
+        assert_eq!(
+            Some(SolverError::InvalidConfiguration(
+                "trial_count must be positive".to_string()
+            )),
+            report.err()
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! two_phase solves in two phases: first it searches for a feasible solution, ignoring the soft score, and once it has
+//! one it optimizes the soft score without ever giving up feasibility again. Searching on the full score from the
+//! start lets many small soft improvements outvote the few moves that repair hard constraints, e.g. with
+//! energy-based acceptance, and runs can end with hard violations left.
+//!
+//! Wrap the score calculator of IteratedLocalSearch, and of its LocalSearch, in TwoPhaseScoreCalculator, and its
+//! acceptance criterion in TwoPhaseAcceptanceCriterion.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::constraints::ConstraintViolation;
+use crate::iterated_local_search::AcceptanceCriterion;
+use crate::local_search::{History, ScoredSolution, SolutionScoreCalculator};
+use crate::score::{FromHardSoftScore, HardSoftScoring};
+
+/// SolvePhase is what a two phase solve currently optimizes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SolvePhase {
+    /// Only the hard score counts, soft scores are reported as zero.
+    Feasibility,
+
+    /// The full score counts. Starts with the first feasible solution that is scored.
+    Optimization,
+}
+
+/// TwoPhaseScoreCalculator reports scores without their soft part until solution_score_calculator scores the first
+/// feasible solution, and full scores from then on. Clones share their phase, so that the calculators of
+/// IteratedLocalSearch and LocalSearch switch together.
+#[derive(Clone)]
+pub struct TwoPhaseScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+{
+    solution_score_calculator: _SSC,
+    is_feasible_found: Arc<AtomicBool>,
+}
+
+impl<_SSC> TwoPhaseScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+{
+    pub fn new(solution_score_calculator: _SSC) -> Self {
+        Self {
+            solution_score_calculator,
+            is_feasible_found: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn get_phase(&self) -> SolvePhase {
+        match self.is_feasible_found.load(Ordering::Relaxed) {
+            false => SolvePhase::Feasibility,
+            true => SolvePhase::Optimization,
+        }
+    }
+
+    pub fn get_solution_score_calculator(&self) -> &_SSC {
+        &self.solution_score_calculator
+    }
+}
+
+impl<_SSC> SolutionScoreCalculator for TwoPhaseScoreCalculator<_SSC>
+where
+    _SSC: SolutionScoreCalculator,
+    _SSC::_Score: FromHardSoftScore,
+{
+    type _Solution = _SSC::_Solution;
+    type _Score = _SSC::_Score;
+
+    fn get_scored_solution(
+        &self,
+        solution: Self::_Solution,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let scored_solution = self.solution_score_calculator.get_scored_solution(solution);
+        let hard_score = scored_solution.score.get_hard_score();
+        if hard_score == 0.0 {
+            self.is_feasible_found.store(true, Ordering::Relaxed);
+        }
+        match self.get_phase() {
+            SolvePhase::Feasibility => ScoredSolution {
+                score: Self::_Score::from_hard_soft_score(hard_score, 0.0),
+                ..scored_solution
+            },
+            SolvePhase::Optimization => scored_solution,
+        }
+    }
+
+    /// Verifies with the wrapped calculator in either phase, since feasibility only depends on the hard score.
+    fn verify(&self, solution: &Self::_Solution) -> Result<(), Vec<ConstraintViolation>>
+    where
+        Self::_Score: HardSoftScoring,
+    {
+        self.solution_score_calculator.verify(solution)
+    }
+}
+
+/// TwoPhaseAcceptanceCriterion lets acceptance_criterion choose, but never moves from a feasible local minima to an
+/// infeasible one, so that the optimization phase never worsens the hard score.
+pub struct TwoPhaseAcceptanceCriterion<_AC>
+where
+    _AC: AcceptanceCriterion,
+    _AC::_Score: HardSoftScoring,
+{
+    acceptance_criterion: _AC,
+}
+
+impl<_AC> TwoPhaseAcceptanceCriterion<_AC>
+where
+    _AC: AcceptanceCriterion,
+    _AC::_Score: HardSoftScoring,
+{
+    pub fn new(acceptance_criterion: _AC) -> Self {
+        Self { acceptance_criterion }
+    }
+}
+
+impl<_AC> AcceptanceCriterion for TwoPhaseAcceptanceCriterion<_AC>
+where
+    _AC: AcceptanceCriterion,
+    _AC::_Score: HardSoftScoring,
+{
+    type _R = _AC::_R;
+    type _Solution = _AC::_Solution;
+    type _Score = _AC::_Score;
+
+    fn choose(
+        &mut self,
+        existing_local_minima: &ScoredSolution<Self::_Solution, Self::_Score>,
+        new_local_minima: &ScoredSolution<Self::_Solution, Self::_Score>,
+        history: &History<Self::_R, Self::_Solution, Self::_Score>,
+        rng: &mut Self::_R,
+    ) -> ScoredSolution<Self::_Solution, Self::_Score> {
+        let chosen = self
+            .acceptance_criterion
+            .choose(existing_local_minima, new_local_minima, history, rng);
+        match existing_local_minima.score.get_hard_score() == 0.0 && chosen.score.get_hard_score() > 0.0 {
+            true => existing_local_minima.clone(),
+            false => chosen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ordered_float::OrderedFloat;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    use crate::fixtures::{assert_verify_lists_no_ones, new_no_ones_score_calculator};
+    use crate::iterated_local_search::AcceptanceCriterion;
+    use crate::local_search::{History, ScoredSolution, Solution, SolutionScoreCalculator};
+    use crate::score::FloatHardSoft;
+    use crate::two_phase::{SolvePhase, TwoPhaseAcceptanceCriterion, TwoPhaseScoreCalculator};
+
+    #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+    struct Number(i64);
+    impl Solution for Number {}
+
+    /// Numbers above 3 are infeasible, and smaller numbers are better.
+    #[derive(Clone)]
+    struct NumberScoreCalculator;
+    impl SolutionScoreCalculator for NumberScoreCalculator {
+        type _Solution = Number;
+        type _Score = FloatHardSoft;
+
+        fn get_scored_solution(&self, solution: Number) -> ScoredSolution<Number, FloatHardSoft> {
+            let score = FloatHardSoft::new(
+                OrderedFloat((solution.0 - 3).max(0) as f64),
+                OrderedFloat(solution.0 as f64 + 10.0),
+            );
+            ScoredSolution::new(solution, score)
+        }
+    }
+
+    fn score(hard_score: f64, soft_score: f64) -> FloatHardSoft {
+        FloatHardSoft::new(OrderedFloat(hard_score), OrderedFloat(soft_score))
+    }
+
+    #[test]
+    fn ignores_soft_score_until_feasible() {
+        let two_phase = TwoPhaseScoreCalculator::new(NumberScoreCalculator);
+        let shared = two_phase.clone();
+        assert_eq!(score(7.0, 0.0), two_phase.get_scored_solution(Number(10)).score);
+        assert_eq!(SolvePhase::Feasibility, two_phase.get_phase());
+        assert_eq!(score(0.0, 12.0), two_phase.get_scored_solution(Number(2)).score);
+        assert_eq!(SolvePhase::Optimization, shared.get_phase());
+        assert_eq!(score(7.0, 20.0), shared.get_scored_solution(Number(10)).score);
+    }
+
+    /// Always moves to the new local minima.
+    struct AcceptNew;
+    impl AcceptanceCriterion for AcceptNew {
+        type _R = ChaCha20Rng;
+        type _Solution = Number;
+        type _Score = FloatHardSoft;
+
+        fn choose(
+            &mut self,
+            _existing_local_minima: &ScoredSolution<Number, FloatHardSoft>,
+            new_local_minima: &ScoredSolution<Number, FloatHardSoft>,
+            _history: &History<ChaCha20Rng, Number, FloatHardSoft>,
+            _rng: &mut ChaCha20Rng,
+        ) -> ScoredSolution<Number, FloatHardSoft> {
+            new_local_minima.clone()
+        }
+    }
+
+    #[test]
+    fn never_gives_up_feasibility() {
+        let mut acceptance_criterion = TwoPhaseAcceptanceCriterion::new(AcceptNew);
+        let history = History::default();
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let mut choose = |existing: i64, new: i64| {
+            acceptance_criterion
+                .choose(
+                    &NumberScoreCalculator.get_scored_solution(Number(existing)),
+                    &NumberScoreCalculator.get_scored_solution(Number(new)),
+                    &history,
+                    &mut rng,
+                )
+                .solution
+        };
+        assert_eq!(Number(2), choose(2, 10));
+        assert_eq!(Number(3), choose(2, 3));
+        assert_eq!(Number(10), choose(5, 10));
+    }
+
+    #[test]
+    fn verify_lists_the_violations_of_the_wrapped_calculator() {
+        assert_verify_lists_no_ones(&TwoPhaseScoreCalculator::new(new_no_ones_score_calculator()));
+    }
+}
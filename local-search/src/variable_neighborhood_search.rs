@@ -0,0 +1,131 @@
+//! variable_neighborhood_search changes neighborhoods instead of perturbing, see [1]. A local minimum of one
+//! neighborhood is often not a local minimum of a larger one, so VariableNeighborhoodSearch only gives up once no
+//! neighborhood improves on the current solution.
+//!
+//! [1] Hansen, Pierre and Nenad Mladenović. "Variable Neighborhood Search: Principles and Applications." European
+//! Journal of Operational Research 130.3 (2001): 449-467.
+use std::time::Instant;
+
+use crate::local_search::{MoveProposer, Score, ScoredSolution, Solution, SolutionScoreCalculator};
+use crate::termination::{Termination, TerminationState};
+
+type MoveProposers<_R, _Solution> = Vec<Box<dyn MoveProposer<R = _R, Solution = _Solution> + Send>>;
+
+/// VariableNeighborhoodSearch is variable neighborhood descent over move_proposers, which should be ordered from the
+/// smallest neighborhood to the largest. Every step moves to the best of at most window_size neighbors of the current
+/// neighborhood if it improves on the current solution, and then starts over at the first neighborhood. Otherwise it
+/// tries the next neighborhood.
+pub struct VariableNeighborhoodSearch<_R, _Solution, _Score, _SSC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+{
+    solution_score_calculator: _SSC,
+    move_proposers: MoveProposers<_R, _Solution>,
+    window_size: usize,
+    rng: _R,
+}
+
+impl<_R, _Solution, _Score, _SSC> VariableNeighborhoodSearch<_R, _Solution, _Score, _SSC>
+where
+    _R: rand::Rng,
+    _Score: Score,
+    _Solution: Solution,
+    _SSC: SolutionScoreCalculator<_Solution = _Solution, _Score = _Score>,
+{
+    pub fn new(
+        solution_score_calculator: _SSC,
+        move_proposers: MoveProposers<_R, _Solution>,
+        window_size: usize,
+        rng: _R,
+    ) -> Self {
+        assert!(!move_proposers.is_empty(), "move_proposers must not be empty");
+        assert!(window_size > 0, "window_size must be positive");
+        Self {
+            solution_score_calculator,
+            move_proposers,
+            window_size,
+            rng,
+        }
+    }
+
+    /// Descend from start until no neighborhood improves on the current solution, until termination says to stop, see
+    /// the termination module, or until a best possible solution is found, and return the final solution. Iterations
+    /// are steps, including the steps that only switch to the next neighborhood.
+    pub fn execute<_T>(&mut self, start: _Solution, termination: &_T) -> ScoredSolution<_Solution, _Score>
+    where
+        _T: Termination<_Score>,
+    {
+        let _span = tracing::trace_span!("variable_neighborhood_search").entered();
+        let started = Instant::now();
+        let mut current = self.solution_score_calculator.get_scored_solution(start);
+        let mut neighborhood = 0;
+        let mut no_improvement_for = 0;
+        for iteration in 0.. {
+            let state = TerminationState {
+                iteration,
+                elapsed: started.elapsed(),
+                best_score: Some(&current.score),
+                iterations_without_improvement: no_improvement_for,
+            };
+            if current.score.is_best() || termination.is_terminated(&state) {
+                break;
+            }
+            let neighborhood_best = self.move_proposers[neighborhood]
+                .iter_local_moves(&current.solution, &mut self.rng)
+                .take(self.window_size)
+                .map(|solution| self.solution_score_calculator.get_scored_solution(solution))
+                .min();
+            match neighborhood_best {
+                Some(neighborhood_best) if neighborhood_best.score < current.score => {
+                    tracing::trace!(neighborhood, score = ?neighborhood_best.score, "improved");
+                    current = neighborhood_best;
+                    neighborhood = 0;
+                    no_improvement_for = 0;
+                }
+                _ => {
+                    neighborhood += 1;
+                    no_improvement_for += 1;
+                    if neighborhood == self.move_proposers.len() {
+                        break;
+                    }
+                }
+            }
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod ackley_tests {
+    use rand::SeedableRng;
+
+    use crate::ackley::{AckleyInitialSolutionGenerator, AckleyMoveProposer, AckleySolutionScoreCalculator};
+    use crate::local_search::{InitialSolutionGenerator, SolutionScoreCalculator};
+    use crate::termination::MaxIterations;
+    use crate::variable_neighborhood_search::VariableNeighborhoodSearch;
+
+    #[test]
+    fn descends_from_the_start() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(42);
+        let start = AckleyInitialSolutionGenerator::new(2).generate_initial_solution(&mut rng);
+        let start_score = AckleySolutionScoreCalculator::default()
+            .get_scored_solution(start.clone())
+            .score;
+        let mut variable_neighborhood_search = VariableNeighborhoodSearch::new(
+            AckleySolutionScoreCalculator::default(),
+            vec![
+                Box::new(AckleyMoveProposer::new(2, 1e-3, 1e-2)),
+                Box::new(AckleyMoveProposer::new(2, 1e-2, 0.5)),
+                Box::new(AckleyMoveProposer::new(2, 0.5, 5.0)),
+            ],
+            100,
+            rng,
+        );
+        let result = variable_neighborhood_search.execute(start, &MaxIterations(100_000));
+        println!("start score: {:?}, result: {:?}", start_score, result);
+        assert!(result.score < start_score);
+    }
+}
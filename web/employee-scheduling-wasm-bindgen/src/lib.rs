@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 use employee_scheduling::{get_ils, Employee, Holiday, IlsType, MainArgs, ScheduleScore};
+use local_search::config::SolverConfig;
+use local_search::constraints::ConstraintWeights;
 
 #[wasm_bindgen]
 pub struct SolverContext {
@@ -16,7 +18,7 @@ pub struct SolverContext {
 }
 
 #[wasm_bindgen]
-pub fn create_solver(input: &JsValue) -> SolverContext {
+pub fn create_solver(input: &JsValue) -> Result<SolverContext, JsValue> {
     let input: EmployeeSchedulingInput = input.into_serde().unwrap();
     let employee_to_holidays: HashMap<Employee, HashSet<Holiday>> =
         itertools::zip(input.employees.clone(), input.employee_holidays)
@@ -27,34 +29,36 @@ pub fn create_solver(input: &JsValue) -> SolverContext {
                 )
             })
             .collect();
+    let constraint_weights = ConstraintWeights::new();
+    for (name, weight) in input.constraint_weights {
+        constraint_weights
+            .try_set_weight(name, weight)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+    }
     let seed = "42";
-    let local_search_max_iterations = 1_000;
-    let window_size = 100;
-    let best_solutions_capacity = 64;
-    let all_solutions_capacity = 100_000;
-    let all_solution_iteration_expiry = 1_000;
-    let iterated_local_search_max_iterations = 250;
-    let max_allow_no_improvement_for = 20;
-    let ils = get_ils(MainArgs {
+    let solver_config =
+        SolverConfig::from_toml_str(include_str!("../../../examples/employee-scheduling/solver.toml"))
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let iterated_local_search_max_iterations = solver_config.iterated_local_search.max_iterations;
+    let mut ils = get_ils(MainArgs {
         start_date: input.start_date,
         end_date: input.end_date,
         employees: input.employees.iter().copied().collect(),
         employee_to_holidays,
         seed,
-        local_search_max_iterations,
-        window_size,
-        best_solutions_capacity,
-        all_solutions_capacity: all_solutions_capacity as usize,
-        all_solution_iteration_expiry,
-        iterated_local_search_max_iterations,
-        max_allow_no_improvement_for,
-    });
-    SolverContext { solver: ils }
+        solver_config,
+        constraint_weights,
+    })
+    .map_err(|error| JsValue::from_str(&error.to_string()))?;
+    ils.set_iteration_history_capacity(iterated_local_search_max_iterations as usize);
+    Ok(SolverContext { solver: ils })
 }
 
 #[wasm_bindgen]
-pub fn execute_solver_round(ctx: &mut SolverContext) {
-    ctx.solver.execute_round();
+pub fn execute_solver_round(ctx: &mut SolverContext) -> Result<(), JsValue> {
+    ctx.solver
+        .execute_round()
+        .map_err(|error| JsValue::from_str(&error.to_string()))
 }
 
 #[wasm_bindgen]
@@ -63,6 +67,12 @@ pub fn get_iteration_info(ctx: &mut SolverContext) -> JsValue {
     JsValue::from_serde(&result).unwrap()
 }
 
+/// Get the IterationInfo at the end of every round so far, oldest first.
+#[wasm_bindgen]
+pub fn get_iteration_history(ctx: &SolverContext) -> JsValue {
+    JsValue::from_serde(ctx.solver.get_iteration_history()).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn is_solver_finished(ctx: &SolverContext) -> bool {
     ctx.solver.is_finished()
@@ -95,6 +105,9 @@ pub struct EmployeeSchedulingInput {
 
     #[serde(rename = "employeeHolidays")]
     pub employee_holidays: Vec<Vec<NaiveDate>>,
+
+    #[serde(rename = "constraintWeights", default)]
+    pub constraint_weights: HashMap<String, f64>,
 }
 
 #[derive(thiserror::Error, Debug)]